@@ -3,7 +3,7 @@
 //! This module provides pure business logic for validating date and time inputs,
 //! generating date suggestions, and other date-related operations.
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -11,6 +11,109 @@ lazy_static! {
     static ref TIME_PATTERN: Regex = Regex::new(r"^(?:[01]\d|2[0-3]):[0-5]\d$").unwrap();
     static ref HOUR_PATTERN: Regex = Regex::new(r"^(?:[01]?\d|2[0-3])$").unwrap();
     static ref SINGLE_HOUR_PATTERN: Regex = Regex::new(r"^[0-9]$").unwrap();
+    static ref TWELVE_HOUR_PATTERN: Regex =
+        Regex::new(r"(?i)^(1[0-2]|0?[1-9])(?::([0-5]\d))?\s*([ap])\.?m?\.?$").unwrap();
+}
+
+/// The order of day and month in typed and displayed dates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `DD/MM`, such as 24/12.
+    #[default]
+    DayMonth,
+    /// `MM/DD`, such as 12/24.
+    MonthDay,
+    /// `YYYY-MM-DD`, such as 2026-12-24; `MM-DD` is accepted as input.
+    Iso,
+}
+
+impl DateOrder {
+    /// Parses a format name: `dd/mm`, `mm/dd` or `iso`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "dd/mm" => Some(Self::DayMonth),
+            "mm/dd" => Some(Self::MonthDay),
+            "iso" | "yyyy-mm-dd" => Some(Self::Iso),
+            _ => None,
+        }
+    }
+
+    /// Returns the input pattern for messages, such as `DD/MM`.
+    pub fn pattern(self) -> &'static str {
+        match self {
+            Self::DayMonth => "DD/MM",
+            Self::MonthDay => "MM/DD",
+            Self::Iso => "YYYY-MM-DD",
+        }
+    }
+
+    /// Formats a date as it is typed: without the year unless ISO.
+    pub fn format_short(self, date: NaiveDate) -> String {
+        match self {
+            Self::DayMonth => date.format("%d/%m").to_string(),
+            Self::MonthDay => date.format("%m/%d").to_string(),
+            Self::Iso => date.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    /// Formats a date with its year.
+    pub fn format(self, date: NaiveDate) -> String {
+        match self {
+            Self::DayMonth => date.format("%d/%m/%Y").to_string(),
+            Self::MonthDay => date.format("%m/%d/%Y").to_string(),
+            Self::Iso => date.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// Whether times are shown on a 24-hour or a 12-hour clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// `14:30`.
+    #[default]
+    TwentyFourHour,
+    /// `2:30 PM`.
+    TwelveHour,
+}
+
+impl TimeFormat {
+    /// Parses a format name: `24h` or `12h`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "24h" | "24" => Some(Self::TwentyFourHour),
+            "12h" | "12" => Some(Self::TwelveHour),
+            _ => None,
+        }
+    }
+
+    pub fn format(self, time: NaiveTime) -> String {
+        match self {
+            Self::TwentyFourHour => time.format("%H:%M").to_string(),
+            Self::TwelveHour => time.format("%-I:%M %p").to_string(),
+        }
+    }
+}
+
+/// Parses a time input accepted by `validate_time_input`, on either clock.
+/// Returns `None` for empty input (an all-day event).
+pub fn parse_time_input(input: &str) -> Result<Option<NaiveTime>, String> {
+    validate_time_input(input)?;
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let (hour, minute) = if let Some(captures) = TWELVE_HOUR_PATTERN.captures(trimmed) {
+        let hour = captures[1].parse::<u32>().unwrap() % 12;
+        let minute = captures.get(2).map_or(0, |m| m.as_str().parse().unwrap());
+        let pm = captures[3].eq_ignore_ascii_case("p");
+        (if pm { hour + 12 } else { hour }, minute)
+    } else {
+        match trimmed.split_once(':') {
+            Some((hour, minute)) => (hour.parse().unwrap(), minute.parse().unwrap()),
+            None => (trimmed.parse().unwrap(), 0),
+        }
+    };
+    Ok(NaiveTime::from_hms_opt(hour, minute, 0))
 }
 
 /// Validates a time input string and returns Ok(()) for valid inputs or Err(message) for invalid.
@@ -18,6 +121,7 @@ lazy_static! {
 /// - HH:MM format (e.g., "14:30", "09:05", "00:00")
 /// - HH format (e.g., "14", "9", "23", "0")  
 /// - H format (single digit, e.g., "9", "0")
+/// - 12-hour format (e.g., "2pm", "2:30 PM", "12am")
 ///
 /// Empty input is considered valid (for all-day events).
 pub fn validate_time_input(input: &str) -> Result<(), String> {
@@ -43,7 +147,15 @@ pub fn validate_time_input(input: &str) -> Result<(), String> {
         return Ok(());
     }
 
-    Err("Invalid time format. Use HH:MM, HH, or H (e.g., 14:30, 14, 9)".to_string())
+    // Check 12-hour format with am/pm
+    if TWELVE_HOUR_PATTERN.is_match(trimmed) {
+        return Ok(());
+    }
+
+    Err(
+        "Invalid time format. Use HH:MM, HH, H or H:MM am/pm (e.g., 14:30, 14, 9, 2:30pm)"
+            .to_string(),
+    )
 }
 
 /// Validates a date input string in DD/MM format and returns a NaiveDate.
@@ -51,20 +163,59 @@ pub fn validate_time_input(input: &str) -> Result<(), String> {
 /// is before or on the same day as start_date, it assumes the next year.
 /// Returns an error string for invalid formats or dates.
 pub fn validate_date_input(input: &str, start_date: NaiveDate) -> Result<NaiveDate, String> {
+    validate_date_input_in(input, start_date, DateOrder::DayMonth)
+}
+
+/// Validates a date input string in the given order, like
+/// `validate_date_input`. ISO input may leave out the year (`MM-DD`), and
+/// the year is then assumed the same way.
+pub fn validate_date_input_in(
+    input: &str,
+    start_date: NaiveDate,
+    order: DateOrder,
+) -> Result<NaiveDate, String> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return Ok(start_date);
     }
+    let invalid_format = || format!("Invalid format. Use {}", order.pattern());
+
+    let (day_part, month_part) = match order {
+        DateOrder::Iso => {
+            let parts: Vec<&str> = trimmed.split('-').collect();
+            match parts[..] {
+                [year, month, day] if year.len() == 4 => {
+                    let year = year
+                        .parse::<i32>()
+                        .map_err(|_| "Invalid year".to_string())?;
+                    let month = month
+                        .parse::<u32>()
+                        .map_err(|_| "Invalid month".to_string())?;
+                    let day = day.parse::<u32>().map_err(|_| "Invalid day".to_string())?;
+                    return NaiveDate::from_ymd_opt(year, month, day)
+                        .ok_or_else(|| "Invalid date".to_string());
+                }
+                [month, day] => (day, month),
+                _ => return Err(invalid_format()),
+            }
+        }
+        _ => {
+            let parts: Vec<&str> = trimmed.split('/').collect();
+            if parts.len() != 2 {
+                return Err(invalid_format());
+            }
+            if order == DateOrder::MonthDay {
+                (parts[1], parts[0])
+            } else {
+                (parts[0], parts[1])
+            }
+        }
+    };
 
-    let parts: Vec<&str> = trimmed.split('/').collect();
-    if parts.len() != 2 {
-        return Err("Invalid format. Use DD/MM".to_string());
-    }
-
-    let day = parts[0]
+    let day = day_part
         .parse::<u32>()
         .map_err(|_| "Invalid day".to_string())?;
-    let month = parts[1]
+    let month = month_part
         .parse::<u32>()
         .map_err(|_| "Invalid month".to_string())?;
 
@@ -93,6 +244,17 @@ pub fn get_date_suggestions(
     input: &str,
     start_date: NaiveDate,
     current_date: NaiveDate,
+) -> Vec<(String, bool)> {
+    get_date_suggestions_in(input, start_date, current_date, DateOrder::DayMonth)
+}
+
+/// Generates date suggestions like `get_date_suggestions`, with the dates
+/// written in the given order.
+pub fn get_date_suggestions_in(
+    input: &str,
+    start_date: NaiveDate,
+    current_date: NaiveDate,
+    order: DateOrder,
 ) -> Vec<(String, bool)> {
     let mut suggestions = Vec::new();
     let input_lower = input.to_lowercase();
@@ -187,10 +349,12 @@ pub fn get_date_suggestions(
                     } else {
                         num
                     };
-                    let month_str = format!("{month:02}");
-                    let date_str = format!("{day:02}/{month_str}");
+                    let Some(date) = NaiveDate::from_ymd_opt(*year_for_leap, *month, day) else {
+                        continue;
+                    };
+                    let date_str = order.format_short(date);
 
-                    let is_valid = validate_date_input(&date_str, start_date).is_ok();
+                    let is_valid = validate_date_input_in(&date_str, start_date, order).is_ok();
                     if is_valid {
                         suggestions.push((date_str, true));
                     }
@@ -229,9 +393,7 @@ pub fn get_date_suggestions(
         ];
 
         for (date, desc) in top_suggestions {
-            let day = date.format("%d").to_string().parse::<u32>().unwrap();
-            let month = date.format("%m").to_string().parse::<u32>().unwrap();
-            suggestions.push((format!("{desc} ({day:02}/{month:02})"), true));
+            suggestions.push((format!("{desc} ({})", order.format_short(date)), true));
         }
     } else {
         // Common relative dates
@@ -326,9 +488,7 @@ pub fn get_date_suggestions(
                     || possible.contains(&input_lower)
                     || input_lower.contains(possible)
                 {
-                    let day = date.format("%d").to_string().parse::<u32>().unwrap();
-                    let month = date.format("%m").to_string().parse::<u32>().unwrap();
-                    suggestions.push((format!("{desc} ({day:02}/{month:02})"), true));
+                    suggestions.push((format!("{desc} ({})", order.format_short(*date)), true));
                     break; // Only add once per date
                 }
             }
@@ -345,26 +505,35 @@ pub fn get_date_suggestions(
                 || possible.contains(&input_lower)
                 || input_lower.contains(&possible)
             {
-                let day = date.format("%d").to_string().parse::<u32>().unwrap();
-                let month = date.format("%m").to_string().parse::<u32>().unwrap();
-                suggestions.push((format!("Next {weekday} ({day:02}/{month:02})"), true));
+                suggestions.push((
+                    format!("Next {weekday} ({})", order.format_short(*date)),
+                    true,
+                ));
                 break; // Only one weekday suggestion
             }
         }
 
         // Enhanced partial input completion
-        if input.contains('/') {
+        let short = |day: &str, month: &str| match order {
+            DateOrder::MonthDay => format!("{month}/{day}"),
+            _ => format!("{day}/{month}"),
+        };
+        if order != DateOrder::Iso && input.contains('/') {
             let parts: Vec<&str> = input.split('/').collect();
             if parts.len() == 2 {
-                let day_part = parts[0].trim();
-                let month_part = parts[1].trim();
+                let (day_part, month_part) = if order == DateOrder::MonthDay {
+                    (parts[1].trim(), parts[0].trim())
+                } else {
+                    (parts[0].trim(), parts[1].trim())
+                };
                 if !day_part.is_empty() && month_part.is_empty() {
                     // "15/" -> complete with current month
                     if let Ok(day) = day_part.parse::<u32>() {
                         if (1..=31).contains(&day) {
                             let month = start_date.format("%m").to_string();
-                            let date_str = format!("{day:02}/{month}");
-                            let is_valid = validate_date_input(&date_str, start_date).is_ok();
+                            let date_str = short(&format!("{day:02}"), &month);
+                            let is_valid =
+                                validate_date_input_in(&date_str, start_date, order).is_ok();
                             suggestions.push((date_str, is_valid));
                         }
                     }
@@ -373,8 +542,9 @@ pub fn get_date_suggestions(
                     if let Ok(month) = month_part.parse::<u32>() {
                         if (1..=12).contains(&month) {
                             let day = start_date.format("%d").to_string();
-                            let date_str = format!("{day}/{month_part}");
-                            let is_valid = validate_date_input(&date_str, start_date).is_ok();
+                            let date_str = short(&day, month_part);
+                            let is_valid =
+                                validate_date_input_in(&date_str, start_date, order).is_ok();
                             suggestions.push((date_str, is_valid));
                         }
                     }
@@ -387,8 +557,9 @@ pub fn get_date_suggestions(
                         (day_part.parse::<u32>(), month_part.parse::<u32>())
                     {
                         if (1..=31).contains(&day) && (1..=12).contains(&month) {
-                            let date_str = format!("{day:02}/{month:02}");
-                            let is_valid = validate_date_input(&date_str, start_date).is_ok();
+                            let date_str = short(&format!("{day:02}"), &format!("{month:02}"));
+                            let is_valid =
+                                validate_date_input_in(&date_str, start_date, order).is_ok();
                             suggestions.push((date_str, is_valid));
                         }
                     }
@@ -398,23 +569,11 @@ pub fn get_date_suggestions(
 
         // Common date patterns
         if input_lower.contains("last day") || input_lower.contains("lastday") {
-            let day = end_of_month
-                .format("%d")
-                .to_string()
-                .parse::<u32>()
-                .unwrap();
-            let month = end_of_month
-                .format("%m")
-                .to_string()
-                .parse::<u32>()
-                .unwrap();
-            let date_str = format!("Last day of month ({day:02}/{month:02})");
+            let date_str = format!("Last day of month ({})", order.format_short(end_of_month));
             suggestions.push((date_str, true));
         }
         if input_lower.contains("first of next") || input_lower.contains("firstofnext") {
-            let day = next_month.format("%d").to_string().parse::<u32>().unwrap();
-            let month = next_month.format("%m").to_string().parse::<u32>().unwrap();
-            let date_str = format!("First of next month ({day:02}/{month:02})");
+            let date_str = format!("First of next month ({})", order.format_short(next_month));
             suggestions.push((date_str, true));
         }
     }
@@ -462,6 +621,60 @@ mod tests {
         assert!(validate_time_input("   ").is_ok()); // Whitespace is valid
     }
 
+    #[test]
+    fn test_twelve_hour_time_input() {
+        let time = |h, m| Ok(NaiveTime::from_hms_opt(h, m, 0));
+        assert_eq!(parse_time_input("2pm"), time(14, 0));
+        assert_eq!(parse_time_input("2:30 PM"), time(14, 30));
+        assert_eq!(parse_time_input("12am"), time(0, 0));
+        assert_eq!(parse_time_input("12:15 p.m."), time(12, 15));
+        assert_eq!(parse_time_input("09:05"), time(9, 5));
+        assert_eq!(parse_time_input(" "), Ok(None));
+        assert!(parse_time_input("13pm").is_err());
+        assert!(parse_time_input("0am").is_err());
+
+        let afternoon = NaiveTime::from_hms_opt(14, 30, 0).unwrap();
+        assert_eq!(TimeFormat::TwelveHour.format(afternoon), "2:30 PM");
+        assert_eq!(TimeFormat::TwentyFourHour.format(afternoon), "14:30");
+    }
+
+    #[test]
+    fn test_date_input_orders() {
+        let start_date = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let christmas = NaiveDate::from_ymd_opt(2026, 12, 24).unwrap();
+        assert_eq!(
+            validate_date_input_in("12/24", start_date, DateOrder::MonthDay),
+            Ok(christmas)
+        );
+        assert_eq!(
+            validate_date_input_in("2026-12-24", start_date, DateOrder::Iso),
+            Ok(christmas)
+        );
+        // The year is assumed for ISO input without one
+        assert_eq!(
+            validate_date_input_in("01-02", start_date, DateOrder::Iso),
+            Ok(NaiveDate::from_ymd_opt(2027, 1, 2).unwrap())
+        );
+        assert_eq!(
+            validate_date_input_in("24/12", start_date, DateOrder::MonthDay),
+            Err("Month must be between 1 and 12".to_string())
+        );
+        assert_eq!(
+            validate_date_input_in("24/12", start_date, DateOrder::Iso),
+            Err("Invalid format. Use YYYY-MM-DD".to_string())
+        );
+        assert_eq!(DateOrder::MonthDay.format(christmas), "12/24/2026");
+
+        let suggestions =
+            get_date_suggestions_in("tom", start_date, start_date, DateOrder::MonthDay);
+        assert!(suggestions.contains(&("Tomorrow (10/16)".to_string(), true)));
+        let suggestions =
+            get_date_suggestions_in("10/", start_date, start_date, DateOrder::MonthDay);
+        assert!(suggestions.contains(&("10/15".to_string(), true)));
+        let suggestions = get_date_suggestions_in("24", start_date, start_date, DateOrder::Iso);
+        assert_eq!(suggestions[0], ("2026-10-24".to_string(), true));
+    }
+
     #[test]
     fn test_validate_time_input_single_digit() {
         assert!(validate_time_input("0").is_ok());
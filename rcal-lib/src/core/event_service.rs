@@ -397,7 +397,7 @@ mod tests {
 
     #[test]
     fn test_validate_event_invalid_date_range() {
-        let mut event = CalendarEvent {
+        let event = CalendarEvent {
             id: "test".to_string(),
             title: "Test".to_string(),
             description: "".to_string(),
@@ -0,0 +1,292 @@
+//! Free/busy computation.
+//!
+//! Events, with recurring events expanded, are turned into busy periods, and
+//! a `FreeSlotFinder` looks for gaps between them within the working hours
+//! of each day.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::core::EventService;
+use crate::models::CalendarEvent;
+
+/// A period of time, from `start` up to but not including `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeSlot {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl TimeSlot {
+    /// Creates a slot between the given times.
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the length of the slot.
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Returns the period an event occupies.
+///
+/// All-day events take their whole days. Timed events run from their start
+/// to their end time on their last day; without an end time they only
+/// block their start time.
+fn event_period(event: &CalendarEvent) -> TimeSlot {
+    let end_date = event.effective_end_date();
+    if event.is_all_day {
+        return TimeSlot::new(
+            event.start_date.and_time(NaiveTime::MIN),
+            (end_date + Duration::days(1)).and_time(NaiveTime::MIN),
+        );
+    }
+    let start = event.start_date.and_time(event.start_time);
+    let end = end_date.and_time(event.end_time.unwrap_or(event.start_time));
+    TimeSlot::new(start, end.max(start))
+}
+
+/// Joins overlapping or touching periods. The periods must be sorted by
+/// start.
+fn merge(periods: impl IntoIterator<Item = TimeSlot>) -> Vec<TimeSlot> {
+    let mut merged: Vec<TimeSlot> = Vec::new();
+    for period in periods {
+        match merged.last_mut() {
+            Some(last) if period.start <= last.end => last.end = last.end.max(period.end),
+            _ => merged.push(period),
+        }
+    }
+    merged
+}
+
+/// Returns the merged periods the events occupy between the given days,
+/// inclusive, sorted by start. Recurring events are expanded; all-day events
+/// are left out unless `include_all_day` is set.
+pub fn busy_periods(
+    events: &[CalendarEvent],
+    from: NaiveDate,
+    to: NaiveDate,
+    include_all_day: bool,
+) -> Vec<TimeSlot> {
+    let mut periods: Vec<_> = EventService::with_events(events.to_vec())
+        .get_all_events_for_range(from, to)
+        .into_iter()
+        .filter(|event| event.start_date <= to && event.effective_end_date() >= from)
+        .filter(|event| include_all_day || !event.is_all_day)
+        .map(|event| event_period(&event))
+        .collect();
+    periods.sort();
+    merge(periods)
+}
+
+/// Finds free time slots of a minimum length between events.
+#[derive(Debug, Clone)]
+pub struct FreeSlotFinder {
+    duration: Duration,
+    day_start: NaiveTime,
+    /// End of the working hours; `NaiveTime::MIN` stands for midnight at the
+    /// end of the day.
+    day_end: NaiveTime,
+    buffer: Duration,
+    not_before: Option<NaiveDateTime>,
+    include_all_day: bool,
+}
+
+impl FreeSlotFinder {
+    /// Creates a finder for slots of at least `duration` between 09:00 and
+    /// 17:00, treating all-day events as busy.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            day_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or(NaiveTime::MIN),
+            day_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap_or(NaiveTime::MIN),
+            buffer: Duration::zero(),
+            not_before: None,
+            include_all_day: true,
+        }
+    }
+
+    /// Only looks for slots between these times of each day. An end of
+    /// 00:00 stands for midnight at the end of the day.
+    pub fn between_times(mut self, start: NaiveTime, end: NaiveTime) -> Self {
+        self.day_start = start;
+        self.day_end = end;
+        self
+    }
+
+    /// Keeps this much time free before and after every event.
+    pub fn with_buffer(mut self, buffer: Duration) -> Self {
+        self.buffer = buffer;
+        self
+    }
+
+    /// Ignores time before the given moment, such as the current time.
+    pub fn not_before(mut self, moment: NaiveDateTime) -> Self {
+        self.not_before = Some(moment);
+        self
+    }
+
+    /// Sets whether all-day events make their days busy (the default).
+    pub fn include_all_day(mut self, include: bool) -> Self {
+        self.include_all_day = include;
+        self
+    }
+
+    /// Returns the free slots between the given days, inclusive, in order.
+    ///
+    /// Each slot is a whole gap between events within the working hours of a
+    /// day, so it may be longer than the requested duration.
+    pub fn find(&self, events: &[CalendarEvent], from: NaiveDate, to: NaiveDate) -> Vec<TimeSlot> {
+        // Events on the neighbouring days can reach into the range
+        let busy = merge(
+            busy_periods(
+                events,
+                from - Duration::days(1),
+                to + Duration::days(1),
+                self.include_all_day,
+            )
+            .into_iter()
+            .map(|period| TimeSlot::new(period.start - self.buffer, period.end + self.buffer)),
+        );
+
+        let mut slots = Vec::new();
+        for date in from.iter_days().take_while(|date| *date <= to) {
+            let mut cursor = date.and_time(self.day_start);
+            if let Some(not_before) = self.not_before {
+                cursor = cursor.max(not_before);
+            }
+            let window_end = if self.day_end == NaiveTime::MIN {
+                (date + Duration::days(1)).and_time(NaiveTime::MIN)
+            } else {
+                date.and_time(self.day_end)
+            };
+            let mut gaps = Vec::new();
+            for period in &busy {
+                if period.end < cursor {
+                    continue;
+                }
+                if period.start >= window_end {
+                    break;
+                }
+                if period.start > cursor {
+                    gaps.push(TimeSlot::new(cursor, period.start));
+                }
+                cursor = cursor.max(period.end);
+            }
+            if cursor < window_end {
+                gaps.push(TimeSlot::new(cursor, window_end));
+            }
+            slots.extend(
+                gaps.into_iter()
+                    .filter(|gap| gap.duration() >= self.duration),
+            );
+        }
+        slots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Recurrence;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn slot(day: u32, start: (u32, u32), end: (u32, u32)) -> TimeSlot {
+        TimeSlot::new(
+            date(day).and_time(time(start.0, start.1)),
+            date(day).and_time(time(end.0, end.1)),
+        )
+    }
+
+    fn meeting(title: &str, day: u32, start: u32, end: u32) -> CalendarEvent {
+        let mut event =
+            CalendarEvent::new(title.to_string(), String::new(), date(day), time(start, 0));
+        event.end_time = Some(time(end, 0));
+        event
+    }
+
+    #[test]
+    fn test_busy_periods_merge_and_expand() {
+        let mut standup = meeting("Standup", 12, 9, 10);
+        standup.recurrence = Recurrence::Daily;
+        let events = vec![
+            standup,
+            meeting("Review", 19, 9, 11),
+            meeting("Lunch", 19, 11, 12),
+        ];
+        let busy = busy_periods(&events, date(19), date(19), true);
+        assert_eq!(busy, vec![slot(19, (9, 0), (12, 0))]);
+    }
+
+    #[test]
+    fn test_find_free_slots() {
+        let events = vec![meeting("Review", 19, 10, 11), meeting("Lunch", 19, 12, 13)];
+        let finder = FreeSlotFinder::new(Duration::minutes(90));
+        assert_eq!(
+            finder.find(&events, date(19), date(19)),
+            vec![slot(19, (13, 0), (17, 0))]
+        );
+
+        let short = FreeSlotFinder::new(Duration::minutes(60));
+        assert_eq!(
+            short.find(&events, date(19), date(19)),
+            vec![
+                slot(19, (9, 0), (10, 0)),
+                slot(19, (11, 0), (12, 0)),
+                slot(19, (13, 0), (17, 0)),
+            ]
+        );
+
+        // Buffers shrink the gaps around events
+        let buffered = short.clone().with_buffer(Duration::minutes(15));
+        assert_eq!(
+            buffered.find(&events, date(19), date(19)),
+            vec![slot(19, (13, 15), (17, 0))]
+        );
+
+        let later = short.not_before(date(19).and_time(time(14, 30)));
+        assert_eq!(
+            later.find(&events, date(19), date(20)),
+            vec![slot(19, (14, 30), (17, 0)), slot(20, (9, 0), (17, 0))]
+        );
+    }
+
+    #[test]
+    fn test_all_day_and_multi_day_events() {
+        let mut holiday = CalendarEvent::new(
+            "Holiday".to_string(),
+            String::new(),
+            date(20),
+            NaiveTime::MIN,
+        );
+        holiday.is_all_day = true;
+        let mut trip = meeting("Trip", 21, 15, 10);
+        trip.end_date = Some(date(23));
+        let events = vec![holiday, trip];
+
+        let finder = FreeSlotFinder::new(Duration::hours(1));
+        assert_eq!(
+            finder.find(&events, date(20), date(23)),
+            vec![slot(21, (9, 0), (15, 0)), slot(23, (10, 0), (17, 0))]
+        );
+        let ignoring = finder.include_all_day(false);
+        assert_eq!(ignoring.find(&events, date(20), date(20)).len(), 1);
+
+        let evening =
+            FreeSlotFinder::new(Duration::hours(1)).between_times(time(20, 0), NaiveTime::MIN);
+        assert_eq!(
+            evening.find(&events, date(19), date(19)),
+            vec![TimeSlot::new(
+                date(19).and_time(time(20, 0)),
+                date(20).and_time(NaiveTime::MIN)
+            )]
+        );
+    }
+}
@@ -2,6 +2,18 @@
 
 pub mod date_utils;
 pub mod event_service;
+pub mod free_busy;
+pub mod quick_add;
+pub mod search;
 
-pub use date_utils::{get_date_suggestions, validate_date_input, validate_time_input};
+pub use date_utils::{
+    get_date_suggestions, parse_time_input, validate_date_input, validate_time_input, DateOrder,
+    TimeFormat,
+};
 pub use event_service::EventService;
+pub use free_busy::{busy_periods, FreeSlotFinder, TimeSlot};
+pub use quick_add::{
+    parse_date, parse_duration, parse_quick_add, parse_recurrence, parse_time, parse_time_range,
+    QuickAdd,
+};
+pub use search::{EventSearch, SearchError, DEFAULT_SEARCH_FIELDS};
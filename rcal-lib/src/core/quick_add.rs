@@ -0,0 +1,349 @@
+//! Natural-language event input.
+//!
+//! Parses the loose date, time and recurrence expressions accepted by the
+//! command line, and quick-add strings such as
+//! "Lunch with Sam tomorrow 12:30 for 1h" that mix them into the title.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::date_utils::validate_date_input;
+use crate::models::Recurrence;
+
+lazy_static! {
+    static ref CLOCK_PATTERN: Regex = Regex::new(r"^(\d{1,2})(?::(\d{2}))?\s*(am|pm)?$").unwrap();
+    static ref DURATION_PATTERN: Regex =
+        Regex::new(r"^(?:(\d+)\s*h(?:ours?|rs?)?)?\s*(?:(\d+)\s*m(?:in(?:utes?)?)?)?$").unwrap();
+}
+
+/// The parts recognized in a quick-add string.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QuickAdd {
+    /// The words that weren't recognized as a date, time or recurrence.
+    pub title: String,
+    /// The date, if one was given.
+    pub date: Option<NaiveDate>,
+    /// The start time, if one was given.
+    pub start_time: Option<NaiveTime>,
+    /// The end time, from a time range or a duration.
+    pub end_time: Option<NaiveTime>,
+    /// The recurrence, from "every day", "every week" and so on.
+    pub recurrence: Option<Recurrence>,
+}
+
+/// Parses a date relative to `today`.
+///
+/// Accepts "today", "tomorrow", "yesterday", weekday names (the next such
+/// day, optionally prefixed with "next"), "in N days/weeks", `YYYY-MM-DD`
+/// and `DD/MM` (the next such date).
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let input = input.trim().to_lowercase();
+    let words: Vec<&str> = input.split_whitespace().collect();
+    match words.as_slice() {
+        ["today"] => return Ok(today),
+        ["tomorrow"] => return Ok(today + Duration::days(1)),
+        ["yesterday"] => return Ok(today - Duration::days(1)),
+        [day] | ["next", day] if parse_weekday(day).is_some() => {
+            let weekday = parse_weekday(day).unwrap_or(Weekday::Mon);
+            let ahead =
+                (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+            return Ok(today + Duration::days(if ahead == 0 { 7 } else { ahead.into() }));
+        }
+        ["in", count, unit] => {
+            let count: i64 = count
+                .parse()
+                .map_err(|_| format!("Invalid number '{count}'"))?;
+            let days = match unit.trim_end_matches('s') {
+                "day" => Some(count),
+                "week" => count.checked_mul(7),
+                _ => return Err(format!("Unknown unit '{unit}', use days or weeks")),
+            };
+            return days
+                .and_then(Duration::try_days)
+                .and_then(|offset| today.checked_add_signed(offset))
+                .ok_or_else(|| format!("Date 'in {count} {unit}' is out of range"));
+        }
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    if input.contains('/') {
+        return validate_date_input(&input, today);
+    }
+    Err(format!(
+        "Invalid date '{input}'. Use YYYY-MM-DD, DD/MM, today, tomorrow or a weekday"
+    ))
+}
+
+/// Parses a time such as `14:30`, `9`, `2pm` or `12:30am`.
+pub fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    let input = input.trim().to_lowercase();
+    let invalid = || format!("Invalid time '{input}'. Use HH:MM, HH or 2pm");
+    let captures = CLOCK_PATTERN.captures(&input).ok_or_else(invalid)?;
+    let mut hour: u32 = captures[1].parse().map_err(|_| invalid())?;
+    let minute: u32 = captures
+        .get(2)
+        .map_or(Ok(0), |m| m.as_str().parse())
+        .map_err(|_| invalid())?;
+    match captures.get(3).map(|m| m.as_str()) {
+        Some(_) if hour == 0 || hour > 12 => return Err(invalid()),
+        Some("am") if hour == 12 => hour = 0,
+        Some("pm") if hour < 12 => hour += 12,
+        _ => {}
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(invalid)
+}
+
+/// Parses a time or a time range such as `14:00-15:00`.
+pub fn parse_time_range(input: &str) -> Result<(NaiveTime, Option<NaiveTime>), String> {
+    match input.split_once('-') {
+        Some((start, end)) => {
+            let start = parse_time(start)?;
+            let end = parse_time(end)?;
+            if end < start {
+                return Err("End time cannot be before start time".to_string());
+            }
+            Ok((start, Some(end)))
+        }
+        None => Ok((parse_time(input)?, None)),
+    }
+}
+
+/// Parses a duration such as `1h`, `30m`, `1h30m` or `90min`.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase();
+    let captures = DURATION_PATTERN.captures(&input)?;
+    let hours: Option<i64> = captures.get(1).and_then(|m| m.as_str().parse().ok());
+    let minutes: Option<i64> = captures.get(2).and_then(|m| m.as_str().parse().ok());
+    if hours.is_none() && minutes.is_none() {
+        return None;
+    }
+    Some(Duration::hours(hours.unwrap_or(0)) + Duration::minutes(minutes.unwrap_or(0)))
+}
+
+/// Parses a recurrence name: none, daily, weekly, monthly or yearly.
+pub fn parse_recurrence(input: &str) -> Result<Recurrence, String> {
+    match input.trim().to_lowercase().as_str() {
+        "none" | "" => Ok(Recurrence::None),
+        "daily" | "day" => Ok(Recurrence::Daily),
+        "weekly" | "week" => Ok(Recurrence::Weekly),
+        "monthly" | "month" => Ok(Recurrence::Monthly),
+        "yearly" | "year" => Ok(Recurrence::Yearly),
+        other => Err(format!(
+            "Invalid recurrence '{other}'. Use none, daily, weekly, monthly or yearly"
+        )),
+    }
+}
+
+/// Splits a quick-add string into the title and the date, time and
+/// recurrence expressions found in it.
+///
+/// Recognized words are removed from the title; everything else is kept in
+/// order. Bare numbers are only read as times after "at", and weekday
+/// abbreviations only as dates after "on" or "next", so titles like
+/// "Call 3 people" or "Walk in the sun" stay intact.
+pub fn parse_quick_add(input: &str, today: NaiveDate) -> QuickAdd {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let mut result = QuickAdd::default();
+    let mut title = Vec::new();
+    let mut duration = None;
+    let mut i = 0;
+
+    while i < words.len() {
+        let word = words[i];
+        let lower = word.to_lowercase();
+        let next = words.get(i + 1).map(|w| w.to_lowercase());
+
+        // Two-word expressions
+        if let Some(next) = &next {
+            let pair = format!("{lower} {next}");
+            let consumed = match lower.as_str() {
+                "at" if result.start_time.is_none() => parse_time_range(next)
+                    .map(|(start, end)| {
+                        result.start_time = Some(start);
+                        result.end_time = end;
+                    })
+                    .is_ok(),
+                "on" | "next" if result.date.is_none() => {
+                    let date_input = if lower == "on" { next.as_str() } else { &pair };
+                    parse_date(date_input, today)
+                        .map(|date| result.date = Some(date))
+                        .is_ok()
+                }
+                "for" if duration.is_none() => {
+                    parse_duration(next).map(|d| duration = Some(d)).is_some()
+                }
+                "every" if result.recurrence.is_none() => parse_recurrence(next)
+                    .ok()
+                    .filter(|r| *r != Recurrence::None)
+                    .map(|r| result.recurrence = Some(r))
+                    .is_some(),
+                _ => false,
+            };
+            if consumed {
+                i += 2;
+                continue;
+            }
+            if lower == "in" && result.date.is_none() {
+                if let Some(unit) = words.get(i + 2) {
+                    let triple = format!("{pair} {}", unit.to_lowercase());
+                    if let Ok(date) = parse_date(&triple, today) {
+                        result.date = Some(date);
+                        i += 3;
+                        continue;
+                    }
+                }
+            }
+        }
+
+        // Single words: relative dates, weekdays, ISO dates and clock times
+        let is_date_word = matches!(lower.as_str(), "today" | "tomorrow")
+            || is_full_weekday(&lower)
+            || NaiveDate::parse_from_str(&lower, "%Y-%m-%d").is_ok();
+        let is_clock = lower.contains(':') || lower.ends_with("am") || lower.ends_with("pm");
+        if result.date.is_none() && is_date_word {
+            if let Ok(date) = parse_date(&lower, today) {
+                result.date = Some(date);
+                i += 1;
+                continue;
+            }
+        }
+        if result.start_time.is_none() && is_clock {
+            if let Ok((start, end)) = parse_time_range(&lower) {
+                result.start_time = Some(start);
+                result.end_time = end;
+                i += 1;
+                continue;
+            }
+        }
+
+        title.push(word);
+        i += 1;
+    }
+
+    if let (Some(start), Some(duration), None) = (result.start_time, duration, result.end_time) {
+        result.end_time = Some(start + duration);
+    }
+    result.title = title.join(" ");
+    result
+}
+
+/// Returns true for a weekday name that is spelled out, such as "sunday".
+fn is_full_weekday(word: &str) -> bool {
+    word.len() > 3 && parse_weekday(word).is_some()
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Monday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let date = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        assert_eq!(parse_date("today", today()), Ok(date(19)));
+        assert_eq!(parse_date("Tomorrow", today()), Ok(date(20)));
+        assert_eq!(parse_date("wednesday", today()), Ok(date(21)));
+        assert_eq!(parse_date("next monday", today()), Ok(date(26)));
+        assert_eq!(parse_date("in 2 days", today()), Ok(date(21)));
+        assert!(parse_date("in 99999999999999 weeks", today()).is_err());
+        assert_eq!(parse_date("2026-10-25", today()), Ok(date(25)));
+        assert_eq!(parse_date("25/10", today()), Ok(date(25)));
+        assert!(parse_date("someday", today()).is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("14:30"), Ok(time(14, 30)));
+        assert_eq!(parse_time("9"), Ok(time(9, 0)));
+        assert_eq!(parse_time("2pm"), Ok(time(14, 0)));
+        assert_eq!(parse_time("12:15am"), Ok(time(0, 15)));
+        assert!(parse_time("25:00").is_err());
+        assert!(parse_time("13pm").is_err());
+        assert_eq!(
+            parse_time_range("14:00-15:30"),
+            Ok((time(14, 0), Some(time(15, 30))))
+        );
+        assert!(parse_time_range("15:00-14:00").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_and_recurrence() {
+        assert_eq!(parse_duration("1h"), Some(Duration::hours(1)));
+        assert_eq!(parse_duration("90min"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("people"), None);
+        assert_eq!(parse_recurrence("Weekly"), Ok(Recurrence::Weekly));
+        assert!(parse_recurrence("fortnightly").is_err());
+    }
+
+    #[test]
+    fn test_parse_quick_add() {
+        let parsed = parse_quick_add("Lunch with Sam tomorrow 12:30 for 1h", today());
+        assert_eq!(parsed.title, "Lunch with Sam");
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(parsed.start_time, Some(time(12, 30)));
+        assert_eq!(parsed.end_time, Some(time(13, 30)));
+
+        let parsed = parse_quick_add("Standup every day at 9 on 2026-11-02", today());
+        assert_eq!(parsed.title, "Standup");
+        assert_eq!(parsed.recurrence, Some(Recurrence::Daily));
+        assert_eq!(parsed.start_time, Some(time(9, 0)));
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2026, 11, 2));
+    }
+
+    #[test]
+    fn test_parse_quick_add_weekday_abbreviations() {
+        let parsed = parse_quick_add("Walk in the sun", today());
+        assert_eq!(parsed.title, "Walk in the sun");
+        assert_eq!(parsed.date, None);
+
+        let parsed = parse_quick_add("Brunch on sun", today());
+        assert_eq!(parsed.title, "Brunch");
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2026, 10, 25));
+
+        let parsed = parse_quick_add("Market next sat", today());
+        assert_eq!(parsed.title, "Market");
+        assert!(parsed.date.is_some());
+
+        let parsed = parse_quick_add("Yoga sunday", today());
+        assert_eq!(parsed.title, "Yoga");
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2026, 10, 25));
+    }
+
+    #[test]
+    fn test_parse_quick_add_keeps_plain_words() {
+        let parsed = parse_quick_add("Call 3 people for feedback", today());
+        assert_eq!(parsed.title, "Call 3 people for feedback");
+        assert_eq!(
+            parsed,
+            QuickAdd {
+                title: parsed.title.clone(),
+                ..QuickAdd::default()
+            }
+        );
+    }
+}
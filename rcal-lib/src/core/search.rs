@@ -0,0 +1,200 @@
+//! Full-text search over calendar events.
+//!
+//! An `EventSearch` matches a case-insensitive substring or regular
+//! expression against the text fields of events, optionally limited to a
+//! date range.
+
+use chrono::NaiveDate;
+use regex::{Regex, RegexBuilder};
+use thiserror::Error;
+
+use crate::core::EventService;
+use crate::models::{CalendarEvent, EventField};
+
+/// The fields searched unless `EventSearch::with_fields` picks others.
+pub const DEFAULT_SEARCH_FIELDS: [EventField; 2] = [EventField::Title, EventField::Description];
+
+/// Errors building a search.
+#[derive(Error, Debug)]
+pub enum SearchError {
+    /// The pattern is not a valid regular expression.
+    #[error("invalid regular expression: {0}")]
+    InvalidRegex(#[from] regex::Error),
+}
+
+/// How the search text is matched.
+#[derive(Debug, Clone)]
+enum Matcher {
+    /// Lowercased text searched as a substring of the lowercased field.
+    Substring(String),
+    Regex(Regex),
+}
+
+/// A search over the text of calendar events.
+#[derive(Debug, Clone)]
+pub struct EventSearch {
+    matcher: Matcher,
+    fields: Vec<EventField>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl EventSearch {
+    /// Creates a search for events containing the text, ignoring case.
+    pub fn new(text: &str) -> Self {
+        Self {
+            matcher: Matcher::Substring(text.to_lowercase()),
+            fields: DEFAULT_SEARCH_FIELDS.to_vec(),
+            from: None,
+            to: None,
+        }
+    }
+
+    /// Creates a search for events matching the regular expression,
+    /// ignoring case.
+    pub fn regex(pattern: &str) -> Result<Self, SearchError> {
+        let regex = RegexBuilder::new(pattern).case_insensitive(true).build()?;
+        Ok(Self {
+            matcher: Matcher::Regex(regex),
+            ..Self::new("")
+        })
+    }
+
+    /// Searches the given fields instead of the title and description.
+    pub fn with_fields(mut self, fields: &[EventField]) -> Self {
+        self.fields = fields.to_vec();
+        self
+    }
+
+    /// Only finds events occurring between the given days, inclusive. Either
+    /// bound may be left open.
+    pub fn between(mut self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    /// Returns true if one of the searched fields of the event matches,
+    /// regardless of the date range.
+    pub fn matches(&self, event: &CalendarEvent) -> bool {
+        self.fields.iter().any(|field| {
+            let value = field.display(event);
+            match &self.matcher {
+                Matcher::Substring(text) => value.to_lowercase().contains(text),
+                Matcher::Regex(regex) => regex.is_match(&value),
+            }
+        })
+    }
+
+    /// Returns the matching events in the date range, sorted by start.
+    ///
+    /// With both bounds set, recurring events are expanded and every
+    /// occurrence in the range is returned. Otherwise the stored events are
+    /// returned, a recurring event counting as occurring from its start date
+    /// on.
+    pub fn search(&self, events: &[CalendarEvent]) -> Vec<CalendarEvent> {
+        let matching: Vec<_> = events
+            .iter()
+            .filter(|event| self.matches(event))
+            .cloned()
+            .collect();
+        let mut results: Vec<_> = match (self.from, self.to) {
+            (Some(from), Some(to)) => EventService::with_events(matching)
+                .get_all_events_for_range(from, to)
+                .into_iter()
+                .filter(|event| event.start_date <= to && event.effective_end_date() >= from)
+                .collect(),
+            (from, to) => matching
+                .into_iter()
+                .filter(|event| {
+                    let ends_after_from = from.is_none_or(|from| {
+                        event.recurrence.is_recurring() || event.effective_end_date() >= from
+                    });
+                    ends_after_from && to.is_none_or(|to| event.start_date <= to)
+                })
+                .collect(),
+        };
+        results.sort_by(|a, b| {
+            (a.start_date, a.start_time, &a.title).cmp(&(b.start_date, b.start_time, &b.title))
+        });
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Recurrence;
+    use chrono::NaiveTime;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn event(title: &str, description: &str, start: NaiveDate) -> CalendarEvent {
+        CalendarEvent::new(
+            title.to_string(),
+            description.to_string(),
+            start,
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        )
+    }
+
+    fn events() -> Vec<CalendarEvent> {
+        let mut yoga = event("Yoga", "Bring the mat", date(1, 5));
+        yoga.recurrence = Recurrence::Weekly;
+        vec![
+            event("Dentist", "Dr. Molar", date(3, 2)),
+            event("dentist checkup", "", date(9, 14)),
+            event("Lunch", "with the DENTIST", date(6, 1)),
+            yoga,
+        ]
+    }
+
+    fn titles(events: &[CalendarEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_substring_search() {
+        let results = EventSearch::new("Dentist").search(&events());
+        assert_eq!(
+            titles(&results),
+            vec!["Dentist", "Lunch", "dentist checkup"]
+        );
+
+        let titles_only = EventSearch::new("dentist")
+            .with_fields(&[EventField::Title])
+            .search(&events());
+        assert_eq!(titles_only.len(), 2);
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let results = EventSearch::regex(r"^dentist$").unwrap().search(&events());
+        assert_eq!(titles(&results), vec!["Dentist"]);
+        assert!(matches!(
+            EventSearch::regex("(unclosed"),
+            Err(SearchError::InvalidRegex(_))
+        ));
+    }
+
+    #[test]
+    fn test_search_in_range() {
+        let after_march = EventSearch::new("dentist").between(Some(date(4, 1)), None);
+        assert_eq!(
+            titles(&after_march.search(&events())),
+            vec!["Lunch", "dentist checkup"]
+        );
+        let until_march = EventSearch::new("dentist").between(None, Some(date(3, 31)));
+        assert_eq!(titles(&until_march.search(&events())), vec!["Dentist"]);
+
+        // Bounded ranges expand recurring events into their occurrences
+        let february = EventSearch::new("mat").between(Some(date(2, 1)), Some(date(2, 28)));
+        let results = february.search(&events());
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|e| e.is_recurring_instance));
+        let open = EventSearch::new("mat").between(Some(date(2, 1)), None);
+        assert_eq!(open.search(&events()).len(), 1);
+    }
+}
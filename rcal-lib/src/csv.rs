@@ -0,0 +1,625 @@
+//! CSV import and export of calendar events.
+//!
+//! Spreadsheets and HR holiday lists rarely agree on column names or date
+//! formats, so a `ColumnMapping` tells which column holds which field (or
+//! finds them by common header names) and the date format is detected from
+//! the values. Every row is validated on its own, so one bad row doesn't stop
+//! the import of the others.
+//!
+//! `events_to_csv` writes the columns `CsvImporter` reads by default, so
+//! exported calendars can be imported again.
+
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveTime};
+use thiserror::Error;
+
+use crate::core::quick_add::{parse_recurrence, parse_time};
+use crate::models::CalendarEvent;
+use crate::validation::validate_event_with_details;
+
+/// Header of the CSV written by `events_to_csv`.
+pub const CSV_HEADER: [&str; 9] = [
+    "id",
+    "date",
+    "end_date",
+    "start_time",
+    "end_time",
+    "all_day",
+    "title",
+    "description",
+    "recurrence",
+];
+
+/// Date formats tried, in order, when none is given. Day-first formats come
+/// before month-first ones, as elsewhere in rcal.
+pub const DATE_FORMATS: [&str; 10] = [
+    "%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y", "%d.%m.%Y", "%d-%m-%Y", "%m-%d-%Y", "%Y/%m/%d", "%Y%m%d",
+    "%d %b %Y", "%b %d %Y",
+];
+
+/// Errors that stop a whole CSV import.
+#[derive(Debug, Error, PartialEq)]
+pub enum CsvError {
+    /// The input has no header row.
+    #[error("the CSV file is empty")]
+    Empty,
+
+    /// A quoted field isn't closed before the end of the input.
+    #[error("line {0}: unterminated quoted field")]
+    UnterminatedQuote(usize),
+
+    /// A column of the mapping, or a required field, isn't in the header.
+    #[error("no column for {field} (looked for {looked_for})")]
+    MissingColumn {
+        /// The field without a column.
+        field: CsvField,
+        /// The column names or numbers looked for.
+        looked_for: String,
+    },
+
+    /// A column mapping can't be parsed.
+    #[error("invalid column mapping '{0}', use field=column")]
+    InvalidMapping(String),
+
+    /// No date format matches all dates of the file.
+    #[error("could not detect the date format of '{0}'; give one, e.g. %d.%m.%Y")]
+    UnknownDateFormat(String),
+}
+
+/// Event fields a CSV column can be mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsvField {
+    Title,
+    Date,
+    EndDate,
+    StartTime,
+    EndTime,
+    AllDay,
+    Description,
+    Recurrence,
+}
+
+impl CsvField {
+    /// All fields, in the order of `CSV_HEADER`.
+    pub const ALL: [CsvField; 8] = [
+        CsvField::Date,
+        CsvField::EndDate,
+        CsvField::StartTime,
+        CsvField::EndTime,
+        CsvField::AllDay,
+        CsvField::Title,
+        CsvField::Description,
+        CsvField::Recurrence,
+    ];
+
+    /// Returns the name used in mappings and in the exported header.
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvField::Title => "title",
+            CsvField::Date => "date",
+            CsvField::EndDate => "end_date",
+            CsvField::StartTime => "start_time",
+            CsvField::EndTime => "end_time",
+            CsvField::AllDay => "all_day",
+            CsvField::Description => "description",
+            CsvField::Recurrence => "recurrence",
+        }
+    }
+
+    /// Parses a field name as written in a mapping.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace([' ', '-'], "_");
+        Self::ALL.into_iter().find(|field| {
+            field.name() == name || (*field == CsvField::Date && name == "start_date")
+        })
+    }
+
+    /// Header names recognized without a mapping, lowercased.
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            CsvField::Title => &["title", "summary", "subject", "name", "event", "holiday"],
+            CsvField::Date => &["date", "start_date", "start date", "day", "start"],
+            CsvField::EndDate => &["end_date", "end date", "until", "end"],
+            CsvField::StartTime => &["start_time", "start time", "time", "from"],
+            CsvField::EndTime => &["end_time", "end time", "to"],
+            CsvField::AllDay => &["all_day", "all day", "all-day", "allday"],
+            CsvField::Description => &["description", "notes", "note", "details", "comment"],
+            CsvField::Recurrence => &["recurrence", "repeat", "repeats", "frequency"],
+        }
+    }
+}
+
+impl std::fmt::Display for CsvField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which column holds which field.
+///
+/// Columns are named by their header, ignoring case, or by their 1-based
+/// number. Fields without a column in the mapping are looked up by common
+/// header names; title and date are required.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnMapping {
+    columns: Vec<(CsvField, String)>,
+}
+
+impl ColumnMapping {
+    /// Creates a mapping that finds every column by its header name.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the field from the given column.
+    pub fn with_column(mut self, field: CsvField, column: &str) -> Self {
+        self.columns.retain(|(f, _)| *f != field);
+        self.columns.push((field, column.trim().to_string()));
+        self
+    }
+
+    /// Parses a mapping such as `title=Holiday,date=Day,description=3`.
+    pub fn parse(spec: &str) -> Result<Self, CsvError> {
+        let mut mapping = Self::new();
+        for pair in spec.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (field, column) = pair
+                .split_once('=')
+                .ok_or_else(|| CsvError::InvalidMapping(pair.to_string()))?;
+            let field = CsvField::from_name(field)
+                .ok_or_else(|| CsvError::InvalidMapping(pair.to_string()))?;
+            mapping = mapping.with_column(field, column);
+        }
+        Ok(mapping)
+    }
+
+    /// Returns the column index of each field found in the header.
+    fn resolve(&self, header: &[String]) -> Result<HashMap<CsvField, usize>, CsvError> {
+        let lowered: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+        let mut indices = HashMap::new();
+        for field in CsvField::ALL {
+            let explicit = self.columns.iter().find(|(f, _)| *f == field);
+            let index = match explicit {
+                Some((_, column)) => {
+                    let by_number = column
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| (1..=header.len()).contains(n))
+                        .map(|n| n - 1);
+                    let index = by_number
+                        .or_else(|| lowered.iter().position(|h| *h == column.to_lowercase()));
+                    Some(index.ok_or_else(|| CsvError::MissingColumn {
+                        field,
+                        looked_for: format!("'{column}'"),
+                    })?)
+                }
+                None => field
+                    .aliases()
+                    .iter()
+                    .find_map(|alias| lowered.iter().position(|h| h == alias)),
+            };
+            if let Some(index) = index {
+                indices.insert(field, index);
+            }
+        }
+        for field in [CsvField::Title, CsvField::Date] {
+            if !indices.contains_key(&field) {
+                return Err(CsvError::MissingColumn {
+                    field,
+                    looked_for: field.aliases().join(", "),
+                });
+            }
+        }
+        Ok(indices)
+    }
+}
+
+/// A row that couldn't be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRowError {
+    /// Line of the row in the input, starting at 1 for the header.
+    pub line: usize,
+    /// What is wrong with the row.
+    pub messages: Vec<String>,
+}
+
+/// The outcome of parsing a CSV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvImport {
+    /// The valid events with the line they were read from.
+    pub events: Vec<(usize, CalendarEvent)>,
+    /// The rows that were rejected.
+    pub errors: Vec<CsvRowError>,
+    /// The date format used, given or detected.
+    pub date_format: String,
+}
+
+/// Reads calendar events from CSV.
+#[derive(Debug, Clone, Default)]
+pub struct CsvImporter {
+    mapping: ColumnMapping,
+    date_format: Option<String>,
+    delimiter: Option<char>,
+}
+
+impl CsvImporter {
+    /// Creates an importer finding columns by header name, detecting the
+    /// date format and the delimiter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses the given column mapping.
+    pub fn with_mapping(mut self, mapping: ColumnMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Parses dates with the given chrono format instead of detecting it.
+    pub fn with_date_format(mut self, format: &str) -> Self {
+        self.date_format = Some(format.to_string());
+        self
+    }
+
+    /// Splits fields at the given character instead of detecting `,`, `;`
+    /// or tab from the header.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Parses the input into events, validating each row.
+    pub fn parse(&self, input: &str) -> Result<CsvImport, CsvError> {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let delimiter = self.delimiter.unwrap_or_else(|| detect_delimiter(input));
+        let mut records = read_records(input, delimiter)?.into_iter();
+        let (_, header) = records.next().ok_or(CsvError::Empty)?;
+        let columns = self.mapping.resolve(&header)?;
+        let records: Vec<_> = records
+            .filter(|(_, record)| record.iter().any(|value| !value.trim().is_empty()))
+            .collect();
+
+        let value = |record: &[String], field: CsvField| -> String {
+            columns
+                .get(&field)
+                .and_then(|&index| record.get(index))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let date_format = match &self.date_format {
+            Some(format) => format.clone(),
+            None => {
+                let dates: Vec<String> = records
+                    .iter()
+                    .flat_map(|(_, record)| {
+                        [
+                            value(record, CsvField::Date),
+                            value(record, CsvField::EndDate),
+                        ]
+                    })
+                    .filter(|date| !date.is_empty())
+                    .collect();
+                detect_date_format(&dates)?.to_string()
+            }
+        };
+
+        let mut import = CsvImport {
+            events: Vec::new(),
+            errors: Vec::new(),
+            date_format: date_format.clone(),
+        };
+        for (line, record) in records {
+            let get = |field| value(&record, field);
+            match build_event(get, &date_format) {
+                Ok(event) => import.events.push((line, event)),
+                Err(messages) => import.errors.push(CsvRowError { line, messages }),
+            }
+        }
+        Ok(import)
+    }
+}
+
+/// Builds and validates the event of one row.
+fn build_event(
+    get: impl Fn(CsvField) -> String,
+    date_format: &str,
+) -> Result<CalendarEvent, Vec<String>> {
+    let mut messages = Vec::new();
+    let parse_date = |value: &str| NaiveDate::parse_from_str(value, date_format);
+    let parse_clock =
+        |value: &str| NaiveTime::parse_from_str(value, "%H:%M:%S").or_else(|_| parse_time(value));
+
+    let title = get(CsvField::Title);
+    if title.is_empty() {
+        messages.push("title is empty".to_string());
+    }
+    let date = match get(CsvField::Date) {
+        value if value.is_empty() => {
+            messages.push("date is empty".to_string());
+            None
+        }
+        value => parse_date(&value)
+            .map_err(|_| messages.push(format!("invalid date '{value}'")))
+            .ok(),
+    };
+    let end_date = match get(CsvField::EndDate) {
+        value if value.is_empty() => None,
+        value => parse_date(&value)
+            .map_err(|_| messages.push(format!("invalid end date '{value}'")))
+            .ok(),
+    };
+    let all_day = match get(CsvField::AllDay).to_lowercase().as_str() {
+        "" | "false" | "no" | "n" | "0" => false,
+        "true" | "yes" | "y" | "1" | "x" => true,
+        other => {
+            messages.push(format!("invalid all-day flag '{other}'"));
+            false
+        }
+    };
+    let start_time = match get(CsvField::StartTime) {
+        value if value.is_empty() || all_day => None,
+        value => parse_clock(&value)
+            .map_err(|_| messages.push(format!("invalid start time '{value}'")))
+            .ok(),
+    };
+    let end_time = match get(CsvField::EndTime) {
+        value if value.is_empty() || all_day => None,
+        value => parse_clock(&value)
+            .map_err(|_| messages.push(format!("invalid end time '{value}'")))
+            .ok(),
+    };
+    let recurrence = parse_recurrence(&get(CsvField::Recurrence))
+        .map_err(|e| messages.push(e))
+        .unwrap_or_default();
+
+    let Some(date) = date.filter(|_| messages.is_empty()) else {
+        return Err(messages);
+    };
+    let mut event = CalendarEvent::new(
+        title,
+        get(CsvField::Description),
+        date,
+        start_time.unwrap_or(NaiveTime::MIN),
+    );
+    event.end_date = end_date;
+    event.is_all_day = start_time.is_none();
+    event.end_time = end_time.filter(|_| !event.is_all_day);
+    event.recurrence = recurrence;
+    validate_event_with_details(&event)
+        .map_err(|errors| errors.iter().map(ToString::to_string).collect::<Vec<_>>())?;
+    Ok(event)
+}
+
+/// Returns the one of `DATE_FORMATS` that parses the most dates, the first
+/// one on a tie, so a few malformed dates are reported as row errors.
+pub fn detect_date_format(dates: &[String]) -> Result<&'static str, CsvError> {
+    let parsed = |format: &str| {
+        dates
+            .iter()
+            .filter(|date| NaiveDate::parse_from_str(date, format).is_ok())
+            .count()
+    };
+    let mut best = (DATE_FORMATS[0], parsed(DATE_FORMATS[0]));
+    for format in &DATE_FORMATS[1..] {
+        let count = parsed(format);
+        if count > best.1 {
+            best = (format, count);
+        }
+    }
+    match (best, dates.first()) {
+        ((_, 0), Some(date)) => Err(CsvError::UnknownDateFormat(date.clone())),
+        ((format, _), _) => Ok(format),
+    }
+}
+
+/// Picks the most frequent of `,`, `;` and tab in the first line.
+fn detect_delimiter(input: &str) -> char {
+    let header = input.lines().next().unwrap_or_default();
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|delimiter| (header.matches(*delimiter).count(), *delimiter == ','))
+        .unwrap_or(',')
+}
+
+/// Splits the input into records, with the line each record starts on.
+/// Quoted fields may contain delimiters, doubled quotes and line breaks.
+fn read_records(input: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            '\n' if in_quotes => {
+                field.push(c);
+                line += 1;
+            }
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            c if c == delimiter && !in_quotes => record.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(CsvError::UnterminatedQuote(record_line));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    Ok(records)
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+pub fn quote_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes the events as CSV with the columns of `CSV_HEADER`. Times are
+/// empty for all-day events.
+pub fn events_to_csv(events: &[CalendarEvent]) -> String {
+    let mut output = CSV_HEADER.join(",") + "\n";
+    for event in events {
+        let time = |time: Option<NaiveTime>| {
+            time.filter(|_| !event.is_all_day)
+                .map(|time| time.format("%H:%M").to_string())
+                .unwrap_or_default()
+        };
+        let fields = [
+            event.stable_id(),
+            event.start_date.to_string(),
+            event.effective_end_date().to_string(),
+            time(Some(event.start_time)),
+            time(event.end_time),
+            event.is_all_day.to_string(),
+            event.title.clone(),
+            event.description.clone(),
+            event.recurrence.to_storage_string().to_string(),
+        ];
+        let row: Vec<_> = fields.iter().map(|field| quote_field(field)).collect();
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Recurrence;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_read_records_with_quotes() {
+        let records = read_records("a,b\r\n\"x, \"\"y\"\"\",\"two\nlines\"\n3,4", ',').unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (2, vec!["x, \"y\"".to_string(), "two\nlines".to_string()]),
+                (4, vec!["3".to_string(), "4".to_string()]),
+            ]
+        );
+        assert_eq!(
+            read_records("a\n\"open", ','),
+            Err(CsvError::UnterminatedQuote(2))
+        );
+    }
+
+    #[test]
+    fn test_import_with_mapping_and_detected_format() {
+        let input = "Holiday;Day;Notes\n\
+                     New Year;01.01.2026;Public holiday\n\
+                     Labour Day;01.05.2026;\n\
+                     ;25.12.2026;no title\n\
+                     Boxing Day;26.13.2026;\n";
+        let mapping = ColumnMapping::parse("title=Holiday, date=day, description=3").unwrap();
+        let import = CsvImporter::new()
+            .with_mapping(mapping)
+            .parse(input)
+            .unwrap();
+        assert_eq!(import.date_format, "%d.%m.%Y");
+        let titles: Vec<_> = import
+            .events
+            .iter()
+            .map(|(_, e)| e.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["New Year", "Labour Day"]);
+        let (line, new_year) = &import.events[0];
+        assert_eq!(*line, 2);
+        assert!(new_year.is_all_day);
+        assert_eq!(new_year.description, "Public holiday");
+        assert_eq!(
+            import.errors,
+            vec![
+                CsvRowError {
+                    line: 4,
+                    messages: vec!["title is empty".to_string()],
+                },
+                CsvRowError {
+                    line: 5,
+                    messages: vec!["invalid date '26.13.2026'".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_date_format() {
+        let dates = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            detect_date_format(&dates(&["03/04/2026"])).unwrap(),
+            "%d/%m/%Y"
+        );
+        assert_eq!(
+            detect_date_format(&dates(&["03/04/2026", "04/30/2026"])).unwrap(),
+            "%m/%d/%Y"
+        );
+        assert!(matches!(
+            detect_date_format(&dates(&["soon", "2026-01-01"])),
+            Ok("%Y-%m-%d")
+        ));
+        assert!(matches!(
+            detect_date_format(&dates(&["soon"])),
+            Err(CsvError::UnknownDateFormat(date)) if date == "soon"
+        ));
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let mut standup = CalendarEvent::new(
+            "Standup, daily".to_string(),
+            "Say \"hi\"".to_string(),
+            date(10, 19),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        );
+        standup.end_time = NaiveTime::from_hms_opt(9, 15, 0);
+        standup.recurrence = Recurrence::Weekly;
+        let mut trip = CalendarEvent::new(
+            "Trip".to_string(),
+            String::new(),
+            date(11, 2),
+            NaiveTime::MIN,
+        );
+        trip.is_all_day = true;
+        trip.end_date = Some(date(11, 4));
+
+        let csv = events_to_csv(&[standup.clone(), trip.clone()]);
+        assert!(csv.starts_with("id,date,end_date,start_time,end_time,all_day,title"));
+        let import = CsvImporter::new().parse(&csv).unwrap();
+        assert!(import.errors.is_empty());
+        let imported: Vec<_> = import.events.into_iter().map(|(_, e)| e).collect();
+        assert_eq!(imported.len(), 2);
+        for (imported, original) in imported.iter().zip([&standup, &trip]) {
+            assert_eq!(imported.title, original.title);
+            assert_eq!(imported.description, original.description);
+            assert_eq!(imported.start_date, original.start_date);
+            assert_eq!(imported.effective_end_date(), original.effective_end_date());
+            assert_eq!(imported.start_time, original.start_time);
+            assert_eq!(imported.end_time, original.end_time);
+            assert_eq!(imported.is_all_day, original.is_all_day);
+            assert_eq!(imported.recurrence, original.recurrence);
+        }
+    }
+}
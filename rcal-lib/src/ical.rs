@@ -0,0 +1,537 @@
+//! iCalendar (RFC 5545) conversion for calendar events.
+//!
+//! Maps `CalendarEvent` to and from `VEVENT` components so events can be
+//! exchanged with CalDAV servers and other calendar applications.
+//!
+//! Times are written as floating local times. When parsing, UTC times are
+//! converted to local time and `TZID` parameters are treated as local time.
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use thiserror::Error;
+
+use crate::models::{CalendarEvent, Recurrence};
+
+/// Product identifier written into generated calendars.
+const PRODID: &str = "-//rcal//rcal-lib//EN";
+
+/// Maximum line length in octets before folding (RFC 5545 section 3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Errors that can occur while parsing iCalendar data.
+#[derive(Debug, Error, PartialEq)]
+pub enum IcalError {
+    /// A VEVENT is missing a required property.
+    #[error("VEVENT is missing required property {0}")]
+    MissingProperty(&'static str),
+
+    /// A property has a value that cannot be parsed.
+    #[error("Invalid value for {property}: {value}")]
+    InvalidValue {
+        /// The property name.
+        property: String,
+        /// The offending value.
+        value: String,
+    },
+}
+
+/// A VEVENT parsed from iCalendar data, together with its UID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcalEvent {
+    /// The UID of the VEVENT.
+    pub uid: String,
+    /// The event converted to the library's model.
+    pub event: CalendarEvent,
+}
+
+/// Serializes an event as a complete VCALENDAR object with a single VEVENT.
+pub fn event_to_ical(event: &CalendarEvent, uid: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODID}"),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", escape_text(uid)),
+        format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")),
+    ];
+
+    if event.is_all_day {
+        // DTEND is exclusive for all-day events
+        let end = event.effective_end_date() + Duration::days(1);
+        lines.push(format!(
+            "DTSTART;VALUE=DATE:{}",
+            event.start_date.format("%Y%m%d")
+        ));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+    } else {
+        let start = event.start_date.and_time(event.start_time);
+        lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+        let end_date = event.effective_end_date();
+        let end_time = event.end_time.unwrap_or(event.start_time);
+        let end = end_date.and_time(end_time);
+        if end > start {
+            lines.push(format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")));
+        }
+    }
+
+    lines.push(format!("SUMMARY:{}", escape_text(&event.title)));
+    if !event.description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+    }
+    if let Some(freq) = recurrence_to_freq(&event.recurrence) {
+        lines.push(format!("RRULE:FREQ={freq}"));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&fold_line(&line));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+/// Parses all VEVENT components from iCalendar data.
+///
+/// Components other than VEVENT (e.g. VTODO, VTIMEZONE, VALARM) are ignored,
+/// as are VEVENTs carrying a `RECURRENCE-ID` since overridden instances of a
+/// series cannot be represented.
+pub fn parse_ical(input: &str) -> Result<Vec<IcalEvent>, IcalError> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    let mut nested_depth = 0;
+
+    for line in unfold(input) {
+        let Some(property) = Property::parse(&line) else {
+            continue;
+        };
+        match (
+            property.name.as_str(),
+            property.value.to_uppercase().as_str(),
+        ) {
+            ("BEGIN", "VEVENT") if current.is_none() => current = Some(Vec::new()),
+            ("BEGIN", _) if current.is_some() => nested_depth += 1,
+            ("END", "VEVENT") if nested_depth == 0 => {
+                if let Some(properties) = current.take() {
+                    if let Some(event) = build_event(&properties)? {
+                        events.push(event);
+                    }
+                }
+            }
+            ("END", _) if nested_depth > 0 => nested_depth -= 1,
+            _ => {
+                if nested_depth == 0 {
+                    if let Some(properties) = current.as_mut() {
+                        properties.push(property);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// A single content line split into name, parameters and value.
+#[derive(Debug)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        // The value starts after the first colon that is not inside a quoted parameter
+        let mut in_quotes = false;
+        let mut split_at = None;
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ':' if !in_quotes => {
+                    split_at = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let split_at = split_at?;
+        let (head, value) = (&line[..split_at], &line[split_at + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim().to_uppercase();
+        let params = parts
+            .filter_map(|p| {
+                let (key, val) = p.split_once('=')?;
+                Some((key.to_uppercase(), val.trim_matches('"').to_string()))
+            })
+            .collect();
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A parsed DATE or DATE-TIME value.
+enum DateValue {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+fn build_event(properties: &[Property]) -> Result<Option<IcalEvent>, IcalError> {
+    let find = |name: &str| properties.iter().find(|p| p.name == name);
+
+    if find("RECURRENCE-ID").is_some() {
+        return Ok(None);
+    }
+
+    let uid = find("UID")
+        .map(|p| unescape_text(&p.value))
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let title = find("SUMMARY")
+        .map(|p| unescape_text(&p.value))
+        .unwrap_or_default();
+    let description = find("DESCRIPTION")
+        .map(|p| unescape_text(&p.value))
+        .unwrap_or_default();
+    let recurrence = find("RRULE")
+        .map(|p| parse_rrule(&p.value))
+        .unwrap_or_default();
+
+    let start = parse_date_value(find("DTSTART").ok_or(IcalError::MissingProperty("DTSTART"))?)?;
+    let end = match find("DTEND") {
+        Some(p) => Some(parse_date_value(p)?),
+        None => None,
+    };
+    let duration = match find("DURATION") {
+        Some(p) => Some(
+            parse_duration(&p.value).ok_or_else(|| IcalError::InvalidValue {
+                property: "DURATION".to_string(),
+                value: p.value.clone(),
+            })?,
+        ),
+        None => None,
+    };
+
+    let mut event = CalendarEvent::new(
+        title,
+        description,
+        NaiveDate::MIN,
+        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+    );
+    event.recurrence = recurrence;
+
+    match start {
+        DateValue::Date(start_date) => {
+            let exclusive_end = match (end, duration) {
+                (Some(DateValue::Date(d)), _) => d,
+                (Some(DateValue::DateTime(dt)), _) => dt.date(),
+                (None, Some(d)) => start_date + d,
+                (None, None) => start_date + Duration::days(1),
+            };
+            let end_date = (exclusive_end - Duration::days(1)).max(start_date);
+            event.is_all_day = true;
+            event.start_date = start_date;
+            event.end_date = Some(end_date);
+        }
+        DateValue::DateTime(start_dt) => {
+            let end_dt = match (end, duration) {
+                (Some(DateValue::DateTime(dt)), _) => Some(dt),
+                (Some(DateValue::Date(d)), _) => Some(d.and_time(start_dt.time())),
+                (None, Some(d)) => Some(start_dt + d),
+                (None, None) => None,
+            }
+            .filter(|end| *end > start_dt);
+            event.start_date = start_dt.date();
+            event.start_time = start_dt.time();
+            event.end_date = Some(end_dt.map(|e| e.date()).unwrap_or(start_dt.date()));
+            event.end_time = end_dt.map(|e| e.time());
+        }
+    }
+
+    Ok(Some(IcalEvent { uid, event }))
+}
+
+fn parse_date_value(property: &Property) -> Result<DateValue, IcalError> {
+    let invalid = || IcalError::InvalidValue {
+        property: property.name.clone(),
+        value: property.value.clone(),
+    };
+    let value = property.value.trim();
+
+    if property.param("VALUE") == Some("DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(DateValue::Date)
+            .map_err(|_| invalid());
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        let local = Utc.from_utc_datetime(&naive).with_timezone(&Local);
+        return Ok(DateValue::DateTime(local.naive_local()));
+    }
+
+    // Floating time or time with TZID: both are treated as local time
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map(DateValue::DateTime)
+        .map_err(|_| invalid())
+}
+
+/// Parses an RFC 5545 duration such as `PT1H30M`, `P1D` or `P2W`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (c, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+fn parse_rrule(value: &str) -> Recurrence {
+    value
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .find(|(key, _)| key.eq_ignore_ascii_case("FREQ"))
+        .map(|(_, freq)| match freq.to_uppercase().as_str() {
+            "DAILY" => Recurrence::Daily,
+            "WEEKLY" => Recurrence::Weekly,
+            "MONTHLY" => Recurrence::Monthly,
+            "YEARLY" => Recurrence::Yearly,
+            _ => Recurrence::None,
+        })
+        .unwrap_or_default()
+}
+
+fn recurrence_to_freq(recurrence: &Recurrence) -> Option<&'static str> {
+    match recurrence {
+        Recurrence::None => None,
+        Recurrence::Daily => Some("DAILY"),
+        Recurrence::Weekly => Some("WEEKLY"),
+        Recurrence::Monthly => Some("MONTHLY"),
+        Recurrence::Yearly => Some("YEARLY"),
+    }
+}
+
+/// Escapes a TEXT value (RFC 5545 section 3.3.11).
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverses `escape_text`.
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => unescaped.push('\n'),
+                Some(other) => unescaped.push(other),
+                None => unescaped.push('\\'),
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// Folds a content line so that no physical line exceeds 75 octets.
+fn fold_line(line: &str) -> String {
+    if line.len() <= MAX_LINE_OCTETS {
+        return line.to_string();
+    }
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut current_len = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts towards the limit
+        if current_len + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            current_len = 1;
+        }
+        folded.push(c);
+        current_len += c.len_utf8();
+    }
+    folded
+}
+
+/// Splits input into logical content lines, joining folded continuation lines.
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in input.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(continuation) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timed_event() -> CalendarEvent {
+        let mut event = CalendarEvent::new(
+            "Team Meeting".to_string(),
+            "Agenda: planning, review; notes".to_string(),
+            NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(),
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+        );
+        event.end_date = Some(NaiveDate::from_ymd_opt(2024, 3, 5).unwrap());
+        event.end_time = Some(NaiveTime::from_hms_opt(10, 45, 0).unwrap());
+        event.recurrence = Recurrence::Weekly;
+        event
+    }
+
+    #[test]
+    fn test_event_to_ical_timed_event() {
+        let ical = event_to_ical(&timed_event(), "uid-1");
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.contains("UID:uid-1\r\n"));
+        assert!(ical.contains("DTSTART:20240305T093000\r\n"));
+        assert!(ical.contains("DTEND:20240305T104500\r\n"));
+        assert!(ical.contains("SUMMARY:Team Meeting\r\n"));
+        assert!(ical.contains("DESCRIPTION:Agenda: planning\\, review\\; notes\r\n"));
+        assert!(ical.contains("RRULE:FREQ=WEEKLY\r\n"));
+    }
+
+    #[test]
+    fn test_event_to_ical_all_day_uses_exclusive_end() {
+        let mut event = CalendarEvent::new(
+            "Holiday".to_string(),
+            String::new(),
+            NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+            NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+        );
+        event.is_all_day = true;
+        event.end_date = Some(NaiveDate::from_ymd_opt(2024, 7, 3).unwrap());
+
+        let ical = event_to_ical(&event, "uid-2");
+        assert!(ical.contains("DTSTART;VALUE=DATE:20240701\r\n"));
+        assert!(ical.contains("DTEND;VALUE=DATE:20240704\r\n"));
+    }
+
+    #[test]
+    fn test_round_trip_preserves_fields() {
+        let event = timed_event();
+        let parsed = parse_ical(&event_to_ical(&event, "uid-3")).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].uid, "uid-3");
+        let parsed = &parsed[0].event;
+        assert_eq!(parsed.title, event.title);
+        assert_eq!(parsed.description, event.description);
+        assert_eq!(parsed.start_date, event.start_date);
+        assert_eq!(parsed.start_time, event.start_time);
+        assert_eq!(parsed.end_date, event.end_date);
+        assert_eq!(parsed.end_time, event.end_time);
+        assert_eq!(parsed.recurrence, Recurrence::Weekly);
+        assert!(!parsed.is_all_day);
+    }
+
+    #[test]
+    fn test_parse_all_day_and_duration() {
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a\r\nSUMMARY:Trip\r\n\
+                     DTSTART;VALUE=DATE:20240701\r\nDTEND;VALUE=DATE:20240704\r\nEND:VEVENT\r\n\
+                     BEGIN:VEVENT\r\nUID:b\r\nSUMMARY:Call\r\nDTSTART:20240702T140000\r\n\
+                     DURATION:PT1H30M\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let events = parse_ical(input).unwrap();
+        assert_eq!(events.len(), 2);
+
+        let trip = &events[0].event;
+        assert!(trip.is_all_day);
+        assert_eq!(trip.end_date, NaiveDate::from_ymd_opt(2024, 7, 3));
+
+        let call = &events[1].event;
+        assert_eq!(call.end_time, NaiveTime::from_hms_opt(15, 30, 0));
+    }
+
+    #[test]
+    fn test_parse_skips_alarms_and_overrides() {
+        let input = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:a\nSUMMARY:Main\nDTSTART:20240702T140000\n\
+                     BEGIN:VALARM\nSUMMARY:Alarm\nEND:VALARM\nEND:VEVENT\n\
+                     BEGIN:VEVENT\nUID:a\nRECURRENCE-ID:20240709T140000\nSUMMARY:Moved\n\
+                     DTSTART:20240710T140000\nEND:VEVENT\nEND:VCALENDAR\n";
+        let events = parse_ical(input).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event.title, "Main");
+    }
+
+    #[test]
+    fn test_parse_missing_dtstart() {
+        let input = "BEGIN:VEVENT\nUID:a\nSUMMARY:Broken\nEND:VEVENT\n";
+        assert_eq!(
+            parse_ical(input),
+            Err(IcalError::MissingProperty("DTSTART"))
+        );
+    }
+
+    #[test]
+    fn test_fold_and_unfold_long_lines() {
+        let mut event = timed_event();
+        event.description = "é".repeat(100);
+        let ical = event_to_ical(&event, "uid-4");
+        assert!(ical.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        let parsed = parse_ical(&ical).unwrap();
+        assert_eq!(parsed[0].event.description, event.description);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1D"), Some(Duration::days(1)));
+        assert_eq!(parse_duration("P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_duration("-PT15M"), Some(Duration::minutes(-15)));
+        assert_eq!(parse_duration("1H"), None);
+    }
+}
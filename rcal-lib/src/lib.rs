@@ -7,8 +7,13 @@
 //!
 //! - **Event Management**: Create, update, delete, and query calendar events
 //! - **Recurring Events**: Support for daily, weekly, monthly, and yearly recurrence
+//! - **Search**: Case-insensitive substring or regex search over event text
 //! - **File Storage**: Markdown-based event storage in the file system
 //! - **Git Sync**: Optional Git-based synchronization for calendar sharing
+//!   (libgit2 backend with the `native-git` feature, `git` CLI otherwise)
+//! - **Directory Sync**: Two-way sync with another local folder (Syncthing, USB, NFS)
+//! - **CalDAV Sync**: Two-way sync with CalDAV servers (`caldav` feature)
+//! - **iCalendar**: Conversion between events and iCalendar `VEVENT`s
 //! - **Desktop Notifications**: Optional desktop notification support (Linux with D-Bus)
 //!
 //! ## Usage
@@ -36,12 +41,14 @@
 
 // Re-export models
 pub mod models;
-pub use models::{CalendarEvent, Recurrence, SyncStatus};
+pub use models::{CalendarEvent, EventField, Recurrence, SyncStatus};
 
 // Re-export core
 pub mod core;
 pub use core::{
-    date_utils, get_date_suggestions, validate_date_input, validate_time_input, EventService,
+    busy_periods, date_utils, get_date_suggestions, parse_time_input, validate_date_input,
+    validate_time_input, DateOrder, EventSearch, EventService, FreeSlotFinder, SearchError,
+    TimeFormat, TimeSlot,
 };
 
 // Re-export storage
@@ -52,7 +59,26 @@ pub use storage::{
 
 // Re-export sync
 pub mod sync;
-pub use sync::{GitSyncProvider, SyncProvider};
+#[cfg(feature = "caldav")]
+pub use sync::{CalDavError, CalDavSyncProvider};
+pub use sync::{
+    DirectorySyncProvider, EventRevision, GitSyncProvider, PendingOperation, ResolvedConflict,
+    SyncConflict, SyncJournal, SyncProvider,
+};
+#[cfg(feature = "native-git")]
+pub use sync::{GitError, GitProgress, NativeGitSyncProvider};
+
+// Re-export iCalendar conversion
+pub mod ical;
+pub use ical::{event_to_ical, parse_ical, IcalError, IcalEvent};
+
+// Re-export CSV import and export
+pub mod csv;
+pub use csv::{
+    events_to_csv, ColumnMapping, CsvError, CsvField, CsvImport, CsvImporter, CsvRowError,
+};
+
+mod hash;
 
 // Re-export notifications
 pub mod notifications;
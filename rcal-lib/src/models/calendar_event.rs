@@ -6,6 +6,8 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use crate::hash::fnv1a;
+
 /// Represents the recurrence pattern for a calendar event.
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
 pub enum Recurrence {
@@ -122,6 +124,21 @@ impl CalendarEvent {
             .unwrap_or(false)
     }
 
+    /// Returns an id derived from the title and start date, the key events
+    /// are stored under. Unlike `id`, which is generated on every load, it is
+    /// the same across runs and machines; recurring instances share the id
+    /// of their base event.
+    pub fn stable_id(&self) -> String {
+        // FNV-1a with a MurmurHash3 finalizer, so the id doesn't depend on
+        // the standard library's hasher and similar keys get unrelated ids
+        let date = self.base_date.unwrap_or(self.start_date).to_string();
+        let key = format!("{}\0{date}", self.title);
+        let mut hash = fnv1a(key.as_bytes());
+        hash = (hash ^ (hash >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash = (hash ^ (hash >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        format!("{:08x}", (hash ^ (hash >> 33)) >> 32)
+    }
+
     /// Creates a recurring instance from this base event for a specific date.
     pub fn create_instance(&self, instance_date: NaiveDate) -> Self {
         let duration = self
@@ -231,4 +248,29 @@ mod tests {
             NaiveDate::from_ymd_opt(2024, 1, 22).unwrap()
         );
     }
+
+    #[test]
+    fn test_stable_id() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let mut event = CalendarEvent::new(
+            "Standup".to_string(),
+            String::new(),
+            date,
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        );
+        let id = event.stable_id();
+        // Ids must not change between versions
+        assert_eq!(id, "b7859c13");
+        event.id = "other".to_string();
+        event.description = "Room 2".to_string();
+        assert_eq!(event.stable_id(), id);
+        assert_eq!(
+            event
+                .create_instance(date + chrono::Duration::days(7))
+                .stable_id(),
+            id
+        );
+        event.start_date = date + chrono::Duration::days(1);
+        assert_ne!(event.stable_id(), id);
+    }
 }
@@ -0,0 +1,85 @@
+//! EventField enum naming the user-visible fields of an event.
+
+use super::CalendarEvent;
+
+/// A user-visible field of an event, used to compare two versions of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventField {
+    /// The event title.
+    Title,
+    /// Start and end date.
+    Dates,
+    /// Start time, end time and the all-day flag.
+    Times,
+    /// The description.
+    Description,
+    /// The recurrence pattern.
+    Recurrence,
+}
+
+impl EventField {
+    /// All fields, in display order.
+    pub const ALL: [EventField; 5] = [
+        EventField::Title,
+        EventField::Dates,
+        EventField::Times,
+        EventField::Description,
+        EventField::Recurrence,
+    ];
+
+    /// Returns the label shown next to the field.
+    pub fn label(self) -> &'static str {
+        match self {
+            EventField::Title => "Title",
+            EventField::Dates => "Date",
+            EventField::Times => "Time",
+            EventField::Description => "Description",
+            EventField::Recurrence => "Recurrence",
+        }
+    }
+
+    /// Formats the field of an event for display.
+    pub fn display(self, event: &CalendarEvent) -> String {
+        match self {
+            EventField::Title => event.title.clone(),
+            EventField::Dates => match event.end_date {
+                Some(end) if end != event.start_date => format!("{} to {}", event.start_date, end),
+                _ => event.start_date.to_string(),
+            },
+            EventField::Times if event.is_all_day => "all day".to_string(),
+            EventField::Times => match event.end_time {
+                Some(end) => format!(
+                    "{} to {}",
+                    event.start_time.format("%H:%M"),
+                    end.format("%H:%M")
+                ),
+                None => event.start_time.format("%H:%M").to_string(),
+            },
+            EventField::Description => event.description.clone(),
+            EventField::Recurrence => event.recurrence.to_storage_string().to_string(),
+        }
+    }
+
+    /// Copies the field from one event to another.
+    pub fn copy(self, from: &CalendarEvent, to: &mut CalendarEvent) {
+        match self {
+            EventField::Title => to.title = from.title.clone(),
+            EventField::Dates => {
+                to.start_date = from.start_date;
+                to.end_date = from.end_date;
+            }
+            EventField::Times => {
+                to.start_time = from.start_time;
+                to.end_time = from.end_time;
+                to.is_all_day = from.is_all_day;
+            }
+            EventField::Description => to.description = from.description.clone(),
+            EventField::Recurrence => to.recurrence = from.recurrence.clone(),
+        }
+    }
+
+    /// Returns the position of the field in `ALL`.
+    pub(crate) fn index(self) -> usize {
+        Self::ALL.iter().position(|f| *f == self).unwrap_or(0)
+    }
+}
@@ -1,7 +1,9 @@
 //! Models module - contains core data structures for the calendar library.
 
 pub mod calendar_event;
+pub mod event_field;
 pub mod sync_status;
 
 pub use calendar_event::{CalendarEvent, Recurrence};
+pub use event_field::EventField;
 pub use sync_status::SyncStatus;
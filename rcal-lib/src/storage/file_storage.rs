@@ -12,8 +12,13 @@ use uuid::Uuid;
 
 use crate::models::{CalendarEvent, Recurrence};
 use crate::storage::traits::{CalendarPathProvider, DynSyncProvider, EventRepository};
+use crate::sync::{as_sync_provider, push_with_journal, OperationKind, PendingOperation};
 
-/// Default path provider that uses ~/calendar as the calendar directory.
+/// Environment variable overriding the default calendar directory.
+pub const CALENDAR_DIR_ENV: &str = "RCAL_CALENDAR_DIR";
+
+/// Default path provider that uses the `RCAL_CALENDAR_DIR` environment
+/// variable, or ~/calendar if it isn't set, as the calendar directory.
 pub struct DefaultPathProvider {
     calendar_dir: PathBuf,
 }
@@ -21,6 +26,9 @@ pub struct DefaultPathProvider {
 impl DefaultPathProvider {
     /// Creates a new DefaultPathProvider with the default calendar directory.
     pub fn new() -> Result<Self, Box<dyn Error>> {
+        if let Some(dir) = std::env::var_os(CALENDAR_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            return Ok(Self::with_path(PathBuf::from(dir)));
+        }
         let home = dirs::home_dir().ok_or("Could not find home directory")?;
         Ok(Self {
             calendar_dir: home.join("calendar"),
@@ -35,11 +43,7 @@ impl DefaultPathProvider {
 
 impl Default for DefaultPathProvider {
     fn default() -> Self {
-        Self {
-            calendar_dir: dirs::home_dir()
-                .expect("Could not find home directory")
-                .join("calendar"),
-        }
+        Self::new().expect("Could not find home directory")
     }
 }
 
@@ -91,7 +95,6 @@ impl FileEventRepository {
         for entry in entries {
             let entry = entry?;
             let path = entry.path();
-            let _filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
             if path
                 .file_name()
@@ -100,59 +103,8 @@ impl FileEventRepository {
                 .unwrap_or(false)
             {
                 let content = fs::read_to_string(&path)?;
-                // Parse new format (title-based, no ID in content)
-                let mut title = String::new();
-                let mut start_date = None;
-                let mut end_date = None;
-                let mut start_time = None;
-                let mut end_time = None;
-                let mut description = String::new();
-                let mut recurrence = Recurrence::None;
-                for line in content.lines() {
-                    if let Some(stripped) = line.strip_prefix("# Event: ") {
-                        title = stripped.trim().to_string();
-                    } else if let Some(stripped) = line.strip_prefix("- **Date**: ") {
-                        let date_str = stripped.trim();
-                        if date_str.contains(" to ") {
-                            let parts: Vec<&str> = date_str.split(" to ").collect();
-                            start_date = NaiveDate::parse_from_str(parts[0], "%Y-%m-%d").ok();
-                            end_date = NaiveDate::parse_from_str(parts[1], "%Y-%m-%d").ok();
-                        } else {
-                            start_date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok();
-                        }
-                    } else if let Some(stripped) = line.strip_prefix("- **Time**: ") {
-                        let time_str = stripped.trim();
-                        if time_str.contains(" to ") {
-                            let parts: Vec<&str> = time_str.split(" to ").collect();
-                            start_time = NaiveTime::parse_from_str(parts[0], "%H:%M").ok();
-                            end_time = NaiveTime::parse_from_str(parts[1], "%H:%M").ok();
-                        } else {
-                            start_time = NaiveTime::parse_from_str(time_str, "%H:%M").ok();
-                        }
-                    } else if let Some(stripped) = line.strip_prefix("- **Description**: ") {
-                        description = stripped.trim().to_string();
-                    } else if let Some(stripped) = line.strip_prefix("- **Recurrence**: ") {
-                        let rec_str = stripped.trim();
-                        recurrence = Recurrence::from_storage_string(rec_str);
-                    }
-                }
-                if let Some(sd) = start_date {
-                    let is_all_day = start_time.is_none();
-                    let st = start_time.unwrap_or(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-                    // Generate a new UUID for each loaded event
-                    events.push(CalendarEvent {
-                        id: Uuid::new_v4().to_string(),
-                        title,
-                        description,
-                        recurrence,
-                        is_recurring_instance: false,
-                        base_date: None,
-                        start_date: sd,
-                        end_date: end_date.or(Some(sd)),
-                        start_time: st,
-                        end_time,
-                        is_all_day,
-                    });
+                if let Some(event) = Self::event_from_markdown(&content) {
+                    events.push(event);
                 }
             }
         }
@@ -166,6 +118,66 @@ impl FileEventRepository {
         Ok(events)
     }
 
+    /// Parses a single event from its markdown representation.
+    ///
+    /// Returns `None` if the content has no valid `Date` line. A new UUID is
+    /// generated for the event since IDs are not persisted.
+    pub fn event_from_markdown(content: &str) -> Option<CalendarEvent> {
+        // Parse new format (title-based, no ID in content)
+        let mut title = String::new();
+        let mut start_date = None;
+        let mut end_date = None;
+        let mut start_time = None;
+        let mut end_time = None;
+        let mut description = String::new();
+        let mut recurrence = Recurrence::None;
+        for line in content.lines() {
+            if let Some(stripped) = line.strip_prefix("# Event: ") {
+                title = stripped.trim().to_string();
+            } else if let Some(stripped) = line.strip_prefix("- **Date**: ") {
+                let date_str = stripped.trim();
+                if date_str.contains(" to ") {
+                    let parts: Vec<&str> = date_str.split(" to ").collect();
+                    start_date = NaiveDate::parse_from_str(parts[0], "%Y-%m-%d").ok();
+                    end_date = NaiveDate::parse_from_str(parts[1], "%Y-%m-%d").ok();
+                } else {
+                    start_date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok();
+                }
+            } else if let Some(stripped) = line.strip_prefix("- **Time**: ") {
+                let time_str = stripped.trim();
+                if time_str.contains(" to ") {
+                    let parts: Vec<&str> = time_str.split(" to ").collect();
+                    start_time = NaiveTime::parse_from_str(parts[0], "%H:%M").ok();
+                    end_time = NaiveTime::parse_from_str(parts[1], "%H:%M").ok();
+                } else {
+                    start_time = NaiveTime::parse_from_str(time_str, "%H:%M").ok();
+                }
+            } else if let Some(stripped) = line.strip_prefix("- **Description**: ") {
+                description = stripped.trim().to_string();
+            } else if let Some(stripped) = line.strip_prefix("- **Recurrence**: ") {
+                let rec_str = stripped.trim();
+                recurrence = Recurrence::from_storage_string(rec_str);
+            }
+        }
+        let sd = start_date?;
+        let is_all_day = start_time.is_none();
+        let st = start_time.unwrap_or(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+        // Generate a new UUID for each loaded event
+        Some(CalendarEvent {
+            id: Uuid::new_v4().to_string(),
+            title,
+            description,
+            recurrence,
+            is_recurring_instance: false,
+            base_date: None,
+            start_date: sd,
+            end_date: end_date.or(Some(sd)),
+            start_time: st,
+            end_time,
+            is_all_day,
+        })
+    }
+
     /// Saves an event to a specific directory.
     pub fn save_to_path(
         &self,
@@ -276,8 +288,9 @@ impl FileEventRepository {
     }
 
     /// Finds the filepath for an event by title and start_date key.
-    /// This is used for delete operations since UUIDs are not persisted in files.
-    fn find_event_filepath_by_key(
+    /// This is used for delete operations and history lookups since UUIDs are
+    /// not persisted in files.
+    pub fn find_event_filepath_by_key(
         &self,
         calendar_dir: &Path,
         title: &str,
@@ -504,10 +517,13 @@ impl EventRepository for FileEventRepository {
         sync_provider: Option<&DynSyncProvider>,
         calendar_dir: &Path,
     ) -> Result<(), Box<dyn Error>> {
-        // Default implementation - just save without sync
-        // Sync functionality can be handled at a higher level by the caller
-        let _ = (sync_provider, calendar_dir);
-        self.save_to_path(event, &self.path_provider.calendar_dir())
+        self.save_to_path(event, calendar_dir)?;
+        if let Some(provider) = sync_provider.and_then(as_sync_provider) {
+            // A failed push is queued in the sync journal and retried later
+            let operation = PendingOperation::for_event(OperationKind::Save, event);
+            let _ = push_with_journal(provider, calendar_dir, operation);
+        }
+        Ok(())
     }
 
     fn delete_with_sync(
@@ -517,10 +533,21 @@ impl EventRepository for FileEventRepository {
         sync_provider: Option<&DynSyncProvider>,
         calendar_dir: &Path,
     ) -> Result<(), Box<dyn Error>> {
-        // Default implementation - just delete without sync
-        // Sync functionality can be handled at a higher level by the caller
-        let _ = (sync_provider, calendar_dir);
-        self.delete(title, start_date)
+        let filepath = self.find_event_filepath_by_key(calendar_dir, title, start_date)?;
+        fs::remove_file(filepath)?;
+        if let Some(provider) = sync_provider.and_then(as_sync_provider) {
+            // A failed push is queued in the sync journal and retried later
+            let operation = PendingOperation {
+                kind: OperationKind::Delete,
+                title: title.to_string(),
+                date: Some(start_date),
+                queued_at: chrono::Local::now(),
+                attempts: 0,
+                last_error: None,
+            };
+            let _ = push_with_journal(provider, calendar_dir, operation);
+        }
+        Ok(())
     }
 }
 
@@ -693,8 +720,11 @@ mod tests {
     #[test]
     fn test_default_path_provider() {
         let provider = DefaultPathProvider::new().unwrap();
-        let home = dirs::home_dir().unwrap();
-        assert_eq!(provider.calendar_dir(), home.join("calendar"));
+        let expected = match std::env::var_os(CALENDAR_DIR_ENV) {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => dirs::home_dir().unwrap().join("calendar"),
+        };
+        assert_eq!(provider.calendar_dir(), expected);
     }
 
     #[test]
@@ -753,4 +783,31 @@ mod tests {
         // Verify file is deleted
         assert!(!file_path.exists());
     }
+
+    #[test]
+    fn test_save_and_delete_with_sync_push() {
+        use crate::sync::{DirectorySyncProvider, SyncProvider};
+
+        let local = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        let provider: Box<dyn SyncProvider> =
+            Box::new(DirectorySyncProvider::new(remote.path().to_path_buf()));
+        provider.init(local.path()).unwrap();
+        let repo = FileEventRepository::with_path(local.path().to_path_buf());
+        let event = CalendarEvent::new(
+            "Dentist".to_string(),
+            String::new(),
+            NaiveDate::from_ymd_opt(2026, 10, 21).unwrap(),
+            NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+        );
+
+        repo.save_with_sync(&event, Some(&provider), local.path())
+            .unwrap();
+        assert!(remote.path().join("Dentist.md").exists());
+
+        repo.delete_with_sync("Dentist", event.start_date, Some(&provider), local.path())
+            .unwrap();
+        assert!(!local.path().join("Dentist.md").exists());
+        assert!(!remote.path().join("Dentist.md").exists());
+    }
 }
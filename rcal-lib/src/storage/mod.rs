@@ -6,5 +6,5 @@
 pub mod file_storage;
 pub mod traits;
 
-pub use file_storage::{DefaultPathProvider, FileEventRepository};
-pub use traits::{CalendarPathProvider, EventRepository};
+pub use file_storage::{DefaultPathProvider, FileEventRepository, CALENDAR_DIR_ENV};
+pub use traits::{CalendarPathProvider, DynSyncProvider, EventRepository};
@@ -0,0 +1,1148 @@
+//! CalDAV synchronization provider.
+//!
+//! Implements the SyncProvider trait against a CalDAV calendar collection
+//! (RFC 4791). Each markdown event file is mapped to one `VEVENT` resource.
+//! ETags and WebDAV sync tokens (RFC 6578) are tracked in a state file inside
+//! the calendar directory, which enables incremental two-way sync and
+//! detection of events changed on both sides.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use base64::Engine;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+use crate::ical::{event_to_ical, parse_ical};
+use crate::models::SyncStatus;
+use crate::storage::FileEventRepository;
+use crate::sync::conflict::{parse_version, write_resolution, ResolvedConflict, SyncConflict};
+use crate::sync::content_hash;
+use crate::sync::traits::SyncProvider;
+use crate::validation::sanitize_title_for_filename;
+
+/// Name of the file holding the sync state, relative to the calendar directory.
+pub const CALDAV_STATE_FILE: &str = ".caldav-sync.yaml";
+
+/// Timeout applied to every HTTP request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+const PROPFIND_DISCOVERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:resourcetype/>
+    <d:displayname/>
+    <d:current-user-principal/>
+    <c:calendar-home-set/>
+  </d:prop>
+</d:propfind>"#;
+
+const PROPFIND_LISTING: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+  <d:prop>
+    <d:resourcetype/>
+    <d:getetag/>
+    <d:sync-token/>
+    <cs:getctag/>
+  </d:prop>
+</d:propfind>"#;
+
+/// Errors returned by the CalDAV provider.
+#[derive(Debug, Error)]
+pub enum CalDavError {
+    /// The HTTP request could not be sent or the connection failed.
+    #[error("CalDAV request failed: {0}")]
+    Transport(String),
+
+    /// The server answered with an unexpected status code.
+    #[error("CalDAV server returned {status} for {method} {url}")]
+    Status {
+        /// The HTTP method of the request.
+        method: String,
+        /// The requested URL.
+        url: String,
+        /// The HTTP status code.
+        status: u16,
+    },
+
+    /// The server response could not be understood.
+    #[error("Invalid CalDAV response: {0}")]
+    InvalidResponse(String),
+
+    /// No calendar collection could be discovered.
+    #[error("No calendar collection found at {0}")]
+    NoCalendar(String),
+
+    /// `init` has not been run for the calendar directory.
+    #[error("CalDAV sync is not initialized for this directory")]
+    NotInitialized,
+
+    /// The conflict to resolve does not exist.
+    #[error("No conflict recorded for {0}")]
+    NoSuchConflict(String),
+}
+
+impl CalDavError {
+    fn status_code(&self) -> Option<u16> {
+        match self {
+            CalDavError::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// The remote side of a conflicting event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteVersion {
+    /// ETag of the remote resource, if it still exists.
+    pub etag: Option<String>,
+    /// iCalendar data of the remote resource, or `None` if it was deleted remotely.
+    pub ical: Option<String>,
+}
+
+/// An event that was changed both locally and on the server since the last sync.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalDavConflict {
+    /// File name of the local event, relative to the calendar directory.
+    pub file_name: String,
+    /// The remote version of the event.
+    pub remote: RemoteVersion,
+}
+
+/// How to resolve a conflict reported by the CalDAV provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Keep the local file; it overwrites the server on the next push.
+    KeepLocal,
+    /// Replace the local file with the server version.
+    KeepRemote,
+}
+
+/// Sync state of a single event resource.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct ResourceState {
+    /// Absolute URL of the remote resource.
+    href: String,
+    /// UID of the VEVENT stored in the resource.
+    uid: String,
+    /// ETag of the resource when it was last synced.
+    etag: Option<String>,
+    /// Hash of the local file content when it was last synced.
+    hash: String,
+}
+
+/// Persisted sync state for a calendar directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// Absolute URL of the calendar collection.
+    collection_url: String,
+    /// WebDAV sync token from the last pull.
+    sync_token: Option<String>,
+    /// CalendarServer ctag from the last pull, for servers without sync tokens.
+    ctag: Option<String>,
+    /// Known resources, keyed by local file name.
+    resources: BTreeMap<String, ResourceState>,
+    /// Unresolved conflicts, keyed by local file name.
+    conflicts: BTreeMap<String, RemoteVersion>,
+}
+
+impl SyncState {
+    fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let state_path = path.join(CALDAV_STATE_FILE);
+        if !state_path.exists() {
+            return Err(CalDavError::NotInitialized.into());
+        }
+        Ok(serde_yaml::from_str(&fs::read_to_string(state_path)?)?)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path.join(CALDAV_STATE_FILE), serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn file_for_href(&self, href: &str) -> Option<String> {
+        self.resources
+            .iter()
+            .find(|(_, r)| r.href == href)
+            .map(|(file, _)| file.clone())
+    }
+
+    /// Returns the tag used to detect remote changes: the sync token if the
+    /// server supports it, the ctag otherwise.
+    fn collection_tag(&self) -> Option<&String> {
+        self.sync_token.as_ref().or(self.ctag.as_ref())
+    }
+}
+
+/// A single `DAV:response` element of a multistatus body.
+#[derive(Debug, Default)]
+struct DavResponse {
+    href: String,
+    status: Option<u16>,
+    props: HashMap<String, DavProp>,
+}
+
+/// The value of a single property with a 2xx propstat.
+#[derive(Debug, Default, Clone)]
+struct DavProp {
+    text: String,
+    hrefs: Vec<String>,
+    children: Vec<String>,
+}
+
+/// A parsed `DAV:multistatus` body.
+#[derive(Debug, Default)]
+struct Multistatus {
+    responses: Vec<DavResponse>,
+    sync_token: Option<String>,
+}
+
+/// Remote changes since the last pull.
+#[derive(Debug, Default)]
+struct RemoteChanges {
+    /// Changed or created resources with their ETags.
+    changed: Vec<(String, Option<String>)>,
+    /// Removed resources.
+    removed: Vec<String>,
+    sync_token: Option<String>,
+    ctag: Option<String>,
+}
+
+/// Raw HTTP response returned by `CalDavSyncProvider::request`.
+struct HttpResponse {
+    etag: Option<String>,
+    body: String,
+}
+
+/// CalDAV-based sync provider for calendar synchronization.
+#[derive(Clone)]
+pub struct CalDavSyncProvider {
+    /// URL of the server, the user principal or the calendar collection.
+    pub server_url: String,
+    /// Username for HTTP basic authentication.
+    pub username: Option<String>,
+    /// Password for HTTP basic authentication.
+    pub password: Option<String>,
+    /// Display name of the calendar to use when several are discovered.
+    pub calendar_name: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl CalDavSyncProvider {
+    /// Creates a new CalDavSyncProvider for the given server URL.
+    pub fn new(server_url: String) -> Self {
+        Self {
+            server_url,
+            username: None,
+            password: None,
+            calendar_name: None,
+            agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+        }
+    }
+
+    /// Sets the credentials used for HTTP basic authentication.
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.username = Some(username.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// Selects the calendar with the given display name during discovery.
+    pub fn with_calendar_name(mut self, name: &str) -> Self {
+        self.calendar_name = Some(name.to_string());
+        self
+    }
+
+    /// Discovers the calendar collection URL.
+    ///
+    /// Follows `current-user-principal` and `calendar-home-set` from the
+    /// configured URL, unless it already points at a calendar collection.
+    pub fn discover_collection(&self) -> Result<String, CalDavError> {
+        let base = self.server_url.clone();
+        let response = self.propfind(&base, "0", PROPFIND_DISCOVERY)?;
+        let root = response.responses.first();
+        if root.is_some_and(is_calendar) {
+            return Ok(ensure_trailing_slash(&base));
+        }
+
+        let mut home = root
+            .and_then(|r| r.props.get("calendar-home-set"))
+            .and_then(|p| p.hrefs.first().cloned());
+        if home.is_none() {
+            let principal = root
+                .and_then(|r| r.props.get("current-user-principal"))
+                .and_then(|p| p.hrefs.first())
+                .map(|href| self.resolve(&base, href))
+                .transpose()?;
+            if let Some(principal) = principal {
+                let response = self.propfind(&principal, "0", PROPFIND_DISCOVERY)?;
+                home = response
+                    .responses
+                    .first()
+                    .and_then(|r| r.props.get("calendar-home-set"))
+                    .and_then(|p| p.hrefs.first().cloned());
+            }
+        }
+        let home = match home {
+            Some(href) => self.resolve(&base, &href)?,
+            None => base.clone(),
+        };
+
+        let response = self.propfind(&home, "1", PROPFIND_DISCOVERY)?;
+        let calendars: Vec<&DavResponse> = response
+            .responses
+            .iter()
+            .filter(|r| is_calendar(r))
+            .collect();
+        let chosen = match &self.calendar_name {
+            Some(name) => calendars.into_iter().find(|r| {
+                r.props
+                    .get("displayname")
+                    .is_some_and(|p| p.text.trim() == name)
+            }),
+            None => calendars.into_iter().next(),
+        };
+        match chosen {
+            Some(calendar) => Ok(ensure_trailing_slash(&self.resolve(&home, &calendar.href)?)),
+            None => Err(CalDavError::NoCalendar(home)),
+        }
+    }
+
+    /// Returns the unresolved conflicts for the given calendar directory.
+    pub fn conflicts(&self, path: &Path) -> Result<Vec<CalDavConflict>, Box<dyn Error>> {
+        let state = SyncState::load(path)?;
+        Ok(state
+            .conflicts
+            .into_iter()
+            .map(|(file_name, remote)| CalDavConflict { file_name, remote })
+            .collect())
+    }
+
+    /// Resolves a conflict by keeping either the local or the remote version.
+    pub fn resolve_conflict(
+        &self,
+        path: &Path,
+        file_name: &str,
+        resolution: ConflictResolution,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut state = SyncState::load(path)?;
+        let remote = state
+            .conflicts
+            .remove(file_name)
+            .ok_or_else(|| CalDavError::NoSuchConflict(file_name.to_string()))?;
+
+        match resolution {
+            ConflictResolution::KeepLocal => match remote.etag {
+                // Adopt the remote ETag so the next push overwrites the server
+                Some(etag) => {
+                    if let Some(resource) = state.resources.get_mut(file_name) {
+                        resource.etag = Some(etag);
+                        resource.hash.clear();
+                    }
+                }
+                // The server copy is gone; the next push recreates it
+                None => {
+                    state.resources.remove(file_name);
+                }
+            },
+            ConflictResolution::KeepRemote => {
+                let file_path = path.join(file_name);
+                match remote.ical.as_deref().map(parse_ical).transpose()? {
+                    Some(events) if !events.is_empty() => {
+                        let parsed = &events[0];
+                        let content = FileEventRepository::event_to_markdown(&parsed.event);
+                        fs::write(&file_path, &content)?;
+                        let resource = state.resources.entry(file_name.to_string()).or_default();
+                        resource.uid = parsed.uid.clone();
+                        resource.etag = remote.etag;
+                        resource.hash = content_hash(&content);
+                    }
+                    _ => {
+                        if file_path.exists() {
+                            fs::remove_file(&file_path)?;
+                        }
+                        state.resources.remove(file_name);
+                    }
+                }
+            }
+        }
+
+        state.save(path)
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> Result<HttpResponse, CalDavError> {
+        let mut request = self.agent.request(method, url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        if let Some(username) = &self.username {
+            let credentials = format!("{}:{}", username, self.password.as_deref().unwrap_or(""));
+            let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+            request = request.set("Authorization", &format!("Basic {encoded}"));
+        }
+
+        let result = match body {
+            Some(body) => request.send_string(body),
+            None => request.call(),
+        };
+        match result {
+            Ok(response) => {
+                let etag = response.header("ETag").map(|s| s.to_string());
+                let body = response
+                    .into_string()
+                    .map_err(|e| CalDavError::Transport(e.to_string()))?;
+                Ok(HttpResponse { etag, body })
+            }
+            Err(ureq::Error::Status(status, _)) => Err(CalDavError::Status {
+                method: method.to_string(),
+                url: url.to_string(),
+                status,
+            }),
+            Err(ureq::Error::Transport(e)) => Err(CalDavError::Transport(e.to_string())),
+        }
+    }
+
+    fn propfind(&self, url: &str, depth: &str, body: &str) -> Result<Multistatus, CalDavError> {
+        let response = self.request(
+            "PROPFIND",
+            url,
+            &[
+                ("Depth", depth),
+                ("Content-Type", "application/xml; charset=utf-8"),
+            ],
+            Some(body),
+        )?;
+        parse_multistatus(&response.body)
+    }
+
+    fn resolve(&self, base: &str, href: &str) -> Result<String, CalDavError> {
+        Url::parse(base)
+            .and_then(|b| b.join(href))
+            .map(|u| u.to_string())
+            .map_err(|e| CalDavError::InvalidResponse(format!("Invalid href '{href}': {e}")))
+    }
+
+    /// Lists all resources of the collection along with its sync token and ctag.
+    fn list_collection(&self, collection: &str) -> Result<RemoteChanges, CalDavError> {
+        let response = self.propfind(collection, "1", PROPFIND_LISTING)?;
+        let mut changes = RemoteChanges::default();
+        for entry in &response.responses {
+            let href = self.resolve(collection, &entry.href)?;
+            if same_url(&href, collection) {
+                changes.sync_token = prop_text(entry, "sync-token");
+                changes.ctag = prop_text(entry, "getctag");
+            } else if !entry
+                .props
+                .get("resourcetype")
+                .is_some_and(|p| p.children.iter().any(|c| c == "collection"))
+            {
+                changes.changed.push((href, prop_text(entry, "getetag")));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Fetches the collection's current sync token and ctag.
+    fn collection_tags(&self, collection: &str) -> Result<RemoteChanges, CalDavError> {
+        let response = self.propfind(collection, "0", PROPFIND_LISTING)?;
+        let root = response.responses.first();
+        Ok(RemoteChanges {
+            sync_token: root.and_then(|r| prop_text(r, "sync-token")),
+            ctag: root.and_then(|r| prop_text(r, "getctag")),
+            ..Default::default()
+        })
+    }
+
+    /// Runs a `sync-collection` report for the changes since `token`.
+    fn sync_collection(&self, collection: &str, token: &str) -> Result<RemoteChanges, CalDavError> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:sync-collection xmlns:d="DAV:">
+  <d:sync-token>{}</d:sync-token>
+  <d:sync-level>1</d:sync-level>
+  <d:prop><d:getetag/></d:prop>
+</d:sync-collection>"#,
+            escape_xml(token)
+        );
+        let response = self.request(
+            "REPORT",
+            collection,
+            &[
+                ("Depth", "0"),
+                ("Content-Type", "application/xml; charset=utf-8"),
+            ],
+            Some(&body),
+        )?;
+        let multistatus = parse_multistatus(&response.body)?;
+
+        let mut changes = RemoteChanges {
+            sync_token: multistatus.sync_token,
+            ..Default::default()
+        };
+        for entry in &multistatus.responses {
+            let href = self.resolve(collection, &entry.href)?;
+            if same_url(&href, collection) {
+                continue;
+            }
+            if entry.status == Some(404) {
+                changes.removed.push(href);
+            } else {
+                changes.changed.push((href, prop_text(entry, "getetag")));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Determines what changed on the server since the last pull, preferring
+    /// an incremental sync-collection report and falling back to a full listing.
+    fn remote_changes(&self, state: &SyncState) -> Result<RemoteChanges, CalDavError> {
+        if let Some(token) = &state.sync_token {
+            match self.sync_collection(&state.collection_url, token) {
+                Ok(changes) => return Ok(changes),
+                // Invalid or expired token, or no RFC 6578 support
+                Err(e) if matches!(e.status_code(), Some(400 | 403 | 405 | 409 | 412 | 501)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut changes = self.list_collection(&state.collection_url)?;
+        changes.removed = state
+            .resources
+            .values()
+            .map(|r| r.href.clone())
+            .filter(|href| !changes.changed.iter().any(|(h, _)| h == href))
+            .collect();
+        Ok(changes)
+    }
+
+    fn fetch(&self, href: &str) -> Result<HttpResponse, CalDavError> {
+        self.request("GET", href, &[], None)
+    }
+
+    /// Fetches the remote version of a resource for conflict bookkeeping.
+    fn remote_version(&self, href: &str) -> Result<RemoteVersion, CalDavError> {
+        match self.fetch(href) {
+            Ok(response) => Ok(RemoteVersion {
+                etag: response.etag,
+                ical: Some(response.body),
+            }),
+            Err(e) if e.status_code() == Some(404) => Ok(RemoteVersion {
+                etag: None,
+                ical: None,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn local_status(&self, path: &Path, state: &SyncState) -> Result<SyncStatus, Box<dyn Error>> {
+        if !state.conflicts.is_empty() {
+            return Ok(SyncStatus::Conflicts);
+        }
+        let local = read_local_files(path)?;
+        let ahead = local.iter().any(|(file, content)| {
+            state
+                .resources
+                .get(file)
+                .is_none_or(|r| r.hash != content_hash(content))
+        }) || state.resources.keys().any(|file| !local.contains_key(file));
+        Ok(if ahead {
+            SyncStatus::Ahead
+        } else {
+            SyncStatus::UpToDate
+        })
+    }
+}
+
+impl SyncProvider for CalDavSyncProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn sync_conflicts(&self, path: &Path) -> Result<Vec<SyncConflict>, Box<dyn Error>> {
+        self.conflicts(path)?
+            .into_iter()
+            .map(|conflict| {
+                let local = fs::read_to_string(path.join(&conflict.file_name)).ok();
+                let remote = match conflict.remote.ical.as_deref() {
+                    Some(ical) => parse_ical(ical)?.into_iter().next().map(|e| e.event),
+                    None => None,
+                };
+                Ok(SyncConflict {
+                    local: parse_version(local.as_deref()),
+                    remote,
+                    file_name: conflict.file_name,
+                })
+            })
+            .collect()
+    }
+
+    fn resolve_sync_conflicts(
+        &self,
+        path: &Path,
+        resolutions: &[ResolvedConflict],
+    ) -> Result<SyncStatus, Box<dyn Error>> {
+        for resolution in resolutions {
+            write_resolution(path, resolution)?;
+            // The resolved file now holds the wanted content, so it overwrites the server
+            self.resolve_conflict(path, &resolution.file_name, ConflictResolution::KeepLocal)?;
+        }
+        self.push(path)
+    }
+
+    fn init(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(path)?;
+        let collection_url = self.discover_collection()?;
+
+        // Keep existing state when re-initializing against the same collection
+        if let Ok(state) = SyncState::load(path) {
+            if state.collection_url == collection_url {
+                return Ok(());
+            }
+        }
+
+        SyncState {
+            collection_url,
+            ..Default::default()
+        }
+        .save(path)
+    }
+
+    fn pull(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        let mut state = SyncState::load(path)?;
+        let changes = self.remote_changes(&state)?;
+        let local = read_local_files(path)?;
+
+        for (href, etag) in changes.changed {
+            let file = state.file_for_href(&href);
+            if let Some(file) = &file {
+                let known = &state.resources[file];
+                if etag.is_some() && known.etag == etag {
+                    // Our own upload, or unchanged
+                    continue;
+                }
+            }
+
+            let response = match self.fetch(&href) {
+                Ok(response) => response,
+                // Deleted between listing and fetching
+                Err(e) if e.status_code() == Some(404) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let etag = response.etag.or(etag);
+            let Some(parsed) = parse_ical(&response.body)?.into_iter().next() else {
+                // Not an event (e.g. a VTODO)
+                continue;
+            };
+            let content = FileEventRepository::event_to_markdown(&parsed.event);
+
+            let file = match file {
+                Some(file) => {
+                    let known = &state.resources[&file];
+                    let locally_modified = local
+                        .get(&file)
+                        .is_none_or(|c| content_hash(c) != known.hash);
+                    if locally_modified {
+                        state.conflicts.insert(
+                            file,
+                            RemoteVersion {
+                                etag,
+                                ical: Some(response.body),
+                            },
+                        );
+                        continue;
+                    }
+                    file
+                }
+                None => {
+                    // Adopt an identical untracked local file instead of duplicating it
+                    let untracked = local
+                        .iter()
+                        .find(|(f, c)| !state.resources.contains_key(*f) && **c == content);
+                    match untracked {
+                        Some((f, _)) => f.clone(),
+                        None => unique_file_name(path, &parsed.event.title),
+                    }
+                }
+            };
+
+            fs::write(path.join(&file), &content)?;
+            state.resources.insert(
+                file,
+                ResourceState {
+                    href,
+                    uid: parsed.uid,
+                    etag,
+                    hash: content_hash(&content),
+                },
+            );
+        }
+
+        for href in changes.removed {
+            let Some(file) = state.file_for_href(&href) else {
+                continue;
+            };
+            let known = &state.resources[&file];
+            match local.get(&file) {
+                Some(content) if content_hash(content) != known.hash => {
+                    state.conflicts.insert(
+                        file,
+                        RemoteVersion {
+                            etag: None,
+                            ical: None,
+                        },
+                    );
+                }
+                Some(_) => {
+                    fs::remove_file(path.join(&file))?;
+                    state.resources.remove(&file);
+                }
+                None => {
+                    state.resources.remove(&file);
+                }
+            }
+        }
+
+        if changes.sync_token.is_some() {
+            state.sync_token = changes.sync_token;
+        }
+        if changes.ctag.is_some() {
+            state.ctag = changes.ctag;
+        } else if let Ok(tags) = self.collection_tags(&state.collection_url) {
+            state.ctag = tags.ctag;
+        }
+        state.save(path)?;
+
+        self.local_status(path, &state)
+    }
+
+    fn push(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        let mut state = SyncState::load(path)?;
+        let local = read_local_files(path)?;
+
+        // If nothing changed remotely since the last pull, the tags can be
+        // advanced after pushing so our own uploads don't look like remote changes
+        let remote_unchanged = self
+            .collection_tags(&state.collection_url)
+            .map(|tags| tags.sync_token.as_ref().or(tags.ctag.as_ref()) == state.collection_tag())
+            .unwrap_or(false);
+
+        for (file, content) in &local {
+            if state.conflicts.contains_key(file) {
+                continue;
+            }
+            let hash = content_hash(content);
+            let known = state.resources.get(file).cloned();
+            if known.as_ref().is_some_and(|r| r.hash == hash) {
+                continue;
+            }
+            let Some(event) = FileEventRepository::event_from_markdown(content) else {
+                continue;
+            };
+
+            let (href, uid, precondition) = match &known {
+                Some(resource) => (
+                    resource.href.clone(),
+                    resource.uid.clone(),
+                    resource.etag.clone().map(|etag| ("If-Match", etag)),
+                ),
+                None => {
+                    let uid = format!("{}@rcal", uuid::Uuid::new_v4());
+                    let stem = file.strip_suffix(".md").unwrap_or(file);
+                    let href = self.resolve(&state.collection_url, &format!("{stem}.ics"))?;
+                    (href, uid, Some(("If-None-Match", "*".to_string())))
+                }
+            };
+
+            let body = event_to_ical(&event, &uid);
+            let mut headers = vec![("Content-Type", "text/calendar; charset=utf-8")];
+            if let Some((name, value)) = &precondition {
+                headers.push((name, value.as_str()));
+            }
+            let (href, response) = match self.request("PUT", &href, &headers, Some(&body)) {
+                Ok(response) => (href, response),
+                Err(e) if e.status_code() == Some(412) && known.is_none() => {
+                    // Name collision with a resource we don't track: use a unique name
+                    let href = self.resolve(
+                        &state.collection_url,
+                        &format!("{}.ics", uuid::Uuid::new_v4()),
+                    )?;
+                    let response = self.request("PUT", &href, &headers, Some(&body))?;
+                    (href, response)
+                }
+                Err(e) if e.status_code() == Some(412) => {
+                    // Changed on the server since the last pull
+                    let remote = self.remote_version(&href)?;
+                    state.conflicts.insert(file.clone(), remote);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            state.resources.insert(
+                file.clone(),
+                ResourceState {
+                    href,
+                    uid,
+                    etag: response.etag,
+                    hash,
+                },
+            );
+        }
+
+        let deleted: Vec<(String, ResourceState)> = state
+            .resources
+            .iter()
+            .filter(|(file, _)| !local.contains_key(*file) && !state.conflicts.contains_key(*file))
+            .map(|(file, resource)| (file.clone(), resource.clone()))
+            .collect();
+        for (file, resource) in deleted {
+            let mut headers = Vec::new();
+            if let Some(etag) = &resource.etag {
+                headers.push(("If-Match", etag.as_str()));
+            }
+            match self.request("DELETE", &resource.href, &headers, None) {
+                Ok(_) => {}
+                Err(e) if e.status_code() == Some(404) => {}
+                Err(e) if e.status_code() == Some(412) => {
+                    let remote = self.remote_version(&resource.href)?;
+                    state.conflicts.insert(file, remote);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            }
+            state.resources.remove(&file);
+        }
+
+        if remote_unchanged {
+            if let Ok(tags) = self.collection_tags(&state.collection_url) {
+                state.sync_token = tags.sync_token;
+                state.ctag = tags.ctag;
+            }
+        }
+        state.save(path)?;
+
+        self.local_status(path, &state)
+    }
+
+    fn status(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        let state = match SyncState::load(path) {
+            Ok(state) => state,
+            Err(_) => {
+                return Ok(SyncStatus::Error(
+                    "CalDAV sync is not initialized".to_string(),
+                ))
+            }
+        };
+
+        let local = self.local_status(path, &state)?;
+        if local == SyncStatus::Conflicts {
+            return Ok(local);
+        }
+
+        let tags = match self.collection_tags(&state.collection_url) {
+            Ok(tags) => tags,
+            Err(e) => return Ok(SyncStatus::Error(e.to_string())),
+        };
+        let remote_tag = tags.sync_token.as_ref().or(tags.ctag.as_ref());
+        let behind = remote_tag.is_none() || remote_tag != state.collection_tag();
+
+        // When both sides changed, pulling first is the correct next step
+        Ok(if behind { SyncStatus::Behind } else { local })
+    }
+}
+
+/// Reads all markdown event files in the calendar directory, keyed by file name.
+fn read_local_files(path: &Path) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let mut files = BTreeMap::new();
+    if !path.exists() {
+        return Ok(files);
+    }
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(".md") && entry.file_type()?.is_file() {
+            files.insert(name, fs::read_to_string(entry.path())?);
+        }
+    }
+    Ok(files)
+}
+
+/// Returns an unused markdown file name derived from the event title.
+fn unique_file_name(path: &Path, title: &str) -> String {
+    let base_name = sanitize_title_for_filename(title);
+    let mut filename = format!("{base_name}.md");
+    let mut counter = 1;
+    while path.join(&filename).exists() {
+        filename = format!("{base_name}_{counter}.md");
+        counter += 1;
+    }
+    filename
+}
+
+fn is_calendar(response: &DavResponse) -> bool {
+    response
+        .props
+        .get("resourcetype")
+        .is_some_and(|p| p.children.iter().any(|c| c == "calendar"))
+}
+
+fn prop_text(response: &DavResponse, name: &str) -> Option<String> {
+    response
+        .props
+        .get(name)
+        .map(|p| p.text.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+fn ensure_trailing_slash(url: &str) -> String {
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{url}/")
+    }
+}
+
+fn same_url(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Parses a `DAV:multistatus` response body, keeping only properties from
+/// propstats with a 2xx status.
+fn parse_multistatus(xml: &str) -> Result<Multistatus, CalDavError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut result = Multistatus::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut response: Option<DavResponse> = None;
+    let mut propstat_props: HashMap<String, DavProp> = HashMap::new();
+    let mut propstat_status: Option<u16> = None;
+    let mut current_prop: Option<String> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| CalDavError::InvalidResponse(e.to_string()))?;
+        let (name, is_empty) = match &event {
+            Event::Start(e) => (local_name(e.local_name().as_ref()), false),
+            Event::Empty(e) => (local_name(e.local_name().as_ref()), true),
+            Event::End(e) => {
+                let name = local_name(e.local_name().as_ref());
+                match name.as_str() {
+                    "response" => {
+                        if let Some(r) = response.take() {
+                            result.responses.push(r);
+                        }
+                    }
+                    "propstat" => {
+                        if propstat_status.is_none_or(|s| (200..300).contains(&s)) {
+                            if let Some(r) = response.as_mut() {
+                                r.props.extend(propstat_props.drain());
+                            }
+                        }
+                        propstat_props.clear();
+                        propstat_status = None;
+                    }
+                    _ => {}
+                }
+                if current_prop.as_deref() == Some(name.as_str())
+                    && stack.iter().rev().nth(1).map(String::as_str) == Some("prop")
+                {
+                    current_prop = None;
+                }
+                stack.pop();
+                continue;
+            }
+            Event::Text(text) => {
+                let text = text
+                    .unescape()
+                    .map_err(|e| CalDavError::InvalidResponse(e.to_string()))?
+                    .to_string();
+                handle_text(
+                    &stack,
+                    &text,
+                    &mut result,
+                    &mut response,
+                    &mut propstat_status,
+                    &mut propstat_props,
+                    current_prop.as_deref(),
+                );
+                continue;
+            }
+            Event::CData(data) => {
+                let text = String::from_utf8_lossy(data.as_ref()).to_string();
+                handle_text(
+                    &stack,
+                    &text,
+                    &mut result,
+                    &mut response,
+                    &mut propstat_status,
+                    &mut propstat_props,
+                    current_prop.as_deref(),
+                );
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let parent = stack.last().map(String::as_str);
+        match (parent, name.as_str()) {
+            (_, "response") => response = Some(DavResponse::default()),
+            (Some("prop"), _) => {
+                propstat_props.insert(name.clone(), DavProp::default());
+                if !is_empty {
+                    current_prop = Some(name.clone());
+                }
+            }
+            (Some(p), _) if current_prop.as_deref() == Some(p) => {
+                if let Some(prop) = propstat_props.get_mut(p) {
+                    prop.children.push(name.clone());
+                }
+            }
+            _ => {}
+        }
+        if !is_empty {
+            stack.push(name);
+        }
+    }
+
+    Ok(result)
+}
+
+fn handle_text(
+    stack: &[String],
+    text: &str,
+    result: &mut Multistatus,
+    response: &mut Option<DavResponse>,
+    propstat_status: &mut Option<u16>,
+    propstat_props: &mut HashMap<String, DavProp>,
+    current_prop: Option<&str>,
+) {
+    let element = stack.last().map(String::as_str);
+    let parent = stack.iter().rev().nth(1).map(String::as_str);
+    match (parent, element) {
+        (Some("response"), Some("href")) => {
+            if let Some(r) = response.as_mut() {
+                r.href.push_str(text.trim());
+            }
+        }
+        (Some("response"), Some("status")) => {
+            if let Some(r) = response.as_mut() {
+                r.status = parse_status_line(text);
+            }
+        }
+        (Some("propstat"), Some("status")) => *propstat_status = parse_status_line(text),
+        (Some("multistatus"), Some("sync-token")) => {
+            result.sync_token = Some(text.trim().to_string());
+        }
+        (Some(p), Some("href")) if Some(p) == current_prop => {
+            if let Some(prop) = propstat_props.get_mut(p) {
+                prop.hrefs.push(text.trim().to_string());
+            }
+        }
+        (_, Some(e)) if Some(e) == current_prop => {
+            if let Some(prop) = propstat_props.get_mut(e) {
+                prop.text.push_str(text);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn local_name(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).to_string()
+}
+
+/// Extracts the status code from a line like `HTTP/1.1 404 Not Found`.
+fn parse_status_line(line: &str) -> Option<u16> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caldav_provider_new() {
+        let provider = CalDavSyncProvider::new("https://dav.example.com/".to_string())
+            .with_credentials("user", "secret")
+            .with_calendar_name("Work");
+        assert_eq!(provider.server_url, "https://dav.example.com/");
+        assert_eq!(provider.username.as_deref(), Some("user"));
+        assert_eq!(provider.password.as_deref(), Some("secret"));
+        assert_eq!(provider.calendar_name.as_deref(), Some("Work"));
+    }
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/cal/work/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:collection/><c:calendar/></d:resourcetype>
+        <d:displayname>Work &amp; Life</d:displayname>
+        <c:calendar-home-set><d:href>/cal/</d:href></c:calendar-home-set>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:getetag/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/cal/work/gone.ics</d:href>
+    <d:status>HTTP/1.1 404 Not Found</d:status>
+  </d:response>
+  <d:sync-token>token-2</d:sync-token>
+</d:multistatus>"#;
+        let multistatus = parse_multistatus(xml).unwrap();
+        assert_eq!(multistatus.responses.len(), 2);
+        assert_eq!(multistatus.sync_token.as_deref(), Some("token-2"));
+
+        let calendar = &multistatus.responses[0];
+        assert_eq!(calendar.href, "/cal/work/");
+        assert!(is_calendar(calendar));
+        assert_eq!(
+            prop_text(calendar, "displayname").as_deref(),
+            Some("Work & Life")
+        );
+        assert_eq!(calendar.props["calendar-home-set"].hrefs, vec!["/cal/"]);
+        assert!(!calendar.props.contains_key("getetag"));
+
+        let gone = &multistatus.responses[1];
+        assert_eq!(gone.status, Some(404));
+    }
+
+    #[test]
+    fn test_status_not_initialized() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let provider = CalDavSyncProvider::new("http://localhost/".to_string());
+        assert_eq!(
+            provider.status(temp_dir.path()).unwrap(),
+            SyncStatus::Error("CalDAV sync is not initialized".to_string())
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Conflicts that need to be resolved by the user.
+//!
+//! When an event was changed both locally and on the remote, providers report
+//! it as a `SyncConflict` holding both versions. The user picks a side per
+//! field, keeps both versions, or edits the merged result, and hands the
+//! outcome back as a `ResolvedConflict`.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::models::{CalendarEvent, EventField};
+use crate::storage::FileEventRepository;
+use crate::validation::sanitize_title_for_filename;
+
+/// Which version of a conflicting event to take a field from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictSide {
+    /// The version in the calendar directory.
+    #[default]
+    Local,
+    /// The version on the remote.
+    Remote,
+}
+
+impl ConflictSide {
+    /// Returns the other side.
+    pub fn toggled(self) -> Self {
+        match self {
+            ConflictSide::Local => ConflictSide::Remote,
+            ConflictSide::Remote => ConflictSide::Local,
+        }
+    }
+}
+
+/// The side chosen for each field of a conflicting event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConflictChoices {
+    sides: [ConflictSide; EventField::ALL.len()],
+}
+
+impl ConflictChoices {
+    /// Creates choices taking every field from the given side.
+    pub fn all(side: ConflictSide) -> Self {
+        Self {
+            sides: [side; EventField::ALL.len()],
+        }
+    }
+
+    /// Returns the side chosen for a field.
+    pub fn get(&self, field: EventField) -> ConflictSide {
+        self.sides[field.index()]
+    }
+
+    /// Chooses the side for a field.
+    pub fn set(&mut self, field: EventField, side: ConflictSide) {
+        self.sides[field.index()] = side;
+    }
+}
+
+/// An event that was changed both locally and on the remote.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncConflict {
+    /// File name of the event, relative to the calendar directory.
+    pub file_name: String,
+    /// The local version, or `None` if the event was deleted locally.
+    pub local: Option<CalendarEvent>,
+    /// The remote version, or `None` if the event was deleted on the remote.
+    pub remote: Option<CalendarEvent>,
+}
+
+impl SyncConflict {
+    /// Returns the fields whose values differ between both versions.
+    ///
+    /// If one side deleted the event, every field differs.
+    pub fn differing_fields(&self) -> Vec<EventField> {
+        match (&self.local, &self.remote) {
+            (Some(local), Some(remote)) => EventField::ALL
+                .into_iter()
+                .filter(|field| field.display(local) != field.display(remote))
+                .collect(),
+            _ => EventField::ALL.to_vec(),
+        }
+    }
+
+    /// Builds the merged event from the chosen side of each field.
+    ///
+    /// If one side deleted the event, fields can't be mixed and the side
+    /// chosen for the title decides whether the event is kept. Returns `None`
+    /// if the merged result is a deletion.
+    pub fn merge(&self, choices: &ConflictChoices) -> Option<CalendarEvent> {
+        match (&self.local, &self.remote) {
+            (Some(local), Some(remote)) => {
+                let mut merged = local.clone();
+                for field in EventField::ALL {
+                    if choices.get(field) == ConflictSide::Remote {
+                        field.copy(remote, &mut merged);
+                    }
+                }
+                Some(merged)
+            }
+            _ => match choices.get(EventField::Title) {
+                ConflictSide::Local => self.local.clone(),
+                ConflictSide::Remote => self.remote.clone(),
+            },
+        }
+    }
+
+    /// Resolves the conflict with the merged result of the given choices.
+    pub fn resolve(&self, choices: &ConflictChoices) -> ResolvedConflict {
+        ResolvedConflict {
+            file_name: self.file_name.clone(),
+            events: self.merge(choices).into_iter().collect(),
+        }
+    }
+
+    /// Resolves the conflict by keeping both versions as separate events.
+    pub fn keep_both(&self) -> ResolvedConflict {
+        ResolvedConflict {
+            file_name: self.file_name.clone(),
+            events: self.local.iter().chain(&self.remote).cloned().collect(),
+        }
+    }
+}
+
+/// The user's resolution of a `SyncConflict`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedConflict {
+    /// File name of the conflicting event, relative to the calendar directory.
+    pub file_name: String,
+    /// The events replacing the conflicting one: none deletes it, the first
+    /// is written to `file_name` and any others are saved as new events.
+    pub events: Vec<CalendarEvent>,
+}
+
+/// Writes a resolution into the calendar directory.
+pub(crate) fn write_resolution(
+    path: &Path,
+    resolution: &ResolvedConflict,
+) -> Result<(), Box<dyn Error>> {
+    let file_path = path.join(&resolution.file_name);
+    let Some((first, others)) = resolution.events.split_first() else {
+        if file_path.exists() {
+            fs::remove_file(file_path)?;
+        }
+        return Ok(());
+    };
+
+    fs::write(&file_path, FileEventRepository::event_to_markdown(first))?;
+    for event in others {
+        let base_name = sanitize_title_for_filename(&event.title);
+        let mut file_name = format!("{base_name}.md");
+        let mut counter = 1;
+        while path.join(&file_name).exists() {
+            file_name = format!("{base_name}_{counter}.md");
+            counter += 1;
+        }
+        fs::write(
+            path.join(file_name),
+            FileEventRepository::event_to_markdown(event),
+        )?;
+    }
+    Ok(())
+}
+
+/// Parses an event file's content for display in a conflict.
+pub(crate) fn parse_version(content: Option<&str>) -> Option<CalendarEvent> {
+    content.and_then(FileEventRepository::event_from_markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+    use tempfile::TempDir;
+
+    fn event(title: &str, description: &str, hour: u32) -> CalendarEvent {
+        CalendarEvent::new(
+            title.to_string(),
+            description.to_string(),
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(),
+            NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+        )
+    }
+
+    fn conflict() -> SyncConflict {
+        SyncConflict {
+            file_name: "Standup.md".to_string(),
+            local: Some(event("Standup", "Room 1", 9)),
+            remote: Some(event("Standup", "Room 2", 10)),
+        }
+    }
+
+    #[test]
+    fn test_differing_fields() {
+        assert_eq!(
+            conflict().differing_fields(),
+            vec![EventField::Times, EventField::Description]
+        );
+        let deleted = SyncConflict {
+            remote: None,
+            ..conflict()
+        };
+        assert_eq!(deleted.differing_fields().len(), EventField::ALL.len());
+    }
+
+    #[test]
+    fn test_merge_picks_fields_per_side() {
+        let conflict = conflict();
+        let mut choices = ConflictChoices::default();
+        choices.set(EventField::Description, ConflictSide::Remote);
+        let merged = conflict.merge(&choices).unwrap();
+        assert_eq!(merged.description, "Room 2");
+        assert_eq!(merged.start_time, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+
+        let remote = conflict
+            .merge(&ConflictChoices::all(ConflictSide::Remote))
+            .unwrap();
+        assert_eq!(remote.description, "Room 2");
+        assert_eq!(
+            remote.start_time,
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_merge_with_deleted_side() {
+        let conflict = SyncConflict {
+            local: None,
+            ..conflict()
+        };
+        assert!(conflict.merge(&ConflictChoices::default()).is_none());
+        assert!(conflict
+            .resolve(&ConflictChoices::default())
+            .events
+            .is_empty());
+        assert_eq!(
+            conflict
+                .merge(&ConflictChoices::all(ConflictSide::Remote))
+                .unwrap()
+                .description,
+            "Room 2"
+        );
+    }
+
+    #[test]
+    fn test_write_resolution_keep_both() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Standup.md"), "old").unwrap();
+        write_resolution(temp_dir.path(), &conflict().keep_both()).unwrap();
+
+        let first = fs::read_to_string(temp_dir.path().join("Standup.md")).unwrap();
+        let second = fs::read_to_string(temp_dir.path().join("Standup_1.md")).unwrap();
+        assert_eq!(parse_version(Some(&first)).unwrap().description, "Room 1");
+        assert_eq!(parse_version(Some(&second)).unwrap().description, "Room 2");
+    }
+
+    #[test]
+    fn test_write_resolution_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Standup.md"), "old").unwrap();
+        let resolution = ResolvedConflict {
+            file_name: "Standup.md".to_string(),
+            events: Vec::new(),
+        };
+        write_resolution(temp_dir.path(), &resolution).unwrap();
+        assert!(!temp_dir.path().join("Standup.md").exists());
+    }
+}
@@ -0,0 +1,518 @@
+//! Directory-based synchronization provider.
+//!
+//! Implements the SyncProvider trait by two-way synchronizing the calendar
+//! directory with another local directory, such as a Syncthing folder, a USB
+//! drive or an NFS mount. A snapshot of the file hashes from the last sync is
+//! kept in a state file, which is what allows creations, edits and deletions
+//! to be told apart on each side.
+
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::SyncStatus;
+use crate::sync::conflict::{parse_version, write_resolution, ResolvedConflict, SyncConflict};
+use crate::sync::content_hash;
+use crate::sync::traits::SyncProvider;
+
+/// Name of the file holding the sync snapshot, relative to the calendar directory.
+pub const DIR_SYNC_STATE_FILE: &str = ".dir-sync.yaml";
+
+/// Marker in the names of conflict copies, e.g. `Meeting.sync-conflict-20250101-120000.md`.
+const CONFLICT_MARKER: &str = ".sync-conflict-";
+
+/// Snapshot of the files as they were on both sides after the last sync.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// Content hash per file name.
+    files: BTreeMap<String, String>,
+}
+
+impl SyncState {
+    fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let state_path = path.join(DIR_SYNC_STATE_FILE);
+        if !state_path.exists() {
+            return Err("Directory sync is not initialized".into());
+        }
+        Ok(serde_yaml::from_str(&fs::read_to_string(state_path)?)?)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        write_file(
+            &path.join(DIR_SYNC_STATE_FILE),
+            &serde_yaml::to_string(self)?,
+        )
+    }
+}
+
+/// Which side's changes a sync run applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Pull,
+    Push,
+}
+
+/// Outcome of comparing both directories against the snapshot.
+#[derive(Debug, Default)]
+struct Comparison {
+    ahead: bool,
+    behind: bool,
+    conflicts: bool,
+}
+
+/// Directory-based sync provider for calendar synchronization.
+#[derive(Debug, Clone)]
+pub struct DirectorySyncProvider {
+    /// Directory to synchronize the calendar directory with.
+    pub target_dir: PathBuf,
+}
+
+impl DirectorySyncProvider {
+    /// Creates a new DirectorySyncProvider for the given target directory.
+    pub fn new(target_dir: PathBuf) -> Self {
+        Self { target_dir }
+    }
+
+    /// Returns the conflict copies currently present in the calendar directory.
+    pub fn conflict_copies(&self, path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(read_event_files(path)?
+            .into_keys()
+            .filter(|name| is_conflict_copy(name))
+            .collect())
+    }
+
+    fn check_target(&self) -> Result<(), Box<dyn Error>> {
+        if !self.target_dir.is_dir() {
+            return Err(format!(
+                "Sync directory {} is not available",
+                self.target_dir.display()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Compares both directories with the snapshot and, if a direction is
+    /// given, applies that side's changes to the other side.
+    ///
+    /// Files changed on both sides are always reconciled: an edit wins over a
+    /// deletion, and when both sides edited a file the local version is kept
+    /// while the other version is saved next to it as a conflict copy.
+    fn reconcile(
+        &self,
+        path: &Path,
+        direction: Option<Direction>,
+    ) -> Result<Comparison, Box<dyn Error>> {
+        self.check_target()?;
+        let mut state = SyncState::load(path)?;
+        let local = read_event_files(path)?;
+        let remote = read_event_files(&self.target_dir)?;
+
+        let names: BTreeSet<String> = local
+            .keys()
+            .chain(remote.keys())
+            .chain(state.files.keys())
+            .cloned()
+            .collect();
+
+        let mut comparison = Comparison::default();
+        for name in names {
+            let local_content = local.get(&name);
+            let remote_content = remote.get(&name);
+            let local_hash = local_content.map(|c| content_hash(c));
+            let remote_hash = remote_content.map(|c| content_hash(c));
+            let base_hash = state.files.get(&name).cloned();
+
+            let synced_hash = if local_hash == remote_hash {
+                local_hash
+            } else {
+                let local_changed = local_hash != base_hash;
+                let remote_changed = remote_hash != base_hash;
+                match (local_changed, remote_changed) {
+                    (true, false) => {
+                        comparison.ahead = true;
+                        if direction != Some(Direction::Push) {
+                            continue;
+                        }
+                        mirror(local_content, &self.target_dir.join(&name))?;
+                        local_hash
+                    }
+                    (false, true) => {
+                        comparison.behind = true;
+                        if direction != Some(Direction::Pull) {
+                            continue;
+                        }
+                        mirror(remote_content, &path.join(&name))?;
+                        remote_hash
+                    }
+                    _ => {
+                        comparison.conflicts = true;
+                        if direction.is_none() {
+                            continue;
+                        }
+                        match (local_content, remote_content) {
+                            (Some(local_content), Some(remote_content)) => {
+                                write_file(&path.join(conflict_copy_name(&name)), remote_content)?;
+                                write_file(&self.target_dir.join(&name), local_content)?;
+                                local_hash
+                            }
+                            // Deleted remotely but edited locally: keep the edit
+                            (Some(local_content), None) => {
+                                write_file(&self.target_dir.join(&name), local_content)?;
+                                local_hash
+                            }
+                            // Deleted locally but edited remotely: keep the edit
+                            (None, Some(remote_content)) => {
+                                write_file(&path.join(&name), remote_content)?;
+                                remote_hash
+                            }
+                            (None, None) => None,
+                        }
+                    }
+                }
+            };
+
+            match synced_hash {
+                Some(hash) => {
+                    state.files.insert(name, hash);
+                }
+                None => {
+                    state.files.remove(&name);
+                }
+            }
+        }
+
+        state.save(path)?;
+        Ok(comparison)
+    }
+
+    fn status_after(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        let comparison = self.reconcile(path, None)?;
+        let has_conflict_copies = !self.conflict_copies(path)?.is_empty();
+        Ok(if comparison.conflicts || has_conflict_copies {
+            SyncStatus::Conflicts
+        } else if comparison.behind {
+            SyncStatus::Behind
+        } else if comparison.ahead {
+            SyncStatus::Ahead
+        } else {
+            SyncStatus::UpToDate
+        })
+    }
+}
+
+impl SyncProvider for DirectorySyncProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn init(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(path)?;
+        fs::create_dir_all(&self.target_dir)?;
+        if !path.join(DIR_SYNC_STATE_FILE).exists() {
+            SyncState::default().save(path)?;
+        }
+        Ok(())
+    }
+
+    fn pull(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        self.reconcile(path, Some(Direction::Pull))?;
+        self.status_after(path)
+    }
+
+    fn push(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        self.reconcile(path, Some(Direction::Push))?;
+        self.status_after(path)
+    }
+
+    fn status(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        match self.status_after(path) {
+            Ok(status) => Ok(status),
+            Err(e) => Ok(SyncStatus::Error(e.to_string())),
+        }
+    }
+
+    /// Pairs each conflict copy with the event it was made for. If an event
+    /// has several copies, only the newest one is reported.
+    fn sync_conflicts(&self, path: &Path) -> Result<Vec<SyncConflict>, Box<dyn Error>> {
+        let files = read_event_files(path)?;
+        let mut conflicts = BTreeMap::new();
+        for (name, content) in &files {
+            let Some(original) = conflict_copy_original(name) else {
+                continue;
+            };
+            conflicts.insert(
+                original.clone(),
+                SyncConflict {
+                    local: parse_version(files.get(&original).map(String::as_str)),
+                    remote: parse_version(Some(content)),
+                    file_name: original,
+                },
+            );
+        }
+        Ok(conflicts.into_values().collect())
+    }
+
+    fn resolve_sync_conflicts(
+        &self,
+        path: &Path,
+        resolutions: &[ResolvedConflict],
+    ) -> Result<SyncStatus, Box<dyn Error>> {
+        for resolution in resolutions {
+            write_resolution(path, resolution)?;
+            for copy in self.conflict_copies(path)? {
+                if conflict_copy_original(&copy).as_ref() == Some(&resolution.file_name) {
+                    fs::remove_file(path.join(copy))?;
+                }
+            }
+        }
+        self.push(path)
+    }
+}
+
+/// Reads all markdown event files in a directory, keyed by file name.
+fn read_event_files(dir: &Path) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let mut files = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(".md") && !name.starts_with('.') && entry.file_type()?.is_file() {
+            files.insert(name, fs::read_to_string(entry.path())?);
+        }
+    }
+    Ok(files)
+}
+
+/// Makes `target` match `content`, deleting it when the source was deleted.
+fn mirror(content: Option<&String>, target: &Path) -> Result<(), Box<dyn Error>> {
+    match content {
+        Some(content) => write_file(target, content),
+        None => {
+            if target.exists() {
+                fs::remove_file(target)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes a file through a temporary file and a rename, so that other sync
+/// tools watching the directory never see a partially written file.
+fn write_file(target: &Path, content: &str) -> Result<(), Box<dyn Error>> {
+    let file_name = target
+        .file_name()
+        .ok_or("Invalid target file name")?
+        .to_string_lossy();
+    let temp = target.with_file_name(format!(".{file_name}.rcal-tmp"));
+    fs::write(&temp, content)?;
+    fs::rename(&temp, target)?;
+    Ok(())
+}
+
+fn is_conflict_copy(name: &str) -> bool {
+    name.contains(CONFLICT_MARKER)
+}
+
+/// Returns the file name of the event a conflict copy was made for.
+fn conflict_copy_original(name: &str) -> Option<String> {
+    name.split_once(CONFLICT_MARKER)
+        .map(|(stem, _)| format!("{stem}.md"))
+}
+
+fn conflict_copy_name(name: &str) -> String {
+    let stem = name.strip_suffix(".md").unwrap_or(name);
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    format!("{stem}{CONFLICT_MARKER}{timestamp}.md")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, TempDir, DirectorySyncProvider) {
+        let local = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        let provider = DirectorySyncProvider::new(remote.path().to_path_buf());
+        provider.init(local.path()).unwrap();
+        (local, remote, provider)
+    }
+
+    fn read(dir: &TempDir, name: &str) -> Option<String> {
+        fs::read_to_string(dir.path().join(name)).ok()
+    }
+
+    fn write(dir: &TempDir, name: &str, content: &str) {
+        fs::write(dir.path().join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), content_hash("a"));
+        assert_ne!(content_hash("a"), content_hash("b"));
+    }
+
+    #[test]
+    fn test_status_not_initialized() {
+        let local = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        let provider = DirectorySyncProvider::new(remote.path().to_path_buf());
+        assert_eq!(
+            provider.status(local.path()).unwrap(),
+            SyncStatus::Error("Directory sync is not initialized".to_string())
+        );
+        assert!(provider.pull(local.path()).is_err());
+    }
+
+    #[test]
+    fn test_status_target_unavailable() {
+        let (local, remote, provider) = setup();
+        let path = remote.path().to_path_buf();
+        drop(remote);
+        let err = provider.pull(local.path()).unwrap_err();
+        assert!(err.to_string().contains(&path.display().to_string()));
+        assert!(provider.status(local.path()).unwrap().is_error());
+    }
+
+    #[test]
+    fn test_push_and_pull_creations() {
+        let (local, remote, provider) = setup();
+        write(&local, "Local.md", "local");
+        write(&remote, "Remote.md", "remote");
+        assert_eq!(provider.status(local.path()).unwrap(), SyncStatus::Behind);
+
+        assert_eq!(provider.push(local.path()).unwrap(), SyncStatus::Behind);
+        assert_eq!(read(&remote, "Local.md").as_deref(), Some("local"));
+        assert_eq!(read(&local, "Remote.md"), None);
+
+        assert_eq!(provider.pull(local.path()).unwrap(), SyncStatus::UpToDate);
+        assert_eq!(read(&local, "Remote.md").as_deref(), Some("remote"));
+    }
+
+    #[test]
+    fn test_edits_and_deletions_propagate() {
+        let (local, remote, provider) = setup();
+        write(&local, "A.md", "a");
+        write(&local, "B.md", "b");
+        provider.push(local.path()).unwrap();
+
+        write(&local, "A.md", "a2");
+        fs::remove_file(local.path().join("B.md")).unwrap();
+        assert_eq!(provider.status(local.path()).unwrap(), SyncStatus::Ahead);
+        assert_eq!(provider.push(local.path()).unwrap(), SyncStatus::UpToDate);
+        assert_eq!(read(&remote, "A.md").as_deref(), Some("a2"));
+        assert_eq!(read(&remote, "B.md"), None);
+
+        write(&remote, "A.md", "a3");
+        fs::remove_file(remote.path().join("A.md")).unwrap();
+        write(&remote, "C.md", "c");
+        assert_eq!(provider.pull(local.path()).unwrap(), SyncStatus::UpToDate);
+        assert_eq!(read(&local, "A.md"), None);
+        assert_eq!(read(&local, "C.md").as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_both_sides_edited_creates_conflict_copy() {
+        let (local, remote, provider) = setup();
+        write(&local, "Meeting.md", "base");
+        provider.push(local.path()).unwrap();
+
+        write(&local, "Meeting.md", "local edit");
+        write(&remote, "Meeting.md", "remote edit");
+        assert_eq!(
+            provider.status(local.path()).unwrap(),
+            SyncStatus::Conflicts
+        );
+
+        assert_eq!(provider.pull(local.path()).unwrap(), SyncStatus::Conflicts);
+        assert_eq!(read(&local, "Meeting.md").as_deref(), Some("local edit"));
+        assert_eq!(read(&remote, "Meeting.md").as_deref(), Some("local edit"));
+        let copies = provider.conflict_copies(local.path()).unwrap();
+        assert_eq!(copies.len(), 1);
+        assert!(copies[0].starts_with("Meeting.sync-conflict-"));
+        assert_eq!(read(&local, &copies[0]).as_deref(), Some("remote edit"));
+
+        // Removing the copy resolves the conflict
+        fs::remove_file(local.path().join(&copies[0])).unwrap();
+        assert_eq!(provider.status(local.path()).unwrap(), SyncStatus::UpToDate);
+    }
+
+    #[test]
+    fn test_resolve_conflict_copy() {
+        use crate::models::CalendarEvent;
+        use crate::models::EventField;
+        use crate::storage::FileEventRepository;
+        use crate::sync::conflict::{ConflictChoices, ConflictSide};
+
+        let event = |description: &str| {
+            FileEventRepository::event_to_markdown(&CalendarEvent::new(
+                "Meeting".to_string(),
+                description.to_string(),
+                chrono::NaiveDate::from_ymd_opt(2025, 5, 1).unwrap(),
+                chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            ))
+        };
+        let (local, remote, provider) = setup();
+        write(&local, "Meeting.md", &event("base"));
+        provider.push(local.path()).unwrap();
+        write(&local, "Meeting.md", &event("local edit"));
+        write(&remote, "Meeting.md", &event("remote edit"));
+        provider.pull(local.path()).unwrap();
+
+        let conflicts = provider.sync_conflicts(local.path()).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].file_name, "Meeting.md");
+        assert_eq!(
+            conflicts[0].local.as_ref().unwrap().description,
+            "local edit"
+        );
+        assert_eq!(
+            conflicts[0].remote.as_ref().unwrap().description,
+            "remote edit"
+        );
+
+        let mut choices = ConflictChoices::default();
+        choices.set(EventField::Description, ConflictSide::Remote);
+        let resolution = conflicts[0].resolve(&choices);
+        assert_eq!(
+            provider
+                .resolve_sync_conflicts(local.path(), &[resolution])
+                .unwrap(),
+            SyncStatus::UpToDate
+        );
+        assert!(provider.conflict_copies(local.path()).unwrap().is_empty());
+        assert!(read(&remote, "Meeting.md").unwrap().contains("remote edit"));
+    }
+
+    #[test]
+    fn test_edit_wins_over_deletion() {
+        let (local, remote, provider) = setup();
+        write(&local, "A.md", "a");
+        write(&local, "B.md", "b");
+        provider.push(local.path()).unwrap();
+
+        write(&local, "A.md", "a edited");
+        fs::remove_file(remote.path().join("A.md")).unwrap();
+        fs::remove_file(local.path().join("B.md")).unwrap();
+        write(&remote, "B.md", "b edited");
+
+        assert_eq!(provider.push(local.path()).unwrap(), SyncStatus::UpToDate);
+        assert_eq!(read(&remote, "A.md").as_deref(), Some("a edited"));
+        assert_eq!(read(&local, "B.md").as_deref(), Some("b edited"));
+        assert!(provider.conflict_copies(local.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_non_event_files_are_ignored() {
+        let (local, remote, provider) = setup();
+        write(&local, "notes.txt", "not an event");
+        assert_eq!(provider.push(local.path()).unwrap(), SyncStatus::UpToDate);
+        assert_eq!(read(&remote, "notes.txt"), None);
+        assert_eq!(read(&remote, DIR_SYNC_STATE_FILE), None);
+    }
+}
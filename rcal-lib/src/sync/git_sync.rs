@@ -3,11 +3,17 @@
 //! Implements the SyncProvider trait using Git for calendar synchronization.
 
 use std::any::Any;
+use std::collections::HashSet;
 use std::error::Error;
 use std::path::Path;
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 
-use crate::models::SyncStatus;
+use chrono::DateTime;
+
+use crate::models::{CalendarEvent, SyncStatus};
+use crate::sync::conflict::{parse_version, write_resolution, ResolvedConflict, SyncConflict};
+use crate::sync::history::{link_revisions, EventRevision};
+use crate::sync::journal::SYNC_JOURNAL_FILE;
 use crate::sync::traits::SyncProvider;
 
 /// Git-based sync provider for calendar synchronization.
@@ -34,6 +40,49 @@ impl GitSyncProvider {
     }
 }
 
+impl GitSyncProvider {
+    fn upstream(&self) -> String {
+        format!("origin/{}", self.branch)
+    }
+
+    /// Returns true if both the local and the remote branch have commits the
+    /// other one lacks.
+    fn diverged(&self, path: &Path) -> Result<bool, Box<dyn Error>> {
+        let range = format!("HEAD...{}", self.upstream());
+        let output = run_git(path, &["rev-list", "--left-right", "--count", &range])?;
+        if !output.status.success() {
+            return Ok(false);
+        }
+        let counts = String::from_utf8_lossy(&output.stdout);
+        let mut counts = counts.split_whitespace().map(|n| n.parse::<u32>());
+        Ok(matches!(
+            (counts.next(), counts.next()),
+            (Some(Ok(ahead)), Some(Ok(behind))) if ahead > 0 && behind > 0
+        ))
+    }
+
+    /// Returns the files changed since `base` in the given revision.
+    fn changed_files(
+        &self,
+        path: &Path,
+        base: &str,
+        rev: &str,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let output = run_git(path, &["diff", "--name-only", base, rev])?;
+        if !output.status.success() {
+            return Err(format!(
+                "Git diff failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )
+            .into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.to_string())
+            .collect())
+    }
+}
+
 impl SyncProvider for GitSyncProvider {
     fn as_any(&self) -> &dyn Any {
         self
@@ -99,6 +148,9 @@ impl SyncProvider for GitSyncProvider {
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             if stderr.contains("conflict") {
+                // Leave the repository on the local commits so the conflicts
+                // can be resolved with resolve_sync_conflicts()
+                let _ = run_git(path, &["rebase", "--abort"]);
                 return Err("Merge conflicts detected. Please resolve manually.".into());
             }
             return Err(format!("Git pull failed: {stderr}").into());
@@ -109,9 +161,11 @@ impl SyncProvider for GitSyncProvider {
     }
 
     fn push(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
-        // Add all changes
+        // Add all changes, leaving out local sync bookkeeping: the journal,
+        // its lock and its temporary file all start with the journal's name
+        let journal_exclude = format!(":(exclude){SYNC_JOURNAL_FILE}*");
         let status = Command::new("git")
-            .args(["add", "."])
+            .args(["add", ".", &journal_exclude])
             .current_dir(path)
             .stdout(Stdio::null())
             .stderr(Stdio::null())
@@ -128,20 +182,24 @@ impl SyncProvider for GitSyncProvider {
             .stderr(Stdio::null())
             .status()?;
         if diff_output.success() {
-            // No changes to commit, return current status
-            return self.status(path);
-        }
-
-        // Commit
-        let output = Command::new("git")
-            .args(["commit", "-m", "Sync events"])
-            .current_dir(path)
-            .stdout(Stdio::null())
-            .output()?;
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.contains("nothing to commit") {
-                return Err(format!("Git commit failed: {stderr}").into());
+            // No changes to commit; still push commits left over from a failed
+            // push, including the first one when no remote branch exists yet
+            let status = self.status(path)?;
+            if !matches!(status, SyncStatus::Ahead | SyncStatus::Error(_)) {
+                return Ok(status);
+            }
+        } else {
+            // Commit
+            let output = Command::new("git")
+                .args(["commit", "-m", "Sync events"])
+                .current_dir(path)
+                .stdout(Stdio::null())
+                .output()?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                if !stderr.contains("nothing to commit") {
+                    return Err(format!("Git commit failed: {stderr}").into());
+                }
             }
         }
 
@@ -163,6 +221,128 @@ impl SyncProvider for GitSyncProvider {
         self.status(path)
     }
 
+    fn sync_conflicts(&self, path: &Path) -> Result<Vec<SyncConflict>, Box<dyn Error>> {
+        if !self.diverged(path)? {
+            return Ok(Vec::new());
+        }
+        let upstream = self.upstream();
+        let output = run_git(path, &["merge-base", "HEAD", &upstream])?;
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+        let base = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let remote_files = self.changed_files(path, &base, &upstream)?;
+        let mut conflicts = Vec::new();
+        for file_name in self.changed_files(path, &base, "HEAD")? {
+            if !remote_files.contains(&file_name) {
+                continue;
+            }
+            let local = show_file(path, "HEAD", &file_name)?;
+            let remote = show_file(path, &upstream, &file_name)?;
+            if local != remote {
+                conflicts.push(SyncConflict {
+                    local: parse_version(local.as_deref()),
+                    remote: parse_version(remote.as_deref()),
+                    file_name,
+                });
+            }
+        }
+        Ok(conflicts)
+    }
+
+    fn resolve_sync_conflicts(
+        &self,
+        path: &Path,
+        resolutions: &[ResolvedConflict],
+    ) -> Result<SyncStatus, Box<dyn Error>> {
+        if !self.diverged(path)? {
+            return self.push(path);
+        }
+
+        // Conflicting files are left with markers and overwritten below
+        run_git(path, &["merge", "--no-ff", "--no-commit", &self.upstream()])?;
+        for resolution in resolutions {
+            write_resolution(path, resolution)?;
+        }
+        let journal_exclude = format!(":(exclude){SYNC_JOURNAL_FILE}*");
+        run_git(path, &["add", "-A", "--", ".", &journal_exclude])?;
+
+        let unmerged = run_git(path, &["diff", "--name-only", "--diff-filter=U"])?;
+        let unmerged = String::from_utf8_lossy(&unmerged.stdout).trim().to_string();
+        if !unmerged.is_empty() {
+            let _ = run_git(path, &["merge", "--abort"]);
+            return Err(format!("Unresolved conflicts in {}", unmerged.replace('\n', ", ")).into());
+        }
+
+        let output = run_git(
+            path,
+            &["commit", "--no-edit", "-m", "Resolve sync conflicts"],
+        )?;
+        if !output.status.success() {
+            return Err(format!(
+                "Git commit failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )
+            .into());
+        }
+        self.push(path)
+    }
+
+    fn event_history(
+        &self,
+        path: &Path,
+        file_name: &str,
+    ) -> Result<Vec<EventRevision>, Box<dyn Error>> {
+        let format = format!("--format={LOG_FORMAT}");
+        let Some(log) = git_log(path, &[&format, "--", file_name])? else {
+            return Ok(Vec::new());
+        };
+        let mut revisions = Vec::new();
+        for line in log.lines() {
+            let commit = line.split('\x1f').next().unwrap_or_default();
+            let content = show_file(path, commit, file_name)?;
+            if let Some(revision) =
+                parse_revision(line, file_name, parse_version(content.as_deref()))
+            {
+                revisions.push(revision);
+            }
+        }
+        link_revisions(&mut revisions);
+        Ok(revisions)
+    }
+
+    fn deleted_events(&self, path: &Path) -> Result<Vec<EventRevision>, Box<dyn Error>> {
+        let format = format!("--format=%x1e{LOG_FORMAT}");
+        let Some(log) = git_log(path, &["--diff-filter=D", "--name-only", &format])? else {
+            return Ok(Vec::new());
+        };
+        let mut seen = HashSet::new();
+        let mut deleted = Vec::new();
+        for record in log.split('\x1e') {
+            let mut lines = record.lines().filter(|line| !line.is_empty());
+            let Some(header) = lines.next() else {
+                continue;
+            };
+            let commit = header.split('\x1f').next().unwrap_or_default();
+            for file_name in lines {
+                if !file_name.ends_with(".md")
+                    || path.join(file_name).exists()
+                    || !seen.insert(file_name.to_string())
+                {
+                    continue;
+                }
+                let content = show_file(path, &format!("{commit}^"), file_name)?;
+                if let Some(revision) =
+                    parse_revision(header, file_name, parse_version(content.as_deref()))
+                {
+                    deleted.push(revision);
+                }
+            }
+        }
+        Ok(deleted)
+    }
+
     fn status(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
         // Check if repo exists
         if !path.join(".git").exists() {
@@ -193,6 +373,62 @@ impl SyncProvider for GitSyncProvider {
     }
 }
 
+/// `git log` format of a revision: id, author, date and subject separated by
+/// unit separators.
+const LOG_FORMAT: &str = "%H%x1f%an%x1f%aI%x1f%s";
+
+fn run_git(path: &Path, args: &[&str]) -> std::io::Result<Output> {
+    Command::new("git").args(args).current_dir(path).output()
+}
+
+/// Runs `git log` with the given arguments, returning `None` if the
+/// repository has no commits yet.
+fn git_log(path: &Path, args: &[&str]) -> Result<Option<String>, Box<dyn Error>> {
+    let mut log_args = vec!["log"];
+    log_args.extend(args);
+    let output = run_git(path, &log_args)?;
+    if output.status.success() {
+        return Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()));
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("does not have any commits") {
+        Ok(None)
+    } else {
+        Err(format!("Git log failed: {stderr}").into())
+    }
+}
+
+/// Builds a revision from a line in `LOG_FORMAT`.
+fn parse_revision(
+    line: &str,
+    file_name: &str,
+    event: Option<CalendarEvent>,
+) -> Option<EventRevision> {
+    let mut fields = line.splitn(4, '\x1f');
+    let commit = fields.next()?.to_string();
+    let author = fields.next()?.to_string();
+    let date = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+    let summary = fields.next().unwrap_or_default().to_string();
+    Some(EventRevision {
+        commit,
+        file_name: file_name.to_string(),
+        author,
+        date,
+        summary,
+        event,
+        changes: Vec::new(),
+    })
+}
+
+/// Returns the content of a file at a revision, or `None` if it doesn't exist there.
+fn show_file(path: &Path, rev: &str, file_name: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let output = run_git(path, &["show", &format!("{rev}:{file_name}")])?;
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
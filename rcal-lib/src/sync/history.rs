@@ -0,0 +1,175 @@
+//! Version history of event files.
+//!
+//! Providers that keep the calendar directory under version control can list
+//! every committed version of an event file as an `EventRevision`. A revision
+//! records who changed the event and when, and which fields changed compared
+//! to the previous version, and can be written back with `restore_revision`.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::models::{CalendarEvent, EventField};
+use crate::storage::FileEventRepository;
+
+/// A field that differs between two versions of an event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// The changed field.
+    pub field: EventField,
+    /// The value before the change, or `None` if the event didn't exist.
+    pub old: Option<String>,
+    /// The value after the change, or `None` if the event was deleted.
+    pub new: Option<String>,
+}
+
+/// A committed version of an event file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRevision {
+    /// Full id of the commit.
+    pub commit: String,
+    /// File name of the event, relative to the calendar directory.
+    pub file_name: String,
+    /// Name of the commit author.
+    pub author: String,
+    /// When the commit was authored.
+    pub date: DateTime<FixedOffset>,
+    /// First line of the commit message.
+    pub summary: String,
+    /// The event as of this commit, or `None` if the commit deleted it.
+    pub event: Option<CalendarEvent>,
+    /// The fields changed compared to the previous version.
+    pub changes: Vec<FieldChange>,
+}
+
+impl EventRevision {
+    /// Returns the abbreviated commit id shown to the user.
+    pub fn short_id(&self) -> &str {
+        &self.commit[..self.commit.len().min(7)]
+    }
+
+    /// Returns true if the commit deleted the event.
+    pub fn is_deletion(&self) -> bool {
+        self.event.is_none()
+    }
+
+    /// Returns true if the revision id starts with the given prefix.
+    pub fn matches(&self, prefix: &str) -> bool {
+        !prefix.is_empty() && self.commit.starts_with(prefix)
+    }
+}
+
+/// Returns the fields that differ between two versions of an event.
+///
+/// A missing version counts as every field being empty, so creations and
+/// deletions list all fields.
+pub fn field_changes(old: Option<&CalendarEvent>, new: Option<&CalendarEvent>) -> Vec<FieldChange> {
+    EventField::ALL
+        .into_iter()
+        .filter_map(|field| {
+            let old = old.map(|event| field.display(event));
+            let new = new.map(|event| field.display(event));
+            (old != new).then_some(FieldChange { field, old, new })
+        })
+        .collect()
+}
+
+/// Fills in the changes of revisions listed newest first, comparing each one
+/// to the next older revision.
+pub(crate) fn link_revisions(revisions: &mut [EventRevision]) {
+    for i in 0..revisions.len() {
+        let older = revisions.get(i + 1).and_then(|r| r.event.clone());
+        revisions[i].changes = field_changes(older.as_ref(), revisions[i].event.as_ref());
+    }
+}
+
+/// Writes the event of a revision back to its file, restoring an earlier
+/// version or resurrecting a deleted event. Returns the path of the file.
+pub fn restore_revision(path: &Path, revision: &EventRevision) -> Result<PathBuf, Box<dyn Error>> {
+    let event = revision.event.as_ref().ok_or_else(|| {
+        format!(
+            "Revision {} deleted '{}' and can't be restored",
+            revision.short_id(),
+            revision.file_name
+        )
+    })?;
+    let file_path = path.join(&revision.file_name);
+    fs::write(&file_path, FileEventRepository::event_to_markdown(event))?;
+    Ok(file_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+    use tempfile::TempDir;
+
+    fn event(description: &str) -> CalendarEvent {
+        CalendarEvent::new(
+            "Standup".to_string(),
+            description.to_string(),
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        )
+    }
+
+    fn revision(commit: &str, event: Option<CalendarEvent>) -> EventRevision {
+        EventRevision {
+            commit: commit.to_string(),
+            file_name: "Standup.md".to_string(),
+            author: "Alice".to_string(),
+            date: DateTime::parse_from_rfc3339("2025-03-01T10:00:00+01:00").unwrap(),
+            summary: "Sync events".to_string(),
+            event,
+            changes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_field_changes() {
+        let changes = field_changes(Some(&event("Room 1")), Some(&event("Room 2")));
+        assert_eq!(
+            changes,
+            vec![FieldChange {
+                field: EventField::Description,
+                old: Some("Room 1".to_string()),
+                new: Some("Room 2".to_string()),
+            }]
+        );
+        assert_eq!(
+            field_changes(None, Some(&event("Room 1"))).len(),
+            EventField::ALL.len()
+        );
+    }
+
+    #[test]
+    fn test_link_revisions() {
+        let mut revisions = vec![
+            revision("ccc", None),
+            revision("bbb", Some(event("Room 2"))),
+            revision("aaa", Some(event("Room 1"))),
+        ];
+        link_revisions(&mut revisions);
+        assert!(revisions[0].changes.iter().all(|c| c.new.is_none()));
+        assert_eq!(revisions[1].changes.len(), 1);
+        assert!(revisions[2].changes.iter().all(|c| c.old.is_none()));
+    }
+
+    #[test]
+    fn test_restore_revision() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = restore_revision(
+            temp_dir.path(),
+            &revision("1234567890", Some(event("Room 1"))),
+        )
+        .unwrap();
+        let restored =
+            FileEventRepository::event_from_markdown(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(restored.description, "Room 1");
+
+        let err = restore_revision(temp_dir.path(), &revision("1234567890", None)).unwrap_err();
+        assert!(err.to_string().contains("1234567"));
+    }
+}
@@ -0,0 +1,521 @@
+//! Durable journal of pending sync operations.
+//!
+//! When a push fails (no network, authentication failure, unavailable sync
+//! directory), the operation that triggered it is recorded in a journal file
+//! inside the calendar directory. The journal survives restarts and is
+//! replayed on the next successful connection. Since every provider pushes
+//! the whole calendar directory, one successful push flushes all entries
+//! that were recorded before it started.
+//!
+//! Pushes may run concurrently, such as the background pushes of the TUI, so
+//! every change to the journal is made under a lock (see `SyncJournal::update`).
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{CalendarEvent, SyncStatus};
+use crate::sync::traits::SyncProvider;
+
+/// Name of the journal file, relative to the calendar directory.
+pub const SYNC_JOURNAL_FILE: &str = ".sync-journal.yaml";
+
+/// Name of the lock file taken while the journal is changed, relative to the
+/// calendar directory. It shares the prefix of the journal so sync providers
+/// leave both out.
+pub const SYNC_JOURNAL_LOCK_FILE: &str = ".sync-journal.yaml.lock";
+
+/// Serializes journal changes between the threads of this process; the lock
+/// file serializes them between processes.
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+/// Number of journal writes made by this process.
+static JOURNAL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a number that changes whenever this process writes a journal, so
+/// a cached `pending_count` only needs to be re-read when it differs.
+pub fn journal_generation() -> u64 {
+    JOURNAL_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Kind of change waiting to be synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    /// An event was created or edited.
+    Save,
+    /// An event was deleted.
+    Delete,
+    /// Old events were removed by the automatic cleanup.
+    Cleanup,
+    /// Events were imported from a file.
+    Import,
+}
+
+impl std::fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            OperationKind::Save => "save",
+            OperationKind::Delete => "delete",
+            OperationKind::Cleanup => "cleanup",
+            OperationKind::Import => "import",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A local change that has not reached the remote yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingOperation {
+    /// What kind of change this is.
+    pub kind: OperationKind,
+    /// Title of the affected event, empty for cleanups.
+    pub title: String,
+    /// Start date of the affected event.
+    pub date: Option<NaiveDate>,
+    /// When the operation was first queued.
+    pub queued_at: DateTime<Local>,
+    /// Number of failed push attempts.
+    pub attempts: u32,
+    /// Error message of the last failed attempt.
+    pub last_error: Option<String>,
+}
+
+impl PendingOperation {
+    /// Creates a pending operation for the given event.
+    pub fn for_event(kind: OperationKind, event: &CalendarEvent) -> Self {
+        Self {
+            kind,
+            title: event.title.clone(),
+            date: Some(event.start_date),
+            queued_at: Local::now(),
+            attempts: 0,
+            last_error: None,
+        }
+    }
+
+    /// Returns true if both describe the same queued change, even when
+    /// failed attempts were recorded on one of them since.
+    fn is_same(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.title == other.title
+            && self.date == other.date
+            && self.queued_at == other.queued_at
+    }
+
+    /// Creates a pending operation for an automatic cleanup run.
+    pub fn cleanup(deleted_count: usize) -> Self {
+        Self {
+            kind: OperationKind::Cleanup,
+            title: format!("{deleted_count} old events"),
+            date: None,
+            queued_at: Local::now(),
+            attempts: 0,
+            last_error: None,
+        }
+    }
+
+    /// Creates a pending operation for events imported from a file.
+    pub fn import(imported_count: usize) -> Self {
+        Self {
+            kind: OperationKind::Import,
+            title: format!("{imported_count} imported events"),
+            ..Self::cleanup(0)
+        }
+    }
+}
+
+/// Journal of pending sync operations for a calendar directory.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncJournal {
+    /// Pending operations, oldest first.
+    pub operations: Vec<PendingOperation>,
+}
+
+impl SyncJournal {
+    /// Loads the journal of a calendar directory, or an empty one if none exists.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let journal_path = path.join(SYNC_JOURNAL_FILE);
+        if !journal_path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_yaml::from_str(&fs::read_to_string(journal_path)?)?)
+    }
+
+    /// Saves the journal, removing the file when it is empty.
+    ///
+    /// This replaces the journal without taking the lock; use `update` to
+    /// change the journal of a calendar directory that others may write to.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        JOURNAL_GENERATION.fetch_add(1, Ordering::SeqCst);
+        let journal_path = path.join(SYNC_JOURNAL_FILE);
+        if self.operations.is_empty() {
+            if journal_path.exists() {
+                fs::remove_file(journal_path)?;
+            }
+            return Ok(());
+        }
+        // Write through a temporary file so concurrent readers never see a partial journal
+        let temp_path = path.join(format!("{SYNC_JOURNAL_FILE}.tmp"));
+        fs::write(&temp_path, serde_yaml::to_string(self)?)?;
+        fs::rename(temp_path, journal_path)?;
+        Ok(())
+    }
+
+    /// Loads the journal of a calendar directory, changes it with `change`
+    /// and saves it, holding the journal lock throughout so concurrent
+    /// changes from other threads or processes are not lost.
+    pub fn update<T>(
+        path: &Path,
+        change: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, Box<dyn Error>> {
+        let _guard = JOURNAL_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let lock_file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.join(SYNC_JOURNAL_LOCK_FILE))?;
+        // Released when the file is closed
+        lock_file.lock()?;
+        let mut journal = Self::load(path)?;
+        let result = change(&mut journal);
+        journal.save(path)?;
+        Ok(result)
+    }
+
+    /// Returns the number of pending operations.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Returns true if nothing is waiting to be synced.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Returns the number of pending operations of a calendar directory.
+    ///
+    /// An unreadable journal counts as empty.
+    pub fn pending_count(path: &Path) -> usize {
+        Self::load(path).map(|journal| journal.len()).unwrap_or(0)
+    }
+
+    /// Appends an operation to the journal of a calendar directory.
+    pub fn record(path: &Path, operation: PendingOperation) -> Result<(), Box<dyn Error>> {
+        Self::update(path, |journal| journal.operations.push(operation))
+    }
+
+    /// Removes the operations of `flushed`, the journal as it was loaded
+    /// before a successful push, from the journal of a calendar directory.
+    /// Operations recorded while the push ran are kept. Returns the number
+    /// of operations removed.
+    pub fn remove_flushed(path: &Path, flushed: &SyncJournal) -> Result<usize, Box<dyn Error>> {
+        Self::update(path, |journal| {
+            let pending = journal.len();
+            journal
+                .operations
+                .retain(|operation| !flushed.operations.iter().any(|f| f.is_same(operation)));
+            pending - journal.len()
+        })
+    }
+
+    /// Records a failed attempt on every pending operation.
+    fn mark_failed(&mut self, error: &str) {
+        for operation in &mut self.operations {
+            operation.attempts += 1;
+            operation.last_error = Some(error.to_string());
+        }
+    }
+}
+
+/// Pushes the calendar directory, journaling `operation` if the push fails.
+///
+/// A successful push also flushes the operations pending when it started.
+/// A push stopped on conflicts journals `operation` without counting it as
+/// a failed attempt.
+pub fn push_with_journal(
+    provider: &dyn SyncProvider,
+    path: &Path,
+    mut operation: PendingOperation,
+) -> Result<SyncStatus, Box<dyn Error>> {
+    // The lock is not held during the push, so other pushes may record
+    // operations meanwhile; only the ones read here are flushed
+    let pending = SyncJournal::load(path).unwrap_or_default();
+    match push_checked(provider, path) {
+        Ok(SyncStatus::Conflicts) => {
+            SyncJournal::record(path, operation)?;
+            Ok(SyncStatus::Conflicts)
+        }
+        Ok(status) => {
+            SyncJournal::remove_flushed(path, &pending)?;
+            Ok(status)
+        }
+        Err(e) => {
+            operation.attempts += 1;
+            operation.last_error = Some(e.to_string());
+            SyncJournal::update(path, |journal| {
+                journal.mark_failed(&e.to_string());
+                journal.operations.push(operation);
+            })?;
+            Err(e)
+        }
+    }
+}
+
+/// Replays the journal by pushing the calendar directory.
+///
+/// Returns the number of operations flushed, which is zero when nothing was
+/// pending. On failure the attempt is recorded and the journal is kept, and
+/// a push stopped on conflicts keeps it too.
+pub fn replay_journal(provider: &dyn SyncProvider, path: &Path) -> Result<usize, Box<dyn Error>> {
+    let pending = SyncJournal::load(path)?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    match push_checked(provider, path) {
+        Ok(SyncStatus::Conflicts) => Err("push stopped on conflicts".into()),
+        Ok(_) => SyncJournal::remove_flushed(path, &pending),
+        Err(e) => {
+            SyncJournal::update(path, |journal| journal.mark_failed(&e.to_string()))?;
+            Err(e)
+        }
+    }
+}
+
+/// Pushes and treats a `SyncStatus::Error` result as a failure.
+fn push_checked(provider: &dyn SyncProvider, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+    match provider.push(path)? {
+        SyncStatus::Error(e) => Err(e.into()),
+        status => Ok(status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tempfile::TempDir;
+
+    /// Provider whose pushes fail while `online` is false.
+    struct FlakyProvider {
+        online: AtomicBool,
+    }
+
+    impl SyncProvider for FlakyProvider {
+        fn init(&self, _path: &Path) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn pull(&self, _path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+            Ok(SyncStatus::UpToDate)
+        }
+
+        fn push(&self, _path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+            if self.online.load(Ordering::SeqCst) {
+                Ok(SyncStatus::UpToDate)
+            } else {
+                Err("network unreachable".into())
+            }
+        }
+
+        fn status(&self, _path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+            Ok(SyncStatus::UpToDate)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// Provider whose pushes return `status` after another push journaled
+    /// an operation while they ran.
+    struct RacingProvider {
+        status: SyncStatus,
+    }
+
+    impl SyncProvider for RacingProvider {
+        fn init(&self, _path: &Path) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn pull(&self, _path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+            Ok(SyncStatus::UpToDate)
+        }
+
+        fn push(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+            let op = PendingOperation::for_event(OperationKind::Save, &event("Concurrent"));
+            SyncJournal::record(path, op)?;
+            Ok(self.status.clone())
+        }
+
+        fn status(&self, _path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+            Ok(SyncStatus::UpToDate)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn event(title: &str) -> CalendarEvent {
+        CalendarEvent::new(
+            title.to_string(),
+            String::new(),
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_load_missing_journal_is_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = SyncJournal::load(temp_dir.path()).unwrap();
+        assert!(journal.is_empty());
+        assert_eq!(SyncJournal::pending_count(temp_dir.path()), 0);
+    }
+
+    #[test]
+    fn test_failed_push_is_journaled() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = FlakyProvider {
+            online: AtomicBool::new(false),
+        };
+
+        let op = PendingOperation::for_event(OperationKind::Save, &event("Dentist"));
+        assert!(push_with_journal(&provider, temp_dir.path(), op).is_err());
+        let op = PendingOperation::for_event(OperationKind::Delete, &event("Gym"));
+        assert!(push_with_journal(&provider, temp_dir.path(), op).is_err());
+
+        let journal = SyncJournal::load(temp_dir.path()).unwrap();
+        assert_eq!(journal.len(), 2);
+        assert_eq!(journal.operations[0].kind, OperationKind::Save);
+        assert_eq!(journal.operations[0].title, "Dentist");
+        assert_eq!(journal.operations[0].attempts, 2);
+        assert_eq!(journal.operations[1].kind, OperationKind::Delete);
+        assert_eq!(journal.operations[1].attempts, 1);
+        assert_eq!(
+            journal.operations[1].last_error.as_deref(),
+            Some("network unreachable")
+        );
+    }
+
+    #[test]
+    fn test_replay_flushes_journal_when_online() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = FlakyProvider {
+            online: AtomicBool::new(false),
+        };
+        let op = PendingOperation::for_event(OperationKind::Save, &event("Dentist"));
+        let _ = push_with_journal(&provider, temp_dir.path(), op);
+
+        assert!(replay_journal(&provider, temp_dir.path()).is_err());
+        assert_eq!(
+            SyncJournal::load(temp_dir.path()).unwrap().operations[0].attempts,
+            2
+        );
+
+        provider.online.store(true, Ordering::SeqCst);
+        assert_eq!(replay_journal(&provider, temp_dir.path()).unwrap(), 1);
+        assert_eq!(SyncJournal::pending_count(temp_dir.path()), 0);
+        assert!(!temp_dir.path().join(SYNC_JOURNAL_FILE).exists());
+        assert_eq!(replay_journal(&provider, temp_dir.path()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_successful_push_clears_earlier_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        SyncJournal::record(temp_dir.path(), PendingOperation::cleanup(3)).unwrap();
+        let provider = FlakyProvider {
+            online: AtomicBool::new(true),
+        };
+
+        let op = PendingOperation::for_event(OperationKind::Save, &event("Dentist"));
+        assert_eq!(
+            push_with_journal(&provider, temp_dir.path(), op).unwrap(),
+            SyncStatus::UpToDate
+        );
+        assert_eq!(SyncJournal::pending_count(temp_dir.path()), 0);
+    }
+
+    #[test]
+    fn test_push_keeps_operations_recorded_during_it() {
+        let temp_dir = TempDir::new().unwrap();
+        SyncJournal::record(temp_dir.path(), PendingOperation::cleanup(3)).unwrap();
+        let provider = RacingProvider {
+            status: SyncStatus::UpToDate,
+        };
+
+        let op = PendingOperation::for_event(OperationKind::Save, &event("Dentist"));
+        push_with_journal(&provider, temp_dir.path(), op).unwrap();
+        let journal = SyncJournal::load(temp_dir.path()).unwrap();
+        assert_eq!(journal.len(), 1);
+        assert_eq!(journal.operations[0].title, "Concurrent");
+
+        // A replay flushes the earlier operation but not the new one
+        assert_eq!(replay_journal(&provider, temp_dir.path()).unwrap(), 1);
+        assert_eq!(SyncJournal::pending_count(temp_dir.path()), 1);
+    }
+
+    #[test]
+    fn test_push_stopped_on_conflicts_keeps_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        SyncJournal::record(temp_dir.path(), PendingOperation::cleanup(3)).unwrap();
+        let provider = RacingProvider {
+            status: SyncStatus::Conflicts,
+        };
+
+        let op = PendingOperation::for_event(OperationKind::Save, &event("Dentist"));
+        assert_eq!(
+            push_with_journal(&provider, temp_dir.path(), op).unwrap(),
+            SyncStatus::Conflicts
+        );
+        let journal = SyncJournal::load(temp_dir.path()).unwrap();
+        let titles: Vec<_> = journal
+            .operations
+            .iter()
+            .map(|op| op.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["3 old events", "Concurrent", "Dentist"]);
+        assert_eq!(journal.operations[2].attempts, 0);
+
+        assert!(replay_journal(&provider, temp_dir.path()).is_err());
+        assert_eq!(SyncJournal::pending_count(temp_dir.path()), 4);
+    }
+
+    #[test]
+    fn test_concurrent_failed_pushes_are_all_journaled() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = FlakyProvider {
+            online: AtomicBool::new(false),
+        };
+        let generation = journal_generation();
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let provider = &provider;
+                let path = temp_dir.path();
+                scope.spawn(move || {
+                    let op = PendingOperation::for_event(
+                        OperationKind::Save,
+                        &event(&format!("Event {i}")),
+                    );
+                    assert!(push_with_journal(provider, path, op).is_err());
+                });
+            }
+        });
+
+        let journal = SyncJournal::load(temp_dir.path()).unwrap();
+        assert_eq!(journal.len(), 8);
+        let mut titles: Vec<_> = journal.operations.iter().map(|op| &op.title).collect();
+        titles.sort();
+        titles.dedup();
+        assert_eq!(titles.len(), 8);
+        assert!(journal_generation() >= generation + 8);
+    }
+}
@@ -1,10 +1,65 @@
 //! Sync module - synchronization providers for calendar data.
 //!
 //! Provides traits and implementations for syncing calendar events
-//! with remote repositories (e.g., Git).
+//! with remote repositories (e.g., Git, CalDAV, plain directories).
 
+#[cfg(feature = "caldav")]
+pub mod caldav_sync;
+pub mod conflict;
+pub mod dir_sync;
 pub mod git_sync;
+pub mod history;
+pub mod journal;
+#[cfg(feature = "native-git")]
+pub mod native_git_sync;
 pub mod traits;
 
+#[cfg(feature = "caldav")]
+pub use caldav_sync::{
+    CalDavConflict, CalDavError, CalDavSyncProvider, ConflictResolution, RemoteVersion,
+    CALDAV_STATE_FILE,
+};
+pub use conflict::{ConflictChoices, ConflictSide, ResolvedConflict, SyncConflict};
+pub use dir_sync::{DirectorySyncProvider, DIR_SYNC_STATE_FILE};
 pub use git_sync::GitSyncProvider;
+pub use history::{field_changes, restore_revision, EventRevision, FieldChange};
+pub use journal::{
+    journal_generation, push_with_journal, replay_journal, OperationKind, PendingOperation,
+    SyncJournal, SYNC_JOURNAL_FILE, SYNC_JOURNAL_LOCK_FILE,
+};
+#[cfg(feature = "native-git")]
+pub use native_git_sync::{GitError, GitProgress, GitProgressCallback, NativeGitSyncProvider};
 pub use traits::SyncProvider;
+
+use crate::hash::fnv1a;
+use crate::storage::DynSyncProvider;
+
+/// Recovers a sync provider from the type-erased form taken by
+/// `EventRepository::save_with_sync()`: either a `Box<dyn SyncProvider>` or
+/// one of the providers of this module.
+pub fn as_sync_provider(provider: &DynSyncProvider) -> Option<&dyn SyncProvider> {
+    if let Some(boxed) = provider.downcast_ref::<Box<dyn SyncProvider>>() {
+        return Some(boxed.as_ref());
+    }
+    if let Some(git) = provider.downcast_ref::<GitSyncProvider>() {
+        return Some(git);
+    }
+    #[cfg(feature = "native-git")]
+    if let Some(native) = provider.downcast_ref::<NativeGitSyncProvider>() {
+        return Some(native);
+    }
+    #[cfg(feature = "caldav")]
+    if let Some(caldav) = provider.downcast_ref::<CalDavSyncProvider>() {
+        return Some(caldav);
+    }
+    provider
+        .downcast_ref::<DirectorySyncProvider>()
+        .map(|dir| dir as &dyn SyncProvider)
+}
+
+/// Stable 64-bit FNV-1a hash of file content, hex encoded.
+///
+/// Used by the sync providers to detect changes since the last sync.
+pub(crate) fn content_hash(content: &str) -> String {
+    format!("{:016x}", fnv1a(content.as_bytes()))
+}
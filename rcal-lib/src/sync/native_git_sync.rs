@@ -0,0 +1,914 @@
+//! Native Git synchronization provider.
+//!
+//! Implements the SyncProvider trait with libgit2 instead of spawning the
+//! `git` CLI, so it works without git on PATH and doesn't depend on parsing
+//! localized command output. Commits are made with a fixed signature rather
+//! than the user's git identity. Authentication uses the SSH agent or the
+//! default SSH keys. The git credential helper, which is found through the
+//! user's git config, is only asked for HTTPS passwords when enabled with
+//! `with_credential_helper`.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, FixedOffset};
+use git2::build::CheckoutBuilder;
+use git2::{
+    AnnotatedCommit, Commit, Cred, CredentialType, Delta, ErrorCode, FetchOptions, Index,
+    IndexAddOption, IndexEntry, Oid, PushOptions, RemoteCallbacks, Repository,
+    RepositoryInitOptions, Signature, Sort, Tree,
+};
+use thiserror::Error;
+
+use crate::models::{CalendarEvent, SyncStatus};
+use crate::sync::conflict::{parse_version, write_resolution, ResolvedConflict, SyncConflict};
+use crate::sync::history::{link_revisions, EventRevision};
+use crate::sync::journal::SYNC_JOURNAL_FILE;
+use crate::sync::traits::SyncProvider;
+
+/// Name of the remote used for synchronization.
+const REMOTE_NAME: &str = "origin";
+
+/// Commit message used for sync commits.
+const COMMIT_MESSAGE: &str = "Sync events";
+
+/// Commit message used for merges completing a conflict resolution.
+const MERGE_MESSAGE: &str = "Resolve sync conflicts";
+
+/// Maximum number of credential attempts before giving up on authentication.
+const MAX_CREDENTIAL_ATTEMPTS: usize = 4;
+
+/// Errors returned by the native Git provider.
+#[derive(Debug, Error)]
+pub enum GitError {
+    /// The calendar directory is not a git repository.
+    #[error("Not a git repository: {0}")]
+    NotARepository(PathBuf),
+
+    /// The remote rejected the credentials, or none were available.
+    #[error("Authentication failed for {0}")]
+    Authentication(String),
+
+    /// The remote refused to update a reference, e.g. because it is not a fast-forward.
+    #[error("Push of {reference} rejected: {reason}")]
+    PushRejected {
+        /// The rejected reference.
+        reference: String,
+        /// The reason reported by the remote.
+        reason: String,
+    },
+
+    /// Local and remote changes touch the same files.
+    #[error("Merge conflicts detected in {}. Please resolve manually.", .0.join(", "))]
+    Conflicts(Vec<String>),
+
+    /// Any other libgit2 error.
+    #[error("Git error: {0}")]
+    Git(#[from] git2::Error),
+}
+
+/// Progress reported during network operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitProgress {
+    /// Objects received while fetching.
+    Fetch {
+        /// Number of objects received so far.
+        received_objects: usize,
+        /// Total number of objects to receive.
+        total_objects: usize,
+        /// Number of bytes received so far.
+        received_bytes: usize,
+    },
+    /// Objects sent while pushing.
+    Push {
+        /// Number of objects sent so far.
+        current: usize,
+        /// Total number of objects to send.
+        total: usize,
+        /// Number of bytes sent so far.
+        bytes: usize,
+    },
+}
+
+/// Callback invoked with progress updates during fetch and push.
+pub type GitProgressCallback = Arc<dyn Fn(GitProgress) + Send + Sync>;
+
+/// Git sync provider backed by libgit2.
+#[derive(Clone)]
+pub struct NativeGitSyncProvider {
+    /// URL of the remote repository.
+    pub remote_url: String,
+    /// Branch to sync with.
+    pub branch: String,
+    /// Name used for sync commits.
+    pub author_name: String,
+    /// Email used for sync commits.
+    pub author_email: String,
+    /// Whether HTTPS passwords are asked from the git credential helper.
+    pub credential_helper: bool,
+    progress: Option<GitProgressCallback>,
+}
+
+impl NativeGitSyncProvider {
+    /// Creates a new NativeGitSyncProvider with the given remote URL.
+    pub fn new(remote_url: String) -> Self {
+        Self {
+            remote_url,
+            branch: "main".to_string(),
+            author_name: "rcal".to_string(),
+            author_email: "rcal@localhost".to_string(),
+            credential_helper: false,
+            progress: None,
+        }
+    }
+
+    /// Sets the branch to sync with.
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = branch.to_string();
+        self
+    }
+
+    /// Sets the signature used for sync commits.
+    pub fn with_author(mut self, name: &str, email: &str) -> Self {
+        self.author_name = name.to_string();
+        self.author_email = email.to_string();
+        self
+    }
+
+    /// Sets whether HTTPS passwords are asked from the git credential helper
+    /// configured in the user's git config. Disabled by default.
+    pub fn with_credential_helper(mut self, enabled: bool) -> Self {
+        self.credential_helper = enabled;
+        self
+    }
+
+    /// Sets a callback that receives progress updates during fetch and push.
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(GitProgress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    fn open(&self, path: &Path) -> Result<Repository, GitError> {
+        Repository::open(path).map_err(|e| match e.code() {
+            ErrorCode::NotFound => GitError::NotARepository(path.to_path_buf()),
+            _ => GitError::Git(e),
+        })
+    }
+
+    fn signature(&self) -> Result<Signature<'static>, GitError> {
+        Ok(Signature::now(&self.author_name, &self.author_email)?)
+    }
+
+    fn local_ref(&self) -> String {
+        format!("refs/heads/{}", self.branch)
+    }
+
+    fn remote_ref(&self) -> String {
+        format!("refs/remotes/{REMOTE_NAME}/{}", self.branch)
+    }
+
+    fn callbacks(&self) -> RemoteCallbacks<'static> {
+        let mut callbacks = RemoteCallbacks::new();
+        let mut attempts = 0;
+        let credential_helper = self.credential_helper;
+        callbacks.credentials(move |url, username, allowed| {
+            attempts += 1;
+            credentials(url, username, allowed, attempts, credential_helper)
+        });
+        if let Some(progress) = self.progress.clone() {
+            let fetch_progress = progress.clone();
+            callbacks.transfer_progress(move |stats| {
+                fetch_progress(GitProgress::Fetch {
+                    received_objects: stats.received_objects(),
+                    total_objects: stats.total_objects(),
+                    received_bytes: stats.received_bytes(),
+                });
+                true
+            });
+            callbacks.push_transfer_progress(move |current, total, bytes| {
+                progress(GitProgress::Push {
+                    current,
+                    total,
+                    bytes,
+                });
+            });
+        }
+        callbacks
+    }
+
+    fn fetch(&self, repo: &Repository) -> Result<(), GitError> {
+        let mut remote = repo.find_remote(REMOTE_NAME)?;
+        let refspec = format!("+{}:{}", self.local_ref(), self.remote_ref());
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(self.callbacks());
+        remote
+            .fetch(&[refspec], Some(&mut options), None)
+            .map_err(|e| self.remote_error(e))
+    }
+
+    fn remote_error(&self, e: git2::Error) -> GitError {
+        match e.code() {
+            ErrorCode::Auth => GitError::Authentication(self.remote_url.clone()),
+            _ => GitError::Git(e),
+        }
+    }
+
+    /// Stages all changes except local sync bookkeeping and returns the
+    /// resulting tree.
+    fn stage_all(&self, repo: &Repository) -> Result<(Index, Oid), GitError> {
+        let mut index = repo.index()?;
+        let mut skip_journal = |path: &Path, _: &[u8]| -> i32 {
+            i32::from(
+                path.to_str()
+                    .is_some_and(|p| p.starts_with(SYNC_JOURNAL_FILE)),
+            )
+        };
+        index.add_all(["*"], IndexAddOption::DEFAULT, Some(&mut skip_journal))?;
+        index.update_all(["*"], Some(&mut skip_journal))?;
+        index.write()?;
+        let tree_id = index.write_tree()?;
+        Ok((index, tree_id))
+    }
+
+    /// Stages all changes except local sync bookkeeping and commits them.
+    /// Returns true if a commit was made.
+    fn commit_all(&self, repo: &Repository) -> Result<bool, GitError> {
+        let (index, tree_id) = self.stage_all(repo)?;
+
+        let parent = match repo.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+            Err(e) => return Err(e.into()),
+        };
+        let unchanged = match &parent {
+            Some(parent) => parent.tree_id() == tree_id,
+            None => index.is_empty(),
+        };
+        if unchanged {
+            return Ok(false);
+        }
+
+        let tree = repo.find_tree(tree_id)?;
+        let signature = self.signature()?;
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            COMMIT_MESSAGE,
+            &tree,
+            &parents,
+        )?;
+        Ok(true)
+    }
+
+    /// Integrates the fetched remote branch into the local branch, rebasing
+    /// local commits on top of it when both sides have new commits.
+    fn integrate(&self, repo: &Repository) -> Result<(), GitError> {
+        let upstream_id = match repo.refname_to_id(&self.remote_ref()) {
+            Ok(id) => id,
+            // Nothing on the remote yet
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let upstream = repo.find_annotated_commit(upstream_id)?;
+
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(e) if e.code() == ErrorCode::UnbornBranch => {
+                return self.fast_forward(repo, upstream_id);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let (analysis, _) = repo.merge_analysis(&[&upstream])?;
+        if analysis.is_up_to_date() {
+            Ok(())
+        } else if analysis.is_fast_forward() {
+            self.fast_forward(repo, upstream_id)
+        } else {
+            // Rebase from the branch reference so finishing updates the branch
+            let local = repo.reference_to_annotated_commit(&head)?;
+            self.rebase(repo, &local, &upstream)
+        }
+    }
+
+    fn fast_forward(&self, repo: &Repository, target: Oid) -> Result<(), GitError> {
+        repo.reference(&self.local_ref(), target, true, "rcal: fast-forward")?;
+        repo.set_head(&self.local_ref())?;
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+        Ok(())
+    }
+
+    fn rebase(
+        &self,
+        repo: &Repository,
+        local: &AnnotatedCommit,
+        upstream: &AnnotatedCommit,
+    ) -> Result<(), GitError> {
+        let signature = self.signature()?;
+        let mut rebase = repo.rebase(Some(local), Some(upstream), None, None)?;
+        while let Some(operation) = rebase.next() {
+            operation?;
+            let index = repo.index()?;
+            if index.has_conflicts() {
+                let paths = index
+                    .conflicts()?
+                    .filter_map(|conflict| conflict.ok())
+                    .filter_map(|conflict| conflict.our.or(conflict.their))
+                    .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+                    .collect();
+                rebase.abort()?;
+                return Err(GitError::Conflicts(paths));
+            }
+            match rebase.commit(None, &signature, None) {
+                Ok(_) => {}
+                // The change already exists upstream
+                Err(e) if e.code() == ErrorCode::Applied => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        rebase.finish(Some(&signature))?;
+        Ok(())
+    }
+
+    fn push_branch(&self, repo: &Repository) -> Result<(), GitError> {
+        let mut remote = repo.find_remote(REMOTE_NAME)?;
+        let mut rejection = None;
+        let mut callbacks = self.callbacks();
+        callbacks.push_update_reference(|reference, status| {
+            if let Some(reason) = status {
+                rejection = Some(GitError::PushRejected {
+                    reference: reference.to_string(),
+                    reason: reason.to_string(),
+                });
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        let refspec = format!("{}:{}", self.local_ref(), self.local_ref());
+        remote
+            .push(&[refspec], Some(&mut options))
+            .map_err(|e| self.remote_error(e))?;
+        drop(options);
+        if let Some(rejection) = rejection {
+            return Err(rejection);
+        }
+
+        // Keep the remote-tracking branch in sync with what was pushed
+        let head = repo.refname_to_id(&self.local_ref())?;
+        repo.reference(&self.remote_ref(), head, true, "rcal: push")?;
+        Ok(())
+    }
+
+    /// Returns the local and the upstream commit if both have commits the
+    /// other one lacks.
+    fn diverged<'r>(
+        &self,
+        repo: &'r Repository,
+    ) -> Result<Option<(Commit<'r>, Commit<'r>)>, GitError> {
+        let upstream = match repo.refname_to_id(&self.remote_ref()) {
+            Ok(id) => id,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let local = match repo.head() {
+            Ok(head) => head.peel_to_commit()?,
+            Err(e) if e.code() == ErrorCode::UnbornBranch => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let (ahead, behind) = repo.graph_ahead_behind(local.id(), upstream)?;
+        if ahead == 0 || behind == 0 {
+            return Ok(None);
+        }
+        Ok(Some((local, repo.find_commit(upstream)?)))
+    }
+
+    fn compute_status(&self, repo: &Repository) -> Result<SyncStatus, GitError> {
+        let upstream = match repo.refname_to_id(&self.remote_ref()) {
+            Ok(id) => id,
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                return Ok(SyncStatus::Error("No remote branch found".to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let local = match repo.head() {
+            Ok(head) => head.peel_to_commit()?.id(),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => return Ok(SyncStatus::Behind),
+            Err(e) => return Err(e.into()),
+        };
+
+        let (ahead, behind) = repo.graph_ahead_behind(local, upstream)?;
+        Ok(match (ahead > 0, behind > 0) {
+            (true, true) => SyncStatus::Conflicts,
+            (true, false) => SyncStatus::Ahead,
+            (false, true) => SyncStatus::Behind,
+            (false, false) => SyncStatus::UpToDate,
+        })
+    }
+}
+
+impl SyncProvider for NativeGitSyncProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn init(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        std::fs::create_dir_all(path)?;
+        let repo = match Repository::open(path) {
+            Ok(repo) => repo,
+            Err(_) => {
+                let mut options = RepositoryInitOptions::new();
+                options.initial_head(&self.branch);
+                Repository::init_opts(path, &options)?
+            }
+        };
+
+        match repo.find_remote(REMOTE_NAME) {
+            Ok(remote) if remote.url() == Some(self.remote_url.as_str()) => {}
+            Ok(_) => repo.remote_set_url(REMOTE_NAME, &self.remote_url)?,
+            Err(_) => {
+                repo.remote(REMOTE_NAME, &self.remote_url)?;
+            }
+        }
+
+        self.fetch(&repo)?;
+        Ok(())
+    }
+
+    fn pull(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        let repo = self.open(path)?;
+        self.fetch(&repo)?;
+        // Commit local edits first so they are rebased instead of overwritten
+        self.commit_all(&repo)?;
+        self.integrate(&repo)?;
+        Ok(self.compute_status(&repo)?)
+    }
+
+    fn push(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        let repo = self.open(path)?;
+        let committed = self.commit_all(&repo)?;
+        if !committed && self.compute_status(&repo)? == SyncStatus::UpToDate {
+            return Ok(SyncStatus::UpToDate);
+        }
+        // An empty repository has nothing to push
+        if repo.head().is_err() {
+            return Ok(self.compute_status(&repo)?);
+        }
+        self.push_branch(&repo)?;
+        Ok(self.compute_status(&repo)?)
+    }
+
+    /// Merges the local and the upstream branch in memory and reports the
+    /// files changed on both sides.
+    fn sync_conflicts(&self, path: &Path) -> Result<Vec<SyncConflict>, Box<dyn Error>> {
+        let repo = self.open(path)?;
+        self.commit_all(&repo)?;
+        let Some((local, upstream)) = self.diverged(&repo)? else {
+            return Ok(Vec::new());
+        };
+        let index = repo.merge_commits(&local, &upstream, None)?;
+        let mut conflicts = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let Some(entry) = conflict.our.as_ref().or(conflict.their.as_ref()) else {
+                continue;
+            };
+            conflicts.push(SyncConflict {
+                file_name: String::from_utf8_lossy(&entry.path).to_string(),
+                local: blob_event(&repo, conflict.our.as_ref())?,
+                remote: blob_event(&repo, conflict.their.as_ref())?,
+            });
+        }
+        Ok(conflicts)
+    }
+
+    /// Completes the sync with a merge commit holding the resolved files.
+    fn resolve_sync_conflicts(
+        &self,
+        path: &Path,
+        resolutions: &[ResolvedConflict],
+    ) -> Result<SyncStatus, Box<dyn Error>> {
+        let repo = self.open(path)?;
+        self.commit_all(&repo)?;
+        let Some((local, upstream)) = self.diverged(&repo)? else {
+            return self.push(path);
+        };
+
+        let mut merged = repo.merge_commits(&local, &upstream, None)?;
+        for conflict in merged.conflicts()? {
+            let conflict = conflict?;
+            let Some(entry) = conflict.our.as_ref().or(conflict.their.as_ref()) else {
+                continue;
+            };
+            let file_name = String::from_utf8_lossy(&entry.path).to_string();
+            if !resolutions.iter().any(|r| r.file_name == file_name) {
+                return Err(GitError::Conflicts(vec![file_name]).into());
+            }
+        }
+
+        // Bring in the remote changes, then overwrite the conflicting files
+        repo.checkout_index(
+            Some(&mut merged),
+            Some(CheckoutBuilder::new().force().allow_conflicts(true)),
+        )?;
+        for resolution in resolutions {
+            write_resolution(path, resolution)?;
+        }
+
+        let (_, tree_id) = self.stage_all(&repo)?;
+        let tree = repo.find_tree(tree_id)?;
+        let signature = self.signature()?;
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            MERGE_MESSAGE,
+            &tree,
+            &[&local, &upstream],
+        )?;
+        self.push_branch(&repo)?;
+        Ok(self.compute_status(&repo)?)
+    }
+
+    fn event_history(
+        &self,
+        path: &Path,
+        file_name: &str,
+    ) -> Result<Vec<EventRevision>, Box<dyn Error>> {
+        let repo = self.open(path)?;
+        let mut revisions = Vec::new();
+        for commit in history(&repo)? {
+            let blob = tree_blob(&commit.tree()?, file_name);
+            let parent_blob = match commit.parent(0) {
+                Ok(parent) => tree_blob(&parent.tree()?, file_name),
+                Err(_) => None,
+            };
+            if blob == parent_blob {
+                continue;
+            }
+            let event = match blob {
+                Some(id) => parse_blob(&repo, id)?,
+                None => None,
+            };
+            revisions.push(revision(&commit, file_name, event));
+        }
+        link_revisions(&mut revisions);
+        Ok(revisions)
+    }
+
+    fn deleted_events(&self, path: &Path) -> Result<Vec<EventRevision>, Box<dyn Error>> {
+        let repo = self.open(path)?;
+        let mut seen = HashSet::new();
+        let mut deleted = Vec::new();
+        for commit in history(&repo)? {
+            // Like `git log`, merges are not diffed against their parents
+            if commit.parent_count() != 1 {
+                continue;
+            }
+            let parent_tree = commit.parent(0)?.tree()?;
+            let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&commit.tree()?), None)?;
+            for delta in diff.deltas() {
+                if delta.status() != Delta::Deleted {
+                    continue;
+                }
+                let Some(file_name) = delta.old_file().path().and_then(|p| p.to_str()) else {
+                    continue;
+                };
+                if !file_name.ends_with(".md")
+                    || path.join(file_name).exists()
+                    || !seen.insert(file_name.to_string())
+                {
+                    continue;
+                }
+                let event = parse_blob(&repo, delta.old_file().id())?;
+                deleted.push(revision(&commit, file_name, event));
+            }
+        }
+        Ok(deleted)
+    }
+
+    fn status(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>> {
+        let repo = match self.open(path) {
+            Ok(repo) => repo,
+            Err(GitError::NotARepository(_)) => {
+                return Ok(SyncStatus::Error("Not a git repository".to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(self.compute_status(&repo)?)
+    }
+}
+
+/// Parses the event stored in a conflict side's blob.
+fn blob_event(
+    repo: &Repository,
+    entry: Option<&IndexEntry>,
+) -> Result<Option<CalendarEvent>, GitError> {
+    match entry {
+        Some(entry) => parse_blob(repo, entry.id),
+        None => Ok(None),
+    }
+}
+
+/// Parses the event stored in a blob.
+fn parse_blob(repo: &Repository, id: Oid) -> Result<Option<CalendarEvent>, GitError> {
+    let blob = repo.find_blob(id)?;
+    Ok(parse_version(std::str::from_utf8(blob.content()).ok()))
+}
+
+/// Returns the commits reachable from HEAD, newest first.
+fn history(repo: &Repository) -> Result<Vec<Commit<'_>>, GitError> {
+    // An empty repository has no history
+    if repo.head().is_err() {
+        return Ok(Vec::new());
+    }
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    walk.map(|oid| Ok(repo.find_commit(oid?)?)).collect()
+}
+
+/// Returns the blob id of a file in a tree.
+fn tree_blob(tree: &Tree, file_name: &str) -> Option<Oid> {
+    tree.get_path(Path::new(file_name))
+        .ok()
+        .map(|entry| entry.id())
+}
+
+/// Builds a revision from the commit that changed an event file.
+fn revision(commit: &Commit, file_name: &str, event: Option<CalendarEvent>) -> EventRevision {
+    let author = commit.author();
+    let when = author.when();
+    let offset = FixedOffset::east_opt(when.offset_minutes() * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    let date = DateTime::from_timestamp(when.seconds(), 0)
+        .unwrap_or_default()
+        .with_timezone(&offset);
+    EventRevision {
+        commit: commit.id().to_string(),
+        file_name: file_name.to_string(),
+        author: author.name().unwrap_or_default().to_string(),
+        date,
+        summary: commit.summary().unwrap_or_default().to_string(),
+        event,
+        changes: Vec::new(),
+    }
+}
+
+/// Supplies credentials for the given attempt, trying the SSH agent first,
+/// then the default SSH keys, then the git credential helper if enabled.
+fn credentials(
+    url: &str,
+    username: Option<&str>,
+    allowed: CredentialType,
+    attempt: usize,
+    credential_helper: bool,
+) -> Result<Cred, git2::Error> {
+    if attempt > MAX_CREDENTIAL_ATTEMPTS {
+        return Err(git2::Error::new(
+            ErrorCode::Auth,
+            git2::ErrorClass::Callback,
+            "no usable credentials",
+        ));
+    }
+    let username = username.unwrap_or("git");
+
+    if allowed.contains(CredentialType::USERNAME) {
+        return Cred::username(username);
+    }
+    if allowed.contains(CredentialType::SSH_KEY) {
+        if attempt == 1 {
+            if let Ok(cred) = Cred::ssh_key_from_agent(username) {
+                return Ok(cred);
+            }
+        }
+        let ssh_dir = dirs::home_dir().unwrap_or_default().join(".ssh");
+        let keys: Vec<PathBuf> = ["id_ed25519", "id_ecdsa", "id_rsa"]
+            .iter()
+            .map(|name| ssh_dir.join(name))
+            .filter(|key| key.exists())
+            .collect();
+        if let Some(key) = keys.get(attempt.saturating_sub(2)).or(keys.first()) {
+            return Cred::ssh_key(username, None, key, None);
+        }
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && credential_helper {
+        let config = git2::Config::open_default()?;
+        return Cred::credential_helper(&config, url, Some(username));
+    }
+    if allowed.contains(CredentialType::DEFAULT) {
+        return Cred::default();
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        return Err(git2::Error::new(
+            ErrorCode::Auth,
+            git2::ErrorClass::Callback,
+            "a password is required, but the git credential helper is disabled",
+        ));
+    }
+    Err(git2::Error::new(
+        ErrorCode::Auth,
+        git2::ErrorClass::Callback,
+        "no supported credential type",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Creates a bare repository and returns it with its file URL.
+    fn bare_remote() -> (TempDir, String) {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init_bare(temp_dir.path()).unwrap();
+        let url = format!("file://{}", temp_dir.path().display());
+        (temp_dir, url)
+    }
+
+    fn client(url: &str) -> (TempDir, NativeGitSyncProvider) {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = NativeGitSyncProvider::new(url.to_string());
+        provider.init(temp_dir.path()).unwrap();
+        (temp_dir, provider)
+    }
+
+    #[test]
+    fn test_native_git_provider_new() {
+        let provider = NativeGitSyncProvider::new("https://example.com/repo.git".to_string())
+            .with_branch("develop")
+            .with_author("Alice", "alice@example.com");
+        assert_eq!(provider.remote_url, "https://example.com/repo.git");
+        assert_eq!(provider.branch, "develop");
+        assert_eq!(provider.author_name, "Alice");
+        assert_eq!(provider.author_email, "alice@example.com");
+    }
+
+    #[test]
+    fn test_status_not_a_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = NativeGitSyncProvider::new("file:///nonexistent".to_string());
+        assert_eq!(
+            provider.status(temp_dir.path()).unwrap(),
+            SyncStatus::Error("Not a git repository".to_string())
+        );
+        let err = provider.pull(temp_dir.path()).unwrap_err();
+        assert!(err.downcast_ref::<GitError>().is_some());
+    }
+
+    #[test]
+    fn test_push_and_pull_between_clients() {
+        let (_remote, url) = bare_remote();
+        let (dir1, provider1) = client(&url);
+        fs::write(dir1.path().join("Meeting.md"), "# Event: Meeting\n").unwrap();
+        assert_eq!(provider1.push(dir1.path()).unwrap(), SyncStatus::UpToDate);
+
+        let (dir2, provider2) = client(&url);
+        assert_eq!(provider2.status(dir2.path()).unwrap(), SyncStatus::Behind);
+        assert_eq!(provider2.pull(dir2.path()).unwrap(), SyncStatus::UpToDate);
+        assert_eq!(
+            fs::read_to_string(dir2.path().join("Meeting.md")).unwrap(),
+            "# Event: Meeting\n"
+        );
+
+        // Deletions propagate
+        fs::remove_file(dir2.path().join("Meeting.md")).unwrap();
+        provider2.push(dir2.path()).unwrap();
+        provider1.pull(dir1.path()).unwrap();
+        assert!(!dir1.path().join("Meeting.md").exists());
+    }
+
+    #[test]
+    fn test_pull_rebases_diverged_changes() {
+        let (_remote, url) = bare_remote();
+        let (dir1, provider1) = client(&url);
+        fs::write(dir1.path().join("A.md"), "a").unwrap();
+        provider1.push(dir1.path()).unwrap();
+        let (dir2, provider2) = client(&url);
+        provider2.pull(dir2.path()).unwrap();
+
+        fs::write(dir1.path().join("B.md"), "b").unwrap();
+        provider1.push(dir1.path()).unwrap();
+        fs::write(dir2.path().join("C.md"), "c").unwrap();
+
+        assert_eq!(provider2.pull(dir2.path()).unwrap(), SyncStatus::Ahead);
+        assert!(dir2.path().join("B.md").exists());
+        assert!(dir2.path().join("C.md").exists());
+        assert_eq!(provider2.push(dir2.path()).unwrap(), SyncStatus::UpToDate);
+    }
+
+    #[test]
+    fn test_pull_reports_conflicts() {
+        let (_remote, url) = bare_remote();
+        let (dir1, provider1) = client(&url);
+        fs::write(dir1.path().join("A.md"), "base").unwrap();
+        provider1.push(dir1.path()).unwrap();
+        let (dir2, provider2) = client(&url);
+        provider2.pull(dir2.path()).unwrap();
+
+        fs::write(dir1.path().join("A.md"), "one").unwrap();
+        provider1.push(dir1.path()).unwrap();
+        fs::write(dir2.path().join("A.md"), "two").unwrap();
+
+        let err = provider2.pull(dir2.path()).unwrap_err();
+        match err.downcast_ref::<GitError>() {
+            Some(GitError::Conflicts(paths)) => assert_eq!(paths, &vec!["A.md".to_string()]),
+            other => panic!("expected conflicts, got {other:?}"),
+        }
+        // The rebase was aborted and the local edit kept
+        assert_eq!(fs::read_to_string(dir2.path().join("A.md")).unwrap(), "two");
+        assert_eq!(
+            provider2.status(dir2.path()).unwrap(),
+            SyncStatus::Conflicts
+        );
+    }
+
+    #[test]
+    fn test_push_rejected_when_behind() {
+        let (_remote, url) = bare_remote();
+        let (dir1, provider1) = client(&url);
+        fs::write(dir1.path().join("A.md"), "a").unwrap();
+        provider1.push(dir1.path()).unwrap();
+        let (dir2, provider2) = client(&url);
+        fs::write(dir2.path().join("B.md"), "b").unwrap();
+
+        let err = provider2.push(dir2.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<GitError>(),
+            Some(GitError::PushRejected { .. }) | Some(GitError::Git(_))
+        ));
+    }
+
+    #[test]
+    fn test_journal_is_not_committed() {
+        let (remote, url) = bare_remote();
+        let (dir, provider) = client(&url);
+        fs::write(dir.path().join("A.md"), "a").unwrap();
+        fs::write(dir.path().join(SYNC_JOURNAL_FILE), "operations: []").unwrap();
+        provider.push(dir.path()).unwrap();
+
+        let repo = Repository::open_bare(remote.path()).unwrap();
+        let tree = repo
+            .find_reference("refs/heads/main")
+            .unwrap()
+            .peel_to_tree()
+            .unwrap();
+        assert!(tree.get_name("A.md").is_some());
+        assert!(tree.get_name(SYNC_JOURNAL_FILE).is_none());
+    }
+
+    #[test]
+    fn test_progress_callback_reports_push() {
+        let (_remote, url) = bare_remote();
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&updates);
+        let temp_dir = TempDir::new().unwrap();
+        let provider = NativeGitSyncProvider::new(url)
+            .with_progress(move |progress| sink.lock().unwrap().push(progress));
+        provider.init(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join("A.md"), "a").unwrap();
+        provider.push(temp_dir.path()).unwrap();
+
+        assert!(updates
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|p| matches!(p, GitProgress::Push { .. })));
+    }
+
+    #[test]
+    fn test_authentication_error_is_typed() {
+        let provider = NativeGitSyncProvider::new("file:///nonexistent".to_string());
+        let e = git2::Error::new(ErrorCode::Auth, git2::ErrorClass::Ssh, "denied");
+        assert!(matches!(
+            provider.remote_error(e),
+            GitError::Authentication(url) if url == "file:///nonexistent"
+        ));
+    }
+
+    #[test]
+    fn test_credential_helper_is_opt_in() {
+        assert!(!NativeGitSyncProvider::new(String::new()).credential_helper);
+        let e = credentials(
+            "https://example.com/calendar.git",
+            Some("me"),
+            CredentialType::USER_PASS_PLAINTEXT,
+            1,
+            false,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(e.code(), ErrorCode::Auth);
+        assert!(e.message().contains("credential helper is disabled"));
+    }
+}
@@ -7,6 +7,8 @@ use std::error::Error;
 use std::path::Path;
 
 use crate::models::SyncStatus;
+use crate::sync::conflict::{ResolvedConflict, SyncConflict};
+use crate::sync::history::EventRevision;
 
 /// Trait for implementing synchronization providers.
 ///
@@ -25,6 +27,40 @@ pub trait SyncProvider: Send + Sync {
     /// Gets the current sync status.
     fn status(&self, path: &Path) -> Result<SyncStatus, Box<dyn Error>>;
 
+    /// Returns the events changed both locally and on the remote that the
+    /// user has to resolve before the sync can complete.
+    ///
+    /// Providers that never stop on conflicts return an empty list.
+    fn sync_conflicts(&self, _path: &Path) -> Result<Vec<SyncConflict>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    /// Applies the user's resolutions and completes the interrupted sync.
+    fn resolve_sync_conflicts(
+        &self,
+        _path: &Path,
+        _resolutions: &[ResolvedConflict],
+    ) -> Result<SyncStatus, Box<dyn Error>> {
+        Err("This sync provider does not support conflict resolution".into())
+    }
+
+    /// Returns the committed versions of an event file, newest first.
+    ///
+    /// Works for deleted files too; the newest revision is then the deletion.
+    fn event_history(
+        &self,
+        _path: &Path,
+        _file_name: &str,
+    ) -> Result<Vec<EventRevision>, Box<dyn Error>> {
+        Err("This sync provider does not keep event history".into())
+    }
+
+    /// Returns the last version of every event file that has been deleted
+    /// since. The commit, author and date describe the deletion.
+    fn deleted_events(&self, _path: &Path) -> Result<Vec<EventRevision>, Box<dyn Error>> {
+        Err("This sync provider does not keep event history".into())
+    }
+
     /// Returns self as Any for downcasting.
     fn as_any(&self) -> &dyn Any;
 }
@@ -1,10 +1,29 @@
 // Import from rcal_lib directly
-use rcal_lib::{CalendarEvent, EventService, SyncStatus};
+use rcal_lib::core::quick_add::parse_time;
+use rcal_lib::sync::{
+    journal_generation, restore_revision, ConflictChoices, EventRevision, ResolvedConflict,
+    SyncConflict,
+};
+use rcal_lib::EventField;
+use rcal_lib::{
+    CalendarEvent, EventSearch, EventService, FileEventRepository, FreeSlotFinder, SyncJournal,
+    SyncStatus, TimeSlot,
+};
 
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, Timelike};
+use rcal_lib::storage::{CalendarPathProvider, DefaultPathProvider};
 use rcal_lib::sync::SyncProvider;
 use std::cell::RefCell;
 use std::sync::mpsc::Receiver;
+use std::time::Instant;
+
+use crate::clipboard::{Clipboard, DateAction};
+use crate::command_line::{CommandHistory, EventFilter};
+use crate::keybindings::Keymap;
+use crate::locale::Locale;
+use crate::mouse::{HitMap, HitTarget};
+use crate::theme::Theme;
+use crate::undo::UndoHistory;
 
 #[derive(PartialEq, Debug)]
 pub enum InputMode {
@@ -14,6 +33,27 @@ pub enum InputMode {
     ViewEventsPopup,
     DeleteConfirmation,
     Sync,
+    ConflictResolver,
+    EventHistory,
+    Search,
+    TargetDate,
+    GoTo,
+    CommandLine,
+    Help,
+}
+
+/// How the calendar area of the main screen shows the selected date.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ViewMode {
+    /// Three months side by side, marking days with events.
+    #[default]
+    Month,
+    /// The week of the selected date with an hourly time grid.
+    Week,
+    /// The selected day's events in time order with their descriptions.
+    Day,
+    /// The twelve months of the selected year, shaded by event count.
+    Year,
 }
 
 #[derive(PartialEq, Debug)]
@@ -30,6 +70,15 @@ pub struct App {
     pub date: NaiveDate, // Now represents the selected date
     pub view_start_month: u32,
     pub view_start_year: i32,
+    /// Number of months the month view shows and how many of them fit side
+    /// by side; set from the terminal size when drawing.
+    pub visible_months: u32,
+    pub month_columns: u32,
+    pub view_mode: ViewMode,
+    /// View to return to when the day view is closed.
+    pub previous_view_mode: ViewMode,
+    /// Index of the selected event in the day view.
+    pub day_view_index: usize,
     pub event_service: RefCell<EventService>,
     pub input: String,
     pub input_mode: InputMode,
@@ -53,8 +102,56 @@ pub struct App {
     pub sync_provider: Option<Box<dyn SyncProvider>>,
     pub sync_status: Option<SyncStatus>,
     pub sync_message: String,
+    pub pending_sync_operations: usize,
+    /// The `journal_generation` when `pending_sync_operations` was read.
+    pub pending_sync_generation: Option<u64>,
+    pub sync_conflicts: Vec<SyncConflict>,
+    pub conflict_choices: Vec<ConflictChoices>,
+    pub conflict_resolutions: Vec<Option<ResolvedConflict>>,
+    pub selected_conflict_index: usize,
+    pub selected_conflict_field: usize,
+    pub is_editing_conflict: bool,
+    pub event_history: Vec<EventRevision>,
+    pub history_file_name: String,
+    pub history_message: String,
+    pub selected_revision_index: usize,
+    pub search_query: String,
+    pub search_regex: bool,
+    pub search_results: Vec<CalendarEvent>,
+    pub search_message: String,
+    pub selected_search_index: usize,
+    pub free_slots: Vec<TimeSlot>,
+    pub free_slot_index: usize,
     pub calendar_dir: std::path::PathBuf,
     pub error_message: String,
+    /// Result of the last undo or redo, shown in the hints line until the
+    /// next key press.
+    pub status_message: String,
+    pub undo_history: UndoHistory,
+    /// Event yanked or cut from the view popup, pasted with `p`.
+    pub clipboard: Option<Clipboard>,
+    /// Action of the target date prompt and what has been typed into it.
+    pub date_action: Option<DateAction>,
+    pub target_date_input: String,
+    /// What has been typed into the go-to prompt.
+    pub goto_input: String,
+    /// The `:` command line, the commands entered before, and the filter
+    /// set with `:filter`.
+    pub command_input: String,
+    pub command_history: CommandHistory,
+    pub event_filter: Option<EventFilter>,
+    /// Clickable areas of the last drawn frame, and the last click to detect
+    /// double clicks.
+    pub hit_map: RefCell<HitMap>,
+    pub last_click: Option<(Instant, HitTarget)>,
+    /// Key bindings of the calendar and the view popup, and the scroll
+    /// position of the help screen listing them.
+    pub keymap: Keymap,
+    pub help_scroll: u16,
+    /// Colour theme of the interface.
+    pub theme: Theme,
+    /// Week start, names and date and time formats.
+    pub locale: Locale,
     pub reload_receiver: Option<Receiver<Result<(), String>>>,
     pub date_input_error: Option<String>,
     pub date_suggestions: Vec<(String, bool)>,
@@ -77,6 +174,11 @@ impl App {
             date,
             view_start_month: date.month(),
             view_start_year: date.year(),
+            visible_months: 3,
+            month_columns: 1,
+            view_mode: ViewMode::Month,
+            previous_view_mode: ViewMode::Month,
+            day_view_index: 0,
             event_service: RefCell::new(EventService::new()),
             input: String::new(),
             input_mode: InputMode::Normal,
@@ -100,10 +202,42 @@ impl App {
             sync_provider: None,
             sync_status: None,
             sync_message: String::new(),
-            calendar_dir: dirs::home_dir()
-                .expect("Could not find home directory")
-                .join("calendar"),
+            pending_sync_operations: 0,
+            pending_sync_generation: None,
+            sync_conflicts: Vec::new(),
+            conflict_choices: Vec::new(),
+            conflict_resolutions: Vec::new(),
+            selected_conflict_index: 0,
+            selected_conflict_field: 0,
+            is_editing_conflict: false,
+            event_history: Vec::new(),
+            history_file_name: String::new(),
+            history_message: String::new(),
+            selected_revision_index: 0,
+            search_query: String::new(),
+            search_regex: false,
+            search_results: Vec::new(),
+            search_message: String::new(),
+            selected_search_index: 0,
+            free_slots: Vec::new(),
+            free_slot_index: 0,
+            calendar_dir: DefaultPathProvider::default().calendar_dir(),
             error_message: String::new(),
+            status_message: String::new(),
+            undo_history: UndoHistory::default(),
+            clipboard: None,
+            date_action: None,
+            target_date_input: String::new(),
+            goto_input: String::new(),
+            command_input: String::new(),
+            command_history: CommandHistory::default(),
+            event_filter: None,
+            hit_map: RefCell::new(HitMap::default()),
+            last_click: None,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            locale: Locale::default(),
+            help_scroll: 0,
             reload_receiver: None,
             date_input_error: None,
             date_suggestions: Vec::new(),
@@ -120,6 +254,11 @@ impl App {
             date,
             view_start_month: date.month(),
             view_start_year: date.year(),
+            visible_months: 3,
+            month_columns: 1,
+            view_mode: ViewMode::Month,
+            previous_view_mode: ViewMode::Month,
+            day_view_index: 0,
             event_service: RefCell::new(EventService::new()),
             input: String::new(),
             input_mode: InputMode::Normal,
@@ -143,8 +282,42 @@ impl App {
             sync_provider: None,
             sync_status: None,
             sync_message: String::new(),
+            pending_sync_operations: 0,
+            pending_sync_generation: None,
+            sync_conflicts: Vec::new(),
+            conflict_choices: Vec::new(),
+            conflict_resolutions: Vec::new(),
+            selected_conflict_index: 0,
+            selected_conflict_field: 0,
+            is_editing_conflict: false,
+            event_history: Vec::new(),
+            history_file_name: String::new(),
+            history_message: String::new(),
+            selected_revision_index: 0,
+            search_query: String::new(),
+            search_regex: false,
+            search_results: Vec::new(),
+            search_message: String::new(),
+            selected_search_index: 0,
+            free_slots: Vec::new(),
+            free_slot_index: 0,
             calendar_dir,
             error_message: String::new(),
+            status_message: String::new(),
+            undo_history: UndoHistory::default(),
+            clipboard: None,
+            date_action: None,
+            target_date_input: String::new(),
+            goto_input: String::new(),
+            command_input: String::new(),
+            command_history: CommandHistory::default(),
+            event_filter: None,
+            hit_map: RefCell::new(HitMap::default()),
+            last_click: None,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            locale: Locale::default(),
+            help_scroll: 0,
             reload_receiver: None,
             date_input_error: None,
             date_suggestions: Vec::new(),
@@ -193,16 +366,24 @@ impl App {
     /// Uses session-level caching to avoid regenerating instances for the same range.
     /// Generates instances with a buffer around the requested range
     /// to support smooth navigation without frequent regenerations.
+    /// Returns the events occurring in the range, including recurring
+    /// instances, leaving out the ones the `:filter` hides.
     pub fn get_all_events_for_range(&self, start: NaiveDate, end: NaiveDate) -> Vec<CalendarEvent> {
-        self.event_service
+        let mut events = self
+            .event_service
             .borrow_mut()
-            .get_all_events_for_range(start, end)
+            .get_all_events_for_range(start, end);
+        if let Some(filter) = &self.event_filter {
+            events.retain(|event| filter.matches(event));
+        }
+        events
     }
 
     pub fn adjust_view_boundaries(&mut self) {
         let cursor_number = (self.date.year() as i64 * 12) + self.date.month() as i64;
         let view_start_number = (self.view_start_year as i64 * 12) + self.view_start_month as i64;
-        let view_end_number = view_start_number + 2;
+        let visible_months = i64::from(self.visible_months.max(1));
+        let view_end_number = view_start_number + visible_months - 1;
 
         if cursor_number < view_start_number {
             // Shift backward
@@ -210,7 +391,7 @@ impl App {
             self.view_start_year = self.date.year();
         } else if cursor_number > view_end_number {
             // Shift forward
-            let new_start = cursor_number - 2;
+            let new_start = cursor_number - (visible_months - 1);
             self.view_start_year = (new_start / 12) as i32;
             let month = (new_start % 12) as u32;
             self.view_start_month = if month == 0 { 12 } else { month };
@@ -220,6 +401,113 @@ impl App {
         }
     }
 
+    /// Switches between the month and the week view. The day and year views
+    /// switch to the week view.
+    pub fn toggle_week_view(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::Month | ViewMode::Day | ViewMode::Year => ViewMode::Week,
+            ViewMode::Week => ViewMode::Month,
+        };
+    }
+
+    /// Opens the year view, or returns from it to the month or week view it
+    /// was opened from.
+    pub fn toggle_year_view(&mut self) {
+        match self.view_mode {
+            ViewMode::Year => {
+                self.view_mode = self.previous_view_mode;
+                self.adjust_view_boundaries();
+            }
+            ViewMode::Day => self.view_mode = ViewMode::Year,
+            _ => {
+                self.previous_view_mode = self.view_mode;
+                self.view_mode = ViewMode::Year;
+            }
+        }
+    }
+
+    /// Moves the selected date by whole months, keeping the day of the month
+    /// where the target month has it and using its last day otherwise.
+    pub fn step_months(&mut self, months: i32) {
+        let shifted = if months < 0 {
+            self.date
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+        } else {
+            self.date.checked_add_months(Months::new(months as u32))
+        };
+        if let Some(date) = shifted {
+            self.date = date;
+            self.adjust_view_boundaries();
+        }
+    }
+
+    /// Returns the events occurring on the date, including recurring
+    /// instances, ordered by start time.
+    pub fn events_on(&self, date: NaiveDate) -> Vec<CalendarEvent> {
+        let mut events: Vec<_> = self
+            .get_all_events_for_range(date, date)
+            .into_iter()
+            .filter(|event| {
+                if let Some(end) = event.end_date {
+                    event.start_date <= date && end >= date
+                } else {
+                    event.start_date == date
+                }
+            })
+            .collect();
+        events.sort_by_key(|event| event.start_time);
+        events
+    }
+
+    /// Opens the day view of the selected date, remembering the current view.
+    pub fn open_day_view(&mut self) {
+        if self.view_mode != ViewMode::Day {
+            self.previous_view_mode = self.view_mode;
+        }
+        self.view_mode = ViewMode::Day;
+        self.day_view_index = 0;
+    }
+
+    /// Returns from the day view to the view it was opened from.
+    pub fn close_day_view(&mut self) {
+        self.view_mode = self.previous_view_mode;
+    }
+
+    /// Moves the selected date by `days`, selecting the first event of the
+    /// new day in the day view.
+    pub fn step_day(&mut self, days: i64) {
+        self.date += Duration::days(days);
+        self.day_view_index = 0;
+        self.adjust_view_boundaries();
+    }
+
+    /// Selects `date` and shows its month first in the month view.
+    pub fn go_to(&mut self, date: NaiveDate) {
+        self.date = date;
+        self.day_view_index = 0;
+        self.view_start_month = date.month();
+        self.view_start_year = date.year();
+    }
+
+    /// Moves the selection of the day view up or down, stopping at the first
+    /// and last event.
+    pub fn select_day_event(&mut self, offset: isize) {
+        let count =
+            crate::day_view::agenda_events(&crate::day_view::selected_day_agenda(self)).len();
+        self.day_view_index = self
+            .day_view_index
+            .saturating_add_signed(offset)
+            .min(count.saturating_sub(1));
+    }
+
+    /// Returns the event selected in the day view.
+    pub fn selected_day_event(&self) -> Option<CalendarEvent> {
+        let entries = crate::day_view::selected_day_agenda(self);
+        crate::day_view::agenda_events(&entries)
+            .get(self.day_view_index)
+            .map(|event| (*event).clone())
+    }
+
     /// Invalidates the cached recurring event instances.
     /// If an event is provided, only instances related to that event are removed (selective invalidation).
     /// If no event is provided, all cached instances are cleared.
@@ -263,4 +551,291 @@ impl App {
     {
         f(&mut self.event_service.borrow_mut())
     }
+
+    /// Updates the number of sync operations waiting in the journal.
+    /// Background pushes update the journal, so this is called before each
+    /// redraw, but the journal is only re-read after it was written, such as
+    /// when a push completes or the journal is replayed.
+    pub fn refresh_pending_sync_operations(&mut self) {
+        if self.sync_provider.is_none() {
+            self.pending_sync_operations = 0;
+            self.pending_sync_generation = None;
+            return;
+        }
+        let generation = journal_generation();
+        if self.pending_sync_generation != Some(generation) {
+            self.pending_sync_generation = Some(generation);
+            self.pending_sync_operations = SyncJournal::pending_count(&self.calendar_dir);
+        }
+    }
+
+    /// Loads the conflicts reported by the sync provider and, if there are
+    /// any, switches to the conflict resolver. Returns true if it was opened.
+    pub fn open_conflict_resolver(&mut self) -> bool {
+        let Some(provider) = &self.sync_provider else {
+            return false;
+        };
+        match provider.sync_conflicts(&self.calendar_dir) {
+            Ok(conflicts) if !conflicts.is_empty() => {
+                self.conflict_choices = vec![ConflictChoices::default(); conflicts.len()];
+                self.conflict_resolutions = vec![None; conflicts.len()];
+                self.sync_conflicts = conflicts;
+                self.selected_conflict_index = 0;
+                self.selected_conflict_field = 0;
+                self.input_mode = InputMode::ConflictResolver;
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                self.sync_message = format!("Failed to load conflicts: {e}");
+                false
+            }
+        }
+    }
+
+    /// Returns the conflict shown in the resolver.
+    pub fn current_conflict(&self) -> Option<&SyncConflict> {
+        self.sync_conflicts.get(self.selected_conflict_index)
+    }
+
+    /// Returns the field selected in the resolver.
+    pub fn current_conflict_field(&self) -> EventField {
+        EventField::ALL[self.selected_conflict_field.min(EventField::ALL.len() - 1)]
+    }
+
+    /// Records the resolution of the current conflict and moves on to the next
+    /// unresolved one. Once every conflict is resolved the sync is completed.
+    pub fn resolve_current_conflict(&mut self, resolution: ResolvedConflict) {
+        if let Some(slot) = self
+            .conflict_resolutions
+            .get_mut(self.selected_conflict_index)
+        {
+            *slot = Some(resolution);
+        }
+        match self.conflict_resolutions.iter().position(Option::is_none) {
+            Some(next) => {
+                self.selected_conflict_index = next;
+                self.selected_conflict_field = 0;
+            }
+            None => self.finish_conflict_resolution(),
+        }
+    }
+
+    /// Hands all resolutions to the sync provider to complete the sync, then
+    /// reloads the events and returns to the sync popup.
+    pub fn finish_conflict_resolution(&mut self) {
+        let resolutions: Vec<ResolvedConflict> =
+            self.conflict_resolutions.drain(..).flatten().collect();
+        if let Some(provider) = &self.sync_provider {
+            let pending = SyncJournal::load(&self.calendar_dir).unwrap_or_default();
+            match provider.resolve_sync_conflicts(&self.calendar_dir, &resolutions) {
+                Ok(status) => {
+                    // The resolution pushed everything that was pending before it
+                    let _ = SyncJournal::remove_flushed(&self.calendar_dir, &pending);
+                    self.sync_message = format!("Resolved {} conflict(s)", resolutions.len());
+                    self.sync_status = Some(status);
+                }
+                Err(e) => {
+                    self.sync_message = format!("Resolving conflicts failed: {e}");
+                    self.sync_status = Some(SyncStatus::Error(e.to_string()));
+                }
+            }
+        }
+        match crate::persistence::load_events_from_path(&self.calendar_dir) {
+            Ok(events) => {
+                self.set_events(events);
+                self.invalidate_instance_cache(None);
+            }
+            Err(e) => self.error_message = format!("Failed to reload events: {e}"),
+        }
+        self.close_conflict_resolver();
+    }
+
+    /// Leaves the conflict resolver without completing the sync.
+    pub fn close_conflict_resolver(&mut self) {
+        self.sync_conflicts.clear();
+        self.conflict_choices.clear();
+        self.conflict_resolutions.clear();
+        self.selected_conflict_index = 0;
+        self.selected_conflict_field = 0;
+        self.is_editing_conflict = false;
+        self.input_mode = InputMode::Sync;
+    }
+
+    /// Loads the committed versions of an event from the sync provider and
+    /// opens the history popup. Problems are shown in the popup.
+    pub fn open_event_history(&mut self, event: &CalendarEvent) {
+        self.event_history.clear();
+        self.history_file_name.clear();
+        self.history_message.clear();
+        self.selected_revision_index = 0;
+        self.input_mode = InputMode::EventHistory;
+
+        let Some(provider) = &self.sync_provider else {
+            self.history_message = "Event history requires git sync".to_string();
+            return;
+        };
+        let repo = FileEventRepository::with_path(self.calendar_dir.clone());
+        match repo.find_event_filepath_by_key(&self.calendar_dir, &event.title, event.start_date) {
+            Ok(path) => {
+                self.history_file_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+            }
+            Err(e) => {
+                self.history_message = e.to_string();
+                return;
+            }
+        }
+        match provider.event_history(&self.calendar_dir, &self.history_file_name) {
+            Ok(revisions) if revisions.is_empty() => {
+                self.history_message = "No synced versions of this event yet".to_string();
+            }
+            Ok(revisions) => self.event_history = revisions,
+            Err(e) => self.history_message = format!("Failed to load history: {e}"),
+        }
+    }
+
+    /// Returns the revision selected in the history popup.
+    pub fn selected_revision(&self) -> Option<&EventRevision> {
+        self.event_history.get(self.selected_revision_index)
+    }
+
+    /// Writes the selected revision back to the calendar directory and
+    /// reloads the events. Returns the restored event.
+    pub fn restore_selected_revision(&mut self) -> Option<CalendarEvent> {
+        let revision = self.selected_revision()?.clone();
+        if let Err(e) = restore_revision(&self.calendar_dir, &revision) {
+            self.history_message = e.to_string();
+            return None;
+        }
+        match crate::persistence::load_events_from_path(&self.calendar_dir) {
+            Ok(events) => {
+                self.set_events(events);
+                self.invalidate_instance_cache(None);
+            }
+            Err(e) => self.error_message = format!("Failed to reload events: {e}"),
+        }
+        revision.event
+    }
+
+    /// Leaves the history popup and returns to the view popup.
+    pub fn close_event_history(&mut self) {
+        self.event_history.clear();
+        self.history_file_name.clear();
+        self.history_message.clear();
+        self.selected_revision_index = 0;
+        self.input_mode = InputMode::ViewEventsPopup;
+    }
+
+    /// Opens the search popup, keeping the previous query and results.
+    pub fn open_search(&mut self) {
+        self.input_mode = InputMode::Search;
+        self.update_search();
+    }
+
+    /// Searches the stored events for the current query, in regex mode if
+    /// `search_regex` is set.
+    pub fn update_search(&mut self) {
+        self.search_results.clear();
+        self.search_message.clear();
+        self.selected_search_index = 0;
+        if self.search_query.is_empty() {
+            return;
+        }
+        let search = if self.search_regex {
+            match EventSearch::regex(&self.search_query) {
+                Ok(search) => search,
+                Err(e) => {
+                    self.search_message = e.to_string();
+                    return;
+                }
+            }
+        } else {
+            EventSearch::new(&self.search_query)
+        };
+        let results = search.search(&self.events());
+        self.search_results = results;
+        if self.search_results.is_empty() {
+            self.search_message = "No matching events".to_string();
+        }
+    }
+
+    /// Returns the result selected in the search popup.
+    pub fn selected_search_result(&self) -> Option<&CalendarEvent> {
+        self.search_results.get(self.selected_search_index)
+    }
+
+    /// Moves the calendar to the date of the selected result and closes the
+    /// search popup. Returns false if there is no result to jump to.
+    pub fn jump_to_search_result(&mut self) -> bool {
+        let Some(date) = self.selected_search_result().map(|event| event.start_date) else {
+            return false;
+        };
+        self.date = date;
+        self.adjust_view_boundaries();
+        self.input_mode = InputMode::Normal;
+        true
+    }
+
+    /// Fills the date and times of the event popup with a free slot.
+    ///
+    /// The first call looks for slots in the two weeks from the popup's date,
+    /// not before `now`, as long as the times entered in the popup or an hour
+    /// otherwise; later calls cycle through the slots found. Returns false,
+    /// with an error message, if there is no free slot.
+    pub fn propose_free_slot(&mut self, now: NaiveDateTime) -> bool {
+        let entered = parse_time(&self.popup_event_time)
+            .and_then(|start| Ok(parse_time(&self.popup_event_end_time)? - start));
+        let duration = entered
+            .ok()
+            .filter(|d| *d > Duration::zero())
+            .unwrap_or(Duration::hours(1));
+        if self.free_slots.is_empty() {
+            // Start on the next quarter hour
+            let minutes = (15 - i64::from(now.minute()) % 15) % 15;
+            let start = (now + Duration::minutes(minutes))
+                .with_second(0)
+                .unwrap_or(now);
+            let from = self.current_date_for_new_event.max(now.date());
+            let edited = self
+                .event_being_edited
+                .as_ref()
+                .filter(|_| self.is_editing)
+                .map(|event| (event.title.clone(), event.start_date));
+            let events: Vec<_> = self
+                .events()
+                .iter()
+                .filter(|event| edited != Some((event.title.clone(), event.start_date)))
+                .cloned()
+                .collect();
+            self.free_slots = FreeSlotFinder::new(duration).not_before(start).find(
+                &events,
+                from,
+                from + Duration::days(13),
+            );
+            self.free_slot_index = 0;
+        } else {
+            self.free_slot_index = (self.free_slot_index + 1) % self.free_slots.len();
+        }
+        let Some(slot) = self.free_slots.get(self.free_slot_index).copied() else {
+            self.error_message = "No free slot in the next two weeks".to_string();
+            return false;
+        };
+        self.current_date_for_new_event = slot.start.date();
+        self.popup_event_time = self.locale.format_time(slot.start.time());
+        self.popup_event_end_time = self.locale.format_time((slot.start + duration).time());
+        self.popup_event_end_date.clear();
+        self.time_input_error = None;
+        self.end_time_input_error = None;
+        self.date_input_error = None;
+        self.error_message.clear();
+        if let PopupInputField::Time = self.selected_input_field {
+            self.cursor_position = self.popup_event_time.chars().count();
+        } else if let PopupInputField::EndTime = self.selected_input_field {
+            self.cursor_position = self.popup_event_end_time.chars().count();
+        }
+        true
+    }
 }
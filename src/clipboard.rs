@@ -0,0 +1,148 @@
+//! Copying and moving events between days in the TUI.
+//!
+//! Events are placed by shifting them by whole days, so the time, duration
+//! and multi-day span stay the same. A recurring event is always handled as
+//! its whole series: the series is shifted so the selected occurrence lands
+//! on the target date.
+
+use chrono::{Duration, NaiveDate};
+use rcal_lib::core::quick_add::parse_date;
+use rcal_lib::CalendarEvent;
+
+/// An event yanked or cut from the view popup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clipboard {
+    /// The stored event; the base event for a recurring series.
+    pub event: CalendarEvent,
+    /// The date of the occurrence that was selected.
+    pub anchor: NaiveDate,
+    /// Whether pasting moves the event instead of copying it.
+    pub cut: bool,
+}
+
+/// What the target date prompt does with the selected event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateAction {
+    Move,
+    Duplicate,
+}
+
+impl DateAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            DateAction::Move => "Move",
+            DateAction::Duplicate => "Duplicate",
+        }
+    }
+}
+
+/// Returns `event` moved by `days`, as a stored event rather than an
+/// occurrence of a series.
+pub fn shifted(event: &CalendarEvent, days: i64) -> CalendarEvent {
+    let offset = Duration::days(days);
+    let mut event = event.clone();
+    event.start_date += offset;
+    event.end_date = event.end_date.map(|date| date + offset);
+    event.is_recurring_instance = false;
+    event.base_date = None;
+    event
+}
+
+/// Parses the target of a move or duplicate: an offset from `from` such as
+/// `+3`, `-2d` or `+1w`, or a date accepted by `parse_date` relative to
+/// `today`.
+pub fn parse_target(input: &str, from: NaiveDate, today: NaiveDate) -> Result<NaiveDate, String> {
+    let input = input.trim();
+    if let Some(sign) = input.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let offset = &input[1..];
+        let (count, weeks) = match offset.strip_suffix(['w', 'W']) {
+            Some(count) => (count, true),
+            None => (offset.strip_suffix(['d', 'D']).unwrap_or(offset), false),
+        };
+        let count: i64 = count
+            .parse()
+            .map_err(|_| format!("Invalid offset '{input}'. Use +N, -Nd or +Nw"))?;
+        let days = if weeks {
+            count.checked_mul(7)
+        } else {
+            Some(count)
+        };
+        return days
+            .and_then(|days| {
+                if sign == '-' {
+                    days.checked_neg()
+                } else {
+                    Some(days)
+                }
+            })
+            .and_then(Duration::try_days)
+            .and_then(|offset| from.checked_add_signed(offset))
+            .ok_or_else(|| "offset out of range".to_string());
+    }
+    parse_date(input, today)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_shifted_keeps_time_and_span() {
+        let mut trip = CalendarEvent::new(
+            "Trip".to_string(),
+            String::new(),
+            date(19),
+            NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+        );
+        trip.end_date = Some(date(21));
+        trip.end_time = Some(NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+        trip.is_recurring_instance = true;
+        trip.base_date = Some(date(12));
+
+        let moved = shifted(&trip, 7);
+        assert_eq!(moved.start_date, date(26));
+        assert_eq!(moved.end_date, Some(date(28)));
+        assert_eq!(moved.start_time, trip.start_time);
+        assert_eq!(moved.end_time, trip.end_time);
+        assert!(!moved.is_recurring_instance);
+        assert_eq!(moved.base_date, None);
+    }
+
+    #[test]
+    fn test_parse_target() {
+        let today = date(1);
+        assert_eq!(parse_target("+3", date(19), today), Ok(date(22)));
+        assert_eq!(parse_target("-2d", date(19), today), Ok(date(17)));
+        assert_eq!(parse_target("+1w", date(19), today), Ok(date(26)));
+        assert_eq!(parse_target("tomorrow", date(19), today), Ok(date(2)));
+        assert_eq!(
+            parse_target("2026-11-05", date(19), today).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 11, 5).unwrap()
+        );
+        assert!(parse_target("+x", date(19), today).is_err());
+        assert!(parse_target("someday", date(19), today).is_err());
+    }
+
+    #[test]
+    fn test_parse_target_out_of_range() {
+        let out_of_range = Err("offset out of range".to_string());
+        for input in [
+            "+999999999",
+            "-999999999d",
+            "+99999999999999w",
+            "+9223372036854775807",
+            "-9223372036854775807w",
+        ] {
+            assert_eq!(
+                parse_target(input, date(19), date(1)),
+                out_of_range,
+                "{input}"
+            );
+        }
+    }
+}
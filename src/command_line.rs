@@ -0,0 +1,453 @@
+//! The `:` command line of the TUI.
+//!
+//! Commands are split into words like a shell would, honouring quotes, and
+//! parsed with the clap arguments of the matching subcommands, so `:add`,
+//! `:delete` and `:export` take the same options as `rcal add`, `rcal
+//! delete` and `rcal export`:
+//!
+//! ```text
+//! :add Lunch with Sam 12:30 for 1h
+//! :delete "Team standup" --on friday
+//! :goto w42
+//! :filter tag:work
+//! :sync push
+//! :export --from today -o week.csv
+//! :q
+//! ```
+
+use clap::{Parser, Subcommand, ValueEnum};
+use rcal_lib::CalendarEvent;
+
+use crate::commands::add::AddArgs;
+use crate::commands::agenda::AgendaArgs;
+use crate::commands::delete::DeleteArgs;
+use crate::commands::export::ExportArgs;
+
+/// Names of the commands, completed with Tab.
+pub const COMMANDS: [&str; 9] = [
+    "add", "delete", "export", "filter", "goto", "help", "q", "quit", "sync",
+];
+
+/// Most commands kept in the history.
+const MAX_HISTORY: usize = 100;
+
+/// A parsed command line.
+#[derive(Debug)]
+pub enum Command {
+    /// Adds an event; without arguments, opens the event popup instead.
+    Add(Option<AddArgs>),
+    /// Deletes matching events; without arguments, the selected one.
+    Delete(Option<DeleteArgs>),
+    Export(ExportArgs),
+    /// Goes to a date, week or month as typed into the go-to prompt.
+    GoTo(String),
+    /// Shows only matching events, or all events again without terms.
+    Filter(Option<EventFilter>),
+    /// Runs a sync action, or opens the sync menu without one.
+    Sync(Option<SyncAction>),
+    Help,
+    Quit,
+}
+
+/// Actions of `:sync`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SyncAction {
+    Pull,
+    Push,
+    Status,
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    name = ":",
+    no_binary_name = true,
+    disable_help_flag = true,
+    disable_version_flag = true,
+    disable_help_subcommand = true
+)]
+struct CommandLineArgs {
+    #[command(subcommand)]
+    command: ParsedCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ParsedCommand {
+    Add(AddArgs),
+    Delete(DeleteArgs),
+    Export(ExportArgs),
+    Goto {
+        #[arg(required = true, num_args = 1.., allow_hyphen_values = true)]
+        target: Vec<String>,
+    },
+    Filter {
+        terms: Vec<String>,
+    },
+    Sync {
+        #[arg(value_enum)]
+        action: Option<SyncAction>,
+    },
+    Help,
+    #[command(alias = "q")]
+    Quit,
+}
+
+/// Parses a command line, without the leading `:`.
+pub fn parse(input: &str) -> Result<Command, String> {
+    let words = split_words(input)?;
+    match words.as_slice() {
+        [] => return Err("Type a command, such as add, goto or q".to_string()),
+        [name] if name == "add" => return Ok(Command::Add(None)),
+        [name] if name == "delete" => return Ok(Command::Delete(None)),
+        _ => {}
+    }
+    let args = CommandLineArgs::try_parse_from(&words).map_err(|e| {
+        // Only the first line of clap's message, without its usage
+        let message = e.to_string();
+        let first = message.lines().next().unwrap_or_default();
+        first.trim_start_matches("error: ").to_string()
+    })?;
+    Ok(match args.command {
+        ParsedCommand::Add(args) => Command::Add(Some(args)),
+        ParsedCommand::Delete(args) => Command::Delete(Some(args)),
+        ParsedCommand::Export(args) => Command::Export(args),
+        ParsedCommand::Goto { target } => Command::GoTo(target.join(" ")),
+        ParsedCommand::Filter { terms } if terms.is_empty() => Command::Filter(None),
+        ParsedCommand::Filter { terms } => Command::Filter(Some(EventFilter::parse(&terms)?)),
+        ParsedCommand::Sync { action } => Command::Sync(action),
+        ParsedCommand::Help => Command::Help,
+        ParsedCommand::Quit => Command::Quit,
+    })
+}
+
+/// Splits a command line into words at whitespace. Single or double quotes
+/// keep whitespace within a word, and a backslash keeps the next character.
+pub fn split_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) if quote != Some('\'') => {
+                let escaped = chars.next().ok_or("Nothing to escape after \\")?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, Some(_)) => word.get_or_insert_with(String::new).push(c),
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (c, None) if c.is_whitespace() => words.extend(word.take()),
+            (c, None) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("Unclosed quote".to_string());
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// The events shown while a `:filter` is set. Its terms are `tag:NAME` or
+/// `#NAME` for tags, `is:all-day` and `is:timed`, and text searched in
+/// titles and descriptions, as `rcal agenda --tag`, `--all-day`, `--timed`
+/// and `--search` take them.
+#[derive(Debug)]
+pub struct EventFilter {
+    /// The terms as typed, shown in the calendar title.
+    pub label: String,
+    criteria: AgendaArgs,
+}
+
+impl EventFilter {
+    pub fn parse(terms: &[String]) -> Result<Self, String> {
+        let mut criteria = AgendaArgs::default();
+        let mut text = Vec::new();
+        for term in terms {
+            if let Some(tag) = term.strip_prefix("tag:").or_else(|| term.strip_prefix('#')) {
+                if tag.is_empty() {
+                    return Err(format!("Missing tag in '{term}'"));
+                }
+                criteria.tag.push(tag.to_string());
+            } else if let Some(kind) = term.strip_prefix("is:") {
+                match kind {
+                    "all-day" => criteria.all_day = true,
+                    "timed" => criteria.timed = true,
+                    _ => {
+                        return Err(format!(
+                            "Unknown filter '{term}', use is:all-day or is:timed"
+                        ))
+                    }
+                }
+            } else {
+                text.push(term.as_str());
+            }
+        }
+        if criteria.all_day && criteria.timed {
+            return Err("is:all-day and is:timed exclude each other".to_string());
+        }
+        if !text.is_empty() {
+            criteria.search = Some(text.join(" "));
+        }
+        Ok(Self {
+            label: terms.join(" "),
+            criteria,
+        })
+    }
+
+    pub fn matches(&self, event: &CalendarEvent) -> bool {
+        self.criteria.matches(event)
+    }
+}
+
+/// Commands entered on the command line, oldest first, and the one shown
+/// while browsing them with Up and Down.
+#[derive(Debug, Default)]
+pub struct CommandHistory {
+    commands: Vec<String>,
+    position: Option<usize>,
+}
+
+impl CommandHistory {
+    /// Adds a command, unless it repeats the last one, and stops browsing.
+    pub fn push(&mut self, command: &str) {
+        self.position = None;
+        if command.trim().is_empty() || self.commands.last().is_some_and(|last| last == command) {
+            return;
+        }
+        self.commands.push(command.to_string());
+        if self.commands.len() > MAX_HISTORY {
+            self.commands.remove(0);
+        }
+    }
+
+    /// Returns the command before the one shown, or the last one.
+    pub fn older(&mut self) -> Option<&str> {
+        let position = match self.position {
+            None => self.commands.len().checked_sub(1)?,
+            Some(position) => position.saturating_sub(1),
+        };
+        self.position = Some(position);
+        self.commands.get(position).map(String::as_str)
+    }
+
+    /// Returns the command after the one shown, or None past the last one,
+    /// which stops browsing.
+    pub fn newer(&mut self) -> Option<&str> {
+        let position = self.position? + 1;
+        if position >= self.commands.len() {
+            self.position = None;
+            return None;
+        }
+        self.position = Some(position);
+        self.commands.get(position).map(String::as_str)
+    }
+
+    pub fn reset(&mut self) {
+        self.position = None;
+    }
+}
+
+/// Completes the last word of `input`: command names for the first word,
+/// sync actions after `sync`, and event titles after `delete`. Returns the
+/// completed input, extended by the longest common prefix of the matches,
+/// and the matches.
+pub fn complete(input: &str, titles: &[String]) -> (String, Vec<String>) {
+    let start = last_word_start(input);
+    let (head, word) = input.split_at(start);
+    let partial = word.trim_start_matches(['"', '\'']).to_lowercase();
+    let command = split_words(head)
+        .ok()
+        .and_then(|words| words.first().cloned());
+    let candidates: Vec<String> = match command.as_deref() {
+        None => COMMANDS.iter().map(|name| name.to_string()).collect(),
+        Some("sync") => ["pull", "push", "status"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        Some("delete") if !word.starts_with('-') => titles.to_vec(),
+        _ => Vec::new(),
+    };
+    let mut matches: Vec<String> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&partial))
+        .collect();
+    matches.sort();
+    matches.dedup();
+
+    let Some(first) = matches.first() else {
+        return (input.to_string(), matches);
+    };
+    let common = matches.iter().fold(first.clone(), |common, candidate| {
+        common
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+            .map(|(a, _)| a)
+            .collect()
+    });
+    let quoted = word.starts_with(['"', '\'']) || matches.iter().any(|m| m.contains(' '));
+    let mut completed = if quoted {
+        format!("{head}\"{common}")
+    } else {
+        format!("{head}{common}")
+    };
+    if matches.len() == 1 {
+        if quoted {
+            completed.push('"');
+        }
+        completed.push(' ');
+    }
+    (completed, matches)
+}
+
+/// Returns where the last word of `input` starts, outside of quotes.
+fn last_word_start(input: &str) -> usize {
+    let mut start = 0;
+    let mut quote = None;
+    for (index, c) in input.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => start = index + c.len_utf8(),
+            None => {}
+        }
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words(r#"delete "Team standup" --on 'next friday' a\ b"#),
+            Ok(vec![
+                "delete".to_string(),
+                "Team standup".to_string(),
+                "--on".to_string(),
+                "next friday".to_string(),
+                "a b".to_string(),
+            ])
+        );
+        assert_eq!(split_words("  "), Ok(Vec::new()));
+        assert_eq!(
+            split_words(r#"add """#),
+            Ok(vec!["add".to_string(), String::new()])
+        );
+        assert!(split_words("add \"Lunch").is_err());
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert!(matches!(parse("add"), Ok(Command::Add(None))));
+        match parse("add Lunch 12:30 --date friday") {
+            Ok(Command::Add(Some(args))) => {
+                assert_eq!(args.title, vec!["Lunch", "12:30"]);
+                assert_eq!(args.date.as_deref(), Some("friday"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match parse("delete \"Team standup\" --all") {
+            Ok(Command::Delete(Some(args))) => {
+                assert_eq!(args.selector.query.as_deref(), Some("Team standup"));
+                assert!(args.selector.all);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(
+            matches!(parse("goto next month"), Ok(Command::GoTo(target)) if target == "next month")
+        );
+        assert!(matches!(
+            parse("sync push"),
+            Ok(Command::Sync(Some(SyncAction::Push)))
+        ));
+        assert!(matches!(parse("sync"), Ok(Command::Sync(None))));
+        assert!(matches!(parse("filter"), Ok(Command::Filter(None))));
+        assert!(matches!(parse("q"), Ok(Command::Quit)));
+        assert!(matches!(
+            parse("export -o week.csv"),
+            Ok(Command::Export(_))
+        ));
+
+        assert_eq!(
+            parse("frobnicate").unwrap_err(),
+            "unrecognized subcommand 'frobnicate'"
+        );
+        assert!(parse("sync sideways").is_err());
+        assert!(parse("goto").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let event = |title: &str, all_day| {
+            let mut event = CalendarEvent::new(
+                title.to_string(),
+                String::new(),
+                date,
+                NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            );
+            event.is_all_day = all_day;
+            event
+        };
+        let terms = |terms: &str| EventFilter::parse(&split_words(terms).unwrap());
+
+        let work = terms("tag:work").unwrap();
+        assert!(work.matches(&event("Standup #work", false)));
+        assert!(!work.matches(&event("Gym", false)));
+
+        let filter = terms("#work is:all-day review").unwrap();
+        assert_eq!(filter.label, "#work is:all-day review");
+        assert!(filter.matches(&event("Review day #work", true)));
+        assert!(!filter.matches(&event("Review day #work", false)));
+        assert!(!filter.matches(&event("Offsite #work", true)));
+
+        assert!(terms("tag:").is_err());
+        assert!(terms("is:busy").is_err());
+        assert!(terms("is:all-day is:timed").is_err());
+    }
+
+    #[test]
+    fn test_command_history() {
+        let mut history = CommandHistory::default();
+        assert_eq!(history.older(), None);
+        history.push("goto w42");
+        history.push("q");
+        history.push("q");
+        assert_eq!(history.older(), Some("q"));
+        assert_eq!(history.older(), Some("goto w42"));
+        assert_eq!(history.older(), Some("goto w42"));
+        assert_eq!(history.newer(), Some("q"));
+        assert_eq!(history.newer(), None);
+        assert_eq!(history.newer(), None);
+    }
+
+    #[test]
+    fn test_complete() {
+        let titles = vec!["Team standup".to_string(), "Tennis".to_string()];
+        assert_eq!(
+            complete("go", &titles),
+            ("goto ".to_string(), vec!["goto".to_string()])
+        );
+        let (input, matches) = complete("q", &titles);
+        assert_eq!(input, "q");
+        assert_eq!(matches, vec!["q", "quit"]);
+        assert_eq!(complete("sync pu", &titles).0, "sync pu");
+        assert_eq!(complete("sync pus", &titles).0, "sync push ");
+
+        assert_eq!(complete("delete t", &titles).0, "delete \"Te");
+        assert_eq!(
+            complete("delete \"team", &titles).0,
+            "delete \"Team standup\" "
+        );
+        assert_eq!(complete("delete ten", &titles).0, "delete Tennis ");
+        assert_eq!(complete("goto w4", &titles).0, "goto w4");
+    }
+}
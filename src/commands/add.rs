@@ -0,0 +1,258 @@
+//! `rcal add`: creates an event from the command line.
+
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{Local, NaiveDate, NaiveTime};
+use clap::Args;
+use rcal_lib::core::quick_add::{parse_date, parse_quick_add, parse_recurrence, parse_time_range};
+use rcal_lib::storage::DynSyncProvider;
+use rcal_lib::{
+    validate_event_with_details, CalendarEvent, EventRepository, FileEventRepository, Recurrence,
+};
+
+/// Arguments of `rcal add`.
+#[derive(Args, Debug, Default)]
+pub struct AddArgs {
+    /// Event title; may include a date, time, duration and recurrence,
+    /// e.g. "Lunch with Sam tomorrow 12:30 for 1h"
+    #[arg(required = true, num_args = 1..)]
+    pub title: Vec<String>,
+
+    /// Date (YYYY-MM-DD, DD/MM, today, tomorrow or a weekday) [default: today]
+    #[arg(long, short)]
+    pub date: Option<String>,
+
+    /// Start time or time range (14:00 or 14:00-15:00); all-day if omitted
+    #[arg(long, short)]
+    pub time: Option<String>,
+
+    /// Last day of a multi-day event
+    #[arg(long, value_name = "DATE")]
+    pub end_date: Option<String>,
+
+    /// Recurrence: none, daily, weekly, monthly or yearly
+    #[arg(long, short, value_name = "RECURRENCE")]
+    pub repeat: Option<String>,
+
+    /// Description
+    #[arg(long, value_name = "TEXT")]
+    pub desc: Option<String>,
+
+    /// Replace an event with the same title on the same date
+    #[arg(long, short)]
+    pub force: bool,
+}
+
+/// Builds and validates the event described by the arguments.
+///
+/// Options given as flags take precedence over the ones found in the title.
+pub fn build_event(args: &AddArgs, today: NaiveDate) -> Result<CalendarEvent, String> {
+    let quick = parse_quick_add(&args.title.join(" "), today);
+    if quick.title.is_empty() {
+        return Err("Event title is empty".to_string());
+    }
+
+    let date = match &args.date {
+        Some(date) => parse_date(date, today)?,
+        None => quick.date.unwrap_or(today),
+    };
+    let (start_time, end_time) = match &args.time {
+        Some(time) => {
+            let (start, end) = parse_time_range(time)?;
+            (Some(start), end)
+        }
+        None => (quick.start_time, quick.end_time),
+    };
+    let recurrence = match &args.repeat {
+        Some(repeat) => parse_recurrence(repeat)?,
+        None => quick.recurrence.unwrap_or(Recurrence::None),
+    };
+    let end_date = match &args.end_date {
+        Some(end) => Some(parse_date(end, date)?),
+        None => None,
+    };
+
+    let mut event = CalendarEvent::new(
+        quick.title,
+        args.desc.clone().unwrap_or_default(),
+        date,
+        start_time.unwrap_or(NaiveTime::MIN),
+    );
+    event.is_all_day = start_time.is_none();
+    event.end_time = end_time;
+    event.end_date = end_date;
+    event.recurrence = recurrence;
+
+    validate_event_with_details(&event).map_err(|errors| {
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    })?;
+    Ok(event)
+}
+
+/// Saves the event described by the arguments, pushing it if a sync
+/// provider is given (see `rcal_lib::sync::as_sync_provider`).
+///
+/// Events are stored by title and start date, so an event already stored
+/// under both is only replaced with `--force`.
+pub fn run(
+    args: &AddArgs,
+    calendar_dir: &Path,
+    sync_provider: Option<&DynSyncProvider>,
+) -> Result<CalendarEvent, Box<dyn Error>> {
+    let event = build_event(args, Local::now().date_naive())?;
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let taken = repo
+        .find_event_filepath_by_key(calendar_dir, &event.title, event.start_date)
+        .is_ok();
+    if taken && !args.force {
+        return Err(format!(
+            "an event named '{}' already exists on {}, use --force to replace it",
+            event.title, event.start_date
+        )
+        .into());
+    }
+    repo.save_with_sync(&event, sync_provider, calendar_dir)?;
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+    }
+
+    fn args(title: &str) -> AddArgs {
+        AddArgs {
+            title: vec![title.to_string()],
+            ..AddArgs::default()
+        }
+    }
+
+    #[test]
+    fn test_build_event_from_flags() {
+        let event = build_event(
+            &AddArgs {
+                date: Some("2026-10-21".to_string()),
+                time: Some("14:00-15:00".to_string()),
+                repeat: Some("weekly".to_string()),
+                desc: Some("Bring X-rays".to_string()),
+                ..args("Dentist")
+            },
+            today(),
+        )
+        .unwrap();
+        assert_eq!(event.title, "Dentist");
+        assert_eq!(
+            event.start_date,
+            NaiveDate::from_ymd_opt(2026, 10, 21).unwrap()
+        );
+        assert_eq!(event.start_time, NaiveTime::from_hms_opt(14, 0, 0).unwrap());
+        assert_eq!(event.end_time, NaiveTime::from_hms_opt(15, 0, 0));
+        assert_eq!(event.recurrence, Recurrence::Weekly);
+        assert_eq!(event.description, "Bring X-rays");
+        assert!(!event.is_all_day);
+    }
+
+    #[test]
+    fn test_build_event_from_quick_add() {
+        let event = build_event(&args("Lunch with Sam tomorrow 12:30 for 1h"), today()).unwrap();
+        assert_eq!(event.title, "Lunch with Sam");
+        assert_eq!(
+            event.start_date,
+            NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()
+        );
+        assert_eq!(event.end_time, NaiveTime::from_hms_opt(13, 30, 0));
+
+        let all_day = build_event(&args("Holiday"), today()).unwrap();
+        assert!(all_day.is_all_day);
+        assert_eq!(all_day.start_date, today());
+    }
+
+    #[test]
+    fn test_build_event_rejects_invalid_input() {
+        assert!(build_event(&args("tomorrow"), today()).is_err());
+        // Events can't run past midnight on a single day
+        assert!(build_event(&args("Party at 23:00 for 2h"), today()).is_err());
+        assert!(build_event(
+            &AddArgs {
+                repeat: Some("hourly".to_string()),
+                ..args("Dentist")
+            },
+            today()
+        )
+        .is_err());
+        assert!(build_event(
+            &AddArgs {
+                end_date: Some("2026-10-01".to_string()),
+                ..args("Trip")
+            },
+            today()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_saves_event() {
+        let temp_dir = TempDir::new().unwrap();
+        let event = run(
+            &args("Dentist on 2026-10-21 at 14:00"),
+            temp_dir.path(),
+            None,
+        )
+        .unwrap();
+        let saved = FileEventRepository::with_path(temp_dir.path().to_path_buf())
+            .load()
+            .unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].title, event.title);
+        assert_eq!(saved[0].start_time, event.start_time);
+    }
+
+    #[test]
+    fn test_run_refuses_to_replace_event() {
+        let temp_dir = TempDir::new().unwrap();
+        let dentist = AddArgs {
+            date: Some("2026-10-21".to_string()),
+            repeat: Some("weekly".to_string()),
+            desc: Some("Bring X-rays".to_string()),
+            ..args("Dentist")
+        };
+        run(&dentist, temp_dir.path(), None).unwrap();
+
+        let again = AddArgs {
+            date: Some("2026-10-21".to_string()),
+            ..args("Dentist")
+        };
+        let error = run(&again, temp_dir.path(), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "an event named 'Dentist' already exists on 2026-10-21, use --force to replace it"
+        );
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        let saved = repo.load().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].description, "Bring X-rays");
+
+        run(
+            &AddArgs {
+                force: true,
+                ..again
+            },
+            temp_dir.path(),
+            None,
+        )
+        .unwrap();
+        let saved = repo.load().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].recurrence, Recurrence::None);
+        assert!(saved[0].description.is_empty());
+    }
+}
@@ -0,0 +1,495 @@
+//! `rcal agenda` (alias `rcal list`): prints the events of a date range.
+//!
+//! Recurring events are expanded through `EventService`, so every occurrence
+//! in the range is listed. The output is plain text for people, or JSON, CSV
+//! or a user-defined template for other tools.
+
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::{Args, ValueEnum};
+use rcal_lib::core::quick_add::parse_date;
+use rcal_lib::{
+    events_to_csv, CalendarEvent, EventRepository, EventSearch, EventService, FileEventRepository,
+};
+
+/// Output formats of `rcal agenda`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Events grouped by day
+    #[default]
+    Plain,
+    /// A JSON array of events
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+    /// One line per event, formatted with --template
+    Template,
+}
+
+/// Arguments of `rcal agenda`.
+#[derive(Args, Debug, Default)]
+pub struct AgendaArgs {
+    /// List today's events (the default)
+    #[arg(long, conflicts_with_all = ["week", "month", "from", "to"])]
+    pub today: bool,
+
+    /// List the events of the current week (Monday to Sunday)
+    #[arg(long, conflicts_with_all = ["month", "from", "to"])]
+    pub week: bool,
+
+    /// List the events of the current month
+    #[arg(long, conflicts_with_all = ["from", "to"])]
+    pub month: bool,
+
+    /// First day of the range [default: today]
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<String>,
+
+    /// Last day of the range [default: the first day]
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<String>,
+
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Line template, e.g. "{date} {time} {title}"; implies --format template.
+    /// Placeholders: id, date, end_date, start, end, time, title, description,
+    /// recurrence, all_day
+    #[arg(long, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+
+    /// Only list events whose title or description contains the text
+    #[arg(long, short, value_name = "TEXT")]
+    pub search: Option<String>,
+
+    /// Only list events tagged with #TAG in the title or description
+    #[arg(long, value_name = "TAG")]
+    pub tag: Vec<String>,
+
+    /// Only list all-day events
+    #[arg(long, conflicts_with = "timed")]
+    pub all_day: bool,
+
+    /// Only list events with a start time
+    #[arg(long)]
+    pub timed: bool,
+}
+
+impl AgendaArgs {
+    /// Returns the first and last day of the requested range.
+    pub fn date_range(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        if self.week {
+            let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            return Ok((monday, monday + Duration::days(6)));
+        }
+        if self.month {
+            let first = today.with_day(1).unwrap();
+            let next = first
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(NaiveDate::MAX);
+            return Ok((first, next.pred_opt().unwrap_or(next)));
+        }
+        let from = match &self.from {
+            Some(from) => parse_date(from, today)?,
+            None => today,
+        };
+        let to = match &self.to {
+            Some(to) => parse_date(to, from)?,
+            None => from,
+        };
+        if to < from {
+            return Err(format!("End of range {to} is before its start {from}"));
+        }
+        Ok((from, to))
+    }
+
+    /// Returns true if the event passes the text, tag and all-day filters.
+    pub fn matches(&self, event: &CalendarEvent) -> bool {
+        if self.all_day && !event.is_all_day || self.timed && event.is_all_day {
+            return false;
+        }
+        if let Some(text) = &self.search {
+            if !EventSearch::new(text).matches(event) {
+                return false;
+            }
+        }
+        self.tag.iter().all(|tag| has_tag(event, tag))
+    }
+}
+
+/// Returns true if `#tag` appears as a word in the title or description,
+/// ignoring case. The tag may be given with or without the `#`.
+pub fn has_tag(event: &CalendarEvent, tag: &str) -> bool {
+    let tag = tag.trim_start_matches('#');
+    !tag.is_empty()
+        && event
+            .title
+            .split_whitespace()
+            .chain(event.description.split_whitespace())
+            .filter_map(|word| word.strip_prefix('#'))
+            .map(|word| word.trim_end_matches(|c: char| c.is_ascii_punctuation()))
+            .any(|word| word.eq_ignore_ascii_case(tag))
+}
+
+/// Returns the time of the event as shown to the user: "all day", "14:00"
+/// or "14:00-15:00".
+pub fn time_label(event: &CalendarEvent) -> String {
+    if event.is_all_day {
+        return "all day".to_string();
+    }
+    match event.end_time {
+        Some(end) => format!(
+            "{}-{}",
+            event.start_time.format("%H:%M"),
+            end.format("%H:%M")
+        ),
+        None => event.start_time.format("%H:%M").to_string(),
+    }
+}
+
+/// Returns the occurrences of the events in the range, sorted by start,
+/// with recurring events expanded.
+pub fn events_in_range(
+    events: Vec<CalendarEvent>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<CalendarEvent> {
+    EventService::with_events(events)
+        .get_all_events_for_range(from, to)
+        .into_iter()
+        .filter(|event| event.start_date <= to && event.effective_end_date() >= from)
+        .collect()
+}
+
+/// Formats the events in the given format. `template` is used by
+/// `OutputFormat::Template`.
+pub fn format_events(
+    events: &[CalendarEvent],
+    format: OutputFormat,
+    template: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> String {
+    match format {
+        OutputFormat::Plain => format_plain(events, from, to),
+        OutputFormat::Json => format_json(events),
+        OutputFormat::Csv => events_to_csv(events),
+        OutputFormat::Template => events
+            .iter()
+            .map(|event| render_template(template, event) + "\n")
+            .collect(),
+    }
+}
+
+/// Lists the events by day, repeating multi-day events on each of their days.
+fn format_plain(events: &[CalendarEvent], from: NaiveDate, to: NaiveDate) -> String {
+    if events.is_empty() {
+        return "No events\n".to_string();
+    }
+    let mut output = String::new();
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        let day: Vec<_> = events.iter().filter(|e| e.occurs_on(date)).collect();
+        if day.is_empty() {
+            continue;
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&format!("{}\n", date.format("%a %Y-%m-%d")));
+        for event in day {
+            output.push_str(&format!("  {:<12} {}\n", time_label(event), event.title));
+        }
+    }
+    output
+}
+
+fn format_json(events: &[CalendarEvent]) -> String {
+    let events: Vec<_> = events
+        .iter()
+        .map(|event| {
+            serde_json::json!({
+                "id": event.stable_id(),
+                "title": event.title,
+                "description": event.description,
+                "start_date": event.start_date.to_string(),
+                "end_date": event.effective_end_date().to_string(),
+                "start_time": (!event.is_all_day)
+                    .then(|| event.start_time.format("%H:%M").to_string()),
+                "end_time": event.end_time
+                    .filter(|_| !event.is_all_day)
+                    .map(|end| end.format("%H:%M").to_string()),
+                "all_day": event.is_all_day,
+                "recurrence": event.recurrence.to_storage_string(),
+                "recurring_instance": event.is_recurring_instance,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&events).unwrap_or_default() + "\n"
+}
+
+/// Returns the value of a template placeholder, or an empty string for
+/// values the event doesn't have.
+fn placeholder(event: &CalendarEvent, name: &str) -> String {
+    match name {
+        "id" => event.stable_id(),
+        "date" => event.start_date.to_string(),
+        "end_date" => event.effective_end_date().to_string(),
+        "start" if !event.is_all_day => event.start_time.format("%H:%M").to_string(),
+        "end" if !event.is_all_day => event
+            .end_time
+            .map(|end| end.format("%H:%M").to_string())
+            .unwrap_or_default(),
+        "time" => time_label(event),
+        "title" => event.title.clone(),
+        "description" => event.description.clone(),
+        "recurrence" => event.recurrence.to_storage_string().to_string(),
+        "all_day" => event.is_all_day.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Replaces the `{name}` placeholders of the template with the values of the
+/// event. Unknown placeholders are left as they are.
+pub fn render_template(template: &str, event: &CalendarEvent) -> String {
+    const NAMES: [&str; 10] = [
+        "id",
+        "date",
+        "end_date",
+        "start",
+        "end",
+        "time",
+        "title",
+        "description",
+        "recurrence",
+        "all_day",
+    ];
+    fill_template(template, &NAMES, |name| placeholder(event, name))
+}
+
+/// Replaces the `{name}` placeholders of the template whose name is one of
+/// `names` with `value(name)`. Other placeholders are left as they are.
+pub fn fill_template(template: &str, names: &[&str], value: impl Fn(&str) -> String) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}').map(|close| (&after[..close], close)) {
+            Some((name, close)) if names.contains(&name) => {
+                output.push_str(&value(name));
+                rest = &after[close + 1..];
+            }
+            _ => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Returns the output format and template to use: a template without an
+/// explicit format implies `OutputFormat::Template`, which needs one.
+pub fn resolve_format(
+    format: OutputFormat,
+    template: Option<&str>,
+) -> Result<(OutputFormat, &str), String> {
+    match (format, template) {
+        (OutputFormat::Plain, Some(template)) => Ok((OutputFormat::Template, template)),
+        (format, Some(template)) => Ok((format, template)),
+        (OutputFormat::Template, None) => Err("--format template needs --template".to_string()),
+        (format, None) => Ok((format, "")),
+    }
+}
+
+/// Loads the events of the calendar directory and formats the ones in the
+/// requested range that pass the filters.
+pub fn run(
+    args: &AgendaArgs,
+    calendar_dir: &Path,
+    today: NaiveDate,
+) -> Result<String, Box<dyn Error>> {
+    let (from, to) = args.date_range(today)?;
+    let (format, template) = resolve_format(args.format, args.template.as_deref())?;
+
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let events: Vec<_> = events_in_range(repo.load()?, from, to)
+        .into_iter()
+        .filter(|event| args.matches(event))
+        .collect();
+    Ok(format_events(&events, format, template, from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use rcal_lib::Recurrence;
+    use tempfile::TempDir;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn event(title: &str, day: u32, hour: Option<u32>) -> CalendarEvent {
+        let mut event = CalendarEvent::new(
+            title.to_string(),
+            String::new(),
+            date(day),
+            NaiveTime::from_hms_opt(hour.unwrap_or(0), 0, 0).unwrap(),
+        );
+        event.is_all_day = hour.is_none();
+        event.end_time = hour.and_then(|hour| NaiveTime::from_hms_opt(hour + 1, 0, 0));
+        event
+    }
+
+    #[test]
+    fn test_date_range() {
+        let today = date(21);
+        assert_eq!(AgendaArgs::default().date_range(today), Ok((today, today)));
+        let week = AgendaArgs {
+            week: true,
+            ..AgendaArgs::default()
+        };
+        assert_eq!(week.date_range(today), Ok((date(19), date(25))));
+        let month = AgendaArgs {
+            month: true,
+            ..AgendaArgs::default()
+        };
+        assert_eq!(month.date_range(today), Ok((date(1), date(31))));
+        let explicit = AgendaArgs {
+            from: Some("2026-10-02".to_string()),
+            to: Some("2026-10-05".to_string()),
+            ..AgendaArgs::default()
+        };
+        assert_eq!(explicit.date_range(today), Ok((date(2), date(5))));
+        let reversed = AgendaArgs {
+            from: Some("2026-10-05".to_string()),
+            to: Some("2026-10-02".to_string()),
+            ..AgendaArgs::default()
+        };
+        assert!(reversed.date_range(today).is_err());
+    }
+
+    #[test]
+    fn test_events_in_range_expands_recurrences() {
+        let mut standup = event("Standup", 1, Some(9));
+        standup.recurrence = Recurrence::Weekly;
+        let mut trip = event("Trip", 17, None);
+        trip.end_date = Some(date(20));
+        let events = events_in_range(
+            vec![standup, trip, event("Dentist", 26, Some(14))],
+            date(19),
+            date(25),
+        );
+        let titles: Vec<_> = events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["Trip", "Standup"]);
+        assert_eq!(events[1].start_date, date(22));
+        assert!(events[1].is_recurring_instance);
+    }
+
+    #[test]
+    fn test_filters() {
+        let mut review = event("Review #work", 19, Some(10));
+        review.description = "Quarterly numbers".to_string();
+        let holiday = event("Holiday", 19, None);
+
+        let search = AgendaArgs {
+            search: Some("QUARTERLY".to_string()),
+            ..AgendaArgs::default()
+        };
+        assert!(search.matches(&review) && !search.matches(&holiday));
+        let tag = AgendaArgs {
+            tag: vec!["#Work".to_string()],
+            ..AgendaArgs::default()
+        };
+        assert!(tag.matches(&review) && !tag.matches(&holiday));
+        let all_day = AgendaArgs {
+            all_day: true,
+            ..AgendaArgs::default()
+        };
+        assert!(!all_day.matches(&review) && all_day.matches(&holiday));
+        let timed = AgendaArgs {
+            timed: true,
+            ..AgendaArgs::default()
+        };
+        assert!(timed.matches(&review) && !timed.matches(&holiday));
+    }
+
+    #[test]
+    fn test_output_formats() {
+        let mut review = event("Review, \"Q3\"", 19, Some(10));
+        review.description = "Bring {title}".to_string();
+        let events = vec![event("Holiday", 19, None), review];
+
+        let plain = format_events(&events, OutputFormat::Plain, "", date(19), date(19));
+        assert_eq!(
+            plain,
+            "Mon 2026-10-19\n  all day      Holiday\n  10:00-11:00  Review, \"Q3\"\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&format_events(
+            &events,
+            OutputFormat::Json,
+            "",
+            date(19),
+            date(19),
+        ))
+        .unwrap();
+        assert_eq!(json[0]["start_time"], serde_json::Value::Null);
+        assert_eq!(json[1]["start_time"], "10:00");
+        assert_eq!(json[1]["end_time"], "11:00");
+        assert_eq!(json[1]["id"], events[1].stable_id());
+
+        let csv = format_events(&events, OutputFormat::Csv, "", date(19), date(19));
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[2],
+            events[1].stable_id() + ",2026-10-19,2026-10-19,10:00,11:00,false,\"Review, \"\"Q3\"\"\",Bring {title},none"
+        );
+
+        let template = format_events(
+            &events,
+            OutputFormat::Template,
+            "{date} {time} {title} {unknown} {",
+            date(19),
+            date(19),
+        );
+        assert_eq!(
+            template,
+            "2026-10-19 all day Holiday {unknown} {\n2026-10-19 10:00-11:00 Review, \"Q3\" {unknown} {\n"
+        );
+        assert_eq!(
+            render_template("{description}", &events[1]),
+            "Bring {title}"
+        );
+    }
+
+    #[test]
+    fn test_run_lists_saved_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        repo.save(&event("Dentist", 19, Some(14))).unwrap();
+        repo.save(&event("Holiday", 20, None)).unwrap();
+
+        let args = AgendaArgs {
+            week: true,
+            template: Some("{date} {title}".to_string()),
+            ..AgendaArgs::default()
+        };
+        assert_eq!(
+            run(&args, temp_dir.path(), date(21)).unwrap(),
+            "2026-10-19 Dentist\n2026-10-20 Holiday\n"
+        );
+        let missing_template = AgendaArgs {
+            format: OutputFormat::Template,
+            ..AgendaArgs::default()
+        };
+        assert!(run(&missing_template, temp_dir.path(), date(21)).is_err());
+    }
+}
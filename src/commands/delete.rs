@@ -0,0 +1,124 @@
+//! `rcal delete`: removes events selected by id, title and date, or query.
+
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use chrono::NaiveDate;
+use clap::Args;
+use rcal_lib::storage::DynSyncProvider;
+use rcal_lib::{CalendarEvent, EventRepository, FileEventRepository};
+
+use super::select::{describe, EventSelector};
+
+/// Arguments of `rcal delete`.
+#[derive(Args, Debug, Default)]
+pub struct DeleteArgs {
+    #[command(flatten)]
+    pub selector: EventSelector,
+}
+
+/// Deletes the selected events, pushing each deletion if a sync provider is
+/// given, and reports them on `output`. Returns the deleted events, or the
+/// ones that would be deleted with `--dry-run`.
+pub fn run(
+    args: &DeleteArgs,
+    calendar_dir: &Path,
+    sync_provider: Option<&DynSyncProvider>,
+    today: NaiveDate,
+    input: Option<&mut dyn BufRead>,
+    output: &mut dyn Write,
+) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let events = args.selector.select(&repo.load()?, today, input, output)?;
+    for event in &events {
+        let series = if event.recurrence.is_recurring() {
+            " and all its occurrences"
+        } else {
+            ""
+        };
+        if args.selector.dry_run {
+            writeln!(output, "Would delete {}{series}", describe(event))?;
+        } else {
+            repo.delete_with_sync(&event.title, event.start_date, sync_provider, calendar_dir)?;
+            writeln!(output, "Deleted {}{series}", describe(event))?;
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, FileEventRepository) {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        for day in [21, 28] {
+            repo.save(&CalendarEvent::new(
+                "Dentist".to_string(),
+                String::new(),
+                NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+                NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+            ))
+            .unwrap();
+        }
+        (temp_dir, repo)
+    }
+
+    fn args(on: Option<&str>, all: bool, dry_run: bool) -> DeleteArgs {
+        DeleteArgs {
+            selector: EventSelector {
+                query: Some("Dentist".to_string()),
+                on: on.map(str::to_string),
+                all,
+                dry_run,
+                ..EventSelector::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_delete_dry_run_keeps_events() {
+        let (temp_dir, repo) = setup();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let mut output = Vec::new();
+        let deleted = run(
+            &args(None, true, true),
+            temp_dir.path(),
+            None,
+            today,
+            None,
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("Would delete 2026-10-21"));
+        assert_eq!(repo.load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_delete_by_title_and_date() {
+        let (temp_dir, repo) = setup();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        run(
+            &args(Some("2026-10-28"), false, false),
+            temp_dir.path(),
+            None,
+            today,
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let remaining = repo.load().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(
+            remaining[0].start_date,
+            NaiveDate::from_ymd_opt(2026, 10, 21).unwrap()
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! `rcal edit`: changes fields of events selected by id, title and date, or
+//! query.
+
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use chrono::NaiveDate;
+use clap::Args;
+use rcal_lib::core::quick_add::{parse_date, parse_recurrence, parse_time_range};
+use rcal_lib::storage::DynSyncProvider;
+use rcal_lib::sync::field_changes;
+use rcal_lib::{validate_event_with_details, CalendarEvent, EventRepository, FileEventRepository};
+
+use super::select::{describe, EventSelector};
+
+/// Arguments of `rcal edit`.
+#[derive(Args, Debug, Default)]
+pub struct EditArgs {
+    #[command(flatten)]
+    pub selector: EventSelector,
+
+    /// New title
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// New date; multi-day events keep their length
+    #[arg(long, short)]
+    pub date: Option<String>,
+
+    /// New start time or time range (14:00 or 14:00-15:00); a start time
+    /// alone keeps the duration
+    #[arg(long, short, conflicts_with = "all_day")]
+    pub time: Option<String>,
+
+    /// Make the event all-day
+    #[arg(long)]
+    pub all_day: bool,
+
+    /// New last day of a multi-day event
+    #[arg(long, value_name = "DATE")]
+    pub end_date: Option<String>,
+
+    /// New recurrence: none, daily, weekly, monthly or yearly
+    #[arg(long, short, value_name = "RECURRENCE")]
+    pub repeat: Option<String>,
+
+    /// New description
+    #[arg(long, value_name = "TEXT")]
+    pub desc: Option<String>,
+}
+
+impl EditArgs {
+    fn has_changes(&self) -> bool {
+        self.title.is_some()
+            || self.date.is_some()
+            || self.time.is_some()
+            || self.all_day
+            || self.end_date.is_some()
+            || self.repeat.is_some()
+            || self.desc.is_some()
+    }
+
+    /// Returns the event with the changes applied, validated like a new
+    /// event.
+    pub fn apply(&self, event: &CalendarEvent, today: NaiveDate) -> Result<CalendarEvent, String> {
+        let mut edited = event.clone();
+        if let Some(title) = &self.title {
+            edited.title = title.trim().to_string();
+        }
+        if let Some(date) = &self.date {
+            edited.start_date = parse_date(date, today)?;
+            edited.end_date = event
+                .end_date
+                .map(|end| edited.start_date + (end - event.start_date));
+        }
+        if let Some(end_date) = &self.end_date {
+            edited.end_date = Some(parse_date(end_date, edited.start_date)?);
+        }
+        if let Some(time) = &self.time {
+            let (start, end) = parse_time_range(time)?;
+            edited.end_time = match (end, event.end_time) {
+                (Some(end), _) => Some(end),
+                // Keep the duration if it still ends on the same day
+                (None, Some(old_end)) if !event.is_all_day => {
+                    let (end, overflow) = start.overflowing_add_signed(old_end - event.start_time);
+                    (overflow == 0).then_some(end)
+                }
+                (None, _) => None,
+            };
+            edited.start_time = start;
+            edited.is_all_day = false;
+        }
+        if self.all_day {
+            edited.is_all_day = true;
+            edited.start_time = chrono::NaiveTime::MIN;
+            edited.end_time = None;
+        }
+        if let Some(repeat) = &self.repeat {
+            edited.recurrence = parse_recurrence(repeat)?;
+        }
+        if let Some(desc) = &self.desc {
+            edited.description = desc.clone();
+        }
+
+        validate_event_with_details(&edited).map_err(|errors| {
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        })?;
+        Ok(edited)
+    }
+}
+
+/// Applies the changes to the selected events, pushing each one if a sync
+/// provider is given, and reports the changed fields on `output`. Returns
+/// the edited events, or the ones that would be saved with `--dry-run`.
+pub fn run(
+    args: &EditArgs,
+    calendar_dir: &Path,
+    sync_provider: Option<&DynSyncProvider>,
+    today: NaiveDate,
+    input: Option<&mut dyn BufRead>,
+    output: &mut dyn Write,
+) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+    if !args.has_changes() {
+        return Err("Nothing to change; see `rcal edit --help`".into());
+    }
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let stored = repo.load()?;
+    let events = args.selector.select(&stored, today, input, output)?;
+
+    // Validate every event before changing any of them
+    let edits = events
+        .iter()
+        .map(|event| {
+            let edited = args
+                .apply(event, today)
+                .map_err(|e| format!("{}: {e}", describe(event)))?;
+            // Events are stored by title and date, so the edited event
+            // mustn't take the place of another one
+            let moved = (&edited.title, edited.start_date) != (&event.title, event.start_date);
+            if moved
+                && stored
+                    .iter()
+                    .any(|e| (&e.title, e.start_date) == (&edited.title, edited.start_date))
+            {
+                return Err(format!(
+                    "{}: '{}' already exists on {}",
+                    describe(event),
+                    edited.title,
+                    edited.start_date
+                ));
+            }
+            Ok((event, edited))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Nor may two events of the batch end up in the same place
+    for (i, (event, edited)) in edits.iter().enumerate() {
+        let key = (&edited.title, edited.start_date);
+        if let Some((other, _)) = edits[..i]
+            .iter()
+            .find(|(_, e)| (&e.title, e.start_date) == key)
+        {
+            return Err(format!(
+                "{} and {} would both become '{}' on {}",
+                describe(other),
+                describe(event),
+                edited.title,
+                edited.start_date
+            )
+            .into());
+        }
+    }
+
+    let verb = if args.selector.dry_run {
+        "Would update"
+    } else {
+        "Updated"
+    };
+    for (event, edited) in &edits {
+        if !args.selector.dry_run {
+            repo.delete(&event.title, event.start_date)?;
+            repo.save_with_sync(edited, sync_provider, calendar_dir)?;
+        }
+        writeln!(output, "{verb} {}", describe(event))?;
+        for change in field_changes(Some(event), Some(edited)) {
+            writeln!(
+                output,
+                "  {}: {} -> {}",
+                change.field.label(),
+                change.old.unwrap_or_default(),
+                change.new.unwrap_or_default()
+            )?;
+        }
+    }
+    Ok(edits.into_iter().map(|(_, edited)| edited).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use rcal_lib::Recurrence;
+    use tempfile::TempDir;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn dentist() -> CalendarEvent {
+        let mut event =
+            CalendarEvent::new("Dentist".to_string(), String::new(), date(21), time(14, 0));
+        event.end_time = Some(time(15, 0));
+        event
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let moved = EditArgs {
+            date: Some("2026-10-23".to_string()),
+            time: Some("16:30".to_string()),
+            repeat: Some("monthly".to_string()),
+            ..EditArgs::default()
+        }
+        .apply(&dentist(), date(19))
+        .unwrap();
+        assert_eq!(moved.start_date, date(23));
+        assert_eq!(moved.start_time, time(16, 30));
+        assert_eq!(moved.end_time, Some(time(17, 30)));
+        assert_eq!(moved.recurrence, Recurrence::Monthly);
+
+        let all_day = EditArgs {
+            all_day: true,
+            end_date: Some("2026-10-22".to_string()),
+            ..EditArgs::default()
+        }
+        .apply(&dentist(), date(19))
+        .unwrap();
+        assert!(all_day.is_all_day);
+        assert_eq!(all_day.end_date, Some(date(22)));
+
+        let invalid = EditArgs {
+            time: Some("16:00-15:00".to_string()),
+            ..EditArgs::default()
+        };
+        assert!(invalid.apply(&dentist(), date(19)).is_err());
+    }
+
+    #[test]
+    fn test_run_edits_and_reports() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        repo.save(&dentist()).unwrap();
+        let mut args = EditArgs {
+            selector: EventSelector {
+                id: Some(dentist().stable_id()),
+                dry_run: true,
+                ..EventSelector::default()
+            },
+            title: Some("Orthodontist".to_string()),
+            ..EditArgs::default()
+        };
+
+        let mut output = Vec::new();
+        run(&args, temp_dir.path(), None, date(19), None, &mut output).unwrap();
+        let report = String::from_utf8(output).unwrap();
+        assert!(report.starts_with("Would update 2026-10-21"));
+        assert!(report.contains("  Title: Dentist -> Orthodontist"));
+        assert_eq!(repo.load().unwrap()[0].title, "Dentist");
+
+        args.selector.dry_run = false;
+        run(
+            &args,
+            temp_dir.path(),
+            None,
+            date(19),
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let events = repo.load().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].title, "Orthodontist");
+        assert_eq!(events[0].end_time, Some(time(15, 0)));
+
+        repo.save(&dentist()).unwrap();
+        assert!(run(
+            &args,
+            temp_dir.path(),
+            None,
+            date(19),
+            None,
+            &mut Vec::new()
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("already exists"));
+
+        args.title = None;
+        assert!(run(
+            &args,
+            temp_dir.path(),
+            None,
+            date(19),
+            None,
+            &mut Vec::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_refuses_batch_edits_to_the_same_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        let mut checkup = dentist();
+        checkup.title = "Dentist checkup".to_string();
+        repo.save(&dentist()).unwrap();
+        repo.save(&checkup).unwrap();
+        let args = EditArgs {
+            selector: EventSelector {
+                query: Some("Dentist".to_string()),
+                all: true,
+                ..EventSelector::default()
+            },
+            title: Some("Orthodontist".to_string()),
+            ..EditArgs::default()
+        };
+
+        let error = run(
+            &args,
+            temp_dir.path(),
+            None,
+            date(19),
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("would both become 'Orthodontist' on 2026-10-21"));
+        // Nothing was written
+        let mut titles: Vec<_> = repo.load().unwrap().into_iter().map(|e| e.title).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Dentist", "Dentist checkup"]);
+    }
+}
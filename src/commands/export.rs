@@ -0,0 +1,96 @@
+//! `rcal export`: writes stored events as CSV that `rcal import` reads back.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use clap::Args;
+use rcal_lib::core::quick_add::parse_date;
+use rcal_lib::{events_to_csv, EventRepository, FileEventRepository};
+
+/// Arguments of `rcal export`.
+#[derive(Args, Debug, Default)]
+pub struct ExportArgs {
+    /// Only export events on or after this day; recurring events that start
+    /// earlier are kept
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<String>,
+
+    /// Only export events starting on or before this day
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<String>,
+
+    /// File to write instead of standard output
+    #[arg(long, short, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+/// Returns the stored events of the calendar directory in the range as CSV,
+/// sorted by start. Recurring events are exported once, with their
+/// recurrence, rather than per occurrence.
+pub fn run(
+    args: &ExportArgs,
+    calendar_dir: &Path,
+    today: NaiveDate,
+) -> Result<String, Box<dyn Error>> {
+    let from = args
+        .from
+        .as_deref()
+        .map(|d| parse_date(d, today))
+        .transpose()?;
+    let to = args
+        .to
+        .as_deref()
+        .map(|d| parse_date(d, today))
+        .transpose()?;
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let mut events: Vec<_> = repo
+        .load()?
+        .into_iter()
+        .filter(|event| {
+            from.is_none_or(|from| {
+                event.recurrence.is_recurring() || event.effective_end_date() >= from
+            }) && to.is_none_or(|to| event.start_date <= to)
+        })
+        .collect();
+    events.sort_by(|a, b| {
+        (a.start_date, a.start_time, &a.title).cmp(&(b.start_date, b.start_time, &b.title))
+    });
+    Ok(events_to_csv(&events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use rcal_lib::{CalendarEvent, CsvImporter};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        for (title, day) in [("Dentist", 2), ("Lunch", 20), ("Review", 9)] {
+            repo.save(&CalendarEvent::new(
+                title.to_string(),
+                String::new(),
+                NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+                NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+            ))
+            .unwrap();
+        }
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let args = ExportArgs {
+            from: Some("2026-10-05".to_string()),
+            ..ExportArgs::default()
+        };
+        let csv = run(&args, temp_dir.path(), today).unwrap();
+        let import = CsvImporter::new().parse(&csv).unwrap();
+        let titles: Vec<_> = import
+            .events
+            .iter()
+            .map(|(_, e)| e.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Review", "Lunch"]);
+    }
+}
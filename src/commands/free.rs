@@ -0,0 +1,301 @@
+//! `rcal free`: finds free time slots for a meeting.
+
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use clap::Args;
+use rcal_lib::core::quick_add::{parse_date, parse_duration, parse_time};
+use rcal_lib::csv::quote_field;
+use rcal_lib::{EventRepository, FileEventRepository, FreeSlotFinder, TimeSlot};
+
+use super::agenda::{fill_template, resolve_format, OutputFormat};
+
+/// Arguments of `rcal free`.
+#[derive(Args, Debug)]
+pub struct FreeArgs {
+    /// Minimum length of a slot, e.g. 90m or 1h30m
+    #[arg(long, short, value_name = "DURATION", default_value = "1h")]
+    pub duration: String,
+
+    /// Hours of each day to search; an end of 00:00 means midnight
+    #[arg(long, value_name = "HH:MM-HH:MM", default_value = "09:00-17:00")]
+    pub between: String,
+
+    /// First day to search [default: today]
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<String>,
+
+    /// Last day to search [default: the first day]
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<String>,
+
+    /// Time to keep free before and after every event, e.g. 15m
+    #[arg(long, value_name = "DURATION")]
+    pub buffer: Option<String>,
+
+    /// Don't treat all-day events as busy
+    #[arg(long)]
+    pub ignore_all_day: bool,
+
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Line template, e.g. "{date} {start}-{end}"; implies --format template.
+    /// Placeholders: date, start, end, duration, minutes
+    #[arg(long, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+}
+
+impl Default for FreeArgs {
+    fn default() -> Self {
+        Self {
+            duration: "1h".to_string(),
+            between: "09:00-17:00".to_string(),
+            from: None,
+            to: None,
+            buffer: None,
+            ignore_all_day: false,
+            format: OutputFormat::default(),
+            template: None,
+        }
+    }
+}
+
+fn positive_duration(input: &str) -> Result<Duration, String> {
+    parse_duration(input)
+        .filter(|duration| *duration > Duration::zero())
+        .ok_or_else(|| format!("Invalid duration '{input}'. Use e.g. 90m or 1h30m"))
+}
+
+impl FreeArgs {
+    /// Returns the first and last day to search.
+    pub fn date_range(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let from = match &self.from {
+            Some(from) => parse_date(from, today)?,
+            None => today,
+        };
+        let to = match &self.to {
+            // "--from mon --to fri" means the Friday after that Monday even
+            // if this week's Friday comes first
+            Some(to) => match parse_date(to, today)? {
+                date if date < from => parse_date(to, from - Duration::days(1))?,
+                date => date,
+            },
+            None => from,
+        };
+        if to < from {
+            return Err(format!("End of range {to} is before its start {from}"));
+        }
+        Ok((from, to))
+    }
+
+    /// Builds the finder described by the arguments, ignoring time before
+    /// `now`.
+    pub fn to_finder(&self, now: NaiveDateTime) -> Result<FreeSlotFinder, String> {
+        let duration = positive_duration(&self.duration)?;
+        let (start, end) = self
+            .between
+            .split_once('-')
+            .ok_or_else(|| "--between needs a range such as 09:00-17:00".to_string())?;
+        let (start, end) = (parse_time(start)?, parse_time(end)?);
+        if end <= start && end != NaiveTime::MIN {
+            return Err(format!("--between ends at {end}, before it starts"));
+        }
+        let mut finder = FreeSlotFinder::new(duration)
+            .between_times(start, end)
+            .include_all_day(!self.ignore_all_day)
+            .not_before(now);
+        if let Some(buffer) = &self.buffer {
+            let buffer = parse_duration(buffer)
+                .ok_or_else(|| format!("Invalid buffer '{buffer}'. Use e.g. 15m"))?;
+            finder = finder.with_buffer(buffer);
+        }
+        Ok(finder)
+    }
+}
+
+/// Formats a duration as `1h30m`, `2h` or `45m`.
+pub fn duration_label(duration: Duration) -> String {
+    let (hours, minutes) = (duration.num_hours(), duration.num_minutes() % 60);
+    match (hours, minutes) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h{minutes}m"),
+    }
+}
+
+/// Formats the end of a slot, writing midnight at the end of the day as
+/// 24:00.
+fn end_label(slot: &TimeSlot) -> String {
+    if slot.end.date() > slot.start.date() && slot.end.time() == NaiveTime::MIN {
+        "24:00".to_string()
+    } else {
+        slot.end.format("%H:%M").to_string()
+    }
+}
+
+fn placeholder(slot: &TimeSlot, name: &str) -> String {
+    match name {
+        "date" => slot.start.date().to_string(),
+        "start" => slot.start.format("%H:%M").to_string(),
+        "end" => end_label(slot),
+        "duration" => duration_label(slot.duration()),
+        "minutes" => slot.duration().num_minutes().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Formats the slots in the given format. `template` is used by
+/// `OutputFormat::Template`.
+pub fn format_slots(slots: &[TimeSlot], format: OutputFormat, template: &str) -> String {
+    const NAMES: [&str; 5] = ["date", "start", "end", "duration", "minutes"];
+    match format {
+        OutputFormat::Plain => {
+            if slots.is_empty() {
+                return "No free slots\n".to_string();
+            }
+            let mut output = String::new();
+            let mut day = None;
+            for slot in slots {
+                let date = slot.start.date();
+                if day != Some(date) {
+                    if day.is_some() {
+                        output.push('\n');
+                    }
+                    output.push_str(&format!("{}\n", date.format("%a %Y-%m-%d")));
+                    day = Some(date);
+                }
+                output.push_str(&format!(
+                    "  {}-{}  {}\n",
+                    placeholder(slot, "start"),
+                    end_label(slot),
+                    duration_label(slot.duration())
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            let slots: Vec<_> = slots
+                .iter()
+                .map(|slot| {
+                    serde_json::json!({
+                        "date": placeholder(slot, "date"),
+                        "start": placeholder(slot, "start"),
+                        "end": end_label(slot),
+                        "minutes": slot.duration().num_minutes(),
+                    })
+                })
+                .collect();
+            serde_json::to_string_pretty(&slots).unwrap_or_default() + "\n"
+        }
+        OutputFormat::Csv => {
+            let mut output = "date,start,end,minutes\n".to_string();
+            for slot in slots {
+                let row: Vec<_> = ["date", "start", "end", "minutes"]
+                    .iter()
+                    .map(|name| quote_field(&placeholder(slot, name)))
+                    .collect();
+                output.push_str(&row.join(","));
+                output.push('\n');
+            }
+            output
+        }
+        OutputFormat::Template => slots
+            .iter()
+            .map(|slot| fill_template(template, &NAMES, |name| placeholder(slot, name)) + "\n")
+            .collect(),
+    }
+}
+
+/// Finds the free slots in the events of the calendar directory.
+pub fn run(
+    args: &FreeArgs,
+    calendar_dir: &Path,
+    now: NaiveDateTime,
+) -> Result<String, Box<dyn Error>> {
+    let (from, to) = args.date_range(now.date())?;
+    let (format, template) = resolve_format(args.format, args.template.as_deref())?;
+    let finder = args.to_finder(now)?;
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let slots = finder.find(&repo.load()?, from, to);
+    Ok(format_slots(&slots, format, template))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcal_lib::CalendarEvent;
+    use tempfile::TempDir;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_date_range_and_finder() {
+        // Wednesday: "mon" is next week's Monday and "fri" the Friday after it
+        let args = FreeArgs {
+            from: Some("mon".to_string()),
+            to: Some("fri".to_string()),
+            ..FreeArgs::default()
+        };
+        assert_eq!(args.date_range(date(21)).unwrap(), (date(26), date(30)));
+
+        let invalid = [
+            FreeArgs {
+                duration: "soon".to_string(),
+                ..FreeArgs::default()
+            },
+            FreeArgs {
+                between: "17:00-09:00".to_string(),
+                ..FreeArgs::default()
+            },
+            FreeArgs {
+                between: "09:00".to_string(),
+                ..FreeArgs::default()
+            },
+        ];
+        for args in invalid {
+            assert!(args.to_finder(date(21).and_time(time(8, 0))).is_err());
+        }
+    }
+
+    #[test]
+    fn test_run_lists_free_slots() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        let mut review =
+            CalendarEvent::new("Review".to_string(), String::new(), date(19), time(10, 0));
+        review.end_time = Some(time(12, 0));
+        repo.save(&review).unwrap();
+
+        let args = FreeArgs {
+            duration: "90m".to_string(),
+            from: Some("2026-10-19".to_string()),
+            to: Some("2026-10-20".to_string()),
+            buffer: Some("15m".to_string()),
+            ..FreeArgs::default()
+        };
+        let now = date(19).and_time(time(8, 0));
+        assert_eq!(
+            run(&args, temp_dir.path(), now).unwrap(),
+            "Mon 2026-10-19\n  12:15-17:00  4h45m\n\nTue 2026-10-20\n  09:00-17:00  8h\n"
+        );
+
+        let csv = FreeArgs {
+            format: OutputFormat::Csv,
+            between: "20:00-00:00".to_string(),
+            ..args
+        };
+        assert_eq!(
+            run(&csv, temp_dir.path(), now).unwrap(),
+            "date,start,end,minutes\n2026-10-19,20:00,24:00,240\n2026-10-20,20:00,24:00,240\n"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! `rcal import`: adds events from a CSV file, such as a holiday list or a
+//! spreadsheet export.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use rcal_lib::storage::DynSyncProvider;
+use rcal_lib::sync::{as_sync_provider, push_with_journal, PendingOperation};
+use rcal_lib::{CalendarEvent, ColumnMapping, CsvImporter, EventRepository, FileEventRepository};
+
+use super::select::describe;
+
+/// Arguments of `rcal import`.
+#[derive(Args, Debug, Default)]
+pub struct ImportArgs {
+    /// CSV file to import, or - for standard input
+    pub file: PathBuf,
+
+    /// Columns of the fields, e.g. "title=Holiday,date=Day,description=3";
+    /// fields: title, date, end_date, start_time, end_time, all_day,
+    /// description, recurrence. Unmapped fields are found by header name
+    #[arg(long, value_name = "FIELD=COLUMN,...")]
+    pub map: Option<String>,
+
+    /// Date format of the file, e.g. %d.%m.%Y [default: detected]
+    #[arg(long, value_name = "FORMAT")]
+    pub date_format: Option<String>,
+
+    /// Field delimiter [default: detected from ",", ";" and tab]
+    #[arg(long, value_name = "CHAR")]
+    pub delimiter: Option<char>,
+
+    /// Print what would be imported without importing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Imports the valid rows of the file into the calendar directory, skipping
+/// rows that are invalid or would replace an existing event, and reports
+/// every row on `output`. The import is pushed at once if a sync provider is
+/// given. Returns the imported events, or the ones that would be imported
+/// with `--dry-run`.
+pub fn run(
+    args: &ImportArgs,
+    calendar_dir: &Path,
+    sync_provider: Option<&DynSyncProvider>,
+    output: &mut dyn Write,
+) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+    let input = if args.file == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        fs::read_to_string(&args.file)
+            .map_err(|e| format!("Cannot read {}: {e}", args.file.display()))?
+    };
+
+    let mut importer = CsvImporter::new();
+    if let Some(map) = &args.map {
+        importer = importer.with_mapping(ColumnMapping::parse(map)?);
+    }
+    if let Some(format) = &args.date_format {
+        importer = importer.with_date_format(format);
+    }
+    if let Some(delimiter) = args.delimiter {
+        importer = importer.with_delimiter(delimiter);
+    }
+    let import = importer.parse(&input)?;
+    writeln!(output, "Date format: {}", import.date_format)?;
+
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    // Events are stored by title and date, so each key can only be used once
+    let mut taken: HashMap<(String, chrono::NaiveDate), Option<usize>> = repo
+        .load()?
+        .into_iter()
+        .map(|event| ((event.title, event.start_date), None))
+        .collect();
+    let mut rows: Vec<(usize, Result<CalendarEvent, String>)> = import
+        .errors
+        .into_iter()
+        .map(|error| (error.line, Err(error.messages.join("; "))))
+        .collect();
+    for (line, event) in import.events {
+        let key = (event.title.clone(), event.start_date);
+        let result = match taken.get(&key) {
+            Some(None) => Err(format!(
+                "'{}' already exists on {}",
+                event.title, event.start_date
+            )),
+            Some(Some(first)) => Err(format!("same title and date as line {first}")),
+            None => {
+                taken.insert(key, Some(line));
+                Ok(event)
+            }
+        };
+        rows.push((line, result));
+    }
+    rows.sort_by_key(|(line, _)| *line);
+
+    let verb = if args.dry_run {
+        "would import"
+    } else {
+        "imported"
+    };
+    let total = rows.len();
+    let mut imported = Vec::new();
+    for (line, result) in rows {
+        match result {
+            Ok(event) => {
+                if !args.dry_run {
+                    repo.save(&event)?;
+                }
+                writeln!(output, "Line {line}: {verb} {}", describe(&event))?;
+                imported.push(event);
+            }
+            Err(message) => writeln!(output, "Line {line}: skipped, {message}")?,
+        }
+    }
+
+    let summary = format!(
+        "{} of {total} events ({} skipped)",
+        imported.len(),
+        total - imported.len()
+    );
+    if args.dry_run {
+        writeln!(output, "Would import {summary}")?;
+        return Ok(imported);
+    }
+    writeln!(output, "Imported {summary}")?;
+    if let Some(provider) = sync_provider.and_then(as_sync_provider) {
+        if !imported.is_empty() {
+            // A failed push is queued in the sync journal and retried later
+            let operation = PendingOperation::import(imported.len());
+            let _ = push_with_journal(provider, calendar_dir, operation);
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_import_reports_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let calendar_dir = temp_dir.path().join("calendar");
+        let repo = FileEventRepository::with_path(calendar_dir.clone());
+        let new_year = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        repo.save(&CalendarEvent::new(
+            "New Year".to_string(),
+            String::new(),
+            new_year,
+            NaiveTime::MIN,
+        ))
+        .unwrap();
+
+        let file = temp_dir.path().join("holidays.csv");
+        fs::write(
+            &file,
+            "Holiday;Day;Time\n\
+             New Year;01.01.2026;\n\
+             Labour Day;01.05.2026;\n\
+             Labour Day;01.05.2026;\n\
+             Team day;14.05.2026;25:00\n\
+             Christmas;25.12.2026;\n",
+        )
+        .unwrap();
+        let mut args = ImportArgs {
+            file,
+            map: Some("title=holiday".to_string()),
+            dry_run: true,
+            ..ImportArgs::default()
+        };
+
+        let mut output = Vec::new();
+        let preview = run(&args, &calendar_dir, None, &mut output).unwrap();
+        let report = String::from_utf8(output).unwrap();
+        assert_eq!(preview.len(), 2);
+        assert_eq!(repo.load().unwrap().len(), 1);
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines[0], "Date format: %d.%m.%Y");
+        assert_eq!(
+            lines[1],
+            "Line 2: skipped, 'New Year' already exists on 2026-01-01"
+        );
+        assert!(lines[2].starts_with("Line 3: would import 2026-05-01 all day Labour Day"));
+        assert_eq!(lines[3], "Line 4: skipped, same title and date as line 3");
+        assert_eq!(lines[4], "Line 5: skipped, invalid start time '25:00'");
+        assert_eq!(lines[6], "Would import 2 of 5 events (3 skipped)");
+
+        args.dry_run = false;
+        run(&args, &calendar_dir, None, &mut Vec::new()).unwrap();
+        assert_eq!(repo.load().unwrap().len(), 3);
+    }
+}
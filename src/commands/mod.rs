@@ -0,0 +1,15 @@
+//! Non-interactive subcommands of the rcal binary.
+//!
+//! Each module holds the clap arguments of one command and the logic behind
+//! it, so the commands can be tested without going through `main`.
+
+pub mod add;
+pub mod agenda;
+pub mod delete;
+pub mod edit;
+pub mod export;
+pub mod free;
+pub mod import;
+pub mod next;
+pub mod search;
+pub mod select;
@@ -0,0 +1,287 @@
+//! `rcal next`: prints the current or next event for status bars such as
+//! waybar, polybar or tmux.
+//!
+//! Events are read through `EventService`, so occurrences of recurring events
+//! are included. `--json` prints a waybar custom module object with the
+//! event as `text`, today's agenda as `tooltip` and its state as `class`.
+
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{Duration, NaiveDateTime};
+use clap::Args;
+use rcal_lib::core::quick_add::parse_duration;
+use rcal_lib::{CalendarEvent, EventRepository, FileEventRepository};
+
+use super::agenda::{events_in_range, fill_template, render_template, time_label};
+use super::free::duration_label;
+
+/// Arguments of `rcal next`.
+#[derive(Args, Debug)]
+pub struct NextArgs {
+    /// Line template; the placeholders of `rcal agenda` plus countdown
+    /// ("in 25m" or "ends in 10m") and status
+    #[arg(long, value_name = "TEMPLATE", default_value = "{title} {countdown}")]
+    pub template: String,
+
+    /// Print a waybar custom module object with text, tooltip and class
+    #[arg(long)]
+    pub json: bool,
+
+    /// How soon an event counts as imminent
+    #[arg(long, value_name = "DURATION", default_value = "15m")]
+    pub imminent: String,
+
+    /// Number of days to look ahead
+    #[arg(long, value_name = "DAYS", default_value_t = 7)]
+    pub days: u32,
+
+    /// Consider all-day events too; they are always in today's agenda
+    #[arg(long)]
+    pub include_all_day: bool,
+
+    /// Text printed when there is no event
+    #[arg(long, value_name = "TEXT", default_value = "")]
+    pub empty: String,
+}
+
+impl Default for NextArgs {
+    fn default() -> Self {
+        Self {
+            template: "{title} {countdown}".to_string(),
+            json: false,
+            imminent: "15m".to_string(),
+            days: 7,
+            include_all_day: false,
+            empty: String::new(),
+        }
+    }
+}
+
+/// Whether the shown event is running or how soon it starts. The names are
+/// the CSS classes of the waybar module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NextStatus {
+    InProgress,
+    Imminent,
+    Upcoming,
+    Idle,
+}
+
+impl NextStatus {
+    pub fn class(self) -> &'static str {
+        match self {
+            NextStatus::InProgress => "in-progress",
+            NextStatus::Imminent => "imminent",
+            NextStatus::Upcoming => "upcoming",
+            NextStatus::Idle => "idle",
+        }
+    }
+}
+
+fn start_of(event: &CalendarEvent) -> NaiveDateTime {
+    event.start_date.and_time(event.start_time)
+}
+
+fn end_of(event: &CalendarEvent) -> NaiveDateTime {
+    if event.is_all_day {
+        return (event.effective_end_date() + Duration::days(1)).and_time(chrono::NaiveTime::MIN);
+    }
+    event
+        .effective_end_date()
+        .and_time(event.end_time.unwrap_or(event.start_time))
+        .max(start_of(event))
+}
+
+/// Formats the time left as `25m`, `2h5m` or `1d3h`, rounding up to whole
+/// minutes.
+fn countdown(left: Duration) -> String {
+    let minutes = (left.num_seconds() + 59) / 60;
+    match minutes {
+        m if m < 24 * 60 => duration_label(Duration::minutes(m)),
+        m => match (m / (24 * 60), m % (24 * 60) / 60) {
+            (days, 0) => format!("{days}d"),
+            (days, hours) => format!("{days}d{hours}h"),
+        },
+    }
+}
+
+/// Returns the event running at `now`, or else the next one to start, with
+/// its status.
+pub fn next_event(
+    events: &[CalendarEvent],
+    now: NaiveDateTime,
+    imminent: Duration,
+    include_all_day: bool,
+) -> Option<(CalendarEvent, NextStatus)> {
+    let candidates = events
+        .iter()
+        .filter(|event| include_all_day || !event.is_all_day);
+    let running = candidates
+        .clone()
+        .filter(|event| start_of(event) <= now && now < end_of(event))
+        .min_by_key(|event| (start_of(event), event.title.clone()));
+    if let Some(event) = running {
+        return Some((event.clone(), NextStatus::InProgress));
+    }
+    candidates
+        .filter(|event| start_of(event) > now)
+        .min_by_key(|event| (start_of(event), event.title.clone()))
+        .map(|event| {
+            let status = if start_of(event) - now <= imminent {
+                NextStatus::Imminent
+            } else {
+                NextStatus::Upcoming
+            };
+            (event.clone(), status)
+        })
+}
+
+/// Renders the template for the event.
+fn render(template: &str, event: &CalendarEvent, status: NextStatus, now: NaiveDateTime) -> String {
+    let label = match status {
+        NextStatus::InProgress => format!("ends in {}", countdown(end_of(event) - now)),
+        _ => format!("in {}", countdown(start_of(event) - now)),
+    };
+    // Event fields come last so text in them isn't taken for placeholders
+    let template = fill_template(template, &["countdown", "status"], |name| match name {
+        "countdown" => label.clone(),
+        _ => status.class().to_string(),
+    });
+    render_template(&template, event)
+}
+
+/// Lists the events of the day for the tooltip.
+fn agenda(events: &[CalendarEvent], now: NaiveDateTime) -> String {
+    let today = now.date();
+    let mut day: Vec<_> = events.iter().filter(|e| e.occurs_on(today)).collect();
+    if day.is_empty() {
+        return "No events today".to_string();
+    }
+    day.sort_by_key(|event| (!event.is_all_day, event.start_time, event.title.clone()));
+    day.iter()
+        .map(|event| format!("{} {}", time_label(event), event.title))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Loads the events of the calendar directory and formats the current or
+/// next one.
+pub fn run(
+    args: &NextArgs,
+    calendar_dir: &Path,
+    now: NaiveDateTime,
+) -> Result<String, Box<dyn Error>> {
+    let imminent = parse_duration(&args.imminent)
+        .ok_or_else(|| format!("Invalid duration '{}'. Use e.g. 15m", args.imminent))?;
+    let today = now.date();
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let events = events_in_range(
+        repo.load()?,
+        today - Duration::days(1),
+        today + Duration::days(i64::from(args.days)),
+    );
+
+    let (text, status) = match next_event(&events, now, imminent, args.include_all_day) {
+        Some((event, status)) => (render(&args.template, &event, status, now), status),
+        None => (args.empty.clone(), NextStatus::Idle),
+    };
+    if !args.json {
+        return Ok(text + "\n");
+    }
+    let module = serde_json::json!({
+        "text": text,
+        "alt": status.class(),
+        "tooltip": agenda(&events, now),
+        "class": status.class(),
+    });
+    Ok(module.to_string() + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+    use rcal_lib::Recurrence;
+    use tempfile::TempDir;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        date(day).and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn setup() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        let mut standup =
+            CalendarEvent::new("Standup".to_string(), String::new(), date(1), time(9, 0));
+        standup.end_time = Some(time(9, 15));
+        standup.recurrence = Recurrence::Daily;
+        let dentist =
+            CalendarEvent::new("Dentist".to_string(), String::new(), date(19), time(14, 0));
+        let mut holiday =
+            CalendarEvent::new("Holiday".to_string(), String::new(), date(19), time(0, 0));
+        holiday.is_all_day = true;
+        for event in [standup, dentist, holiday] {
+            repo.save(&event).unwrap();
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_next_event_text() {
+        let temp_dir = setup();
+        let args = NextArgs::default();
+        let output = |now| run(&args, temp_dir.path(), now).unwrap();
+        assert_eq!(output(at(19, 8, 50)), "Standup in 10m\n");
+        assert_eq!(output(at(19, 9, 5)), "Standup ends in 10m\n");
+        assert_eq!(output(at(19, 9, 30)), "Dentist in 4h30m\n");
+        // Recurring occurrences on the next day are found too
+        assert_eq!(output(at(19, 14, 0)), "Standup in 19h\n");
+
+        let template = NextArgs {
+            template: "{start} {title} [{status}]".to_string(),
+            ..NextArgs::default()
+        };
+        assert_eq!(
+            run(&template, temp_dir.path(), at(19, 13, 50)).unwrap(),
+            "14:00 Dentist [imminent]\n"
+        );
+        let empty = NextArgs {
+            days: 0,
+            empty: "free".to_string(),
+            ..NextArgs::default()
+        };
+        assert_eq!(
+            run(&empty, temp_dir.path(), at(19, 15, 0)).unwrap(),
+            "free\n"
+        );
+        assert_eq!(countdown(Duration::hours(51)), "2d3h");
+    }
+
+    #[test]
+    fn test_waybar_json() {
+        let temp_dir = setup();
+        let args = NextArgs {
+            json: true,
+            ..NextArgs::default()
+        };
+        let output = run(&args, temp_dir.path(), at(19, 9, 5)).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        let module: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(module["text"], "Standup ends in 10m");
+        assert_eq!(module["class"], "in-progress");
+        assert_eq!(
+            module["tooltip"],
+            "all day Holiday\n09:00-09:15 Standup\n14:00 Dentist"
+        );
+
+        let output = run(&args, temp_dir.path(), at(19, 10, 0)).unwrap();
+        let module: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(module["class"], "upcoming");
+    }
+}
@@ -0,0 +1,198 @@
+//! `rcal search`: finds events by text or regular expression.
+
+use std::error::Error;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use clap::{Args, ValueEnum};
+use rcal_lib::core::quick_add::parse_date;
+use rcal_lib::{EventField, EventRepository, EventSearch, FileEventRepository};
+
+use super::agenda::{format_events, resolve_format, OutputFormat};
+use super::select::describe;
+
+/// Event fields `rcal search` can look in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SearchField {
+    Title,
+    Description,
+}
+
+impl From<SearchField> for EventField {
+    fn from(field: SearchField) -> Self {
+        match field {
+            SearchField::Title => EventField::Title,
+            SearchField::Description => EventField::Description,
+        }
+    }
+}
+
+/// Arguments of `rcal search`.
+#[derive(Args, Debug, Default)]
+pub struct SearchArgs {
+    /// Text to search for, ignoring case, or a regular expression with --regex
+    pub pattern: String,
+
+    /// Treat the pattern as a regular expression
+    #[arg(long, short = 'e')]
+    pub regex: bool,
+
+    /// Field to search, may be repeated [default: title and description]
+    #[arg(long = "field", value_enum, value_name = "FIELD")]
+    pub fields: Vec<SearchField>,
+
+    /// Only find events on or after this day
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<String>,
+
+    /// Only find events on or before this day; with --from, recurring events
+    /// are listed once per occurrence
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<String>,
+
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Line template as for `rcal agenda`; implies --format template
+    #[arg(long, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+}
+
+impl SearchArgs {
+    /// Builds the search described by the arguments.
+    pub fn to_search(&self, today: NaiveDate) -> Result<EventSearch, Box<dyn Error>> {
+        let mut search = if self.regex {
+            EventSearch::regex(&self.pattern)?
+        } else {
+            EventSearch::new(&self.pattern)
+        };
+        if !self.fields.is_empty() {
+            let fields: Vec<EventField> = self.fields.iter().map(|&f| f.into()).collect();
+            search = search.with_fields(&fields);
+        }
+        let from = self
+            .from
+            .as_deref()
+            .map(|d| parse_date(d, today))
+            .transpose()?;
+        let to = self
+            .to
+            .as_deref()
+            .map(|d| parse_date(d, today))
+            .transpose()?;
+        if let (Some(from), Some(to)) = (from, to) {
+            if to < from {
+                return Err(format!("End of range {to} is before its start {from}").into());
+            }
+        }
+        Ok(search.between(from, to))
+    }
+}
+
+/// Searches the events of the calendar directory and formats the results.
+pub fn run(
+    args: &SearchArgs,
+    calendar_dir: &Path,
+    today: NaiveDate,
+) -> Result<String, Box<dyn Error>> {
+    let (format, template) = resolve_format(args.format, args.template.as_deref())?;
+    let search = args.to_search(today)?;
+    let repo = FileEventRepository::with_path(calendar_dir.to_path_buf());
+    let results = search.search(&repo.load()?);
+
+    if format != OutputFormat::Plain {
+        let (first, last) = match (results.first(), results.last()) {
+            (Some(first), Some(last)) => (first.start_date, last.effective_end_date()),
+            _ => (today, today),
+        };
+        return Ok(format_events(&results, format, template, first, last));
+    }
+    if results.is_empty() {
+        return Ok("No matching events\n".to_string());
+    }
+    Ok(results.iter().map(|event| describe(event) + "\n").collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use rcal_lib::CalendarEvent;
+    use tempfile::TempDir;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn setup() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileEventRepository::with_path(temp_dir.path().to_path_buf());
+        for (title, description, day) in [
+            ("Dentist", "Dr. Molar", date(3, 2)),
+            ("Dentist", "Cleaning", date(9, 14)),
+            ("Lunch", "Near the dentist", date(6, 1)),
+        ] {
+            repo.save(&CalendarEvent::new(
+                title.to_string(),
+                description.to_string(),
+                day,
+                NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+            ))
+            .unwrap();
+        }
+        temp_dir
+    }
+
+    fn args(pattern: &str) -> SearchArgs {
+        SearchArgs {
+            pattern: pattern.to_string(),
+            ..SearchArgs::default()
+        }
+    }
+
+    #[test]
+    fn test_search_lists_matches() {
+        let temp_dir = setup();
+        let output = run(&args("DENTIST"), temp_dir.path(), date(10, 19)).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("2026-03-02 14:00 Dentist"));
+
+        let titles_only = SearchArgs {
+            fields: vec![SearchField::Title],
+            from: Some("2026-04-01".to_string()),
+            template: Some("{date} {description}".to_string()),
+            ..args("dentist")
+        };
+        assert_eq!(
+            run(&titles_only, temp_dir.path(), date(10, 19)).unwrap(),
+            "2026-09-14 Cleaning\n"
+        );
+    }
+
+    #[test]
+    fn test_search_regex_and_errors() {
+        let temp_dir = setup();
+        let regex = SearchArgs {
+            regex: true,
+            ..args("^(lunch|nothing)$")
+        };
+        assert_eq!(
+            run(&regex, temp_dir.path(), date(10, 19))
+                .unwrap()
+                .lines()
+                .count(),
+            1
+        );
+        let invalid = SearchArgs {
+            regex: true,
+            ..args("(")
+        };
+        assert!(run(&invalid, temp_dir.path(), date(10, 19)).is_err());
+        assert_eq!(
+            run(&args("yoga"), temp_dir.path(), date(10, 19)).unwrap(),
+            "No matching events\n"
+        );
+    }
+}
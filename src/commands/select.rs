@@ -0,0 +1,268 @@
+//! Selection of stored events for the commands that change them.
+//!
+//! Events are picked by their stable id, by exact title and date, or by a
+//! text query. When a query matches several events, the user is asked which
+//! ones to use unless `--all` is given.
+
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+use chrono::NaiveDate;
+use clap::Args;
+use rcal_lib::core::quick_add::parse_date;
+use rcal_lib::{CalendarEvent, EventSearch};
+
+use super::agenda::{events_in_range, time_label};
+
+/// Arguments selecting the events a command applies to.
+#[derive(Args, Debug, Default)]
+pub struct EventSelector {
+    /// Event id as shown by `rcal agenda --format json`, or a prefix of it
+    #[arg(long, conflicts_with_all = ["query", "on"])]
+    pub id: Option<String>,
+
+    /// Exact event title with --on, otherwise text searched in titles and
+    /// descriptions
+    #[arg(required_unless_present = "id")]
+    pub query: Option<String>,
+
+    /// Date of the event, or of one of its occurrences, to match the title
+    /// exactly
+    #[arg(long, value_name = "DATE", requires = "query")]
+    pub on: Option<String>,
+
+    /// Apply to every matching event instead of asking
+    #[arg(long)]
+    pub all: bool,
+
+    /// Print what would change without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl EventSelector {
+    /// Returns the stored events matching the selector.
+    pub fn matching(
+        &self,
+        events: &[CalendarEvent],
+        today: NaiveDate,
+    ) -> Result<Vec<CalendarEvent>, String> {
+        if let Some(id) = &self.id {
+            let id = id.to_lowercase();
+            return Ok(events
+                .iter()
+                .filter(|event| !id.is_empty() && event.stable_id().starts_with(&id))
+                .cloned()
+                .collect());
+        }
+        let query = self.query.as_deref().unwrap_or_default();
+        match &self.on {
+            Some(on) => {
+                let date = parse_date(on, today)?;
+                Ok(events
+                    .iter()
+                    .filter(|event| {
+                        event.title.to_lowercase() == query.to_lowercase()
+                            && !events_in_range(vec![(*event).clone()], date, date).is_empty()
+                    })
+                    .cloned()
+                    .collect())
+            }
+            None => {
+                let search = EventSearch::new(query);
+                Ok(events
+                    .iter()
+                    .filter(|event| search.matches(event))
+                    .cloned()
+                    .collect())
+            }
+        }
+    }
+
+    /// Returns the events to apply the command to.
+    ///
+    /// Several matches are all used with `--all`; otherwise the user picks
+    /// from a numbered list read from `input`, or the selection fails when
+    /// there is no one to ask.
+    pub fn select(
+        &self,
+        events: &[CalendarEvent],
+        today: NaiveDate,
+        input: Option<&mut dyn BufRead>,
+        output: &mut dyn Write,
+    ) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+        let mut matches = self.matching(events, today)?;
+        matches.sort_by(|a, b| {
+            (a.start_date, a.start_time, &a.title).cmp(&(b.start_date, b.start_time, &b.title))
+        });
+        match (matches.len(), input) {
+            (0, _) => Err("No matching event".into()),
+            (1, _) => Ok(matches),
+            _ if self.all => Ok(matches),
+            (count, Some(input)) => choose(matches, input, output).and_then(|chosen| {
+                if chosen.is_empty() {
+                    Err(format!("Cancelled, none of the {count} matches was chosen").into())
+                } else {
+                    Ok(chosen)
+                }
+            }),
+            (count, None) => Err(format!(
+                "{count} events match; use --id, --on or --all to choose:\n{}",
+                list(&matches)
+            )
+            .into()),
+        }
+    }
+}
+
+/// Returns the line describing an event in selection lists and reports.
+pub fn describe(event: &CalendarEvent) -> String {
+    format!(
+        "{} {} {} [{}]",
+        event.start_date,
+        time_label(event),
+        event.title,
+        event.stable_id()
+    )
+}
+
+fn list(events: &[CalendarEvent]) -> String {
+    events
+        .iter()
+        .enumerate()
+        .map(|(i, event)| format!("  {}. {}", i + 1, describe(event)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Asks the user to pick one or all of the events. An empty answer or `q`
+/// picks none.
+fn choose(
+    events: Vec<CalendarEvent>,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+    writeln!(output, "{}", list(&events))?;
+    loop {
+        write!(
+            output,
+            "Select an event [1-{}, a for all, q to cancel]: ",
+            events.len()
+        )?;
+        output.flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(Vec::new());
+        }
+        match answer.trim() {
+            "" | "q" => return Ok(Vec::new()),
+            "a" => return Ok(events),
+            number => match number.parse::<usize>() {
+                Ok(n) if (1..=events.len()).contains(&n) => {
+                    return Ok(vec![events[n - 1].clone()]);
+                }
+                _ => writeln!(output, "Invalid choice '{number}'")?,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use rcal_lib::Recurrence;
+    use std::io::Cursor;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn event(title: &str, day: u32) -> CalendarEvent {
+        CalendarEvent::new(
+            title.to_string(),
+            String::new(),
+            date(day),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        )
+    }
+
+    fn events() -> Vec<CalendarEvent> {
+        let mut standup = event("Standup", 5);
+        standup.recurrence = Recurrence::Weekly;
+        vec![standup, event("Dentist", 21), event("Dentist", 28)]
+    }
+
+    #[test]
+    fn test_matching() {
+        let events = events();
+        let by_id = EventSelector {
+            id: Some(events[1].stable_id()[..4].to_uppercase()),
+            ..EventSelector::default()
+        };
+        assert_eq!(
+            by_id.matching(&events, date(19)).unwrap(),
+            vec![events[1].clone()]
+        );
+
+        let by_occurrence = EventSelector {
+            query: Some("Standup".to_string()),
+            on: Some("2026-10-19".to_string()),
+            ..EventSelector::default()
+        };
+        assert_eq!(
+            by_occurrence.matching(&events, date(19)).unwrap(),
+            vec![events[0].clone()]
+        );
+        let wrong_day = EventSelector {
+            on: Some("2026-10-20".to_string()),
+            ..by_occurrence
+        };
+        assert!(wrong_day.matching(&events, date(19)).unwrap().is_empty());
+
+        let by_query = EventSelector {
+            query: Some("dent".to_string()),
+            ..EventSelector::default()
+        };
+        assert_eq!(by_query.matching(&events, date(19)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_select_disambiguation() {
+        let events = events();
+        let selector = EventSelector {
+            query: Some("dentist".to_string()),
+            ..EventSelector::default()
+        };
+        let mut output = Vec::new();
+        let err = selector
+            .select(&events, date(19), None, &mut output)
+            .unwrap_err();
+        assert!(err.to_string().starts_with("2 events match"));
+
+        let mut input = Cursor::new("7\n2\n");
+        let chosen = selector
+            .select(&events, date(19), Some(&mut input), &mut output)
+            .unwrap();
+        assert_eq!(chosen, vec![events[2].clone()]);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("Invalid choice '7'"));
+
+        let mut cancel = Cursor::new("\n");
+        assert!(selector
+            .select(&events, date(19), Some(&mut cancel), &mut Vec::new())
+            .is_err());
+
+        let all = EventSelector {
+            all: true,
+            ..selector
+        };
+        assert_eq!(
+            all.select(&events, date(19), None, &mut Vec::new())
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
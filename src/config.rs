@@ -0,0 +1,331 @@
+//! Configuration file and calendar directory.
+//!
+//! Both locations are resolved once at start-up into `RcalPaths`, which is
+//! handed to the TUI, the daemon and the commands so they all read and write
+//! the same calendar.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rcal_lib::storage::{CalendarPathProvider, DefaultPathProvider};
+
+use crate::app::App;
+use crate::keybindings::Keymap;
+use crate::locale::Locale;
+use crate::theme::{self, Theme};
+
+/// Environment variable overriding the configuration file.
+pub const CONFIG_ENV: &str = "RCAL_CONFIG";
+
+/// Environment variable overriding the calendar directory.
+pub use rcal_lib::storage::CALENDAR_DIR_ENV;
+
+/// Returns the default configuration file, `rcal/config.toml` in the
+/// platform's configuration directory.
+pub fn default_config_path() -> PathBuf {
+    dirs::config_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_default()
+        .join("rcal")
+        .join("config.toml")
+}
+
+/// Expands a leading `~` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~") {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Locations of the configuration file and the calendar directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RcalPaths {
+    /// The configuration file, which may not exist.
+    pub config_path: PathBuf,
+    /// The directory holding the event files.
+    pub calendar_dir: PathBuf,
+}
+
+impl CalendarPathProvider for RcalPaths {
+    fn calendar_dir(&self) -> PathBuf {
+        self.calendar_dir.clone()
+    }
+}
+
+/// The parsed configuration file along with the paths it was resolved with.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub paths: RcalPaths,
+    value: toml::Value,
+}
+
+impl Config {
+    /// Loads the configuration and resolves the calendar directory.
+    ///
+    /// The configuration file is `config_path` (from `--config` or
+    /// `RCAL_CONFIG`) or the default one. The calendar directory is
+    /// `calendar_dir` (from `--calendar-dir` or `RCAL_CALENDAR_DIR`), else
+    /// the `calendar_dir` key of the configuration, relative to the
+    /// configuration file, else `~/calendar`.
+    pub fn load(config_path: Option<PathBuf>, calendar_dir: Option<PathBuf>) -> Self {
+        let config_path = config_path.unwrap_or_else(default_config_path);
+        let value = read_config(&config_path);
+        let calendar_dir = calendar_dir
+            .or_else(|| {
+                let configured = value.get("calendar_dir")?.as_str()?;
+                let base = config_path.parent().unwrap_or(Path::new(""));
+                Some(base.join(expand_home(configured)))
+            })
+            .unwrap_or_else(|| DefaultPathProvider::default().calendar_dir());
+        Self {
+            paths: RcalPaths {
+                config_path,
+                calendar_dir,
+            },
+            value,
+        }
+    }
+
+    /// Returns the calendar directory.
+    pub fn calendar_dir(&self) -> &Path {
+        &self.paths.calendar_dir
+    }
+
+    /// Returns a top-level value of the configuration.
+    pub fn get(&self, key: &str) -> Option<&toml::Value> {
+        self.value.get(key)
+    }
+
+    /// Returns a string from the `[sync]` table.
+    pub fn sync_value(&self, key: &str) -> Option<String> {
+        self.value
+            .get("sync")?
+            .get(key)?
+            .as_str()
+            .map(|s| s.to_string())
+    }
+
+    /// Returns a boolean from the `[sync]` table, false when not set.
+    pub fn sync_flag(&self, key: &str) -> bool {
+        self.value
+            .get("sync")
+            .and_then(|sync| sync.get(key))
+            .and_then(toml::Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Returns the configured sync remote, if sync was initialized.
+    pub fn remote_url(&self) -> Option<String> {
+        self.sync_value("remote")
+    }
+
+    /// Returns the configured sync backend, `git` by default.
+    pub fn sync_provider_kind(&self) -> String {
+        self.sync_value("provider")
+            .unwrap_or_else(|| "git".to_string())
+    }
+
+    /// Returns the key bindings from the `[keys]` table, or why they are
+    /// invalid.
+    pub fn keymap(&self) -> Result<Keymap, String> {
+        Keymap::from_config(self.get("keys"))
+    }
+
+    /// Returns the colour theme from the `theme` key, which falls back to
+    /// the monochrome theme when `NO_COLOR` is set, or why it is invalid.
+    pub fn theme(&self) -> Result<Theme, String> {
+        Theme::from_config(self.get("theme"), theme::no_color())
+    }
+
+    /// Returns the locale from the `[locale]` table, or why it is invalid.
+    pub fn locale(&self) -> Result<Locale, String> {
+        Locale::from_config(self.get("locale"))
+    }
+
+    /// Applies the key bindings, the colour theme and the locale to the TUI.
+    /// Invalid settings keep their defaults and are reported in the hints
+    /// line.
+    pub fn configure_app(&self, app: &mut App) {
+        let mut errors = Vec::new();
+        match self.keymap() {
+            Ok(keymap) => app.keymap = keymap,
+            Err(e) => errors.push(format!(
+                "Invalid [keys] in config.toml, using the default keys: {e}"
+            )),
+        }
+        match self.theme() {
+            Ok(theme) => app.theme = theme,
+            Err(e) => errors.push(format!(
+                "Invalid theme in config.toml, using the default theme: {e}"
+            )),
+        }
+        match self.locale() {
+            Ok(locale) => app.locale = locale,
+            Err(e) => errors.push(format!(
+                "Invalid [locale] in config.toml, using the default locale: {e}"
+            )),
+        }
+        if !errors.is_empty() {
+            app.status_message = errors.join("; ");
+        }
+    }
+
+    /// Returns true unless `auto_cleanup_old_events = false` is set.
+    pub fn auto_cleanup_old_events(&self) -> bool {
+        self.get("auto_cleanup_old_events")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true)
+    }
+
+    /// Records the sync remote and backend in the `[sync]` table and writes
+    /// the configuration file.
+    pub fn save_sync(&mut self, url: &str, provider: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(table) = self.value.as_table_mut() {
+            let sync = table
+                .entry("sync")
+                .or_insert_with(|| toml::Value::Table(Default::default()));
+            if let Some(sync_table) = sync.as_table_mut() {
+                sync_table.insert("remote".to_string(), toml::Value::String(url.to_string()));
+                sync_table.insert(
+                    "provider".to_string(),
+                    toml::Value::String(provider.to_string()),
+                );
+            }
+        }
+        if let Some(dir) = self.paths.config_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.paths.config_path, toml::to_string(&self.value)?)?;
+        Ok(())
+    }
+}
+
+/// Reads the configuration file, falling back to an empty configuration if
+/// it is missing or invalid.
+fn read_config(path: &Path) -> toml::Value {
+    let empty = || toml::Value::Table(Default::default());
+    if !path.exists() {
+        return empty();
+    }
+    match fs::read_to_string(path) {
+        Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: Failed to parse {}: {e}. Using defaults.",
+                path.display()
+            );
+            empty()
+        }),
+        Err(e) => {
+            eprintln!(
+                "Warning: Failed to read {}: {e}. Using defaults.",
+                path.display()
+            );
+            empty()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_calendar_dir_resolution() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+
+        // Missing config file: built-in default
+        let config = Config::load(Some(config_path.clone()), None);
+        assert_eq!(
+            config.calendar_dir(),
+            DefaultPathProvider::default().calendar_dir()
+        );
+
+        // Config key, relative to the config file
+        fs::write(&config_path, "calendar_dir = \"events\"\n").unwrap();
+        let config = Config::load(Some(config_path.clone()), None);
+        assert_eq!(config.calendar_dir(), temp_dir.path().join("events"));
+
+        fs::write(&config_path, "calendar_dir = \"~/cal\"\n").unwrap();
+        let config = Config::load(Some(config_path.clone()), None);
+        assert_eq!(config.calendar_dir(), dirs::home_dir().unwrap().join("cal"));
+
+        // Flag or environment variable beats the config key
+        let config = Config::load(Some(config_path), Some(PathBuf::from("/tmp/other")));
+        assert_eq!(config.calendar_dir(), Path::new("/tmp/other"));
+        assert_eq!(config.paths.calendar_dir(), PathBuf::from("/tmp/other"));
+    }
+
+    #[test]
+    fn test_save_sync_writes_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("nested").join("config.toml");
+        fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        fs::write(
+            &config_path,
+            "auto_cleanup_old_events = false\n[sync]\ngit_backend = \"cli\"\ngit_credential_helper = true\n",
+        )
+        .unwrap();
+
+        let mut config = Config::load(Some(config_path.clone()), None);
+        assert_eq!(config.remote_url(), None);
+        assert_eq!(config.sync_provider_kind(), "git");
+        assert!(!config.auto_cleanup_old_events());
+        config
+            .save_sync("https://example.com/cal.git", "git")
+            .unwrap();
+
+        let reloaded = Config::load(Some(config_path), None);
+        assert_eq!(
+            reloaded.remote_url().as_deref(),
+            Some("https://example.com/cal.git")
+        );
+        assert_eq!(reloaded.sync_value("git_backend").as_deref(), Some("cli"));
+        assert!(reloaded.sync_flag("git_credential_helper"));
+        assert!(!reloaded.sync_flag("missing"));
+        assert!(!reloaded.auto_cleanup_old_events());
+    }
+
+    #[test]
+    fn test_configure_app() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "[theme]\nname = \"light\"\n[keys.normal]\nquit = \"ctrl+q\"\n[locale]\nlanguage = \"de\"\nweek_start = \"sunday\"\n",
+        )
+        .unwrap();
+        let config = Config::load(Some(config_path.clone()), None);
+        let mut app = App::new_with_calendar_dir(temp_dir.path().to_path_buf());
+        config.configure_app(&mut app);
+        assert_eq!(app.theme, Theme::light());
+        assert_eq!(app.keymap, config.keymap().unwrap());
+        assert_eq!(app.locale.week_start, chrono::Weekday::Sun);
+        assert_eq!(app.locale.month_name(10), "Oktober");
+        assert!(app.status_message.is_empty());
+
+        // Invalid settings are reported and keep the defaults
+        fs::write(
+            &config_path,
+            "theme = \"sepia\"\n[keys.normal]\nquit = \"h\"\n[locale]\nweek_start = \"someday\"\n",
+        )
+        .unwrap();
+        let config = Config::load(Some(config_path), None);
+        let mut app = App::new_with_calendar_dir(temp_dir.path().to_path_buf());
+        config.configure_app(&mut app);
+        assert_eq!(app.theme, Theme::default());
+        assert_eq!(app.locale, Locale::default());
+        assert!(app.status_message.contains("using the default keys"));
+        assert!(app
+            .status_message
+            .contains("Invalid theme in config.toml, using the default theme"));
+        assert!(app
+            .status_message
+            .contains("Invalid [locale] in config.toml"));
+    }
+}
@@ -9,8 +9,12 @@ use rcal_lib::{
     notifications::DefaultNotifier, notifications::NotificationDaemon, EventRepository,
 };
 
-pub fn run_daemon() -> Result<(), Box<dyn Error>> {
-    let repository = FileEventRepository::with_default_path()?;
+use crate::config::RcalPaths;
+
+/// Runs the notification daemon on the events of the given calendar
+/// directory.
+pub fn run_daemon(paths: &RcalPaths) -> Result<(), Box<dyn Error>> {
+    let repository = FileEventRepository::new(Box::new(paths.clone()));
     let notifier = Box::new(DefaultNotifier::new());
     let mut daemon = NotificationDaemon::new(notifier);
 
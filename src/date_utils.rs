@@ -1,6 +1,7 @@
 // Re-export all date utilities from rcal-lib
 pub use rcal_lib::core::date_utils::{
-    get_date_suggestions, validate_date_input, validate_time_input,
+    get_date_suggestions, get_date_suggestions_in, parse_time_input, validate_date_input,
+    validate_date_input_in, validate_time_input,
 };
 
 #[cfg(test)]
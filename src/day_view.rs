@@ -0,0 +1,372 @@
+//! Day view: the selected day's events in time order.
+//!
+//! `day_agenda` turns the events of a day into the entries of the list, with
+//! the free time between timed events and the current time as entries of
+//! their own; `render_day_view` draws them with a time ruler on the left and
+//! the full descriptions under the titles.
+
+use chrono::{Duration, Local, NaiveDate, NaiveTime, Timelike};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use rcal_lib::{CalendarEvent, TimeFormat};
+
+use crate::app::App;
+use crate::commands::free::duration_label;
+
+/// Minutes in a day; an entry ending at midnight ends at this minute.
+const DAY_MINUTES: u32 = 24 * 60;
+/// Free time before the first and after the last event is shown from and to
+/// these hours at least.
+const WORKDAY_START: u32 = 8 * 60;
+const WORKDAY_END: u32 = 18 * 60;
+
+/// An entry of the day view. Times are minutes since midnight.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AgendaEntry {
+    /// An all-day event, or a multi-day event spanning the whole day.
+    AllDay(CalendarEvent),
+    /// A timed event; `end` equals `start` for events without an end time.
+    Event {
+        event: CalendarEvent,
+        start: u32,
+        end: u32,
+    },
+    /// Time without events.
+    Free { start: u32, end: u32 },
+    /// The current time, on today's agenda only.
+    Now(u32),
+}
+
+impl AgendaEntry {
+    /// Returns the event of the entry, if it is one.
+    pub fn event(&self) -> Option<&CalendarEvent> {
+        match self {
+            AgendaEntry::AllDay(event) | AgendaEntry::Event { event, .. } => Some(event),
+            _ => None,
+        }
+    }
+}
+
+fn minute_of_day(time: NaiveTime) -> u32 {
+    time.hour() * 60 + time.minute()
+}
+
+fn time_label(minute: u32, format: TimeFormat) -> String {
+    match format {
+        TimeFormat::TwentyFourHour => format!("{:02}:{:02}", minute / 60, minute % 60),
+        TimeFormat::TwelveHour => {
+            let time = NaiveTime::from_hms_opt(minute / 60 % 24, minute % 60, 0).unwrap();
+            format.format(time)
+        }
+    }
+}
+
+/// Returns the width of the time ruler, "09:00-10:30 " or
+/// "9:00 AM-10:30 AM ".
+fn ruler_width(format: TimeFormat) -> usize {
+    match format {
+        TimeFormat::TwentyFourHour => 12,
+        TimeFormat::TwelveHour => 18,
+    }
+}
+
+/// Builds the entries of `date` from the events occurring on it: all-day
+/// events first, then timed events by start with the free time between
+/// them, and the current time if `now` is given.
+pub fn day_agenda(
+    events: &[CalendarEvent],
+    date: NaiveDate,
+    now: Option<NaiveTime>,
+) -> Vec<AgendaEntry> {
+    let mut all_day = Vec::new();
+    let mut timed = Vec::new();
+    for event in events {
+        let starts_today = event.start_date == date;
+        let ends_today = event.effective_end_date() == date;
+        if event.is_all_day || (!starts_today && !ends_today) {
+            all_day.push(AgendaEntry::AllDay(event.clone()));
+            continue;
+        }
+        // Multi-day events run from their start to midnight, or from
+        // midnight to their end
+        let start = if starts_today {
+            minute_of_day(event.start_time)
+        } else {
+            0
+        };
+        let end = match event.end_time.map(minute_of_day) {
+            _ if !ends_today => DAY_MINUTES,
+            Some(end) if end > start => end,
+            _ => start,
+        };
+        timed.push((start, end, event.clone()));
+    }
+    all_day.sort_by(|a, b| {
+        a.event()
+            .map(|e| &e.title)
+            .cmp(&b.event().map(|e| &e.title))
+    });
+    timed.sort_by(|a, b| (a.0, a.1, &a.2.title).cmp(&(b.0, b.1, &b.2.title)));
+
+    let mut entries = all_day;
+    if timed.is_empty() {
+        if let Some(now) = now {
+            entries.push(AgendaEntry::Now(minute_of_day(now)));
+        }
+        return entries;
+    }
+
+    // Free time is whatever no event covers within the workday, extended to
+    // the first and last event
+    let day_start = WORKDAY_START.min(timed[0].0);
+    let day_end = timed.iter().map(|(_, end, _)| *end).max().unwrap_or(0);
+    let day_end = WORKDAY_END.max(day_end);
+    let mut busy_until = day_start;
+    let mut timeline = Vec::new();
+    for (start, end, event) in timed {
+        if start > busy_until {
+            timeline.push(AgendaEntry::Free {
+                start: busy_until,
+                end: start,
+            });
+        }
+        busy_until = busy_until.max(end);
+        timeline.push(AgendaEntry::Event { event, start, end });
+    }
+    if day_end > busy_until {
+        timeline.push(AgendaEntry::Free {
+            start: busy_until,
+            end: day_end,
+        });
+    }
+
+    if let Some(now) = now.map(minute_of_day) {
+        let position = timeline
+            .iter()
+            .position(|entry| match entry {
+                AgendaEntry::Event { start, .. } | AgendaEntry::Free { start, .. } => *start > now,
+                _ => false,
+            })
+            .unwrap_or(timeline.len());
+        timeline.insert(position, AgendaEntry::Now(now));
+    }
+    entries.extend(timeline);
+    entries
+}
+
+/// Returns the events of the entries, in the order the day view lists them.
+pub fn agenda_events(entries: &[AgendaEntry]) -> Vec<&CalendarEvent> {
+    entries.iter().filter_map(AgendaEntry::event).collect()
+}
+
+/// Returns the entries of the selected day, with the current time if it is
+/// today.
+pub fn selected_day_agenda(app: &App) -> Vec<AgendaEntry> {
+    let now = Local::now().naive_local();
+    let now = (now.date() == app.date).then_some(now.time());
+    day_agenda(&app.events_on(app.date), app.date, now)
+}
+
+/// Draws the selected day's agenda into `area`, keeping the selected event
+/// in view.
+pub fn render_day_view(f: &mut Frame, app: &App, area: Rect) {
+    let entries = selected_day_agenda(app);
+    let ruler = app.theme.hint;
+    let format = app.locale.time_format;
+    let ruler_width = ruler_width(format);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            app.locale.format_long_date(app.date),
+            app.theme.title,
+        )),
+        Line::from(""),
+    ];
+    if agenda_events(&entries).is_empty() {
+        lines.push(Line::from(Span::styled("No events", ruler)));
+    }
+
+    let mut event_index = 0;
+    let mut selected_line = 0;
+    for entry in &entries {
+        match entry {
+            AgendaEntry::Now(minute) => {
+                let label = format!("{} now ", time_label(*minute, format));
+                let rule = "─".repeat((area.width as usize).saturating_sub(label.len()));
+                lines.push(Line::from(Span::styled(label + &rule, app.theme.now)));
+            }
+            AgendaEntry::Free { start, end } => {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{:<width$}┆ ",
+                            format!(
+                                "{}-{}",
+                                time_label(*start, format),
+                                time_label(*end, format)
+                            ),
+                            width = ruler_width
+                        ),
+                        ruler,
+                    ),
+                    Span::styled(
+                        format!(
+                            "free {}",
+                            duration_label(Duration::minutes(i64::from(end - start)))
+                        ),
+                        app.theme.free,
+                    ),
+                ]));
+            }
+            AgendaEntry::AllDay(event) | AgendaEntry::Event { event, .. } => {
+                let time = match entry {
+                    AgendaEntry::Event { start, end, .. } if end > start => {
+                        format!(
+                            "{}-{}",
+                            time_label(*start, format),
+                            time_label(*end, format)
+                        )
+                    }
+                    AgendaEntry::Event { start, .. } => time_label(*start, format),
+                    _ => "all day".to_string(),
+                };
+                let mut title_style = Style::default().add_modifier(Modifier::BOLD);
+                if event_index == app.day_view_index {
+                    title_style = title_style.patch(app.theme.selected);
+                    selected_line = lines.len();
+                }
+                let recurring = if event.recurrence.is_recurring() || event.is_recurring_instance {
+                    " (R)"
+                } else {
+                    ""
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{time:<ruler_width$}│ "), ruler),
+                    Span::styled(format!("{}{recurring}", event.title), title_style),
+                ]));
+                for description in event.description.lines() {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("{:<ruler_width$}│   ", ""), ruler),
+                        Span::raw(description.to_string()),
+                    ]));
+                }
+                event_index += 1;
+            }
+        }
+    }
+
+    // Scroll so the selected event is in the lower half at most
+    let scroll = selected_line.saturating_sub(area.height as usize / 2) as u16;
+    let agenda = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    f.render_widget(agenda, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMAT: TimeFormat = TimeFormat::TwentyFourHour;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn event(title: &str, start: NaiveTime, end: Option<NaiveTime>) -> CalendarEvent {
+        let mut event = CalendarEvent::new(title.to_string(), String::new(), date(19), start);
+        event.end_time = end;
+        event
+    }
+
+    #[test]
+    fn test_day_agenda_entries() {
+        let mut holiday = event("Holiday", time(0, 0), None);
+        holiday.is_all_day = true;
+        let mut trip = event("Trip", time(20, 0), Some(time(10, 0)));
+        trip.end_date = Some(date(20));
+        let events = vec![
+            trip,
+            event("Call", time(10, 30), Some(time(11, 30))),
+            holiday,
+            event("Review", time(10, 0), Some(time(11, 0))),
+            event("Standup", time(9, 0), Some(time(9, 15))),
+            event("Reminder", time(13, 0), None),
+        ];
+
+        let entries = day_agenda(&events, date(19), Some(time(9, 40)));
+        let summary: Vec<String> = entries
+            .iter()
+            .map(|entry| match entry {
+                AgendaEntry::AllDay(event) => format!("all day {}", event.title),
+                AgendaEntry::Event { event, start, end } => {
+                    format!(
+                        "{}-{} {}",
+                        time_label(*start, FORMAT),
+                        time_label(*end, FORMAT),
+                        event.title
+                    )
+                }
+                AgendaEntry::Free { start, end } => {
+                    format!(
+                        "{}-{} free",
+                        time_label(*start, FORMAT),
+                        time_label(*end, FORMAT)
+                    )
+                }
+                AgendaEntry::Now(minute) => format!("{} now", time_label(*minute, FORMAT)),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "all day Holiday",
+                "08:00-09:00 free",
+                "09:00-09:15 Standup",
+                "09:15-10:00 free",
+                "09:40 now",
+                "10:00-11:00 Review",
+                "10:30-11:30 Call",
+                "11:30-13:00 free",
+                "13:00-13:00 Reminder",
+                "13:00-20:00 free",
+                "20:00-24:00 Trip",
+            ]
+        );
+        let titles: Vec<_> = agenda_events(&entries)
+            .iter()
+            .map(|event| event.title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            vec!["Holiday", "Standup", "Review", "Call", "Reminder", "Trip"]
+        );
+
+        // The trip covers the next day until 10:00
+        let next_day = day_agenda(&events[..1], date(20), None);
+        assert!(matches!(
+            &next_day[0],
+            AgendaEntry::Event {
+                start: 0,
+                end: 600,
+                ..
+            }
+        ));
+        assert_eq!(
+            next_day[1],
+            AgendaEntry::Free {
+                start: 600,
+                end: WORKDAY_END
+            }
+        );
+    }
+}
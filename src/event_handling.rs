@@ -1,17 +1,37 @@
+use std::fs;
 use std::io;
+use std::path::PathBuf;
 
-use chrono::{Datelike, NaiveDate, NaiveTime, Utc};
-use crossterm::event::{self, Event as CrosstermEvent, KeyCode};
-use dirs;
+use chrono::{Local, NaiveDate, NaiveTime, Utc};
+use crossterm::event::{
+    self, Event as CrosstermEvent, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::backend::Backend;
 use ratatui::Terminal;
 use std::sync::mpsc::TryRecvError;
 use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::app::{App, InputMode, PopupInputField};
-use rcal_lib::sync::SyncProvider;
+use crate::app::{App, InputMode, PopupInputField, ViewMode};
+use crate::clipboard::{self, Clipboard, DateAction};
+use crate::command_line::{self, Command, SyncAction};
+use crate::commands::{self, add::AddArgs, delete::DeleteArgs};
+use crate::goto;
+use crate::keybindings::{Action, KeyMode};
+use crate::mouse::HitTarget;
+use rcal_lib::core::quick_add::parse_quick_add;
+use rcal_lib::sync::{
+    push_with_journal, ConflictChoices, ConflictSide, OperationKind, PendingOperation,
+    ResolvedConflict, SyncProvider,
+};
 use rcal_lib::validation::{is_valid_date_range, is_valid_title};
-use rcal_lib::{CalendarEvent, GitSyncProvider, Recurrence, SyncStatus};
+use rcal_lib::{
+    CalDavSyncProvider, CalendarEvent, DirectorySyncProvider, EventField, GitSyncProvider,
+    Recurrence, SyncJournal, SyncStatus,
+};
+
+/// File `:export` writes when no `--output` is given.
+const DEFAULT_EXPORT_FILE: &str = "rcal-export.csv";
 
 fn extract_date_from_suggestion(suggestion: &(String, bool)) -> String {
     let s = &suggestion.0;
@@ -36,13 +56,595 @@ fn recurrence_str_to_index(s: &str) -> usize {
 use crate::date_utils;
 use crate::persistence;
 use crate::ui::ui;
+use crate::undo::Change;
+
+/// Returns an owned copy of the provider that can be moved to a background thread.
+fn clone_provider(provider: &dyn SyncProvider) -> Option<Box<dyn SyncProvider>> {
+    let any = provider.as_any();
+    if let Some(git_provider) = any.downcast_ref::<GitSyncProvider>() {
+        Some(Box::new(
+            GitSyncProvider::new(git_provider.remote_url.clone()).with_branch(&git_provider.branch),
+        ))
+    } else if let Some(native_provider) = native_git_provider(any) {
+        Some(native_provider)
+    } else if let Some(caldav_provider) = any.downcast_ref::<CalDavSyncProvider>() {
+        Some(Box::new(caldav_provider.clone()))
+    } else {
+        any.downcast_ref::<DirectorySyncProvider>()
+            .map(|dir_provider| Box::new(dir_provider.clone()) as Box<dyn SyncProvider>)
+    }
+}
+
+#[cfg(feature = "native-git")]
+fn native_git_provider(any: &dyn std::any::Any) -> Option<Box<dyn SyncProvider>> {
+    any.downcast_ref::<rcal_lib::NativeGitSyncProvider>()
+        .map(|provider| Box::new(provider.clone()) as Box<dyn SyncProvider>)
+}
+
+#[cfg(not(feature = "native-git"))]
+fn native_git_provider(_any: &dyn std::any::Any) -> Option<Box<dyn SyncProvider>> {
+    None
+}
+
+/// Opens the event popup prefilled with the fields of an event.
+fn open_event_popup(app: &mut App, event: &CalendarEvent) {
+    app.popup_event_title = event.title.clone();
+    app.popup_event_time = app.locale.format_time(event.start_time);
+    app.popup_event_end_date = event
+        .end_date
+        .map_or(String::new(), |d| app.locale.format_date_input(d));
+    app.popup_event_end_time = event
+        .end_time
+        .map_or(String::new(), |t| app.locale.format_time(t));
+    app.popup_event_recurrence = match event.recurrence {
+        Recurrence::None => "none".to_string(),
+        Recurrence::Daily => "daily".to_string(),
+        Recurrence::Weekly => "weekly".to_string(),
+        Recurrence::Monthly => "monthly".to_string(),
+        Recurrence::Yearly => "yearly".to_string(),
+    };
+    app.popup_event_description = event.description.clone();
+    app.current_date_for_new_event = event.start_date;
+    app.show_add_event_popup = true;
+    app.input_mode = InputMode::EditingEventPopup;
+    app.selected_input_field = PopupInputField::Title;
+    app.cursor_position = app.popup_event_title.chars().count();
+    app.free_slots.clear();
+}
+
+/// Resets the event popup after it was submitted or cancelled.
+fn clear_event_popup(app: &mut App) {
+    app.show_add_event_popup = false;
+    app.popup_event_title.clear();
+    app.popup_event_time.clear();
+    app.popup_event_end_date.clear();
+    app.popup_event_end_time.clear();
+    app.popup_event_description.clear();
+    app.popup_event_recurrence.clear();
+    app.input.clear();
+}
+
+/// Fills the view popup with the events on the selected date, sorted by time.
+fn refresh_view_events_popup(app: &mut App) {
+    app.events_to_display_in_popup = app.events_on(app.date);
+    app.selected_event_index = 0;
+}
+
+/// Opens the view popup of the selected date. From the day view, the event
+/// selected there is selected in the popup.
+fn open_view_events_popup(app: &mut App) {
+    app.show_view_events_popup = true;
+    refresh_view_events_popup(app);
+    if app.view_mode == ViewMode::Day {
+        if let Some(selected) = app.selected_day_event() {
+            app.selected_event_index = app
+                .events_to_display_in_popup
+                .iter()
+                .position(|event| {
+                    (&event.title, event.start_date, event.start_time)
+                        == (&selected.title, selected.start_date, selected.start_time)
+                })
+                .unwrap_or(0);
+        }
+    }
+    app.input_mode = InputMode::ViewEventsPopup;
+}
+
+/// Pushes the calendar directory to the configured remote on a background thread.
+///
+/// If the push fails, the operation is kept in the sync journal for a later retry.
+fn spawn_background_push(app: &App, operation: PendingOperation) {
+    let Some(provider) = app.sync_provider.as_deref().and_then(clone_provider) else {
+        return;
+    };
+    let calendar_dir = app.calendar_dir.clone();
+    thread::spawn(move || {
+        let _ = push_with_journal(provider.as_ref(), &calendar_dir, operation);
+    });
+}
+
+/// Applies `change` to the stored events: the `before` event is removed from
+/// memory and disk, the `after` event is saved, and the change is pushed.
+/// Events are matched by title and start date since ids change on reload.
+fn apply_change(app: &mut App, change: &Change) -> Result<(), String> {
+    if let Some(old) = &change.before {
+        let stored = app.events().iter().any(|event| {
+            !event.is_recurring_instance
+                && event.title == old.title
+                && event.start_date == old.start_date
+        });
+        if !stored {
+            return Err(format!("'{}' was changed elsewhere", old.title));
+        }
+        persistence::delete_event_from_path_without_sync(old, &app.calendar_dir)
+            .map_err(|e| format!("Failed to delete event: {e}"))?;
+        app.with_events_mut(|svc| {
+            svc.events_mut().retain(|event| {
+                event.title != old.title
+                    || (event.start_date != old.start_date
+                        && event.base_date != Some(old.start_date))
+            });
+        });
+    }
+    if let Some(new) = &change.after {
+        let mut event = new.clone();
+        persistence::save_event_to_path_without_sync(&mut event, &app.calendar_dir)
+            .map_err(|e| format!("Failed to save event: {e}"))?;
+        app.add_event(event);
+    }
+    app.invalidate_instance_cache(None);
+
+    let operation = match (&change.after, &change.before) {
+        (Some(event), _) => PendingOperation::for_event(OperationKind::Save, event),
+        (None, Some(event)) => PendingOperation::for_event(OperationKind::Delete, event),
+        (None, None) => return Ok(()),
+    };
+    spawn_background_push(app, operation);
+    Ok(())
+}
+
+/// Reverts the last recorded change.
+fn undo_last_change(app: &mut App) {
+    let Some(change) = app.undo_history.next_undo().cloned() else {
+        app.status_message = "Nothing to undo".to_string();
+        return;
+    };
+    app.status_message = match apply_change(app, &change.inverse()) {
+        Ok(()) => {
+            app.undo_history.mark_undone();
+            format!("Undid {} of '{}'", change.label(), change.title())
+        }
+        Err(e) => format!("Cannot undo: {e}"),
+    };
+    if app.show_view_events_popup {
+        refresh_view_events_popup(app);
+    }
+}
+
+/// Applies the last undone change again.
+fn redo_last_change(app: &mut App) {
+    let Some(change) = app.undo_history.next_redo().cloned() else {
+        app.status_message = "Nothing to redo".to_string();
+        return;
+    };
+    app.status_message = match apply_change(app, &change) {
+        Ok(()) => {
+            app.undo_history.mark_redone();
+            format!("Redid {} of '{}'", change.label(), change.title())
+        }
+        Err(e) => format!("Cannot redo: {e}"),
+    };
+    if app.show_view_events_popup {
+        refresh_view_events_popup(app);
+    }
+}
+
+/// Returns the stored event behind the event selected in the view popup, the
+/// base event for an occurrence of a series, and the date of the occurrence.
+fn selected_stored_event(app: &App) -> Option<(CalendarEvent, NaiveDate)> {
+    let selected = app
+        .events_to_display_in_popup
+        .get(app.selected_event_index)?;
+    let stored =
+        find_base_event_for_instance(selected, &app.events()).unwrap_or_else(|| selected.clone());
+    Some((stored, selected.start_date))
+}
+
+/// Fails if an event with the title of `event` is already stored on its
+/// start date, since events are matched by title and start date.
+fn check_not_taken(app: &App, event: &CalendarEvent) -> Result<(), String> {
+    if stored_with_key(app, event).is_some() {
+        return Err(format!(
+            "'{}' is already on {}",
+            event.title,
+            app.locale.format_date(event.start_date)
+        ));
+    }
+    Ok(())
+}
+
+/// The stored event with the title and start date of `event`, if any.
+fn stored_with_key(app: &App, event: &CalendarEvent) -> Option<CalendarEvent> {
+    app.events()
+        .iter()
+        .find(|stored| {
+            !stored.is_recurring_instance
+                && stored.title == event.title
+                && stored.start_date == event.start_date
+        })
+        .cloned()
+}
+
+/// Places `event` so its occurrence on `anchor` lands on `target`, either
+/// moving it or keeping the original, and records the change for undo.
+fn place_event(
+    app: &mut App,
+    event: &CalendarEvent,
+    anchor: NaiveDate,
+    target: NaiveDate,
+    keep_original: bool,
+) -> Result<CalendarEvent, String> {
+    let placed = clipboard::shifted(event, (target - anchor).num_days());
+    check_not_taken(app, &placed)?;
+    let change = if keep_original {
+        Change::created(placed.clone())
+    } else {
+        Change::edited(event.clone(), placed.clone())
+    };
+    apply_change(app, &change)?;
+    app.undo_history.record(change);
+    Ok(placed)
+}
+
+/// Moves the event selected in the view popup to `target` and follows it
+/// there, keeping it selected.
+fn move_selected_event(app: &mut App, target: NaiveDate) {
+    let Some((event, anchor)) = selected_stored_event(app) else {
+        return;
+    };
+    match place_event(app, &event, anchor, target, false) {
+        Ok(_) => {
+            app.status_message = format!(
+                "Moved '{}' to {}",
+                event.title,
+                app.locale.format_date(target)
+            );
+            app.date = target;
+            app.adjust_view_boundaries();
+            refresh_view_events_popup(app);
+            app.selected_event_index = app
+                .events_to_display_in_popup
+                .iter()
+                .position(|shown| {
+                    shown.title == event.title && shown.start_time == event.start_time
+                })
+                .unwrap_or(0);
+        }
+        Err(e) => app.status_message = format!("Cannot move: {e}"),
+    }
+}
+
+/// Moves the event selected in the view popup by `days`.
+fn shift_selected_event(app: &mut App, days: i64) {
+    if let Some((_, anchor)) = selected_stored_event(app) {
+        move_selected_event(app, anchor + chrono::Duration::days(days));
+    }
+}
+
+/// Pastes the yanked or cut event on the selected date.
+fn paste_clipboard(app: &mut App) {
+    let Some(yanked) = app.clipboard.clone() else {
+        app.status_message = "Nothing to paste".to_string();
+        return;
+    };
+    let target = app.date;
+    app.status_message = match place_event(app, &yanked.event, yanked.anchor, target, !yanked.cut) {
+        Ok(_) => {
+            if yanked.cut {
+                app.clipboard = None;
+            }
+            format!(
+                "{} '{}' to {}",
+                if yanked.cut { "Moved" } else { "Pasted" },
+                yanked.event.title,
+                app.locale.format_date(target)
+            )
+        }
+        Err(e) => format!("Cannot paste: {e}"),
+    };
+    if app.show_view_events_popup {
+        refresh_view_events_popup(app);
+    }
+}
+
+/// Opens the target date prompt for the event selected in the view popup.
+fn open_target_date_prompt(app: &mut App, action: DateAction) {
+    app.date_action = Some(action);
+    app.target_date_input.clear();
+    app.input_mode = InputMode::TargetDate;
+}
+
+/// Handles a key press in the target date prompt.
+fn handle_target_date_key(app: &mut App, key: &KeyEvent) {
+    match app.keymap.text_action(KeyMode::Prompt, key) {
+        (Some(Action::Cancel), _) => {
+            app.date_action = None;
+            app.input_mode = InputMode::ViewEventsPopup;
+        }
+        (Some(Action::Submit), _) => {
+            let Some((event, anchor)) = selected_stored_event(app) else {
+                return;
+            };
+            let today = Local::now().date_naive();
+            let target = match clipboard::parse_target(&app.target_date_input, anchor, today) {
+                Ok(target) => target,
+                Err(e) => {
+                    app.status_message = e;
+                    return;
+                }
+            };
+            app.input_mode = InputMode::ViewEventsPopup;
+            match app.date_action.take() {
+                Some(DateAction::Move) => move_selected_event(app, target),
+                Some(DateAction::Duplicate) => {
+                    app.status_message = match place_event(app, &event, anchor, target, true) {
+                        Ok(_) => format!(
+                            "Duplicated '{}' to {}",
+                            event.title,
+                            app.locale.format_date(target)
+                        ),
+                        Err(e) => format!("Cannot duplicate: {e}"),
+                    };
+                }
+                None => {}
+            }
+        }
+        (Some(Action::DeleteBackward), _) => {
+            app.target_date_input.pop();
+        }
+        (None, KeyCode::Char(c)) => app.target_date_input.push(c),
+        _ => {}
+    }
+}
+
+/// Handles a key press in the go-to prompt.
+fn handle_goto_key(app: &mut App, key: &KeyEvent) {
+    match app.keymap.text_action(KeyMode::Prompt, key) {
+        (Some(Action::Cancel), _) => app.input_mode = InputMode::Normal,
+        (Some(Action::Submit), _) => {
+            let today = Local::now().date_naive();
+            match goto::parse_goto(&app.goto_input, app.date, today, &app.locale) {
+                Ok(date) => {
+                    app.go_to(date);
+                    app.input_mode = InputMode::Normal;
+                }
+                Err(e) => app.status_message = e,
+            }
+        }
+        (Some(Action::DeleteBackward), _) => {
+            app.goto_input.pop();
+        }
+        (None, KeyCode::Char(c)) => app.goto_input.push(c),
+        _ => {}
+    }
+}
+
+/// Handles a key press on the command line. Returns false when `:q` was
+/// entered.
+fn handle_command_line_key(app: &mut App, key: &KeyEvent) -> bool {
+    match app.keymap.text_action(KeyMode::CommandLine, key) {
+        (Some(Action::Cancel), _) => app.input_mode = InputMode::Normal,
+        (Some(Action::Submit), _) => {
+            let input = std::mem::take(&mut app.command_input);
+            app.command_history.push(input.trim());
+            app.input_mode = InputMode::Normal;
+            return run_command(app, &input);
+        }
+        // Like vim, deleting past the start leaves the command line
+        (Some(Action::DeleteBackward), _) if app.command_input.is_empty() => {
+            app.input_mode = InputMode::Normal
+        }
+        (Some(Action::DeleteBackward), _) => {
+            app.command_input.pop();
+        }
+        (Some(Action::HistoryPrevious), _) => {
+            if let Some(command) = app.command_history.older() {
+                app.command_input = command.to_string();
+            }
+        }
+        (Some(Action::HistoryNext), _) => {
+            app.command_input = app.command_history.newer().unwrap_or_default().to_string();
+        }
+        (Some(Action::Complete), _) => {
+            let mut titles: Vec<String> = app
+                .events()
+                .iter()
+                .filter(|event| !event.is_recurring_instance)
+                .map(|event| event.title.clone())
+                .collect();
+            titles.sort();
+            titles.dedup();
+            let (input, matches) = command_line::complete(&app.command_input, &titles);
+            app.command_input = input;
+            if matches.len() > 1 {
+                app.status_message = matches.join("  ");
+            }
+        }
+        (None, KeyCode::Char(c)) => app.command_input.push(c),
+        _ => {}
+    }
+    true
+}
+
+/// Runs a command entered on the command line, reporting its result in the
+/// hints line. Returns false for `:q`.
+fn run_command(app: &mut App, input: &str) -> bool {
+    let command = match command_line::parse(input) {
+        Ok(command) => command,
+        Err(e) => {
+            app.status_message = e;
+            return true;
+        }
+    };
+    let today = Local::now().date_naive();
+    match command {
+        Command::Quit => return false,
+        Command::Help => open_help(app),
+        Command::Add(None) => open_add_event_popup(app),
+        Command::Add(Some(args)) => {
+            app.status_message =
+                add_from_command(app, &args, today).unwrap_or_else(|e| format!("Cannot add: {e}"));
+        }
+        Command::Delete(args) => {
+            app.status_message = delete_from_command(app, args.as_ref(), today)
+                .unwrap_or_else(|e| format!("Cannot delete: {e}"));
+        }
+        Command::Export(args) => {
+            let path = args
+                .output
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_EXPORT_FILE));
+            app.status_message = match commands::export::run(&args, &app.calendar_dir, today) {
+                Ok(csv) => match fs::write(&path, csv) {
+                    Ok(()) => format!("Exported events to {}", path.display()),
+                    Err(e) => format!("Cannot write {}: {e}", path.display()),
+                },
+                Err(e) => format!("Cannot export: {e}"),
+            };
+        }
+        Command::GoTo(target) => match goto::parse_goto(&target, app.date, today, &app.locale) {
+            Ok(date) => app.go_to(date),
+            Err(e) => app.status_message = e,
+        },
+        Command::Filter(filter) => {
+            app.status_message = match &filter {
+                Some(filter) => format!("Showing events matching {}", filter.label),
+                None => "Showing all events".to_string(),
+            };
+            app.event_filter = filter;
+            app.day_view_index = 0;
+        }
+        Command::Sync(action) => {
+            if app.sync_provider.is_none() {
+                app.status_message = "Sync is not configured".to_string();
+                return true;
+            }
+            open_sync_menu(app);
+            match action {
+                Some(SyncAction::Pull) => sync_pull(app),
+                Some(SyncAction::Push) => sync_push(app),
+                Some(SyncAction::Status) | None => {}
+            }
+        }
+    }
+    true
+}
+
+/// Adds the event of `:add`, on the selected date unless the arguments or
+/// the title give one, and records it for undo.
+fn add_from_command(app: &mut App, args: &AddArgs, today: NaiveDate) -> Result<String, String> {
+    let dated = args.date.is_some() || parse_quick_add(&args.title.join(" "), today).date.is_some();
+    let event = commands::add::build_event(args, if dated { today } else { app.date })?;
+    let change = match stored_with_key(app, &event) {
+        Some(stored) if args.force => Change::edited(stored, event.clone()),
+        _ => {
+            check_not_taken(app, &event)?;
+            Change::created(event.clone())
+        }
+    };
+    apply_change(app, &change)?;
+    app.undo_history.record(change);
+    app.date = event.start_date;
+    app.adjust_view_boundaries();
+    Ok(format!(
+        "Added '{}' on {}",
+        event.title,
+        app.locale.format_date(event.start_date)
+    ))
+}
+
+/// Deletes the events of `:delete`, selected as `rcal delete` selects them,
+/// or the selected event without arguments, and records them for undo.
+fn delete_from_command(
+    app: &mut App,
+    args: Option<&DeleteArgs>,
+    today: NaiveDate,
+) -> Result<String, String> {
+    let events = match args {
+        None => vec![command_target(app)?],
+        Some(args) => {
+            let stored: Vec<CalendarEvent> = app
+                .events()
+                .iter()
+                .filter(|event| !event.is_recurring_instance)
+                .cloned()
+                .collect();
+            let matches = args.selector.matching(&stored, today)?;
+            match matches.len() {
+                0 => return Err("No matching event".to_string()),
+                1 => {}
+                count if !args.selector.all => {
+                    return Err(format!(
+                        "{count} events match; use --id, --on or --all to choose"
+                    ))
+                }
+                _ => {}
+            }
+            if args.selector.dry_run {
+                let titles: Vec<String> = matches
+                    .iter()
+                    .map(|event| format!("'{}'", event.title))
+                    .collect();
+                return Ok(format!("Would delete {}", titles.join(", ")));
+            }
+            matches
+        }
+    };
+    for event in &events {
+        let change = Change::deleted(event.clone());
+        apply_change(app, &change)?;
+        app.undo_history.record(change);
+    }
+    app.select_day_event(0);
+    Ok(match events.as_slice() {
+        [event] => format!(
+            "Deleted '{}' on {}",
+            event.title,
+            app.locale.format_date(event.start_date)
+        ),
+        events => format!("Deleted {} events", events.len()),
+    })
+}
+
+/// Returns the stored event a command without arguments applies to: the
+/// event selected in the day view, or the only event of the selected date.
+fn command_target(app: &App) -> Result<CalendarEvent, String> {
+    let selected = if app.view_mode == ViewMode::Day {
+        crate::day_view::agenda_events(&crate::day_view::selected_day_agenda(app))
+            .get(app.day_view_index)
+            .map(|event| (*event).clone())
+    } else {
+        match app.events_on(app.date).as_slice() {
+            [event] => Some(event.clone()),
+            _ => None,
+        }
+    };
+    let selected =
+        selected.ok_or("select an event in the day view, or name it as in :delete TITLE")?;
+    Ok(find_base_event_for_instance(&selected, &app.events()).unwrap_or(selected))
+}
 
 /// Normalizes time input to HH:MM format.
-/// Handles inputs like "14", "9", "14:30", returning "HH:MM" or the original if invalid.
+/// Handles inputs like "14", "9", "14:30" and "2:30pm", returning "HH:MM" or the original if invalid.
 /// Used for consistent time parsing in event creation.
 fn normalize_time_input(input: &str) -> String {
     let trimmed = input.trim();
 
+    // 12-hour times such as "2pm" or "2:30 PM"
+    if let Ok(Some(time)) = date_utils::parse_time_input(trimmed) {
+        return time.format("%H:%M").to_string();
+    }
+
     // If it's already in HH:MM format, return as is
     if trimmed.contains(':') {
         let parts: Vec<&str> = trimmed.split(':').collect();
@@ -105,6 +707,7 @@ where
     std::io::Error: std::convert::From<<B as Backend>::Error>,
 {
     loop {
+        app.refresh_pending_sync_operations();
         terminal.draw(|f| ui(f, &mut app))?;
 
         // Check for reload signal from async sync
@@ -142,102 +745,15 @@ where
 
 pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
     if let CrosstermEvent::Key(key) = event {
+        app.status_message.clear();
         match app.input_mode {
-            InputMode::Normal => match key.code {
-                KeyCode::Char('q') => return Ok(false),
-                KeyCode::Char('a') => {
-                    app.show_add_event_popup = true;
-                    app.input_mode = InputMode::EditingEventPopup;
-                    app.current_date_for_new_event = app.date;
-                    app.popup_event_title.clear();
-                    app.popup_event_time.clear();
-                    app.popup_event_end_date.clear();
-                    app.popup_event_end_time.clear();
-                    app.popup_event_recurrence.clear();
-                    app.popup_event_description.clear();
-                    app.input.clear();
-                    app.selected_input_field = PopupInputField::Title;
-                    app.cursor_position = 0;
-                    app.is_editing = false;
-                    app.event_being_edited = None;
-                    app.date_input_error = None;
-                    app.date_suggestions.clear();
-                    app.show_date_suggestions = false;
-                    app.time_input_error = None;
-                    app.end_time_input_error = None;
-                }
-                KeyCode::Left | KeyCode::Char('h') => {
-                    app.date -= chrono::Duration::days(1);
-                    app.adjust_view_boundaries();
-                }
-                KeyCode::Right | KeyCode::Char('l') => {
-                    app.date += chrono::Duration::days(1);
-                    app.adjust_view_boundaries();
-                }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    app.date -= chrono::Duration::weeks(1);
-                    app.adjust_view_boundaries();
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    app.date += chrono::Duration::weeks(1);
-                    app.adjust_view_boundaries();
-                }
-
-                KeyCode::Char('o') => {
-                    app.show_view_events_popup = true;
-                    let all_events = app.get_all_events_for_range(app.date, app.date);
-                    app.events_to_display_in_popup = all_events
-                        .iter()
-                        .filter(|event| {
-                            if let Some(end) = event.end_date {
-                                event.start_date <= app.date && end >= app.date
-                            } else {
-                                event.start_date == app.date
-                            }
-                        })
-                        .cloned()
-                        .collect();
-                    app.events_to_display_in_popup
-                        .sort_by_key(|event| event.start_time);
-                    app.selected_event_index = 0;
-                    app.input_mode = InputMode::ViewEventsPopup;
-                }
-                KeyCode::Char('s') => {
-                    if app.sync_provider.is_some() {
-                        app.input_mode = InputMode::Sync;
-                        app.sync_message.clear();
-                        app.sync_status = None;
-                        // Automatically check sync status on entry
-                        if let Some(provider) = &app.sync_provider {
-                            match provider.status(&app.calendar_dir) {
-                                Ok(status) => {
-                                    app.sync_message = match &status {
-                                        SyncStatus::UpToDate => "".to_string(),
-                                        SyncStatus::Ahead => "Ahead of remote".to_string(),
-                                        SyncStatus::Behind => "Behind remote".to_string(),
-                                        SyncStatus::Conflicts => "Conflicts detected".to_string(),
-                                        SyncStatus::Error(e) => {
-                                            format!("Status error: {e}")
-                                        }
-                                    };
-                                    if app.selected_input_field == PopupInputField::Recurrence {
-                                        app.input_mode = InputMode::SelectingRecurrence;
-                                        app.selected_recurrence_index =
-                                            recurrence_str_to_index(&app.popup_event_recurrence);
-                                    }
-                                }
-                                Err(e) => {
-                                    app.sync_message = format!("Status failed: {e}");
-                                    app.sync_status = Some(SyncStatus::Error(e.to_string()));
-                                }
-                            }
-                        }
-                    }
-                }
-                _ => {}
+            InputMode::Normal => match app.keymap.action(KeyMode::Normal, &key) {
+                Some(Action::Quit) => return Ok(false),
+                Some(action) => handle_normal_action(app, action),
+                None => {}
             },
-            InputMode::EditingEventPopup => match key.code {
-                KeyCode::Enter => {
+            InputMode::EditingEventPopup => match app.keymap.text_action(KeyMode::Form, &key) {
+                (Some(Action::Submit), _) => {
                     // Use library validation for title
                     if !is_valid_title(&app.popup_event_title) {
                         app.error_message =
@@ -250,9 +766,10 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                     }
                     // Additional validation for end date on submit
                     if !app.popup_event_end_date.trim().is_empty() {
-                        if let Err(e) = date_utils::validate_date_input(
+                        if let Err(e) = date_utils::validate_date_input_in(
                             &app.popup_event_end_date,
                             app.current_date_for_new_event,
+                            app.locale.date_input,
                         ) {
                             app.error_message = e;
                             return Ok(true);
@@ -276,25 +793,12 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                     let end_date = if end_date_str.trim().is_empty() {
                         Some(app.current_date_for_new_event)
                     } else {
-                        let parts: Vec<&str> = end_date_str.trim().split('/').collect();
-                        if parts.len() == 2 {
-                            if let (Ok(day), Ok(month)) =
-                                (parts[0].parse::<u32>(), parts[1].parse::<u32>())
-                            {
-                                let start_date = app.current_date_for_new_event;
-                                let mut year = start_date.year();
-                                if month < start_date.month()
-                                    || (month == start_date.month() && day < start_date.day())
-                                {
-                                    year += 1;
-                                }
-                                NaiveDate::from_ymd_opt(year, month, day)
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
+                        date_utils::validate_date_input_in(
+                            &end_date_str,
+                            app.current_date_for_new_event,
+                            app.locale.date_input,
+                        )
+                        .ok()
                     };
                     let end_time_str = app.popup_event_end_time.drain(..).collect::<String>();
                     let normalized_end_time_str = normalize_time_input(&end_time_str);
@@ -334,6 +838,22 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                         is_all_day,
                     };
 
+                    // The edited merge result of a sync conflict isn't saved
+                    // directly; it's handed to the sync provider with the others
+                    if app.is_editing_conflict {
+                        app.is_editing_conflict = false;
+                        clear_event_popup(app);
+                        app.input_mode = InputMode::ConflictResolver;
+                        if let Some(conflict) = app.current_conflict() {
+                            let resolution = ResolvedConflict {
+                                file_name: conflict.file_name.clone(),
+                                events: vec![event],
+                            };
+                            app.resolve_current_conflict(resolution);
+                        }
+                        return Ok(true);
+                    }
+
                     if app.is_editing {
                         if let Some(old_event) = &app.event_being_edited {
                             // Remove old event from main events list
@@ -345,20 +865,6 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                                 old_event,
                                 &app.calendar_dir,
                             );
-
-                            // Spawn async sync for delete
-                            if let Some(provider) = &app.sync_provider {
-                                if let Some(git_provider) =
-                                    provider.as_any().downcast_ref::<GitSyncProvider>()
-                                {
-                                    let remote_url = git_provider.remote_url.clone();
-                                    let calendar_dir = app.calendar_dir.clone();
-                                    thread::spawn(move || {
-                                        let provider = GitSyncProvider::new(remote_url);
-                                        let _ = provider.push(&calendar_dir);
-                                    });
-                                }
-                            }
                         }
                     }
 
@@ -370,27 +876,24 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                         return Ok(true);
                     }
 
+                    // Spawn async sync for the saved event
+                    spawn_background_push(
+                        app,
+                        PendingOperation::for_event(OperationKind::Save, &event),
+                    );
+                    let change = match app.event_being_edited.clone() {
+                        Some(old_event) if app.is_editing => Change::edited(old_event, event),
+                        _ => Change::created(event),
+                    };
+                    app.undo_history.record(change);
+
                     // Reset editing state
                     app.is_editing = false;
                     app.event_being_edited = None;
 
                     // If we came from the view events popup, refresh it and stay in that mode
                     if app.show_view_events_popup {
-                        let all_events = app.get_all_events_for_range(app.date, app.date);
-                        app.events_to_display_in_popup = all_events
-                            .iter()
-                            .filter(|event| {
-                                if let Some(end) = event.end_date {
-                                    event.start_date <= app.date && end >= app.date
-                                } else {
-                                    event.start_date == app.date
-                                }
-                            })
-                            .cloned()
-                            .collect();
-                        app.events_to_display_in_popup
-                            .sort_by_key(|event| event.start_time);
-                        app.selected_event_index = 0;
+                        refresh_view_events_popup(app);
                         app.input_mode = InputMode::ViewEventsPopup;
                     } else {
                         app.input_mode = InputMode::Normal;
@@ -400,7 +903,10 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                     // Invalidate cached instances after event modification and UI refresh
                     app.invalidate_instance_cache(None);
                 }
-                KeyCode::Char(c) => {
+                (Some(Action::ProposeFreeSlot), _) => {
+                    app.propose_free_slot(Local::now().naive_local());
+                }
+                (None, KeyCode::Char(c)) => {
                     if app.selected_input_field == PopupInputField::Recurrence {
                         return Ok(true);
                     }
@@ -413,15 +919,19 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                     // Real-time validation for end date
                     if app.selected_input_field == PopupInputField::EndDate {
                         let start_date = app.current_date_for_new_event;
-                        app.date_suggestions = date_utils::get_date_suggestions(
+                        app.date_suggestions = date_utils::get_date_suggestions_in(
                             &app.popup_event_end_date,
                             start_date,
                             chrono::Utc::now().date_naive(),
+                            app.locale.date_input,
                         );
                         app.show_date_suggestions = !app.date_suggestions.is_empty();
                         app.selected_suggestion_index = 0;
-                        match date_utils::validate_date_input(&app.popup_event_end_date, start_date)
-                        {
+                        match date_utils::validate_date_input_in(
+                            &app.popup_event_end_date,
+                            start_date,
+                            app.locale.date_input,
+                        ) {
                             Ok(_) => {
                                 app.date_input_error = None;
                             }
@@ -455,110 +965,102 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                         }
                     }
                 }
-                KeyCode::Backspace => {
-                    if app.cursor_position > 0 {
-                        let cursor_pos = app.cursor_position - 1;
-                        let field = app.get_current_field_mut();
-                        let byte_index = App::char_to_byte_index(field, cursor_pos);
-                        field.remove(byte_index);
-                        app.cursor_position -= 1;
-
-                        // Real-time validation for end date
-                        if app.selected_input_field == PopupInputField::EndDate {
-                            let start_date = app.current_date_for_new_event;
-                            app.date_suggestions = date_utils::get_date_suggestions(
-                                &app.popup_event_end_date,
-                                start_date,
-                                Utc::now().date_naive(),
-                            );
-                            app.show_date_suggestions = !app.date_suggestions.is_empty();
-                            app.selected_suggestion_index = 0;
-                            match date_utils::validate_date_input(
-                                &app.popup_event_end_date,
-                                start_date,
-                            ) {
-                                Ok(_) => {
-                                    app.date_input_error = None;
-                                }
-                                Err(e) => {
-                                    app.date_input_error = Some(e);
-                                }
+                (Some(Action::DeleteBackward), _) if app.cursor_position > 0 => {
+                    let cursor_pos = app.cursor_position - 1;
+                    let field = app.get_current_field_mut();
+                    let byte_index = App::char_to_byte_index(field, cursor_pos);
+                    field.remove(byte_index);
+                    app.cursor_position -= 1;
+
+                    // Real-time validation for end date
+                    if app.selected_input_field == PopupInputField::EndDate {
+                        let start_date = app.current_date_for_new_event;
+                        app.date_suggestions = date_utils::get_date_suggestions_in(
+                            &app.popup_event_end_date,
+                            start_date,
+                            Utc::now().date_naive(),
+                            app.locale.date_input,
+                        );
+                        app.show_date_suggestions = !app.date_suggestions.is_empty();
+                        app.selected_suggestion_index = 0;
+                        match date_utils::validate_date_input_in(
+                            &app.popup_event_end_date,
+                            start_date,
+                            app.locale.date_input,
+                        ) {
+                            Ok(_) => {
+                                app.date_input_error = None;
+                            }
+                            Err(e) => {
+                                app.date_input_error = Some(e);
                             }
                         }
+                    }
 
-                        // Real-time validation for time field
-                        if app.selected_input_field == PopupInputField::Time {
-                            match date_utils::validate_time_input(&app.popup_event_time) {
-                                Ok(_) => {
-                                    app.time_input_error = None;
-                                }
-                                Err(e) => {
-                                    app.time_input_error = Some(e);
-                                }
+                    // Real-time validation for time field
+                    if app.selected_input_field == PopupInputField::Time {
+                        match date_utils::validate_time_input(&app.popup_event_time) {
+                            Ok(_) => {
+                                app.time_input_error = None;
+                            }
+                            Err(e) => {
+                                app.time_input_error = Some(e);
                             }
                         }
+                    }
 
-                        // Real-time validation for end time field
-                        if app.selected_input_field == PopupInputField::EndTime {
-                            match date_utils::validate_time_input(&app.popup_event_end_time) {
-                                Ok(_) => {
-                                    app.end_time_input_error = None;
-                                }
-                                Err(e) => {
-                                    app.end_time_input_error = Some(e);
-                                }
+                    // Real-time validation for end time field
+                    if app.selected_input_field == PopupInputField::EndTime {
+                        match date_utils::validate_time_input(&app.popup_event_end_time) {
+                            Ok(_) => {
+                                app.end_time_input_error = None;
+                            }
+                            Err(e) => {
+                                app.end_time_input_error = Some(e);
                             }
                         }
                     }
                 }
-                KeyCode::Up => {
+                (Some(Action::PreviousSuggestion), _)
                     if app.selected_input_field == PopupInputField::EndDate
                         && app.show_date_suggestions
                         && !app.date_suggestions.is_empty()
-                        && app.selected_suggestion_index > 0
-                    {
-                        app.selected_suggestion_index -= 1;
-                    }
+                        && app.selected_suggestion_index > 0 =>
+                {
+                    app.selected_suggestion_index -= 1;
                 }
-                KeyCode::Down => {
+                (Some(Action::NextSuggestion), _)
                     if app.selected_input_field == PopupInputField::EndDate
                         && app.show_date_suggestions
                         && !app.date_suggestions.is_empty()
-                        && app.selected_suggestion_index < app.date_suggestions.len() - 1
-                    {
-                        app.selected_suggestion_index += 1;
-                    }
+                        && app.selected_suggestion_index < app.date_suggestions.len() - 1 =>
+                {
+                    app.selected_suggestion_index += 1;
                 }
-                KeyCode::Esc => {
-                    app.show_add_event_popup = false;
-                    app.popup_event_title.clear();
-                    app.popup_event_time.clear();
-                    app.popup_event_end_date.clear();
-                    app.popup_event_end_time.clear();
-                    app.popup_event_description.clear();
-                    app.popup_event_recurrence.clear();
-                    app.input.clear();
+                (Some(Action::Cancel), _) => {
+                    clear_event_popup(app);
                     app.is_editing = false;
                     app.event_being_edited = None;
 
                     // Return to view events popup if that's where we came from
-                    if app.show_view_events_popup {
+                    if app.is_editing_conflict {
+                        app.is_editing_conflict = false;
+                        app.input_mode = InputMode::ConflictResolver;
+                    } else if app.show_view_events_popup {
                         app.input_mode = InputMode::ViewEventsPopup;
                     } else {
                         app.input_mode = InputMode::Normal;
                     }
                 }
-                KeyCode::Left => {
-                    if app.cursor_position > 0 {
-                        app.cursor_position -= 1;
-                    }
+                (Some(Action::CursorLeft), _) if app.cursor_position > 0 => {
+                    app.cursor_position -= 1;
                 }
-                KeyCode::Right => {
-                    if app.cursor_position < app.get_current_field_char_count() {
-                        app.cursor_position += 1;
-                    }
+                (Some(Action::CursorRight), _)
+                    if app.cursor_position < app.get_current_field_char_count() =>
+                {
+                    app.cursor_position += 1;
                 }
-                KeyCode::BackTab => {
+                (Some(Action::PreviousField), _) => {
                     app.selected_input_field = match app.selected_input_field {
                         PopupInputField::Title => {
                             app.cursor_position = app.popup_event_recurrence.chars().count();
@@ -600,7 +1102,7 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                     }
                 }
 
-                KeyCode::Tab => {
+                (Some(Action::NextField), _) => {
                     app.selected_input_field = match app.selected_input_field {
                         PopupInputField::Title => {
                             app.cursor_position = app.popup_event_time.chars().count();
@@ -610,10 +1112,11 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                             app.cursor_position = app.popup_event_end_date.chars().count();
                             // Show suggestions when entering EndDate field
                             app.date_input_error = None;
-                            app.date_suggestions = date_utils::get_date_suggestions(
+                            app.date_suggestions = date_utils::get_date_suggestions_in(
                                 &app.popup_event_end_date,
                                 app.current_date_for_new_event,
                                 Utc::now().date_naive(),
+                                app.locale.date_input,
                             );
                             app.show_date_suggestions = !app.date_suggestions.is_empty();
                             app.selected_suggestion_index = 0;
@@ -659,15 +1162,11 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                 _ => {}
             },
             InputMode::SelectingRecurrence => match key.code {
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if app.selected_recurrence_index > 0 {
-                        app.selected_recurrence_index -= 1;
-                    }
+                KeyCode::Up | KeyCode::Char('k') if app.selected_recurrence_index > 0 => {
+                    app.selected_recurrence_index -= 1;
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if app.selected_recurrence_index < 4 {
-                        app.selected_recurrence_index += 1;
-                    }
+                KeyCode::Down | KeyCode::Char('j') if app.selected_recurrence_index < 4 => {
+                    app.selected_recurrence_index += 1;
                 }
                 KeyCode::Enter => {
                     let recurrence_str = match app.selected_recurrence_index {
@@ -688,100 +1187,11 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                 }
                 _ => {}
             },
-            InputMode::ViewEventsPopup => match key.code {
-                KeyCode::Esc => {
-                    app.show_view_events_popup = false;
-                    app.events_to_display_in_popup.clear();
-                    app.selected_event_index = 0;
-                    app.event_to_delete_index = None;
-                    app.input_mode = InputMode::Normal;
-                }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if app.events_to_display_in_popup.is_empty() {
-                        return Ok(true);
-                    }
-                    if app.selected_event_index == 0 {
-                        app.selected_event_index = app.events_to_display_in_popup.len() - 1;
-                    } else {
-                        app.selected_event_index -= 1;
-                    }
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if app.events_to_display_in_popup.is_empty() {
-                        return Ok(true);
-                    }
-                    if app.selected_event_index == app.events_to_display_in_popup.len() - 1 {
-                        app.selected_event_index = 0;
-                    } else {
-                        app.selected_event_index += 1;
-                    }
+            InputMode::ViewEventsPopup => {
+                if let Some(action) = app.keymap.action(KeyMode::Popup, &key) {
+                    handle_popup_action(app, action);
                 }
-                KeyCode::Char('e') => {
-                    if !app.events_to_display_in_popup.is_empty() {
-                        let selected_event =
-                            &app.events_to_display_in_popup[app.selected_event_index];
-                        let base_event = if let Some(base) =
-                            find_base_event_for_instance(selected_event, &app.events())
-                        {
-                            base
-                        } else {
-                            selected_event.clone()
-                        };
-                        app.popup_event_title = base_event.title.clone();
-                        app.popup_event_time = base_event.start_time.format("%H:%M").to_string();
-                        app.popup_event_end_date = base_event
-                            .end_date
-                            .map_or(String::new(), |d| d.format("%d/%m").to_string());
-                        app.popup_event_end_time = base_event
-                            .end_time
-                            .map_or(String::new(), |t| t.format("%H:%M").to_string());
-                        app.popup_event_recurrence = match base_event.recurrence {
-                            Recurrence::None => "none".to_string(),
-                            Recurrence::Daily => "daily".to_string(),
-                            Recurrence::Weekly => "weekly".to_string(),
-                            Recurrence::Monthly => "monthly".to_string(),
-                            Recurrence::Yearly => "yearly".to_string(),
-                        };
-                        app.popup_event_description = base_event.description.clone();
-                        app.current_date_for_new_event = base_event.start_date;
-                        app.is_editing = true;
-                        app.event_being_edited = Some(base_event.clone());
-                        app.show_add_event_popup = true;
-                        app.input_mode = InputMode::EditingEventPopup;
-                        app.selected_input_field = PopupInputField::Title;
-                        app.cursor_position = app.popup_event_title.chars().count();
-                    }
-                }
-                KeyCode::Char('a') => {
-                    app.show_add_event_popup = true;
-                    app.input_mode = InputMode::EditingEventPopup;
-                    app.current_date_for_new_event = app.date;
-                    app.popup_event_title.clear();
-                    app.popup_event_time.clear();
-                    app.popup_event_end_date.clear();
-                    app.popup_event_end_time.clear();
-                    app.popup_event_recurrence.clear();
-                    app.popup_event_description.clear();
-                    app.input.clear();
-                    app.selected_input_field = PopupInputField::Title;
-                    app.cursor_position = 0;
-                    app.is_editing = false;
-                    app.event_being_edited = None;
-                    app.date_input_error = None;
-                    app.date_suggestions.clear();
-                    app.show_date_suggestions = false;
-                    app.time_input_error = None;
-                    app.end_time_input_error = None;
-                }
-
-                KeyCode::Char('d') | KeyCode::Delete => {
-                    if !app.events_to_display_in_popup.is_empty() {
-                        app.event_to_delete_index = Some(app.selected_event_index);
-                        app.input_mode = InputMode::DeleteConfirmation;
-                    }
-                }
-                _ => {}
-            },
+            }
             InputMode::DeleteConfirmation => match key.code {
                 KeyCode::Char('y') => {
                     if let Some(index) = app.event_to_delete_index {
@@ -816,6 +1226,7 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                                     base,
                                     &app.calendar_dir,
                                 );
+                                app.undo_history.record(Change::deleted(base.clone()));
                                 Some(base.title.clone())
                             } else {
                                 // Invalidate cached instances before deletion
@@ -830,23 +1241,20 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                                         &event_to_delete,
                                         &app.calendar_dir,
                                     );
+                                    app.undo_history
+                                        .record(Change::deleted(event_to_delete.clone()));
                                 }
                                 None
                             };
 
                             // Spawn async sync for delete
-                            if let Some(provider) = &app.sync_provider {
-                                if let Some(git_provider) =
-                                    provider.as_any().downcast_ref::<GitSyncProvider>()
-                                {
-                                    let remote_url = git_provider.remote_url.clone();
-                                    let calendar_dir = app.calendar_dir.clone();
-                                    thread::spawn(move || {
-                                        let provider = GitSyncProvider::new(remote_url);
-                                        let _ = provider.push(&calendar_dir);
-                                    });
-                                }
-                            }
+                            spawn_background_push(
+                                app,
+                                PendingOperation::for_event(
+                                    OperationKind::Delete,
+                                    base_to_delete.as_ref().unwrap_or(&event_to_delete),
+                                ),
+                            );
                             // Update display list - remove all matching events from popup
                             if let Some(title) = deleted_title {
                                 app.events_to_display_in_popup
@@ -875,45 +1283,12 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                 _ => {}
             },
             InputMode::Sync => match key.code {
-                KeyCode::Char('f') => {
-                    if let Some(provider) = &app.sync_provider {
-                        let home = dirs::home_dir().expect("Could not find home directory");
-                        let calendar_dir = home.join("calendar");
-                        match provider.pull(&calendar_dir) {
-                            Ok(status) => {
-                                app.sync_message = "Pull successful".to_string();
-                                app.sync_status = Some(status);
-                                // Reload events
-                                let events = persistence::load_events().unwrap_or_else(|e| {
-                                    eprintln!("Failed to reload events after pull: {e}");
-                                    Vec::new()
-                                });
-                                app.set_events(events);
-                                // Invalidate cached instances after reloading events
-                                app.invalidate_instance_cache(None);
-                            }
-                            Err(e) => {
-                                app.sync_message = format!("Pull failed: {e}");
-                                app.sync_status = Some(SyncStatus::Error(e.to_string()));
-                            }
-                        }
-                    }
-                }
-                KeyCode::Char('p') => {
-                    if let Some(provider) = &app.sync_provider {
-                        let home = dirs::home_dir().expect("Could not find home directory");
-                        let calendar_dir = home.join("calendar");
-                        match provider.push(&calendar_dir) {
-                            Ok(status) => {
-                                app.sync_message = "Push successful".to_string();
-                                app.sync_status = Some(status);
-                            }
-                            Err(e) => {
-                                app.sync_message = format!("Push failed: {e}");
-                                app.sync_status = Some(SyncStatus::Error(e.to_string()));
-                            }
-                        }
-                    }
+                KeyCode::Char('f') => sync_pull(app),
+                KeyCode::Char('p') => sync_push(app),
+                KeyCode::Char('c') => {
+                    // Replaced by the resolver, or by an error while loading conflicts
+                    app.sync_message = "No conflicts to resolve".to_string();
+                    app.open_conflict_resolver();
                 }
                 KeyCode::Esc => {
                     app.input_mode = InputMode::Normal;
@@ -922,7 +1297,531 @@ pub fn handle_event(app: &mut App, event: CrosstermEvent) -> io::Result<bool> {
                 }
                 _ => {}
             },
+            InputMode::ConflictResolver => handle_conflict_resolver_key(app, key.code),
+            InputMode::EventHistory => handle_event_history_key(app, key.code),
+            InputMode::Search => handle_search_key(app, &key),
+            InputMode::TargetDate => handle_target_date_key(app, &key),
+            InputMode::GoTo => handle_goto_key(app, &key),
+            InputMode::CommandLine => {
+                if !handle_command_line_key(app, &key) {
+                    return Ok(false);
+                }
+            }
+            InputMode::Help => handle_help_key(app, key.code),
+        }
+    }
+    if let CrosstermEvent::Mouse(mouse) = event {
+        return handle_mouse_event(app, mouse);
+    }
+    Ok(true)
+}
+
+/// Pulls remote changes from the sync menu or `:sync pull`, opening the
+/// conflict resolver when the pull stops on conflicts.
+fn sync_pull(app: &mut App) {
+    if let Some(provider) = &app.sync_provider {
+        let calendar_dir = app.calendar_dir.clone();
+        match provider.pull(&calendar_dir) {
+            Ok(SyncStatus::Conflicts) => {
+                app.sync_status = Some(SyncStatus::Conflicts);
+                app.sync_message = "Pull stopped on conflicts".to_string();
+                app.open_conflict_resolver();
+            }
+            Ok(status) => {
+                app.sync_message = "Pull successful".to_string();
+                app.sync_status = Some(status);
+                // Reload events
+                let events =
+                    persistence::load_events_from_path(&calendar_dir).unwrap_or_else(|e| {
+                        eprintln!("Failed to reload events after pull: {e}");
+                        Vec::new()
+                    });
+                app.set_events(events);
+                // Invalidate cached instances after reloading events
+                app.invalidate_instance_cache(None);
+            }
+            Err(e) => {
+                app.sync_message = format!("Pull failed: {e}");
+                app.sync_status = Some(SyncStatus::Error(e.to_string()));
+                // Git pulls fail instead of returning Conflicts
+                app.open_conflict_resolver();
+            }
+        }
+    }
+}
+
+/// Pushes local changes from the sync menu or `:sync push`.
+fn sync_push(app: &mut App) {
+    if let Some(provider) = &app.sync_provider {
+        let calendar_dir = app.calendar_dir.clone();
+        let pending = SyncJournal::load(&calendar_dir).unwrap_or_default();
+        match provider.push(&calendar_dir) {
+            Ok(SyncStatus::Conflicts) => {
+                app.sync_status = Some(SyncStatus::Conflicts);
+                app.sync_message = "Push stopped on conflicts".to_string();
+                app.open_conflict_resolver();
+            }
+            Ok(status) => {
+                // Everything pending before the push has reached the remote
+                let _ = SyncJournal::remove_flushed(&calendar_dir, &pending);
+                app.sync_message = "Push successful".to_string();
+                app.sync_status = Some(status);
+            }
+            Err(e) => {
+                app.sync_message = format!("Push failed: {e}");
+                app.sync_status = Some(SyncStatus::Error(e.to_string()));
+            }
+        }
+    }
+}
+
+/// Opens the event popup for a new event on the selected date.
+fn open_add_event_popup(app: &mut App) {
+    app.show_add_event_popup = true;
+    app.input_mode = InputMode::EditingEventPopup;
+    app.current_date_for_new_event = app.date;
+    app.popup_event_title.clear();
+    app.popup_event_time.clear();
+    app.popup_event_end_date.clear();
+    app.popup_event_end_time.clear();
+    app.popup_event_recurrence.clear();
+    app.popup_event_description.clear();
+    app.input.clear();
+    app.selected_input_field = PopupInputField::Title;
+    app.cursor_position = 0;
+    app.is_editing = false;
+    app.event_being_edited = None;
+    app.date_input_error = None;
+    app.date_suggestions.clear();
+    app.show_date_suggestions = false;
+    app.time_input_error = None;
+    app.end_time_input_error = None;
+    app.free_slots.clear();
+}
+
+/// Opens the sync menu and checks the sync status.
+fn open_sync_menu(app: &mut App) {
+    app.input_mode = InputMode::Sync;
+    app.sync_message.clear();
+    app.sync_status = None;
+    // Automatically check sync status on entry
+    if let Some(provider) = &app.sync_provider {
+        match provider.status(&app.calendar_dir) {
+            Ok(status) => {
+                app.sync_message = match &status {
+                    SyncStatus::UpToDate => "".to_string(),
+                    SyncStatus::Ahead => "Ahead of remote".to_string(),
+                    SyncStatus::Behind => "Behind remote".to_string(),
+                    SyncStatus::Conflicts => "Conflicts detected".to_string(),
+                    SyncStatus::Error(e) => {
+                        format!("Status error: {e}")
+                    }
+                };
+                if app.selected_input_field == PopupInputField::Recurrence {
+                    app.input_mode = InputMode::SelectingRecurrence;
+                    app.selected_recurrence_index =
+                        recurrence_str_to_index(&app.popup_event_recurrence);
+                }
+            }
+            Err(e) => {
+                app.sync_message = format!("Status failed: {e}");
+                app.sync_status = Some(SyncStatus::Error(e.to_string()));
+            }
         }
     }
+}
+
+/// Performs an action of the calendar views. Navigation depends on the
+/// view: the year view moves by months, the day view by days and events.
+fn handle_normal_action(app: &mut App, action: Action) {
+    let view = app.view_mode;
+    match action {
+        Action::NavigateLeft | Action::NavigateRight => {
+            let step = if action == Action::NavigateLeft {
+                -1
+            } else {
+                1
+            };
+            match view {
+                ViewMode::Year => app.step_months(step),
+                ViewMode::Day => app.step_day(step.into()),
+                _ => {
+                    app.date += chrono::Duration::days(step.into());
+                    app.adjust_view_boundaries();
+                }
+            }
+        }
+        Action::NavigateUp | Action::NavigateDown => {
+            let step = if action == Action::NavigateUp { -1 } else { 1 };
+            match view {
+                ViewMode::Year => app.step_months(step * app.month_columns as i32),
+                ViewMode::Day => app.select_day_event(step as isize),
+                _ => {
+                    app.date += chrono::Duration::weeks(step.into());
+                    app.adjust_view_boundaries();
+                }
+            }
+        }
+        Action::PageUp if view == ViewMode::Year => app.step_months(-12),
+        Action::PageDown if view == ViewMode::Year => app.step_months(12),
+        Action::OpenDay => match view {
+            ViewMode::Year => {
+                app.view_mode = ViewMode::Month;
+                app.adjust_view_boundaries();
+            }
+            ViewMode::Day => open_view_events_popup(app),
+            _ => app.open_day_view(),
+        },
+        Action::Back => match view {
+            ViewMode::Year => app.toggle_year_view(),
+            ViewMode::Day => app.close_day_view(),
+            _ => {}
+        },
+        Action::ToggleWeekView => app.toggle_week_view(),
+        Action::ToggleYearView => app.toggle_year_view(),
+        Action::OpenEvents => open_view_events_popup(app),
+        Action::AddEvent => open_add_event_popup(app),
+        Action::Paste => paste_clipboard(app),
+        Action::Undo => undo_last_change(app),
+        Action::Redo => redo_last_change(app),
+        Action::Search => app.open_search(),
+        Action::GoTo => {
+            app.goto_input.clear();
+            app.input_mode = InputMode::GoTo;
+        }
+        Action::CommandLine => {
+            app.command_input.clear();
+            app.command_history.reset();
+            app.input_mode = InputMode::CommandLine;
+        }
+        Action::Sync if app.sync_provider.is_some() => open_sync_menu(app),
+        Action::Help => open_help(app),
+        _ => {}
+    }
+}
+
+/// Performs an action of the view popup.
+fn handle_popup_action(app: &mut App, action: Action) {
+    let has_events = !app.events_to_display_in_popup.is_empty();
+    match action {
+        Action::Close => {
+            app.show_view_events_popup = false;
+            app.events_to_display_in_popup.clear();
+            app.selected_event_index = 0;
+            app.event_to_delete_index = None;
+            app.input_mode = InputMode::Normal;
+        }
+        Action::SelectPrevious if has_events => {
+            if app.selected_event_index == 0 {
+                app.selected_event_index = app.events_to_display_in_popup.len() - 1;
+            } else {
+                app.selected_event_index -= 1;
+            }
+        }
+        Action::SelectNext if has_events => {
+            if app.selected_event_index == app.events_to_display_in_popup.len() - 1 {
+                app.selected_event_index = 0;
+            } else {
+                app.selected_event_index += 1;
+            }
+        }
+        Action::EditEvent if has_events => {
+            let selected_event = &app.events_to_display_in_popup[app.selected_event_index];
+            let base_event =
+                if let Some(base) = find_base_event_for_instance(selected_event, &app.events()) {
+                    base
+                } else {
+                    selected_event.clone()
+                };
+            open_event_popup(app, &base_event);
+            app.is_editing = true;
+            app.event_being_edited = Some(base_event);
+        }
+        Action::AddEvent => open_add_event_popup(app),
+        Action::EventHistory if has_events => {
+            let selected_event = &app.events_to_display_in_popup[app.selected_event_index];
+            let base_event = find_base_event_for_instance(selected_event, &app.events())
+                .unwrap_or_else(|| selected_event.clone());
+            app.open_event_history(&base_event);
+        }
+        Action::DeleteEvent if has_events => {
+            app.event_to_delete_index = Some(app.selected_event_index);
+            app.input_mode = InputMode::DeleteConfirmation;
+        }
+        Action::Copy | Action::Cut if has_events => {
+            if let Some((event, anchor)) = selected_stored_event(app) {
+                let cut = action == Action::Cut;
+                let paste = match app.keymap.keys(KeyMode::Normal, Action::Paste).first() {
+                    Some(key) => format!(", {key} pastes it on the selected day"),
+                    None => String::new(),
+                };
+                app.status_message = format!(
+                    "{} '{}'{paste}",
+                    if cut { "Cut" } else { "Copied" },
+                    event.title
+                );
+                app.clipboard = Some(Clipboard { event, anchor, cut });
+            }
+        }
+        Action::Paste => paste_clipboard(app),
+        Action::MoveEvent if has_events => open_target_date_prompt(app, DateAction::Move),
+        Action::DuplicateEvent if has_events => {
+            open_target_date_prompt(app, DateAction::Duplicate);
+        }
+        Action::ShiftBack => shift_selected_event(app, -1),
+        Action::ShiftForward => shift_selected_event(app, 1),
+        Action::Undo => undo_last_change(app),
+        Action::Redo => redo_last_change(app),
+        Action::Help => open_help(app),
+        _ => {}
+    }
+}
+
+/// Opens the help screen listing the active key bindings.
+fn open_help(app: &mut App) {
+    app.help_scroll = 0;
+    app.input_mode = InputMode::Help;
+}
+
+/// Handles a key press on the help screen, which returns to the view popup
+/// if it was opened from there.
+fn handle_help_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
+            app.input_mode = if app.show_view_events_popup {
+                InputMode::ViewEventsPopup
+            } else {
+                InputMode::Normal
+            };
+        }
+        KeyCode::Up | KeyCode::Char('k') => app.help_scroll = app.help_scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => {
+            // One line per binding, plus a heading and a blank line per mode
+            let lines = KeyMode::ALL
+                .iter()
+                .map(|mode| app.keymap.bindings(*mode).count() + 2)
+                .sum::<usize>();
+            app.help_scroll = (app.help_scroll + 1).min(lines as u16);
+        }
+        _ => {}
+    }
+}
+
+/// Two clicks on the same target within this time are a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// Handles a mouse event: clicks act on what `ui()` drew at the position and
+/// the wheel scrolls the current view or list.
+fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> io::Result<bool> {
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let target = app
+                .hit_map
+                .borrow()
+                .target_at(mouse.column, mouse.row)
+                .cloned();
+            let Some(target) = target else {
+                app.last_click = None;
+                return Ok(true);
+            };
+            let now = Instant::now();
+            let double = matches!(&app.last_click, Some((at, last))
+                if *last == target && now.duration_since(*at) <= DOUBLE_CLICK_TIME);
+            app.last_click = (!double).then(|| (now, target.clone()));
+            handle_click(app, target, double)
+        }
+        MouseEventKind::ScrollUp => handle_scroll(app, -1),
+        MouseEventKind::ScrollDown => handle_scroll(app, 1),
+        _ => Ok(true),
+    }
+}
+
+/// Handles a click on `target`. Buttons act like their keys; dates, events
+/// and search results are selected, and opened on a double click.
+fn handle_click(app: &mut App, target: HitTarget, double: bool) -> io::Result<bool> {
+    match target {
+        HitTarget::Key(code) => {
+            return handle_event(app, CrosstermEvent::Key(KeyEvent::from(code)))
+        }
+        HitTarget::Date(date) if app.input_mode == InputMode::Normal => {
+            app.status_message.clear();
+            app.date = date;
+            app.adjust_view_boundaries();
+            if double {
+                app.open_day_view();
+            }
+        }
+        HitTarget::PopupEvent(index) if app.input_mode == InputMode::ViewEventsPopup => {
+            app.selected_event_index = index;
+            if double {
+                handle_popup_action(app, Action::EditEvent);
+            }
+        }
+        HitTarget::SearchResult(index) if app.input_mode == InputMode::Search => {
+            app.selected_search_index = index;
+            if double {
+                app.jump_to_search_result();
+            }
+        }
+        _ => {}
+    }
+    Ok(true)
+}
+
+/// Scrolls by `step`: months in the month and year views, weeks in the week
+/// view, days in the day view, and the selection in lists.
+fn handle_scroll(app: &mut App, step: i32) -> io::Result<bool> {
+    let down = step > 0;
+    let select = if down {
+        Action::SelectNext
+    } else {
+        Action::SelectPrevious
+    };
+    match app.input_mode {
+        InputMode::Normal => match app.view_mode {
+            ViewMode::Month | ViewMode::Year => app.step_months(step),
+            ViewMode::Week => {
+                app.date += chrono::Duration::weeks(step.into());
+                app.adjust_view_boundaries();
+            }
+            ViewMode::Day => app.step_day(step.into()),
+        },
+        // Scrolling selects directly, so it keeps working with rebound keys
+        InputMode::ViewEventsPopup => handle_popup_action(app, select),
+        InputMode::Search => handle_search_action(app, select),
+        InputMode::EventHistory => {
+            handle_event_history_key(app, if down { KeyCode::Down } else { KeyCode::Up })
+        }
+        _ => {}
+    }
     Ok(true)
 }
+
+/// Handles a key press in the event history popup.
+fn handle_event_history_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => app.close_event_history(),
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.selected_revision_index = app.selected_revision_index.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.selected_revision_index =
+                (app.selected_revision_index + 1).min(app.event_history.len().saturating_sub(1));
+        }
+        KeyCode::Enter | KeyCode::Char('r') => {
+            if let Some(event) = app.restore_selected_revision() {
+                spawn_background_push(
+                    app,
+                    PendingOperation::for_event(OperationKind::Save, &event),
+                );
+                app.close_event_history();
+                refresh_view_events_popup(app);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Handles a key press in the search popup. Typing edits the query and
+/// updates the results right away.
+fn handle_search_key(app: &mut App, key: &KeyEvent) {
+    match app.keymap.text_action(KeyMode::Search, key) {
+        (Some(action), _) => handle_search_action(app, action),
+        (None, KeyCode::Char(c)) => {
+            app.search_query.push(c);
+            app.update_search();
+        }
+        _ => {}
+    }
+}
+
+/// Performs an action of the search popup.
+fn handle_search_action(app: &mut App, action: Action) {
+    match action {
+        Action::Cancel => app.input_mode = InputMode::Normal,
+        Action::Submit => {
+            app.jump_to_search_result();
+        }
+        Action::SelectPrevious => {
+            app.selected_search_index = app.selected_search_index.saturating_sub(1);
+        }
+        Action::SelectNext => {
+            app.selected_search_index =
+                (app.selected_search_index + 1).min(app.search_results.len().saturating_sub(1));
+        }
+        Action::ToggleRegex => {
+            app.search_regex = !app.search_regex;
+            app.update_search();
+        }
+        Action::DeleteBackward => {
+            app.search_query.pop();
+            app.update_search();
+        }
+        _ => {}
+    }
+}
+
+/// Handles a key press in the conflict resolver.
+fn handle_conflict_resolver_key(app: &mut App, code: KeyCode) {
+    let Some(conflict) = app.current_conflict().cloned() else {
+        app.close_conflict_resolver();
+        return;
+    };
+    let index = app.selected_conflict_index;
+    let field = app.current_conflict_field();
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.selected_conflict_field = app.selected_conflict_field.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.selected_conflict_field =
+                (app.selected_conflict_field + 1).min(EventField::ALL.len() - 1);
+        }
+        KeyCode::Left | KeyCode::Char('h') => {
+            app.conflict_choices[index].set(field, ConflictSide::Local);
+        }
+        KeyCode::Right | KeyCode::Char('l') => {
+            app.conflict_choices[index].set(field, ConflictSide::Remote);
+        }
+        KeyCode::Char(' ') => {
+            let side = app.conflict_choices[index].get(field).toggled();
+            app.conflict_choices[index].set(field, side);
+        }
+        KeyCode::Char('L') => {
+            app.conflict_choices[index] = ConflictChoices::all(ConflictSide::Local);
+        }
+        KeyCode::Char('R') => {
+            app.conflict_choices[index] = ConflictChoices::all(ConflictSide::Remote);
+        }
+        KeyCode::Tab => {
+            app.selected_conflict_index = (index + 1) % app.sync_conflicts.len();
+            app.selected_conflict_field = 0;
+        }
+        KeyCode::BackTab => {
+            app.selected_conflict_index =
+                (index + app.sync_conflicts.len() - 1) % app.sync_conflicts.len();
+            app.selected_conflict_field = 0;
+        }
+        KeyCode::Char('b') => app.resolve_current_conflict(conflict.keep_both()),
+        KeyCode::Char('e') => {
+            // Deleting can't be edited, so start from whichever side still exists
+            let merged = conflict
+                .merge(&app.conflict_choices[index])
+                .or_else(|| conflict.local.clone())
+                .or_else(|| conflict.remote.clone());
+            if let Some(merged) = merged {
+                open_event_popup(app, &merged);
+                app.is_editing_conflict = true;
+            }
+        }
+        KeyCode::Enter => {
+            let resolution = conflict.resolve(&app.conflict_choices[index]);
+            app.resolve_current_conflict(resolution);
+        }
+        KeyCode::Esc => {
+            app.close_conflict_resolver();
+            app.sync_message = "Conflict resolution cancelled".to_string();
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,199 @@
+//! The go-to prompt: jumping to a date, a calendar week or a month.
+//!
+//! `parse_goto` accepts, in this order:
+//!
+//! - a calendar week, `w42`, `w42 2027`, `week 42` or `2027-w42`, going to
+//!   its Monday
+//! - a month name, `march`, `mar 2027` or a name of the configured
+//!   language, going to the first of the month
+//! - a step from the selected date, `+3`, `-2d`, `+1w`, `+2m`, `-1y`, or
+//!   `next`/`last` followed by `week`, `month` or `year`
+//! - a date accepted by the target date prompt, such as `2026-12-01`,
+//!   `24/12`, `tomorrow` or `friday`; day and month are typed in the order
+//!   of the locale
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use rcal_lib::DateOrder;
+
+use crate::clipboard::parse_target;
+use crate::date_utils::validate_date_input_in;
+use crate::locale::Locale;
+
+/// Parses what was typed into the go-to prompt. Steps are taken from
+/// `from`, the selected date, and dates are relative to `today`.
+pub fn parse_goto(
+    input: &str,
+    from: NaiveDate,
+    today: NaiveDate,
+    locale: &Locale,
+) -> Result<NaiveDate, String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err("Type a date, a week such as w42 or a month".to_string());
+    }
+    if let Some(week) = parse_week(&input, from) {
+        return week;
+    }
+    if let Some(month) = parse_month(&input, from, locale) {
+        return month;
+    }
+    if let Some(step) = parse_step(&input, from) {
+        return step;
+    }
+    if input.contains('/') && locale.date_input != DateOrder::DayMonth {
+        if let Ok(date) = validate_date_input_in(&input, today, locale.date_input) {
+            return Ok(date);
+        }
+    }
+    parse_target(&input, from, today).map_err(|e| {
+        if input.starts_with(['+', '-']) && e.contains("out of range") {
+            return e;
+        }
+        format!("Invalid date '{input}'. Use a date, a week such as w42, a month or +1w")
+    })
+}
+
+/// Parses a calendar week, in the ISO year of `from` unless one is given.
+fn parse_week(input: &str, from: NaiveDate) -> Option<Result<NaiveDate, String>> {
+    let (year, week) = match input.split_once("-w") {
+        Some((year, week)) => (Some(year), week),
+        None => {
+            let rest = input
+                .strip_prefix("week")
+                .or_else(|| input.strip_prefix('w'))?;
+            let mut words = rest.split_whitespace();
+            let week = words.next()?;
+            let year = words.next();
+            if words.next().is_some() {
+                return None;
+            }
+            (year, week)
+        }
+    };
+    let week: u32 = week.trim().parse().ok()?;
+    let year = match year {
+        Some(year) => match year.trim().parse::<i32>() {
+            Ok(year) => year,
+            Err(_) => return Some(Err(format!("Invalid year '{year}'"))),
+        },
+        None => from.iso_week().year(),
+    };
+    Some(
+        NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            .ok_or_else(|| format!("Week {week} does not exist in {year}")),
+    )
+}
+
+/// Parses a month name with an optional year. Without a year, the month is
+/// the next one with that name, counting the month of `from`.
+fn parse_month(input: &str, from: NaiveDate, locale: &Locale) -> Option<Result<NaiveDate, String>> {
+    let mut words = input.split_whitespace();
+    let month = locale.month_by_name(words.next()?)?;
+    let year = match (words.next(), words.next()) {
+        (None, _) if month < from.month() => from.year() + 1,
+        (None, _) => from.year(),
+        (Some(year), None) => match year.parse::<i32>() {
+            Ok(year) => year,
+            Err(_) => return Some(Err(format!("Invalid year '{year}'"))),
+        },
+        (Some(_), Some(_)) => return None,
+    };
+    Some(NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| format!("Invalid year '{year}'")))
+}
+
+/// Parses a step of months or years from `from`, such as `+2m`, or a step
+/// in words such as `next week`. Offsets of days and weeks such as `+3` are
+/// left to `parse_target`.
+fn parse_step(input: &str, from: NaiveDate) -> Option<Result<NaiveDate, String>> {
+    let (sign, count, unit) = match input.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [direction @ ("next" | "last"), unit @ ("week" | "month" | "year")] => {
+            (if *direction == "next" { 1i64 } else { -1 }, 1u32, *unit)
+        }
+        _ => {
+            let sign = match input.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+            let offset = &input[1..];
+            let (count, unit) = if let Some(count) = offset.strip_suffix('m') {
+                (count, "month")
+            } else {
+                (offset.strip_suffix('y')?, "year")
+            };
+            match count.parse::<u32>() {
+                Ok(count) => (sign, count, unit),
+                Err(_) => return Some(Err(format!("Invalid offset '{input}'"))),
+            }
+        }
+    };
+    let months = match unit {
+        "week" => return Some(Ok(from + Duration::weeks(sign * i64::from(count)))),
+        "month" => Some(count),
+        _ => count.checked_mul(12),
+    };
+    let date = months.and_then(|months| {
+        if sign < 0 {
+            from.checked_sub_months(Months::new(months))
+        } else {
+            from.checked_add_months(Months::new(months))
+        }
+    });
+    Some(date.ok_or_else(|| "offset out of range".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn goto(input: &str) -> Result<NaiveDate, String> {
+        parse_goto(
+            input,
+            date(2026, 10, 19),
+            date(2026, 10, 1),
+            &Locale::default(),
+        )
+    }
+
+    #[test]
+    fn test_parse_goto() {
+        assert_eq!(goto("w42"), Ok(date(2026, 10, 12)));
+        assert_eq!(goto("W1 2027"), Ok(date(2027, 1, 4)));
+        assert_eq!(goto("week 53"), Ok(date(2026, 12, 28)));
+        assert_eq!(goto("2027-w10"), Ok(date(2027, 3, 8)));
+        assert!(goto("w54").is_err());
+
+        assert_eq!(goto("march"), Ok(date(2027, 3, 1)));
+        assert_eq!(goto("Oct"), Ok(date(2026, 10, 1)));
+        assert_eq!(goto("dec 2025"), Ok(date(2025, 12, 1)));
+
+        assert_eq!(goto("+2m"), Ok(date(2026, 12, 19)));
+        assert_eq!(goto("-1y"), Ok(date(2025, 10, 19)));
+        assert_eq!(goto("next month"), Ok(date(2026, 11, 19)));
+        assert_eq!(goto("last week"), Ok(date(2026, 10, 12)));
+        assert_eq!(goto("+3"), Ok(date(2026, 10, 22)));
+        assert_eq!(goto("-1w"), Ok(date(2026, 10, 12)));
+        assert_eq!(goto("+999999999"), Err("offset out of range".to_string()));
+        assert_eq!(goto("+999999999y"), Err("offset out of range".to_string()));
+        assert_eq!(goto("-99999999m"), Err("offset out of range".to_string()));
+
+        assert_eq!(goto("2026-12-01"), Ok(date(2026, 12, 1)));
+        assert_eq!(goto("24/12"), Ok(date(2026, 12, 24)));
+        assert_eq!(goto("tomorrow"), Ok(date(2026, 10, 2)));
+        assert!(goto("someday").is_err());
+        assert!(goto("").is_err());
+    }
+
+    #[test]
+    fn test_parse_goto_in_locale() {
+        let mut locale = Locale::language("fr").unwrap();
+        locale.date_input = DateOrder::MonthDay;
+        let goto = |input| parse_goto(input, date(2026, 10, 19), date(2026, 10, 1), &locale);
+        assert_eq!(goto("12/24"), Ok(date(2026, 12, 24)));
+        assert_eq!(goto("février"), Ok(date(2027, 2, 1)));
+    }
+}
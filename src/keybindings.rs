@@ -0,0 +1,679 @@
+//! Configurable key bindings of the TUI.
+//!
+//! Key presses in the calendar, the view popup, the event form, the date
+//! prompts, the search and the command line are looked up in a `Keymap`,
+//! which turns them into `Action`s. The defaults can be changed in the
+//! `[keys]` table of config.toml, with a table per mode that maps action
+//! names to a key or a list of keys:
+//!
+//! ```toml
+//! [keys.normal]
+//! navigate_left = ["h", "left"]
+//! redo = "ctrl+r"
+//!
+//! [keys.popup]
+//! delete_event = ["x", "delete"]
+//! cut = "X"
+//!
+//! [keys.command_line]
+//! complete = ["tab", "ctrl+n"]
+//! ```
+//!
+//! Listing an action replaces its default keys; a key may only be bound to
+//! one action per mode. In the modes that take text, keys without an action
+//! are typed, so a character bound there can no longer be typed. The other
+//! popups, such as the sync menu and the conflict resolver, have fixed keys.
+
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// The modes with configurable keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyMode {
+    /// The calendar views.
+    Normal,
+    /// The view popup listing the events of a date.
+    Popup,
+    /// The fields of the form adding or editing an event.
+    Form,
+    /// The go-to prompt and the prompt for the date to move or duplicate to.
+    Prompt,
+    /// The search popup.
+    Search,
+    /// The `:` command line.
+    CommandLine,
+}
+
+impl KeyMode {
+    pub const ALL: [KeyMode; 6] = [
+        KeyMode::Normal,
+        KeyMode::Popup,
+        KeyMode::Form,
+        KeyMode::Prompt,
+        KeyMode::Search,
+        KeyMode::CommandLine,
+    ];
+
+    /// Returns the name of the mode's table under `[keys]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyMode::Normal => "normal",
+            KeyMode::Popup => "popup",
+            KeyMode::Form => "form",
+            KeyMode::Prompt => "prompt",
+            KeyMode::Search => "search",
+            KeyMode::CommandLine => "command_line",
+        }
+    }
+
+    /// Returns the heading of the mode on the help screen.
+    pub fn title(self) -> &'static str {
+        match self {
+            KeyMode::Normal => "Calendar",
+            KeyMode::Popup => "Event popup",
+            KeyMode::Form => "Event form",
+            KeyMode::Prompt => "Go-to and target date prompts",
+            KeyMode::Search => "Search",
+            KeyMode::CommandLine => "Command line",
+        }
+    }
+}
+
+/// What a key does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    AddEvent,
+    OpenDay,
+    OpenEvents,
+    Search,
+    GoTo,
+    CommandLine,
+    Sync,
+    Undo,
+    Redo,
+    Paste,
+    ToggleWeekView,
+    ToggleYearView,
+    NavigateLeft,
+    NavigateRight,
+    NavigateUp,
+    NavigateDown,
+    PageUp,
+    PageDown,
+    Back,
+    Close,
+    SelectPrevious,
+    SelectNext,
+    EditEvent,
+    DeleteEvent,
+    EventHistory,
+    Copy,
+    Cut,
+    MoveEvent,
+    DuplicateEvent,
+    ShiftBack,
+    ShiftForward,
+    Submit,
+    Cancel,
+    DeleteBackward,
+    CursorLeft,
+    CursorRight,
+    NextField,
+    PreviousField,
+    PreviousSuggestion,
+    NextSuggestion,
+    ProposeFreeSlot,
+    ToggleRegex,
+    HistoryPrevious,
+    HistoryNext,
+    Complete,
+}
+
+impl Action {
+    /// Returns the name of the action in config.toml.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::AddEvent => "add_event",
+            Action::OpenDay => "open_day",
+            Action::OpenEvents => "open_events",
+            Action::Search => "search",
+            Action::GoTo => "go_to",
+            Action::CommandLine => "command_line",
+            Action::Sync => "sync",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Paste => "paste",
+            Action::ToggleWeekView => "toggle_week_view",
+            Action::ToggleYearView => "toggle_year_view",
+            Action::NavigateLeft => "navigate_left",
+            Action::NavigateRight => "navigate_right",
+            Action::NavigateUp => "navigate_up",
+            Action::NavigateDown => "navigate_down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Back => "back",
+            Action::Close => "close",
+            Action::SelectPrevious => "select_previous",
+            Action::SelectNext => "select_next",
+            Action::EditEvent => "edit_event",
+            Action::DeleteEvent => "delete_event",
+            Action::EventHistory => "event_history",
+            Action::Copy => "copy",
+            Action::Cut => "cut",
+            Action::MoveEvent => "move_event",
+            Action::DuplicateEvent => "duplicate_event",
+            Action::ShiftBack => "shift_back",
+            Action::ShiftForward => "shift_forward",
+            Action::Submit => "submit",
+            Action::Cancel => "cancel",
+            Action::DeleteBackward => "delete_backward",
+            Action::CursorLeft => "cursor_left",
+            Action::CursorRight => "cursor_right",
+            Action::NextField => "next_field",
+            Action::PreviousField => "previous_field",
+            Action::PreviousSuggestion => "previous_suggestion",
+            Action::NextSuggestion => "next_suggestion",
+            Action::ProposeFreeSlot => "propose_free_slot",
+            Action::ToggleRegex => "toggle_regex",
+            Action::HistoryPrevious => "history_previous",
+            Action::HistoryNext => "history_next",
+            Action::Complete => "complete",
+        }
+    }
+
+    /// Returns what the action does, for the help screen.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Show the key bindings",
+            Action::AddEvent => "Add an event on the selected date",
+            Action::OpenDay => "Open the selected day; the month in the year view",
+            Action::OpenEvents => "View the events of the selected date",
+            Action::Search => "Search events",
+            Action::GoTo => "Go to a date, a week such as w42 or a month",
+            Action::CommandLine => "Open the command line for :add, :goto, :filter, :q and more",
+            Action::Sync => "Open the sync menu",
+            Action::Undo => "Undo the last change",
+            Action::Redo => "Redo the last undone change",
+            Action::Paste => "Paste the copied or cut event on the selected date",
+            Action::ToggleWeekView => "Switch between the month and the week view",
+            Action::ToggleYearView => "Switch to and from the year view",
+            Action::NavigateLeft => "Previous day; month in the year view",
+            Action::NavigateRight => "Next day; month in the year view",
+            Action::NavigateUp => {
+                "Previous week; row of months in the year view, event in the day view"
+            }
+            Action::NavigateDown => {
+                "Next week; row of months in the year view, event in the day view"
+            }
+            Action::PageUp => "Previous year in the year view",
+            Action::PageDown => "Next year in the year view",
+            Action::Back => "Leave the day or year view",
+            Action::Close => "Close the popup",
+            Action::SelectPrevious => "Select the previous event",
+            Action::SelectNext => "Select the next event",
+            Action::EditEvent => "Edit the selected event",
+            Action::DeleteEvent => "Delete the selected event",
+            Action::EventHistory => "Show the history of the selected event",
+            Action::Copy => "Copy the selected event",
+            Action::Cut => "Cut the selected event",
+            Action::MoveEvent => "Move the selected event to a date",
+            Action::DuplicateEvent => "Duplicate the selected event to a date",
+            Action::ShiftBack => "Move the selected event a day back",
+            Action::ShiftForward => "Move the selected event a day forward",
+            Action::Submit => "Confirm the input",
+            Action::Cancel => "Close without confirming",
+            Action::DeleteBackward => "Delete the character before the cursor",
+            Action::CursorLeft => "Move the cursor left",
+            Action::CursorRight => "Move the cursor right",
+            Action::NextField => "Next field; take the selected date suggestion",
+            Action::PreviousField => "Previous field",
+            Action::PreviousSuggestion => "Select the previous end date suggestion",
+            Action::NextSuggestion => "Select the next end date suggestion",
+            Action::ProposeFreeSlot => "Fill in the next free time slot",
+            Action::ToggleRegex => "Switch between text and regex search",
+            Action::HistoryPrevious => "Recall the previous command",
+            Action::HistoryNext => "Recall the next command",
+            Action::Complete => "Complete the command, sync action or event title",
+        }
+    }
+}
+
+/// The default keys of each mode, in the order of the help screen.
+const DEFAULT_BINDINGS: &[(KeyMode, Action, &[&str])] = &[
+    (KeyMode::Normal, Action::NavigateLeft, &["h", "left"]),
+    (KeyMode::Normal, Action::NavigateDown, &["j", "down"]),
+    (KeyMode::Normal, Action::NavigateUp, &["k", "up"]),
+    (KeyMode::Normal, Action::NavigateRight, &["l", "right"]),
+    (KeyMode::Normal, Action::PageUp, &["pageup"]),
+    (KeyMode::Normal, Action::PageDown, &["pagedown"]),
+    (KeyMode::Normal, Action::OpenDay, &["enter"]),
+    (KeyMode::Normal, Action::Back, &["esc"]),
+    (KeyMode::Normal, Action::ToggleWeekView, &["w"]),
+    (KeyMode::Normal, Action::ToggleYearView, &["y"]),
+    (KeyMode::Normal, Action::OpenEvents, &["o"]),
+    (KeyMode::Normal, Action::AddEvent, &["a"]),
+    (KeyMode::Normal, Action::Paste, &["p"]),
+    (KeyMode::Normal, Action::Undo, &["u"]),
+    (KeyMode::Normal, Action::Redo, &["ctrl+r"]),
+    (KeyMode::Normal, Action::Search, &["/"]),
+    (KeyMode::Normal, Action::GoTo, &["g"]),
+    (KeyMode::Normal, Action::CommandLine, &[":"]),
+    (KeyMode::Normal, Action::Sync, &["s"]),
+    (KeyMode::Normal, Action::Help, &["?"]),
+    (KeyMode::Normal, Action::Quit, &["q"]),
+    (KeyMode::Popup, Action::SelectNext, &["j", "down"]),
+    (KeyMode::Popup, Action::SelectPrevious, &["k", "up"]),
+    (KeyMode::Popup, Action::EditEvent, &["e"]),
+    (KeyMode::Popup, Action::AddEvent, &["a"]),
+    (KeyMode::Popup, Action::DeleteEvent, &["d", "delete"]),
+    (KeyMode::Popup, Action::Copy, &["y"]),
+    (KeyMode::Popup, Action::Cut, &["x"]),
+    (KeyMode::Popup, Action::Paste, &["p"]),
+    (KeyMode::Popup, Action::MoveEvent, &["m"]),
+    (KeyMode::Popup, Action::DuplicateEvent, &["D"]),
+    (KeyMode::Popup, Action::ShiftBack, &["<"]),
+    (KeyMode::Popup, Action::ShiftForward, &[">"]),
+    (KeyMode::Popup, Action::Undo, &["u"]),
+    (KeyMode::Popup, Action::Redo, &["ctrl+r"]),
+    (KeyMode::Popup, Action::EventHistory, &["H"]),
+    (KeyMode::Popup, Action::Help, &["?"]),
+    (KeyMode::Popup, Action::Close, &["esc"]),
+    (KeyMode::Form, Action::NextField, &["tab"]),
+    (KeyMode::Form, Action::PreviousField, &["backtab"]),
+    (KeyMode::Form, Action::CursorLeft, &["left"]),
+    (KeyMode::Form, Action::CursorRight, &["right"]),
+    (KeyMode::Form, Action::DeleteBackward, &["backspace"]),
+    (KeyMode::Form, Action::PreviousSuggestion, &["up"]),
+    (KeyMode::Form, Action::NextSuggestion, &["down"]),
+    (KeyMode::Form, Action::ProposeFreeSlot, &["ctrl+f"]),
+    (KeyMode::Form, Action::Submit, &["enter"]),
+    (KeyMode::Form, Action::Cancel, &["esc"]),
+    (KeyMode::Prompt, Action::DeleteBackward, &["backspace"]),
+    (KeyMode::Prompt, Action::Submit, &["enter"]),
+    (KeyMode::Prompt, Action::Cancel, &["esc"]),
+    (KeyMode::Search, Action::SelectPrevious, &["up"]),
+    (KeyMode::Search, Action::SelectNext, &["down"]),
+    (KeyMode::Search, Action::ToggleRegex, &["tab"]),
+    (KeyMode::Search, Action::DeleteBackward, &["backspace"]),
+    (KeyMode::Search, Action::Submit, &["enter"]),
+    (KeyMode::Search, Action::Cancel, &["esc"]),
+    (KeyMode::CommandLine, Action::HistoryPrevious, &["up"]),
+    (KeyMode::CommandLine, Action::HistoryNext, &["down"]),
+    (KeyMode::CommandLine, Action::Complete, &["tab"]),
+    (KeyMode::CommandLine, Action::DeleteBackward, &["backspace"]),
+    (KeyMode::CommandLine, Action::Submit, &["enter"]),
+    (KeyMode::CommandLine, Action::Cancel, &["esc"]),
+];
+
+/// A key with its modifiers, such as `q`, `enter` or `ctrl+r`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Parses a key as written in config.toml: a single character, which
+    /// is case-sensitive, or a key name such as `enter`, `esc`, `tab`,
+    /// `space`, `left` or `f1`, optionally prefixed with `ctrl+`, `alt+` or
+    /// `shift+`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid key '{input}'");
+        let (prefix, key) = match input.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None if input.chars().count() == 1 => ("", input),
+            None => input.rsplit_once('+').unwrap_or(("", input)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').map(str::parse::<u8>) {
+                    Some(Ok(number @ 1..=12)) => KeyCode::F(number),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+        if let KeyCode::Char(c) = code {
+            // Terminals report shifted characters as the character itself
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                return Err(format!(
+                    "Invalid key '{input}', write '{}' instead",
+                    c.to_uppercase()
+                ));
+            }
+        }
+        Ok(Self { code, modifiers })
+    }
+
+    /// Returns the chord of a key press. Shift is part of the character for
+    /// character keys and of `backtab`, so it isn't kept as a modifier for
+    /// them.
+    pub fn from_event(event: &KeyEvent) -> Self {
+        let mut modifiers = event.modifiers & !KeyModifiers::SHIFT;
+        if !matches!(event.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers |= event.modifiers & KeyModifiers::SHIFT;
+        }
+        Self {
+            code: event.code,
+            modifiers,
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::F(number) => write!(f, "F{number}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// The keys bound to each action, per mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(KeyMode, Action, Vec<KeyChord>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .map(|(mode, action, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| KeyChord::parse(key).expect("default keys are valid"))
+                    .collect();
+                (*mode, *action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from the `[keys]` table of the configuration,
+    /// starting from the defaults. Unknown modes, actions and keys, and keys
+    /// bound to several actions of a mode, are reported together.
+    pub fn from_config(keys: Option<&toml::Value>) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        let Some(keys) = keys else {
+            return Ok(keymap);
+        };
+        let mut errors = Vec::new();
+        let Some(modes) = keys.as_table() else {
+            return Err("[keys] must be a table".to_string());
+        };
+        for (mode_name, actions) in modes {
+            let Some(mode) = KeyMode::ALL.into_iter().find(|m| m.name() == mode_name) else {
+                let names: Vec<&str> = KeyMode::ALL.iter().map(|m| m.name()).collect();
+                errors.push(format!(
+                    "Unknown mode [keys.{mode_name}], use one of {}",
+                    names.join(", ")
+                ));
+                continue;
+            };
+            let Some(actions) = actions.as_table() else {
+                errors.push(format!("[keys.{mode_name}] must be a table"));
+                continue;
+            };
+            for (action_name, value) in actions {
+                let Some(binding) = keymap
+                    .bindings
+                    .iter_mut()
+                    .find(|(m, action, _)| *m == mode && action.name() == action_name)
+                else {
+                    errors.push(format!(
+                        "Unknown action '{action_name}' in [keys.{mode_name}]"
+                    ));
+                    continue;
+                };
+                let names: Vec<&toml::Value> = match value {
+                    toml::Value::Array(names) => names.iter().collect(),
+                    name => vec![name],
+                };
+                let mut keys = Vec::new();
+                for name in names {
+                    match name.as_str().map(KeyChord::parse) {
+                        Some(Ok(key)) => keys.push(key),
+                        Some(Err(e)) => errors.push(format!("{e} for {action_name}")),
+                        None => errors.push(format!("Keys of {action_name} must be strings")),
+                    }
+                }
+                binding.2 = keys;
+            }
+        }
+
+        for mode in KeyMode::ALL {
+            let mut seen: Vec<(KeyChord, Action)> = Vec::new();
+            for (_, action, keys) in keymap.bindings.iter().filter(|(m, ..)| *m == mode) {
+                for key in keys {
+                    match seen.iter().find(|(seen_key, _)| seen_key == key) {
+                        Some((_, other)) if other != action => errors.push(format!(
+                            "'{key}' is bound to both {} and {} in [keys.{}]",
+                            other.name(),
+                            action.name(),
+                            mode.name()
+                        )),
+                        Some(_) => {}
+                        None => seen.push((*key, *action)),
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(keymap)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    /// Returns the action bound to a key press in `mode`.
+    pub fn action(&self, mode: KeyMode, event: &KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from_event(event);
+        self.bindings
+            .iter()
+            .find(|(m, _, keys)| *m == mode && keys.contains(&chord))
+            .map(|(_, action, _)| *action)
+    }
+
+    /// Returns the action bound to a key press in one of the modes that take
+    /// text, with the key's code to type when no action is bound to it.
+    pub fn text_action(&self, mode: KeyMode, event: &KeyEvent) -> (Option<Action>, KeyCode) {
+        (self.action(mode, event), event.code)
+    }
+
+    /// Returns the keys bound to an action in `mode`.
+    pub fn keys(&self, mode: KeyMode, action: Action) -> &[KeyChord] {
+        self.bindings
+            .iter()
+            .find(|(m, a, _)| *m == mode && *a == action)
+            .map_or(&[], |(_, _, keys)| keys.as_slice())
+    }
+
+    /// Returns the actions of `mode` with their keys, in help screen order.
+    pub fn bindings(&self, mode: KeyMode) -> impl Iterator<Item = (Action, &[KeyChord])> {
+        self.bindings
+            .iter()
+            .filter(move |(m, ..)| *m == mode)
+            .map(|(_, action, keys)| (*action, keys.as_slice()))
+    }
+
+    /// Builds a hints line such as "q: quit, h/j/k/l: navigate" from the
+    /// first key of each action. Items with an unbound action are left out.
+    pub fn hints(&self, mode: KeyMode, items: &[(&[Action], &str)]) -> String {
+        items
+            .iter()
+            .filter_map(|(actions, label)| {
+                let keys: Option<Vec<String>> = actions
+                    .iter()
+                    .map(|action| self.keys(mode, *action).first().map(KeyChord::to_string))
+                    .collect();
+                Some(format!("{}: {label}", keys?.join("/")))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(toml: &str) -> Result<Keymap, String> {
+        let value: toml::Value = toml::from_str(toml).unwrap();
+        Keymap::from_config(value.get("keys"))
+    }
+
+    #[test]
+    fn test_parse_and_display_keys() {
+        for (input, shown) in [
+            ("q", "q"),
+            ("D", "D"),
+            ("+", "+"),
+            ("ctrl+r", "Ctrl+r"),
+            ("Ctrl+Alt+x", "Ctrl+Alt+x"),
+            ("ctrl++", "Ctrl++"),
+            ("enter", "Enter"),
+            ("space", "Space"),
+            ("pgdn", "PgDn"),
+            ("shift+tab", "Shift+Tab"),
+            ("f5", "F5"),
+        ] {
+            assert_eq!(KeyChord::parse(input).unwrap().to_string(), shown);
+        }
+        assert!(KeyChord::parse("hyper+x").is_err());
+        assert!(KeyChord::parse("f13").is_err());
+        assert!(KeyChord::parse("shift+d").is_err());
+
+        // Shift comes with the character from the terminal
+        let event = KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from_event(&event), KeyChord::parse("D").unwrap());
+    }
+
+    #[test]
+    fn test_keymap_from_config() {
+        let keymap = Keymap::default();
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action(KeyMode::Normal, &ctrl_r), Some(Action::Redo));
+        assert_eq!(
+            keymap.action(KeyMode::Popup, &KeyEvent::from(KeyCode::Char('y'))),
+            Some(Action::Copy)
+        );
+
+        let keymap = keys(
+            "[keys.normal]\nnavigate_left = [\"n\", \"left\"]\nquit = \"ctrl+q\"\nhelp = []\n",
+        )
+        .unwrap();
+        let n = KeyEvent::from(KeyCode::Char('n'));
+        assert_eq!(
+            keymap.action(KeyMode::Normal, &n),
+            Some(Action::NavigateLeft)
+        );
+        assert_eq!(
+            keymap.action(KeyMode::Normal, &KeyEvent::from(KeyCode::Char('h'))),
+            None
+        );
+        assert_eq!(
+            keymap.hints(
+                KeyMode::Normal,
+                &[
+                    (&[Action::Quit], "quit"),
+                    (&[Action::Help], "help"),
+                    (&[Action::NavigateLeft, Action::NavigateRight], "navigate"),
+                ]
+            ),
+            "Ctrl+q: quit, n/l: navigate"
+        );
+
+        let errors = keys(
+            "[keys.normal]\nquit = \"h\"\nfly = \"f\"\n[keys.popup]\ncopy = \"shift+y\"\n[keys.other]\n",
+        )
+        .unwrap_err();
+        assert!(errors.contains("'h' is bound to both navigate_left and quit in [keys.normal]"));
+        assert!(errors.contains("Unknown action 'fly' in [keys.normal]"));
+        assert!(errors.contains("write 'Y' instead for copy"));
+        assert!(errors.contains("Unknown mode [keys.other]"));
+    }
+
+    #[test]
+    fn test_text_modes() {
+        let keymap = Keymap::default();
+        let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.action(KeyMode::Form, &ctrl_f),
+            Some(Action::ProposeFreeSlot)
+        );
+        let backtab = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(
+            keymap.action(KeyMode::Form, &backtab),
+            Some(Action::PreviousField)
+        );
+        // Keys without an action are typed
+        assert_eq!(
+            keymap.text_action(KeyMode::Prompt, &KeyEvent::from(KeyCode::Char('q'))),
+            (None, KeyCode::Char('q'))
+        );
+
+        let keymap = keys("[keys.command_line]\ncomplete = [\"tab\", \"ctrl+n\"]\n").unwrap();
+        let ctrl_n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.action(KeyMode::CommandLine, &ctrl_n),
+            Some(Action::Complete)
+        );
+        assert_eq!(keymap.action(KeyMode::Search, &ctrl_n), None);
+
+        let errors = keys("[keys.prompt]\nfly = \"f\"\n[keys.insert]\n").unwrap_err();
+        assert!(errors.contains("Unknown action 'fly' in [keys.prompt]"));
+        assert!(errors.contains(
+            "Unknown mode [keys.insert], use one of normal, popup, form, prompt, search, command_line"
+        ));
+    }
+}
@@ -1,7 +1,19 @@
 // Local TUI modules for integration tests
 pub mod app;
+pub mod clipboard;
+pub mod command_line;
+pub mod commands;
+pub mod config;
 pub mod daemon;
 pub mod date_utils;
+pub mod day_view;
 pub mod event_handling;
+pub mod goto;
+pub mod keybindings;
+pub mod locale;
+pub mod mouse;
 pub mod persistence;
+pub mod theme;
 pub mod ui;
+pub mod undo;
+pub mod week_view;
@@ -0,0 +1,485 @@
+//! Locale settings of the TUI: the first day of the week, month and weekday
+//! names, and the formats of dates and times.
+//!
+//! They are read from the `[locale]` table of config.toml:
+//!
+//! ```toml
+//! [locale]
+//! language = "de"
+//! week_start = "sunday"
+//! date_input = "mm/dd"
+//! date_display = "mm/dd"
+//! time_format = "12h"
+//! ```
+//!
+//! `language` picks built-in names, and `month_names`, `short_month_names`,
+//! `weekday_names` and `short_weekday_names` replace single lists of them.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use rcal_lib::{DateOrder, TimeFormat};
+
+/// Names of a built-in language: months, short months, weekdays and short
+/// weekdays, with weekdays starting on Monday.
+type Names = (
+    &'static str,
+    [&'static str; 12],
+    [&'static str; 12],
+    [&'static str; 7],
+    [&'static str; 7],
+);
+
+const LANGUAGES: [Names; 4] = [
+    (
+        "en",
+        [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+        ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+    ),
+    (
+        "de",
+        [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        [
+            "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+        ],
+        [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    ),
+    (
+        "fr",
+        [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        ["Lu", "Ma", "Me", "Je", "Ve", "Sa", "Di"],
+    ),
+    (
+        "es",
+        [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ],
+        [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        ["Lu", "Ma", "Mi", "Ju", "Vi", "Sá", "Do"],
+    ),
+];
+
+/// How dates and times are shown and typed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Locale {
+    /// The first column of the month grids and the week view.
+    pub week_start: Weekday,
+    pub month_names: [String; 12],
+    pub short_month_names: [String; 12],
+    /// Weekday names, starting on Monday.
+    pub weekday_names: [String; 7],
+    /// Two-letter weekday names of the grid headers, starting on Monday.
+    pub short_weekday_names: [String; 7],
+    /// The order of typed end dates.
+    pub date_input: DateOrder,
+    /// The order of shown dates.
+    pub date_display: DateOrder,
+    pub time_format: TimeFormat,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::language("en").expect("English is built in")
+    }
+}
+
+fn strings<const N: usize>(names: [&str; N]) -> [String; N] {
+    names.map(str::to_string)
+}
+
+impl Locale {
+    /// Returns the locale of a built-in language with the default formats:
+    /// weeks starting on Monday, `DD/MM` input, ISO dates and a 24-hour
+    /// clock.
+    pub fn language(code: &str) -> Option<Self> {
+        let (_, months, short_months, weekdays, short_weekdays) =
+            LANGUAGES.into_iter().find(|(name, ..)| *name == code)?;
+        Some(Self {
+            week_start: Weekday::Mon,
+            month_names: strings(months),
+            short_month_names: strings(short_months),
+            weekday_names: strings(weekdays),
+            short_weekday_names: strings(short_weekdays),
+            date_input: DateOrder::DayMonth,
+            date_display: DateOrder::Iso,
+            time_format: TimeFormat::TwentyFourHour,
+        })
+    }
+
+    /// Builds the locale from the `[locale]` table of the configuration.
+    /// Invalid settings are reported together.
+    pub fn from_config(value: Option<&toml::Value>) -> Result<Self, String> {
+        let Some(value) = value else {
+            return Ok(Self::default());
+        };
+        let Some(table) = value.as_table() else {
+            return Err("[locale] must be a table".to_string());
+        };
+        let mut errors = Vec::new();
+        let string = |key: &str, errors: &mut Vec<String>| match table.get(key) {
+            None => None,
+            Some(toml::Value::String(value)) => Some(value.clone()),
+            Some(_) => {
+                errors.push(format!("{key} must be a string"));
+                None
+            }
+        };
+
+        let mut locale = match string("language", &mut errors) {
+            None => Self::default(),
+            Some(code) => Self::language(&code).unwrap_or_else(|| {
+                let codes: Vec<&str> = LANGUAGES.iter().map(|(code, ..)| *code).collect();
+                errors.push(format!(
+                    "Unknown language '{code}', use one of {}",
+                    codes.join(", ")
+                ));
+                Self::default()
+            }),
+        };
+        if let Some(day) = string("week_start", &mut errors) {
+            match day.parse::<Weekday>() {
+                Ok(day) => locale.week_start = day,
+                Err(_) => errors.push(format!("Invalid week_start '{day}', use a weekday")),
+            }
+        }
+        for (key, order) in [
+            ("date_input", &mut locale.date_input),
+            ("date_display", &mut locale.date_display),
+        ] {
+            if let Some(name) = string(key, &mut errors) {
+                match DateOrder::parse(&name) {
+                    Some(parsed) => *order = parsed,
+                    None => errors.push(format!("Invalid {key} '{name}', use dd/mm, mm/dd or iso")),
+                }
+            }
+        }
+        if let Some(name) = string("time_format", &mut errors) {
+            match TimeFormat::parse(&name) {
+                Some(format) => locale.time_format = format,
+                None => errors.push(format!("Invalid time_format '{name}', use 24h or 12h")),
+            }
+        }
+        read_names(table, "month_names", &mut locale.month_names, &mut errors);
+        read_names(
+            table,
+            "short_month_names",
+            &mut locale.short_month_names,
+            &mut errors,
+        );
+        read_names(
+            table,
+            "weekday_names",
+            &mut locale.weekday_names,
+            &mut errors,
+        );
+        read_names(
+            table,
+            "short_weekday_names",
+            &mut locale.short_weekday_names,
+            &mut errors,
+        );
+
+        let known = [
+            "language",
+            "week_start",
+            "date_input",
+            "date_display",
+            "time_format",
+            "month_names",
+            "short_month_names",
+            "weekday_names",
+            "short_weekday_names",
+        ];
+        for key in table.keys().filter(|key| !known.contains(&key.as_str())) {
+            errors.push(format!("Unknown key '{key}' in [locale]"));
+        }
+
+        if errors.is_empty() {
+            Ok(locale)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    pub fn month_name(&self, month: u32) -> &str {
+        &self.month_names[month as usize - 1]
+    }
+
+    pub fn short_month_name(&self, month: u32) -> &str {
+        &self.short_month_names[month as usize - 1]
+    }
+
+    pub fn weekday_name(&self, day: Weekday) -> &str {
+        &self.weekday_names[day.num_days_from_monday() as usize]
+    }
+
+    pub fn short_weekday_name(&self, day: Weekday) -> &str {
+        &self.short_weekday_names[day.num_days_from_monday() as usize]
+    }
+
+    /// Returns the days of the week in column order.
+    pub fn weekdays(&self) -> [Weekday; 7] {
+        let mut day = self.week_start;
+        [(); 7].map(|_| {
+            let current = day;
+            day = day.succ();
+            current
+        })
+    }
+
+    /// Returns the column of a day in the month grids and the week view.
+    pub fn weekday_column(&self, day: Weekday) -> u32 {
+        day.days_since(self.week_start)
+    }
+
+    /// Returns the first day of the week containing `date`.
+    pub fn week_start_of(&self, date: NaiveDate) -> NaiveDate {
+        date - Duration::days(i64::from(self.weekday_column(date.weekday())))
+    }
+
+    /// Returns the ISO number of the week containing `date`. Weeks starting
+    /// on another day than Monday take the number of the ISO week most of
+    /// their days are in.
+    pub fn week_number(&self, date: NaiveDate) -> u32 {
+        (self.week_start_of(date) + Duration::days(3))
+            .iso_week()
+            .week()
+    }
+
+    /// Returns the month of a full or short month name, in this locale or in
+    /// English, ignoring case.
+    pub fn month_by_name(&self, name: &str) -> Option<u32> {
+        let name = name.to_lowercase();
+        let find = |locale: &Locale| {
+            (1..=12).find(|month| {
+                locale.month_name(*month).to_lowercase() == name
+                    || locale.short_month_name(*month).to_lowercase() == name
+            })
+        };
+        find(self).or_else(|| find(&Self::default()))
+    }
+
+    /// Formats a date in the display order, such as 2026-10-19.
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        self.date_display.format(date)
+    }
+
+    /// Formats a date with its weekday and month names, such as "Monday,
+    /// October 19, 2026", or "Monday, 19 October 2026" when days come first.
+    pub fn format_long_date(&self, date: NaiveDate) -> String {
+        let weekday = self.weekday_name(date.weekday());
+        let month = self.month_name(date.month());
+        match self.date_display {
+            DateOrder::DayMonth => format!("{weekday}, {} {month} {}", date.day(), date.year()),
+            _ => format!("{weekday}, {month} {}, {}", date.day(), date.year()),
+        }
+    }
+
+    /// Formats a day with its short month name, such as "Oct 19", or
+    /// "19 Oct" when days come first.
+    pub fn format_day_month(&self, date: NaiveDate) -> String {
+        let month = self.short_month_name(date.month());
+        match self.date_display {
+            DateOrder::DayMonth => format!("{} {month}", date.day()),
+            _ => format!("{month} {}", date.day()),
+        }
+    }
+
+    /// Formats a date as it is typed, such as 19/10.
+    pub fn format_date_input(&self, date: NaiveDate) -> String {
+        self.date_input.format_short(date)
+    }
+
+    pub fn format_time(&self, time: NaiveTime) -> String {
+        self.time_format.format(time)
+    }
+
+    /// Formats a full hour for time rulers, such as "09:00" or "9 AM".
+    pub fn format_hour(&self, hour: u32) -> String {
+        match self.time_format {
+            TimeFormat::TwentyFourHour => format!("{hour:02}:00"),
+            TimeFormat::TwelveHour => {
+                let time = NaiveTime::from_hms_opt(hour % 24, 0, 0).unwrap();
+                time.format("%-I %p").to_string()
+            }
+        }
+    }
+}
+
+/// Replaces `names` with the list under `key`, which must have as many
+/// names.
+fn read_names<const N: usize>(
+    table: &toml::Table,
+    key: &str,
+    names: &mut [String; N],
+    errors: &mut Vec<String>,
+) {
+    let Some(value) = table.get(key) else {
+        return;
+    };
+    let list: Option<Vec<String>> = value.as_array().and_then(|list| {
+        list.iter()
+            .map(|name| name.as_str().map(str::to_string))
+            .collect()
+    });
+    match list.map(<[String; N]>::try_from) {
+        Some(Ok(list)) => *names = list,
+        _ => errors.push(format!("{key} must be a list of {N} names")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(toml: &str) -> Result<Locale, String> {
+        let value: toml::Value = toml::from_str(toml).unwrap();
+        Locale::from_config(value.get("locale"))
+    }
+
+    #[test]
+    fn test_locale_from_config() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 21).unwrap();
+        let default = locale("").unwrap();
+        assert_eq!(default.week_start_of(date).day(), 19);
+        assert_eq!(default.format_date(date), "2026-10-21");
+        assert_eq!(
+            default.format_long_date(date),
+            "Wednesday, October 21, 2026"
+        );
+
+        let german = locale(
+            "[locale]\nlanguage = \"de\"\nweek_start = \"sunday\"\ndate_display = \"dd/mm\"\ntime_format = \"12h\"\n",
+        )
+        .unwrap();
+        assert_eq!(german.week_start_of(date).day(), 18);
+        assert_eq!(german.weekdays()[0], Weekday::Sun);
+        assert_eq!(german.weekday_column(Weekday::Sat), 6);
+        assert_eq!(german.format_long_date(date), "Mittwoch, 21 Oktober 2026");
+        assert_eq!(german.format_date(date), "21/10/2026");
+        assert_eq!(
+            german.format_time(NaiveTime::from_hms_opt(0, 30, 0).unwrap()),
+            "12:30 AM"
+        );
+
+        let custom = locale(
+            "[locale]\nshort_weekday_names = [\"M\", \"T\", \"W\", \"T\", \"F\", \"S\", \"S\"]\n",
+        )
+        .unwrap();
+        assert_eq!(custom.short_weekday_name(Weekday::Fri), "F");
+
+        let errors = locale(
+            "[locale]\nlanguage = \"xx\"\nweek_start = \"someday\"\ndate_input = \"yy\"\nmonth_names = [\"Jan\"]\nfirst_day = 1\n",
+        )
+        .unwrap_err();
+        assert!(errors.contains("Unknown language 'xx'"));
+        assert!(errors.contains("Invalid week_start 'someday'"));
+        assert!(errors.contains("Invalid date_input 'yy'"));
+        assert!(errors.contains("month_names must be a list of 12 names"));
+        assert!(errors.contains("Unknown key 'first_day' in [locale]"));
+    }
+    #[test]
+    fn test_week_numbers_and_month_names() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+        let default = Locale::default();
+        assert_eq!(default.week_number(date(10, 19)), 43);
+        assert_eq!(default.week_number(date(10, 18)), 42);
+
+        // A week from Sunday to Saturday is numbered like its Monday
+        let german = locale("[locale]\nlanguage = \"de\"\nweek_start = \"sunday\"\n").unwrap();
+        assert_eq!(german.week_number(date(10, 18)), 43);
+        assert_eq!(german.week_number(date(10, 24)), 43);
+
+        assert_eq!(german.month_by_name("März"), Some(3));
+        assert_eq!(german.month_by_name("okt"), Some(10));
+        assert_eq!(german.month_by_name("October"), Some(10));
+        assert_eq!(german.month_by_name("Brumaire"), None);
+    }
+}
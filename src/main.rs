@@ -1,6 +1,13 @@
-use std::{error::Error, fs, io, path::PathBuf, sync::mpsc, thread};
+use std::{
+    error::Error,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -8,134 +15,593 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use rcal::app::App;
+use rcal::commands::{
+    self, add::AddArgs, agenda::AgendaArgs, delete::DeleteArgs, edit::EditArgs, export::ExportArgs,
+    free::FreeArgs, import::ImportArgs, next::NextArgs, search::SearchArgs,
+};
+use rcal::config::Config;
 use rcal::daemon;
 use rcal::event_handling::run_app;
 use rcal::persistence;
-use rcal_lib::{GitSyncProvider, SyncProvider};
+use rcal_lib::storage::DynSyncProvider;
+use rcal_lib::sync::{
+    push_with_journal, replay_journal, restore_revision, EventRevision, OperationKind,
+    PendingOperation,
+};
+use rcal_lib::{
+    CalDavSyncProvider, DirectorySyncProvider, FileEventRepository, GitSyncProvider, SyncJournal,
+    SyncProvider, SyncStatus,
+};
+#[cfg(feature = "native-git")]
+use rcal_lib::{GitProgress, NativeGitSyncProvider};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Run as daemon for notifications
-    #[arg(long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Calendar directory [default: `calendar_dir` from the config file, or ~/calendar]
+    #[arg(long, global = true, value_name = "DIR", env = "RCAL_CALENDAR_DIR")]
+    calendar_dir: Option<PathBuf>,
+
+    /// Configuration file [default: ~/.config/rcal/config.toml]
+    #[arg(long, global = true, value_name = "FILE", env = "RCAL_CONFIG")]
+    config: Option<PathBuf>,
+
+    // The flags below predate the subcommands and are kept for existing
+    // scripts and service files
+    #[arg(long, hide = true)]
     daemon: bool,
 
-    /// Initialize sync with remote URL
-    #[arg(long, value_name = "URL")]
+    #[arg(long, value_name = "URL", hide = true)]
     sync_init: Option<String>,
 
-    /// Pull from remote
-    #[arg(long)]
+    #[arg(long, value_name = "PROVIDER", value_parser = ["git", "caldav", "directory"], hide = true)]
+    sync_provider: Option<String>,
+
+    #[arg(long, hide = true)]
     sync_pull: bool,
 
-    /// Push to remote
-    #[arg(long)]
+    #[arg(long, hide = true)]
     sync_push: bool,
 
-    /// Check sync status
-    #[arg(long)]
+    #[arg(long, hide = true)]
     sync_status: bool,
 }
 
-fn get_config_path() -> PathBuf {
-    let mut path = dirs::config_dir().unwrap_or_else(|| dirs::home_dir().unwrap());
-    path.push("rcal");
-    fs::create_dir_all(&path).unwrap();
-    path.push("config.toml");
-    path
+impl Args {
+    /// Returns the subcommand, translating the legacy flags.
+    fn into_command(self) -> Option<Command> {
+        if self.command.is_some() {
+            return self.command;
+        }
+        let action = if let Some(url) = self.sync_init {
+            SyncCommand::Init {
+                url,
+                provider: self.sync_provider,
+            }
+        } else if self.sync_pull {
+            SyncCommand::Pull
+        } else if self.sync_push {
+            SyncCommand::Push
+        } else if self.sync_status {
+            SyncCommand::Status
+        } else if self.daemon {
+            return Some(Command::Daemon);
+        } else {
+            return None;
+        };
+        Some(Command::Sync { action })
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Add an event without opening the calendar
+    Add(AddArgs),
+    /// List the events of a date range
+    #[command(visible_alias = "list")]
+    Agenda(AgendaArgs),
+    /// Find events by text or regular expression
+    Search(SearchArgs),
+    /// Find free time slots between events
+    Free(FreeArgs),
+    /// Print the current or next event for status bars
+    Next(NextArgs),
+    /// Change events selected by id, title and date, or a search query
+    Edit(EditArgs),
+    /// Delete events selected by id, title and date, or a search query
+    Delete(DeleteArgs),
+    /// Import events from a CSV file
+    Import(ImportArgs),
+    /// Export events of a date range as CSV
+    Export(ExportArgs),
+    /// Run as daemon for notifications
+    Daemon,
+    /// Synchronize the calendar with its remote
+    Sync {
+        #[command(subcommand)]
+        action: SyncCommand,
+    },
+    /// Show the synced versions of an event, or list deleted events
+    History {
+        /// Event title or file name; omit to list deleted events
+        event: Option<String>,
+    },
+    /// Restore an earlier version of an event or resurrect a deleted one
+    Restore {
+        /// Event title or file name
+        event: String,
+        /// Revision id from `rcal history`; defaults to the last version of a deleted event
+        revision: Option<String>,
+    },
 }
 
-fn load_config() -> toml::Value {
-    let config_path = get_config_path();
-    if config_path.exists() {
-        match fs::read_to_string(&config_path) {
-            Ok(content) => match toml::from_str(&content) {
-                Ok(config) => config,
-                Err(e) => {
-                    eprintln!("Warning: Failed to parse config.toml: {e}. Using defaults.");
-                    toml::Value::Table(Default::default())
-                }
-            },
-            Err(e) => {
-                eprintln!("Warning: Failed to read config.toml: {e}. Using defaults.");
-                toml::Value::Table(Default::default())
+#[derive(Subcommand)]
+enum SyncCommand {
+    /// Initialize sync with a remote
+    Init {
+        /// Remote URL, CalDAV server or directory to sync with
+        url: String,
+        /// Sync backend to use
+        #[arg(long, value_parser = ["git", "caldav", "directory"])]
+        provider: Option<String>,
+    },
+    /// Pull from the remote
+    Pull,
+    /// Push to the remote
+    Push,
+    /// Check the sync status
+    Status,
+    /// List operations waiting to be pushed
+    Pending,
+    /// Push now to flush the pending operations
+    Retry,
+}
+
+/// Builds the sync provider of the given kind, reading CalDAV credentials
+/// and calendar name from the `[sync]` config table. `show_progress` prints
+/// git transfer progress and must be false while the TUI is running.
+fn create_sync_provider(
+    config: &Config,
+    kind: &str,
+    url: &str,
+    show_progress: bool,
+) -> Box<dyn SyncProvider> {
+    match kind {
+        "caldav" => {
+            let mut provider = CalDavSyncProvider::new(url.to_string());
+            if let Some(username) = config.sync_value("username") {
+                let password = config.sync_value("password").unwrap_or_default();
+                provider = provider.with_credentials(&username, &password);
             }
+            if let Some(calendar) = config.sync_value("calendar") {
+                provider = provider.with_calendar_name(&calendar);
+            }
+            Box::new(provider)
         }
+        "directory" => Box::new(DirectorySyncProvider::new(PathBuf::from(url))),
+        _ => create_git_provider(config, url, show_progress),
+    }
+}
+
+/// Builds the git provider, using libgit2 unless `[sync] git_backend = "cli"`
+/// is set or rcal was built without the `native-git` feature. The libgit2
+/// backend only asks the git credential helper for HTTPS passwords with
+/// `[sync] git_credential_helper = true`.
+#[cfg(feature = "native-git")]
+fn create_git_provider(config: &Config, url: &str, show_progress: bool) -> Box<dyn SyncProvider> {
+    if config.sync_value("git_backend").as_deref() == Some("cli") {
+        return Box::new(GitSyncProvider::new(url.to_string()));
+    }
+    let provider = NativeGitSyncProvider::new(url.to_string())
+        .with_credential_helper(config.sync_flag("git_credential_helper"));
+    if show_progress {
+        Box::new(provider.with_progress(print_git_progress))
     } else {
-        toml::Value::Table(Default::default())
+        Box::new(provider)
     }
 }
 
-fn load_remote_url() -> Option<String> {
-    let config = load_config();
-    config
-        .get("sync")?
-        .get("remote")?
-        .as_str()
-        .map(|s| s.to_string())
+#[cfg(not(feature = "native-git"))]
+fn create_git_provider(_config: &Config, url: &str, _show_progress: bool) -> Box<dyn SyncProvider> {
+    Box::new(GitSyncProvider::new(url.to_string()))
 }
 
-fn should_auto_cleanup_old_events() -> bool {
-    let config = load_config();
+/// Prints transfer progress on a single terminal line.
+#[cfg(feature = "native-git")]
+fn print_git_progress(progress: GitProgress) {
+    match progress {
+        GitProgress::Fetch {
+            received_objects,
+            total_objects,
+            ..
+        } if total_objects > 0 => {
+            eprint!("\rReceiving objects: {received_objects}/{total_objects}");
+            if received_objects == total_objects {
+                eprintln!();
+            }
+        }
+        GitProgress::Push { current, total, .. } if total > 0 => {
+            eprint!("\rWriting objects: {current}/{total}");
+            if current == total {
+                eprintln!();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Builds the sync provider of the configured remote, if any.
+fn configured_sync_provider(config: &Config) -> Option<Box<dyn SyncProvider>> {
     config
-        .get("auto_cleanup_old_events")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(true)
+        .remote_url()
+        .map(|url| create_sync_provider(config, &config.sync_provider_kind(), &url, true))
+}
+
+/// Warns when a command's push failed and was queued in the sync journal.
+fn warn_if_push_queued(provider: &Option<Box<dyn SyncProvider>>, calendar_dir: &Path) {
+    if provider.is_some() && SyncJournal::pending_count(calendar_dir) > 0 {
+        eprintln!("Sync push failed, queued for retry (see `rcal sync pending`)");
+    }
+}
+
+fn run_add_command(args: &AddArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let calendar_dir = config.calendar_dir();
+    let provider = configured_sync_provider(config);
+    let event = commands::add::run(
+        args,
+        calendar_dir,
+        provider.as_ref().map(|p| p as &DynSyncProvider),
+    )?;
+    println!(
+        "Added '{}' on {} ({})",
+        event.title,
+        event.start_date,
+        commands::agenda::time_label(&event)
+    );
+    warn_if_push_queued(&provider, calendar_dir);
+    Ok(())
 }
 
-fn save_remote_url(url: &str) -> Result<(), Box<dyn Error>> {
-    let mut config = load_config();
-    if let Some(table) = config.as_table_mut() {
-        table
-            .entry("sync")
-            .or_insert_with(|| toml::Value::Table(Default::default()));
-        if let Some(sync_table) = table.get_mut("sync").and_then(|v| v.as_table_mut()) {
-            sync_table.insert("remote".to_string(), toml::Value::String(url.to_string()));
+fn run_import_command(args: &ImportArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let calendar_dir = config.calendar_dir();
+    let provider = if args.dry_run {
+        None
+    } else {
+        configured_sync_provider(config)
+    };
+    commands::import::run(
+        args,
+        calendar_dir,
+        provider.as_ref().map(|p| p as &DynSyncProvider),
+        &mut io::stdout(),
+    )?;
+    warn_if_push_queued(&provider, calendar_dir);
+    Ok(())
+}
+
+fn run_export_command(args: &ExportArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let today = chrono::Local::now().date_naive();
+    let csv = commands::export::run(args, config.calendar_dir(), today)?;
+    match &args.output {
+        Some(path) => {
+            fs::write(path, csv).map_err(|e| format!("Cannot write {}: {e}", path.display()))?
         }
+        None => print!("{csv}"),
     }
-    let config_str = toml::to_string(&config)?;
-    let config_path = get_config_path();
-    fs::write(config_path, config_str)?;
     Ok(())
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
-
-    // Handle sync commands
-    if let Some(url) = args.sync_init {
-        let provider = GitSyncProvider::new(url.clone());
-        let home = dirs::home_dir().expect("Could not find home directory");
-        let calendar_dir = home.join("calendar");
-        provider.init(&calendar_dir)?;
-        save_remote_url(&url)?;
-        println!("Sync initialized with remote: {url}");
-        return Ok(());
+/// Runs `rcal edit` or `rcal delete`, asking which event to use on the
+/// terminal when several match.
+fn run_change_command(command: Command, config: &Config) -> Result<(), Box<dyn Error>> {
+    let calendar_dir = config.calendar_dir();
+    let provider = configured_sync_provider(config);
+    let sync_provider = provider.as_ref().map(|p| p as &DynSyncProvider);
+    let today = chrono::Local::now().date_naive();
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    let mut input = stdin.lock();
+    let input = interactive.then_some(&mut input as &mut dyn io::BufRead);
+    let mut output = io::stdout();
+    match command {
+        Command::Edit(args) => {
+            commands::edit::run(
+                &args,
+                calendar_dir,
+                sync_provider,
+                today,
+                input,
+                &mut output,
+            )?;
+        }
+        Command::Delete(args) => {
+            commands::delete::run(
+                &args,
+                calendar_dir,
+                sync_provider,
+                today,
+                input,
+                &mut output,
+            )?;
+        }
+        _ => unreachable!("not a change command"),
     }
+    warn_if_push_queued(&provider, calendar_dir);
+    Ok(())
+}
 
-    if args.sync_pull || args.sync_push || args.sync_status {
-        if let Some(url) = load_remote_url() {
-            let provider = GitSyncProvider::new(url);
-            let home = dirs::home_dir().expect("Could not find home directory");
-            let calendar_dir = home.join("calendar");
-            if args.sync_pull {
-                provider.pull(&calendar_dir)?;
-                println!("Pulled from remote");
-            } else if args.sync_push {
-                provider.push(&calendar_dir)?;
-                println!("Pushed to remote");
-            } else if args.sync_status {
-                let status = provider.status(&calendar_dir)?;
-                println!("Status: {status:?}");
+fn run_sync_command(action: SyncCommand, mut config: Config) -> Result<(), Box<dyn Error>> {
+    let calendar_dir = config.paths.calendar_dir.clone();
+    let calendar_dir = calendar_dir.as_path();
+    match action {
+        SyncCommand::Init { url, provider } => {
+            let kind = provider.unwrap_or_else(|| config.sync_provider_kind());
+            let provider = create_sync_provider(&config, &kind, &url, true);
+            provider.init(calendar_dir)?;
+            config.save_sync(&url, &kind)?;
+            println!("Sync initialized with remote: {url}");
+        }
+        SyncCommand::Pull | SyncCommand::Push | SyncCommand::Status => {
+            let Some(provider) = configured_sync_provider(&config) else {
+                return Err("No sync remote configured. Use `rcal sync init <url>` first.".into());
+            };
+            match action {
+                SyncCommand::Pull => {
+                    provider.pull(calendar_dir)?;
+                    println!("Pulled from remote");
+                }
+                SyncCommand::Push => {
+                    let pending = SyncJournal::load(calendar_dir)?;
+                    match provider.push(calendar_dir)? {
+                        SyncStatus::Conflicts => {
+                            println!("Push stopped on conflicts; pending operations are kept")
+                        }
+                        SyncStatus::Error(e) => return Err(e.into()),
+                        _ => {
+                            SyncJournal::remove_flushed(calendar_dir, &pending)?;
+                            println!("Pushed to remote");
+                        }
+                    }
+                }
+                _ => {
+                    let status = provider.status(calendar_dir)?;
+                    println!("Status: {status:?}");
+                }
             }
-        } else {
-            eprintln!("No sync remote configured. Use --sync-init <url> first.");
         }
+        SyncCommand::Pending => {
+            let journal = SyncJournal::load(calendar_dir)?;
+            if journal.is_empty() {
+                println!("No pending sync operations");
+                return Ok(());
+            }
+            println!("{} pending sync operation(s):", journal.len());
+            for operation in &journal.operations {
+                let date = operation
+                    .date
+                    .map(|d| format!(" ({})", d.format("%Y-%m-%d")))
+                    .unwrap_or_default();
+                println!(
+                    "  {}  {:<7} {}{}  attempts: {}",
+                    operation.queued_at.format("%Y-%m-%d %H:%M"),
+                    operation.kind,
+                    operation.title,
+                    date,
+                    operation.attempts
+                );
+                if let Some(error) = &operation.last_error {
+                    println!("      last error: {error}");
+                }
+            }
+        }
+        SyncCommand::Retry => {
+            let Some(provider) = configured_sync_provider(&config) else {
+                return Err("No sync remote configured. Use `rcal sync init <url>` first.".into());
+            };
+            match replay_journal(provider.as_ref(), calendar_dir)? {
+                0 => println!("No pending sync operations"),
+                flushed => println!("Pushed {flushed} pending sync operation(s)"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds the provider used to query event history. History is read from
+/// the local repository, so no remote needs to be configured.
+fn create_history_provider(config: &Config) -> Box<dyn SyncProvider> {
+    let url = config.remote_url().unwrap_or_default();
+    create_sync_provider(config, &config.sync_provider_kind(), &url, true)
+}
+
+/// Finds the file of the event matching a title or file name, looking at
+/// current and deleted events.
+fn find_event_file(
+    provider: &dyn SyncProvider,
+    calendar_dir: &Path,
+    query: &str,
+) -> Result<String, Box<dyn Error>> {
+    let matches_query = |file_name: &str, title: Option<&str>| {
+        file_name == query
+            || file_name.strip_suffix(".md") == Some(query)
+            || title.is_some_and(|title| title.eq_ignore_ascii_case(query))
+    };
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(calendar_dir)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !file_name.ends_with(".md") {
+            continue;
+        }
+        let event = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| FileEventRepository::event_from_markdown(&content));
+        if matches_query(file_name, event.as_ref().map(|e| e.title.as_str())) {
+            files.push(file_name.to_string());
+        }
+    }
+    for revision in provider.deleted_events(calendar_dir)? {
+        let title = revision.event.as_ref().map(|e| e.title.as_str());
+        if matches_query(&revision.file_name, title) {
+            files.push(revision.file_name);
+        }
+    }
+
+    files.sort();
+    match files.len() {
+        0 => Err(format!("No event matching '{query}'").into()),
+        1 => Ok(files.remove(0)),
+        _ => Err(format!(
+            "Several events match '{query}', pass one of the file names: {}",
+            files.join(", ")
+        )
+        .into()),
+    }
+}
+
+fn print_revision(revision: &EventRevision) {
+    let deleted = if revision.is_deletion() {
+        "  (deleted)"
+    } else {
+        ""
+    };
+    println!(
+        "{}  {}  {}  {}{deleted}",
+        revision.short_id(),
+        revision.date.format("%Y-%m-%d %H:%M"),
+        revision.author,
+        revision.summary
+    );
+}
+
+fn run_history_command(event: Option<String>, config: &Config) -> Result<(), Box<dyn Error>> {
+    let calendar_dir = config.calendar_dir();
+    let provider = create_history_provider(config);
+    let Some(query) = event else {
+        let deleted = provider.deleted_events(calendar_dir)?;
+        if deleted.is_empty() {
+            println!("No deleted events");
+            return Ok(());
+        }
+        println!("{} deleted event(s):", deleted.len());
+        for revision in &deleted {
+            let title = revision.event.as_ref().map_or("", |e| e.title.as_str());
+            println!(
+                "  {}  {}  deleted {} by {} ({})",
+                revision.file_name,
+                title,
+                revision.date.format("%Y-%m-%d %H:%M"),
+                revision.author,
+                revision.short_id()
+            );
+        }
+        return Ok(());
+    };
+
+    let file_name = find_event_file(provider.as_ref(), calendar_dir, &query)?;
+    let history = provider.event_history(calendar_dir, &file_name)?;
+    if history.is_empty() {
+        println!("No synced versions of {file_name} yet");
         return Ok(());
     }
+    println!("History of {file_name}:");
+    for revision in &history {
+        print_revision(revision);
+        for change in &revision.changes {
+            println!(
+                "    {:<12} {} -> {}",
+                change.field.label(),
+                change.old.as_deref().unwrap_or("(none)"),
+                change.new.as_deref().unwrap_or("(deleted)")
+            );
+        }
+    }
+    Ok(())
+}
+
+fn run_restore_command(
+    event: String,
+    revision: Option<String>,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let calendar_dir = config.calendar_dir();
+    let provider = create_history_provider(config);
+    let file_name = find_event_file(provider.as_ref(), calendar_dir, &event)?;
+    let history = provider.event_history(calendar_dir, &file_name)?;
+    let revision = match &revision {
+        Some(id) => history
+            .iter()
+            .find(|r| r.matches(id))
+            .ok_or_else(|| format!("No revision '{id}' of {file_name}"))?,
+        None if calendar_dir.join(&file_name).exists() => {
+            return Err(format!(
+                "{file_name} still exists, pass a revision from `rcal history {event}`"
+            )
+            .into());
+        }
+        None => history
+            .iter()
+            .find(|r| !r.is_deletion())
+            .ok_or_else(|| format!("No earlier version of {file_name}"))?,
+    };
+
+    restore_revision(calendar_dir, revision)?;
+    println!("Restored {file_name} from {}", revision.short_id());
+
+    if let (Some(restored), Some(_)) = (&revision.event, config.remote_url()) {
+        let operation = PendingOperation::for_event(OperationKind::Save, restored);
+        if let Err(e) = push_with_journal(provider.as_ref(), calendar_dir, operation) {
+            eprintln!("Sync push failed, queued for retry: {e}");
+        }
+    }
+    Ok(())
+}
 
-    if args.daemon {
-        daemon::run_daemon()?;
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = Args::parse();
+    let config = Config::load(args.config.take(), args.calendar_dir.take());
+
+    if let Some(command) = args.into_command() {
+        let calendar_dir = config.calendar_dir().to_path_buf();
+        let result = match command {
+            Command::Add(add_args) => run_add_command(&add_args, &config),
+            Command::Agenda(agenda_args) => {
+                let today = chrono::Local::now().date_naive();
+                commands::agenda::run(&agenda_args, &calendar_dir, today)
+                    .map(|output| print!("{output}"))
+            }
+            Command::Search(search_args) => {
+                let today = chrono::Local::now().date_naive();
+                commands::search::run(&search_args, &calendar_dir, today)
+                    .map(|output| print!("{output}"))
+            }
+            Command::Free(free_args) => {
+                let now = chrono::Local::now().naive_local();
+                commands::free::run(&free_args, &calendar_dir, now).map(|output| print!("{output}"))
+            }
+            Command::Next(next_args) => {
+                let now = chrono::Local::now().naive_local();
+                commands::next::run(&next_args, &calendar_dir, now).map(|output| print!("{output}"))
+            }
+            Command::Edit(_) | Command::Delete(_) => run_change_command(command, &config),
+            Command::Import(import_args) => run_import_command(&import_args, &config),
+            Command::Export(export_args) => run_export_command(&export_args, &config),
+            Command::Daemon => daemon::run_daemon(&config.paths),
+            Command::Sync { action } => run_sync_command(action, config),
+            Command::History { event } => run_history_command(event, &config),
+            Command::Restore { event, revision } => run_restore_command(event, revision, &config),
+        };
+        // Print the message itself rather than its Debug form
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
         return Ok(());
     }
 
@@ -147,15 +613,16 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut terminal = Terminal::new(backend)?;
 
     // create app and run it
-    let mut app = App::new();
+    let mut app = App::new_with_calendar_dir(config.calendar_dir().to_path_buf());
     let events = persistence::load_events_from_path(&app.calendar_dir).unwrap_or_else(|e| {
         eprintln!("Failed to load events: {e}");
         Vec::new()
     });
     app.set_events(events);
+    config.configure_app(&mut app);
 
     // Auto cleanup old events if enabled
-    if should_auto_cleanup_old_events() {
+    if config.auto_cleanup_old_events() {
         match persistence::cleanup_old_events(&app.calendar_dir, app.sync_provider.as_deref()) {
             Ok(_) => {
                 // Reload events to reflect deletions
@@ -171,15 +638,21 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
     let (tx, rx) = mpsc::channel::<Result<(), String>>();
     app.reload_receiver = Some(rx);
-    if let Some(url) = load_remote_url() {
-        app.sync_provider = Some(Box::new(GitSyncProvider::new(url.clone())));
+    if let Some(url) = config.remote_url() {
+        let kind = config.sync_provider_kind();
+        app.sync_provider = Some(create_sync_provider(&config, &kind, &url, false));
         // Spawn async pull on launch
-        let provider = GitSyncProvider::new(url);
+        let provider = create_sync_provider(&config, &kind, &url, false);
         let calendar_dir = app.calendar_dir.clone();
         let tx_clone = tx.clone();
         thread::spawn(move || {
             match provider.pull(&calendar_dir) {
                 Ok(_) => {
+                    // Connection works: flush operations queued while offline
+                    if let Err(e) = replay_journal(provider.as_ref(), &calendar_dir) {
+                        let _ = tx_clone.send(Err(e.to_string()));
+                        return;
+                    }
                     // Send reload signal
                     let _ = tx_clone.send(Ok(()));
                 }
@@ -0,0 +1,106 @@
+//! Mouse support.
+//!
+//! `ui()` records the areas it draws that react to clicks in a `HitMap`,
+//! which the event handler looks mouse positions up in. The map is rebuilt
+//! on every draw, so it always matches what is on the screen.
+
+use chrono::NaiveDate;
+use crossterm::event::KeyCode;
+use ratatui::layout::{Position, Rect};
+
+use crate::keybindings::KeyChord;
+
+/// What a click on an area does.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HitTarget {
+    /// A day of a month grid or a week view column.
+    Date(NaiveDate),
+    /// A row of the view popup's event list.
+    PopupEvent(usize),
+    /// A row of the search results.
+    SearchResult(usize),
+    /// A hint or button, clicked like pressing its key.
+    Key(KeyCode),
+    /// The background of a popup, covering what is drawn below it.
+    Popup,
+}
+
+/// The clickable areas of the last drawn frame, in drawing order.
+#[derive(Debug, Default)]
+pub struct HitMap {
+    areas: Vec<(Rect, HitTarget)>,
+}
+
+impl HitMap {
+    pub fn clear(&mut self) {
+        self.areas.clear();
+    }
+
+    pub fn add(&mut self, area: Rect, target: HitTarget) {
+        self.areas.push((area, target));
+    }
+
+    /// Returns the target at a screen position; areas drawn later are on top.
+    pub fn target_at(&self, column: u16, row: u16) -> Option<&HitTarget> {
+        self.areas
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|(_, target)| target)
+    }
+
+    /// Adds each "key: label" item of a comma-separated hints line drawn at
+    /// the start of `area` as a button for its key. Items listing several
+    /// keys or a key with modifiers are left out.
+    pub fn add_hints(&mut self, area: Rect, hints: &str) {
+        let mut x = area.x;
+        for item in hints.split(", ") {
+            let width = item.chars().count() as u16;
+            let key = item.split_once(": ").and_then(|(key, _)| hint_key(key));
+            if let Some(key) = key {
+                let visible = width.min(area.right().saturating_sub(x));
+                if visible > 0 {
+                    self.add(Rect::new(x, area.y, visible, 1), HitTarget::Key(key));
+                }
+            }
+            x = x.saturating_add(width + 2);
+        }
+    }
+}
+
+fn hint_key(key: &str) -> Option<KeyCode> {
+    KeyChord::parse(key)
+        .ok()
+        .filter(|chord| chord.modifiers.is_empty())
+        .map(|chord| chord.code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint_buttons_and_stacking() {
+        let mut map = HitMap::default();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        map.add(Rect::new(0, 0, 10, 5), HitTarget::Date(date));
+        map.add_hints(Rect::new(0, 4, 30, 1), "q: quit, h/l: day, Enter: open");
+
+        assert_eq!(map.target_at(2, 1), Some(&HitTarget::Date(date)));
+        assert_eq!(
+            map.target_at(3, 4),
+            Some(&HitTarget::Key(KeyCode::Char('q')))
+        );
+        // "h/l: day" isn't a button, so the date below is hit
+        assert_eq!(map.target_at(9, 4), Some(&HitTarget::Date(date)));
+        assert_eq!(map.target_at(20, 4), Some(&HitTarget::Key(KeyCode::Enter)));
+        // Cut off at the end of the area
+        assert_eq!(map.target_at(29, 4), Some(&HitTarget::Key(KeyCode::Enter)));
+        assert_eq!(map.target_at(30, 4), None);
+
+        map.add(Rect::new(0, 0, 5, 5), HitTarget::Popup);
+        assert_eq!(map.target_at(2, 1), Some(&HitTarget::Popup));
+        map.clear();
+        assert_eq!(map.target_at(2, 1), None);
+    }
+}
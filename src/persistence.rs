@@ -1,14 +1,14 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use chrono::{Local, Months, NaiveDate, NaiveTime};
-use dirs;
 use rcal_lib::storage::file_storage::FileEventRepository;
+use rcal_lib::storage::{CalendarPathProvider, DefaultPathProvider};
 use rcal_lib::{
     sanitize_title_for_filename, validate_event, validate_filename, CalendarEvent, Recurrence,
 };
 use uuid::Uuid;
 
-use rcal_lib::sync::SyncProvider;
+use rcal_lib::sync::{push_with_journal, OperationKind, PendingOperation, SyncProvider};
 
 pub fn is_finished_before(event: &CalendarEvent, cutoff: NaiveDate) -> bool {
     // Don't auto-delete recurring events to preserve ongoing schedules
@@ -60,8 +60,9 @@ pub fn cleanup_old_events_with_cutoff(
     // Batch sync once after all deletions
     if deleted_count > 0 {
         if let Some(provider) = sync_provider {
-            if let Err(e) = provider.push(calendar_dir) {
-                eprintln!("Sync push failed after cleanup: {e}");
+            let operation = PendingOperation::cleanup(deleted_count);
+            if let Err(e) = push_with_journal(provider, calendar_dir, operation) {
+                eprintln!("Sync push failed after cleanup, queued for retry: {e}");
             }
         }
     }
@@ -69,9 +70,16 @@ pub fn cleanup_old_events_with_cutoff(
     Ok(deleted_count)
 }
 
+/// Returns the calendar directory used when none is given: the
+/// `RCAL_CALENDAR_DIR` environment variable, or ~/calendar.
+fn default_calendar_dir() -> Result<PathBuf, std::io::Error> {
+    DefaultPathProvider::new()
+        .map(|provider| provider.calendar_dir())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()))
+}
+
 pub fn load_events() -> Result<Vec<CalendarEvent>, Box<dyn std::error::Error>> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
-    load_events_from_path(&home.join("calendar"))
+    load_events_from_path(&default_calendar_dir()?)
 }
 
 pub fn load_events_from_path(
@@ -323,22 +331,16 @@ pub fn generate_recurring_instances(
 }
 
 pub fn save_event(event: &mut CalendarEvent) -> Result<(), std::io::Error> {
-    let home = dirs::home_dir().ok_or(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "Could not find home directory",
-    ))?;
-    save_event_to_path(event, &home.join("calendar"), None)
+    let calendar_dir = default_calendar_dir()?;
+    save_event_to_path(event, &calendar_dir, None)
 }
 
 pub fn save_event_with_sync(
     event: &mut CalendarEvent,
     sync_provider: Option<&dyn SyncProvider>,
 ) -> Result<(), std::io::Error> {
-    let home = dirs::home_dir().ok_or(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "Could not find home directory",
-    ))?;
-    save_event_to_path(event, &home.join("calendar"), sync_provider)
+    let calendar_dir = default_calendar_dir()?;
+    save_event_to_path(event, &calendar_dir, sync_provider)
 }
 
 pub fn save_event_to_path_without_sync(
@@ -387,32 +389,27 @@ pub fn save_event_to_path(
 ) -> Result<(), std::io::Error> {
     save_event_to_path_without_sync(event, calendar_dir)?;
 
-    // Sync after save
+    // Sync after save, queueing the change if the push fails
     if let Some(provider) = sync_provider {
-        if let Err(e) = provider.push(calendar_dir) {
-            eprintln!("Sync push failed: {e}");
+        let operation = PendingOperation::for_event(OperationKind::Save, event);
+        if let Err(e) = push_with_journal(provider, calendar_dir, operation) {
+            eprintln!("Sync push failed, queued for retry: {e}");
         }
     }
     Ok(())
 }
 
 pub fn delete_event(event: &CalendarEvent) -> Result<(), std::io::Error> {
-    let home = dirs::home_dir().ok_or(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "Could not find home directory",
-    ))?;
-    delete_event_from_path(event, &home.join("calendar"), None)
+    let calendar_dir = default_calendar_dir()?;
+    delete_event_from_path(event, &calendar_dir, None)
 }
 
 pub fn delete_event_with_sync(
     event: &CalendarEvent,
     sync_provider: Option<&dyn SyncProvider>,
 ) -> Result<(), std::io::Error> {
-    let home = dirs::home_dir().ok_or(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "Could not find home directory",
-    ))?;
-    delete_event_from_path(event, &home.join("calendar"), sync_provider)
+    let calendar_dir = default_calendar_dir()?;
+    delete_event_from_path(event, &calendar_dir, sync_provider)
 }
 
 pub fn delete_event_from_path_without_sync(
@@ -431,10 +428,11 @@ pub fn delete_event_from_path(
 ) -> Result<(), std::io::Error> {
     delete_event_from_path_without_sync(event, calendar_dir)?;
 
-    // Sync after delete
+    // Sync after delete, queueing the change if the push fails
     if let Some(provider) = sync_provider {
-        if let Err(e) = provider.push(calendar_dir) {
-            eprintln!("Sync push failed: {e}");
+        let operation = PendingOperation::for_event(OperationKind::Delete, event);
+        if let Err(e) = push_with_journal(provider, calendar_dir, operation) {
+            eprintln!("Sync push failed, queued for retry: {e}");
         }
     }
     Ok(())
@@ -0,0 +1,406 @@
+//! Colour themes of the TUI.
+//!
+//! Every styled element of the interface takes its style from a `Theme`.
+//! The `[theme]` table of config.toml picks one of the named themes and can
+//! override the style of each element:
+//!
+//! ```toml
+//! [theme]
+//! name = "light"
+//! selected = { fg = "white", bg = "blue", modifiers = ["bold"] }
+//! today = { bg = "#d0d0d0" }
+//! ```
+//!
+//! Without a theme name, the monochrome theme is used when the `NO_COLOR`
+//! environment variable is set, and the dark theme otherwise.
+
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style};
+
+/// The named themes.
+pub const THEME_NAMES: [&str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+/// The style of each element of the interface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Plain text, such as the calendar days and typed input.
+    pub text: Style,
+    /// Key hints, time rulers and other secondary text.
+    pub hint: Style,
+    /// Month titles, view headings and status messages.
+    pub title: Style,
+    /// Input labels, highlighted suggestions and notices.
+    pub label: Style,
+    /// Popup borders and titles.
+    pub popup: Style,
+    /// The selected day, event or list row.
+    pub selected: Style,
+    /// Today's date.
+    pub today: Style,
+    /// Days with events in the month grids.
+    pub event_day: Style,
+    pub saturday: Style,
+    pub sunday: Style,
+    pub week_number: Style,
+    /// Timed events in the week view.
+    pub event: Style,
+    /// All-day and multi-day events in the week view.
+    pub all_day: Style,
+    /// Error messages and removed values.
+    pub error: Style,
+    /// The delete confirmation and the conflict resolver.
+    pub warning: Style,
+    /// Added values and the chosen side of a conflict.
+    pub added: Style,
+    /// The current time marker of the day view.
+    pub now: Style,
+    /// Free time in the day view.
+    pub free: Style,
+    /// The row being edited in the conflict resolver.
+    pub highlight: Style,
+    /// Days with 1, 2, 3 and 4 or more events in the twelve-month heat map.
+    pub heat: [Style; 4],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+fn fg(color: Color) -> Style {
+    Style::default().fg(color)
+}
+
+fn on(foreground: Color, background: Color) -> Style {
+    Style::default().fg(foreground).bg(background)
+}
+
+fn bg(color: Color) -> Style {
+    Style::default().bg(color)
+}
+
+fn with(modifier: Modifier) -> Style {
+    Style::default().add_modifier(modifier)
+}
+
+impl Theme {
+    /// Light text on a dark terminal background.
+    pub fn dark() -> Self {
+        Self {
+            text: fg(Color::White),
+            hint: fg(Color::Gray),
+            title: fg(Color::Cyan),
+            label: fg(Color::Yellow),
+            popup: fg(Color::LightCyan),
+            selected: on(Color::Black, Color::LightBlue),
+            today: bg(Color::DarkGray),
+            event_day: Style::default(),
+            saturday: fg(Color::LightYellow),
+            sunday: fg(Color::Red),
+            week_number: fg(Color::Magenta),
+            event: on(Color::Black, Color::Cyan),
+            all_day: on(Color::Black, Color::Magenta),
+            error: fg(Color::Red),
+            warning: fg(Color::LightRed),
+            added: fg(Color::LightGreen),
+            now: fg(Color::Red),
+            free: fg(Color::Green),
+            highlight: bg(Color::DarkGray),
+            heat: [22, 28, 34, 40].map(|index| bg(Color::Indexed(index))),
+        }
+    }
+
+    /// Dark text on a light terminal background.
+    pub fn light() -> Self {
+        Self {
+            text: fg(Color::Black),
+            hint: fg(Color::DarkGray),
+            title: fg(Color::Blue),
+            label: fg(Color::Magenta),
+            popup: fg(Color::Blue),
+            selected: on(Color::White, Color::Blue),
+            today: bg(Color::Gray),
+            event_day: Style::default(),
+            saturday: fg(Color::Magenta),
+            sunday: fg(Color::Red),
+            week_number: fg(Color::DarkGray),
+            event: on(Color::Black, Color::LightCyan),
+            all_day: on(Color::Black, Color::LightMagenta),
+            error: fg(Color::Red),
+            warning: fg(Color::Red),
+            added: fg(Color::Green),
+            now: fg(Color::Red),
+            free: fg(Color::Green),
+            highlight: bg(Color::Gray),
+            heat: [194, 157, 120, 83].map(|index| on(Color::Black, Color::Indexed(index))),
+        }
+    }
+
+    /// Bright colours and bold text for the most contrast.
+    pub fn high_contrast() -> Self {
+        let bold = Modifier::BOLD;
+        Self {
+            text: fg(Color::White),
+            hint: fg(Color::White),
+            title: fg(Color::LightCyan).add_modifier(bold),
+            label: fg(Color::LightYellow).add_modifier(bold),
+            popup: fg(Color::White).add_modifier(bold),
+            selected: on(Color::Black, Color::LightYellow).add_modifier(bold),
+            today: with(Modifier::BOLD | Modifier::UNDERLINED),
+            event_day: with(bold),
+            saturday: fg(Color::LightCyan),
+            sunday: fg(Color::LightRed),
+            week_number: fg(Color::LightMagenta),
+            event: on(Color::Black, Color::White),
+            all_day: on(Color::Black, Color::LightMagenta),
+            error: fg(Color::LightRed).add_modifier(bold),
+            warning: fg(Color::LightRed).add_modifier(bold),
+            added: fg(Color::LightGreen).add_modifier(bold),
+            now: fg(Color::LightRed).add_modifier(bold),
+            free: fg(Color::LightGreen),
+            highlight: bg(Color::Blue),
+            heat: [28, 34, 40, 46].map(|index| on(Color::Black, Color::Indexed(index))),
+        }
+    }
+
+    /// No colours at all, only bold, underlined and reversed text.
+    pub fn monochrome() -> Self {
+        Self {
+            text: Style::default(),
+            hint: with(Modifier::DIM),
+            title: with(Modifier::BOLD),
+            label: with(Modifier::BOLD),
+            popup: Style::default(),
+            selected: with(Modifier::REVERSED),
+            today: with(Modifier::BOLD | Modifier::UNDERLINED),
+            event_day: Style::default(),
+            saturday: Style::default(),
+            sunday: Style::default(),
+            week_number: with(Modifier::DIM),
+            event: with(Modifier::REVERSED),
+            all_day: with(Modifier::REVERSED),
+            error: with(Modifier::BOLD),
+            warning: with(Modifier::BOLD),
+            added: with(Modifier::BOLD),
+            now: with(Modifier::BOLD),
+            free: with(Modifier::ITALIC),
+            highlight: with(Modifier::BOLD),
+            heat: [
+                with(Modifier::ITALIC),
+                with(Modifier::ITALIC),
+                with(Modifier::BOLD),
+                with(Modifier::BOLD | Modifier::ITALIC),
+            ],
+        }
+    }
+
+    /// Returns a named theme.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Returns the background of a day with `count` events in the heat map.
+    pub fn heat(&self, count: usize) -> Option<Style> {
+        match count {
+            0 => None,
+            count => Some(self.heat[count.min(4) - 1]),
+        }
+    }
+
+    fn element_mut(&mut self, name: &str) -> Option<&mut Style> {
+        Some(match name {
+            "text" => &mut self.text,
+            "hint" => &mut self.hint,
+            "title" => &mut self.title,
+            "label" => &mut self.label,
+            "popup" => &mut self.popup,
+            "selected" => &mut self.selected,
+            "today" => &mut self.today,
+            "event_day" => &mut self.event_day,
+            "saturday" => &mut self.saturday,
+            "sunday" => &mut self.sunday,
+            "week_number" => &mut self.week_number,
+            "event" => &mut self.event,
+            "all_day" => &mut self.all_day,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "added" => &mut self.added,
+            "now" => &mut self.now,
+            "free" => &mut self.free,
+            "highlight" => &mut self.highlight,
+            "heat_1" => &mut self.heat[0],
+            "heat_2" => &mut self.heat[1],
+            "heat_3" => &mut self.heat[2],
+            "heat_4" => &mut self.heat[3],
+            _ => return None,
+        })
+    }
+
+    /// Builds the theme from the `theme` value of the configuration: a
+    /// theme name, or a table with a `name` and styles of single elements.
+    /// Without a name, `no_color` selects the monochrome theme instead of
+    /// the dark one. Invalid names, elements and styles are reported
+    /// together.
+    pub fn from_config(value: Option<&toml::Value>, no_color: bool) -> Result<Self, String> {
+        let default = || {
+            if no_color {
+                Self::monochrome()
+            } else {
+                Self::dark()
+            }
+        };
+        let named = |name: &str| {
+            Self::named(name).ok_or_else(|| {
+                format!(
+                    "Unknown theme '{name}', use one of {}",
+                    THEME_NAMES.join(", ")
+                )
+            })
+        };
+        let table = match value {
+            None => return Ok(default()),
+            Some(toml::Value::String(name)) => return named(name),
+            Some(toml::Value::Table(table)) => table,
+            Some(_) => return Err("theme must be a name or a table".to_string()),
+        };
+
+        let mut errors = Vec::new();
+        let mut theme = match table.get("name") {
+            None => default(),
+            Some(toml::Value::String(name)) => named(name).unwrap_or_else(|e| {
+                errors.push(e);
+                default()
+            }),
+            Some(_) => {
+                errors.push("The theme name must be a string".to_string());
+                default()
+            }
+        };
+        for (element, value) in table.iter().filter(|(key, _)| *key != "name") {
+            let Some(style) = theme.element_mut(element) else {
+                errors.push(format!("Unknown theme element '{element}'"));
+                continue;
+            };
+            match parse_style(value) {
+                Ok(parsed) => *style = parsed,
+                Err(e) => errors.push(format!("{e} in theme element '{element}'")),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(theme)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+/// Parses the style of an element: a foreground colour, or a table with
+/// `fg`, `bg` and `modifiers`.
+fn parse_style(value: &toml::Value) -> Result<Style, String> {
+    let color = |value: &toml::Value| {
+        let name = value.as_str().ok_or("Colours must be strings")?;
+        Color::from_str(name).map_err(|_| format!("Invalid colour '{name}'"))
+    };
+    let table = match value {
+        toml::Value::String(_) => return Ok(Style::default().fg(color(value)?)),
+        toml::Value::Table(table) => table,
+        _ => return Err("Styles must be a colour or a table".to_string()),
+    };
+
+    let mut style = Style::default();
+    for (key, value) in table {
+        match key.as_str() {
+            "fg" => style = style.fg(color(value)?),
+            "bg" => style = style.bg(color(value)?),
+            "modifiers" => {
+                let names: Vec<&toml::Value> = match value {
+                    toml::Value::Array(names) => names.iter().collect(),
+                    name => vec![name],
+                };
+                for name in names {
+                    let name = name.as_str().ok_or("Modifiers must be strings")?;
+                    style = style.add_modifier(parse_modifier(name)?);
+                }
+            }
+            _ => return Err(format!("Unknown key '{key}', use fg, bg or modifiers")),
+        }
+    }
+    Ok(style)
+}
+
+fn parse_modifier(name: &str) -> Result<Modifier, String> {
+    Ok(match name.to_lowercase().as_str() {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" | "underline" => Modifier::UNDERLINED,
+        "reversed" | "reverse" => Modifier::REVERSED,
+        "crossed_out" | "strikethrough" => Modifier::CROSSED_OUT,
+        _ => return Err(format!("Invalid modifier '{name}'")),
+    })
+}
+
+/// Returns true if the `NO_COLOR` environment variable asks for no colours.
+pub fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme(toml: &str, no_color: bool) -> Result<Theme, String> {
+        let value: toml::Value = toml::from_str(toml).unwrap();
+        Theme::from_config(value.get("theme"), no_color)
+    }
+
+    #[test]
+    fn test_theme_from_config() {
+        assert_eq!(theme("", false), Ok(Theme::dark()));
+        assert_eq!(theme("", true), Ok(Theme::monochrome()));
+        assert_eq!(theme("theme = \"light\"", true), Ok(Theme::light()));
+        assert_eq!(
+            theme("[theme]\nname = \"high-contrast\"", false),
+            Ok(Theme::high_contrast())
+        );
+
+        let custom = theme(
+            "[theme]\nselected = { fg = \"white\", bg = \"#102030\", modifiers = [\"bold\"] }\nsunday = \"lightred\"\nheat_4 = { bg = \"46\" }\n",
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            custom.selected,
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Rgb(0x10, 0x20, 0x30))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(custom.sunday, Style::default().fg(Color::LightRed));
+        assert_eq!(
+            custom.heat(9),
+            Some(Style::default().bg(Color::Indexed(46)))
+        );
+        // Elements that aren't set come from the monochrome theme
+        assert_eq!(custom.today, Theme::monochrome().today);
+
+        let errors = theme(
+            "[theme]\nname = \"solarized\"\ntoday = { bg = \"pink\" }\nweekend = \"red\"\nerror = { modifiers = \"blinking\" }\n",
+            false,
+        )
+        .unwrap_err();
+        assert!(errors.contains("Unknown theme 'solarized'"));
+        assert!(errors.contains("Invalid colour 'pink' in theme element 'today'"));
+        assert!(errors.contains("Unknown theme element 'weekend'"));
+        assert!(errors.contains("Invalid modifier 'blinking'"));
+    }
+}
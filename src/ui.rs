@@ -1,14 +1,24 @@
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use crossterm::event::KeyCode;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table},
     Frame,
 };
 
+use rcal_lib::sync::ConflictSide;
+use rcal_lib::EventField;
 use rcal_lib::{CalendarEvent, Recurrence, SyncStatus};
 
-use crate::app::{App, InputMode, PopupInputField};
+use crate::app::{App, InputMode, PopupInputField, ViewMode};
+use crate::day_view::render_day_view;
+use crate::keybindings::{Action, KeyMode};
+use crate::locale::Locale;
+use crate::mouse::HitTarget;
+use crate::theme::Theme;
+use crate::week_view::render_week_view;
 
 const MAX_OVERLAY_HEIGHT: u16 = 5;
 const MIN_OVERLAY_WIDTH: u16 = 10;
@@ -61,9 +71,7 @@ fn render_suggestions_overlay(f: &mut Frame, app: &App, input_chunks: &[Rect]) {
 
     let overlay_area = Rect::new(overlay_x, overlay_y, overlay_width, overlay_height);
 
-    let overlay_block = Block::default()
-        .borders(Borders::ALL)
-        .style(Style::default().fg(Color::Gray));
+    let overlay_block = Block::default().borders(Borders::ALL).style(app.theme.hint);
     f.render_widget(Clear, overlay_area);
     f.render_widget(&overlay_block, overlay_area);
 
@@ -75,14 +83,12 @@ fn render_suggestions_overlay(f: &mut Frame, app: &App, input_chunks: &[Rect]) {
         .filter(|(_, (s, _))| !s.is_empty())
         .map(|(i, (s, is_valid))| {
             let mut style = if i == app.selected_suggestion_index {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
+                app.theme.label.add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
             };
             if !is_valid {
-                style = style.fg(Color::Gray);
+                style = style.patch(app.theme.hint);
             }
             ListItem::new(s.as_str()).style(style)
         })
@@ -91,15 +97,28 @@ fn render_suggestions_overlay(f: &mut Frame, app: &App, input_chunks: &[Rect]) {
     f.render_widget(suggestions_list_widget, inner_area);
 }
 
+/// Returns the style of a weekday's dates and header: the weekend colours
+/// on Saturday and Sunday.
+pub fn weekday_style(theme: &Theme, day: Weekday) -> Style {
+    match day {
+        Weekday::Sat => theme.saturday,
+        Weekday::Sun => theme.sunday,
+        _ => Style::default(),
+    }
+}
+
 fn build_calendar_table(
     year: i32,
     month: u32,
     events: &[CalendarEvent],
     selected_date: NaiveDate,
+    heat_map: bool,
+    theme: &Theme,
+    locale: &Locale,
 ) -> (Table<'static>, usize) {
     let today = Local::now().date_naive();
     let first_day_of_month = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-    let weekday_of_first = first_day_of_month.weekday().num_days_from_monday();
+    let weekday_of_first = locale.weekday_column(first_day_of_month.weekday());
 
     let mut calendar_days = vec![];
     for _ in 0..weekday_of_first {
@@ -129,37 +148,45 @@ fn build_calendar_table(
             current_date_for_week_num = current_day_date;
 
             let mut day_display_str = day_str.clone();
-            let has_event = events.iter().any(|event| {
-                event.start_date <= current_day_date
-                    && event.end_date.is_none_or(|end| end >= current_day_date)
-            });
-            let symbol = if has_event { "*" } else { "" };
-            day_display_str.push_str(symbol);
+            let event_count = events
+                .iter()
+                .filter(|event| {
+                    event.start_date <= current_day_date
+                        && event.end_date.is_none_or(|end| end >= current_day_date)
+                })
+                .count();
+            if !heat_map && event_count > 0 {
+                day_display_str.push('*');
+                final_style = final_style.patch(theme.event_day);
+            }
 
             // Apply Saturday/Sunday colors
-            if current_day_date.weekday().num_days_from_monday() == 5 {
-                final_style = final_style.fg(Color::LightYellow);
-            } else if current_day_date.weekday().num_days_from_monday() == 6 {
-                final_style = final_style.fg(Color::Red);
-            }
+            final_style = final_style.patch(weekday_style(theme, current_day_date.weekday()));
 
-            // Apply current day background if it's today
-            if current_day_date == today {
-                final_style = final_style.bg(Color::DarkGray);
+            // Shade days by their number of events in the heat map, which
+            // marks today without a background
+            if heat_map {
+                if let Some(heat) = theme.heat(event_count) {
+                    final_style = final_style.patch(heat);
+                }
+                if current_day_date == today {
+                    final_style = final_style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+            } else if current_day_date == today {
+                final_style = final_style.patch(theme.today);
             }
 
             // Apply selected day foreground and bold if it's the selected date
             if current_day_date == selected_date {
-                final_style = final_style.fg(Color::Black).bg(Color::LightBlue);
+                final_style = final_style.patch(theme.selected);
             }
             cell = Cell::from(day_display_str).style(final_style);
         }
         week_cells.push(cell);
 
         if (i + 1) % 7 == 0 || i == calendar_days.len() - 1 {
-            let week_num = current_date_for_week_num.iso_week().week();
-            let row_cells =
-                vec![Cell::from(week_num.to_string()).style(Style::default().fg(Color::Magenta))];
+            let week_num = locale.week_number(current_date_for_week_num);
+            let row_cells = vec![Cell::from(week_num.to_string()).style(theme.week_number)];
             let mut full_row_cells = row_cells;
             full_row_cells.append(&mut week_cells);
             rows.push(Row::new(full_row_cells));
@@ -167,16 +194,11 @@ fn build_calendar_table(
         }
     }
 
-    let mut all_rows = vec![Row::new(vec![
-        Cell::from(""),
-        Cell::from("Mo"),
-        Cell::from("Tu"),
-        Cell::from("We"),
-        Cell::from("Th"),
-        Cell::from("Fr"),
-        Cell::from("Sa").style(Style::default().fg(Color::LightYellow)),
-        Cell::from("Su").style(Style::default().fg(Color::Red)),
-    ])];
+    let mut header = vec![Cell::from("Wk").style(theme.week_number)];
+    header.extend(locale.weekdays().map(|day| {
+        Cell::from(locale.short_weekday_name(day).to_string()).style(weekday_style(theme, day))
+    }));
+    let mut all_rows = vec![Row::new(header)];
     all_rows.append(&mut rows);
 
     let height = all_rows.len();
@@ -194,13 +216,160 @@ fn build_calendar_table(
             Constraint::Length(3),
         ],
     )
-    .style(Style::default().fg(Color::White))
+    .style(theme.text)
     .column_spacing(1);
 
     (calendar, height)
 }
 
+/// Width of a month table with its week numbers, plus the gap to the next.
+const MONTH_WIDTH: u16 = 32;
+/// Height of a month title and table with six weeks, plus the gap below.
+const MONTH_HEIGHT: u16 = 9;
+/// Month counts the month view chooses from, largest first.
+const MONTH_COUNTS: [u16; 4] = [12, 6, 3, 1];
+
+/// Returns how many months fit into an area of the given size, out of 1, 3,
+/// 6 and 12, and how many columns they are arranged in.
+pub fn month_layout(width: u16, height: u16) -> (u32, u32) {
+    let columns_fit = ((width + 1) / MONTH_WIDTH).max(1);
+    let rows_fit = ((height + 1) / MONTH_HEIGHT).max(1);
+    for months in MONTH_COUNTS {
+        let columns = months.div_ceil(rows_fit.min(months));
+        if columns <= columns_fit {
+            return (u32::from(months), u32::from(columns));
+        }
+    }
+    (1, 1)
+}
+
+/// Returns the year and month `offset` months after the given one.
+fn month_after(year: i32, month: u32, offset: u32) -> (i32, u32) {
+    let index = month - 1 + offset;
+    (year + (index / 12) as i32, index % 12 + 1)
+}
+
+/// Draws the months in a grid of `columns`, skipping the first `skip_rows`
+/// rows of months. With `heat_map`, days are shaded by their number of
+/// events.
+fn render_month_grid(
+    f: &mut Frame,
+    app: &App,
+    area: Rect,
+    months: &[(i32, u32)],
+    columns: usize,
+    skip_rows: usize,
+    heat_map: bool,
+) {
+    let selected_date = app.date;
+    let events = events_of_months(app, months);
+    for (i, &(year, month)) in months.iter().enumerate().skip(skip_rows * columns) {
+        let (row, column) = ((i / columns - skip_rows) as u16, (i % columns) as u16);
+        let x = area.x + column * MONTH_WIDTH;
+        let y = area.y + row * MONTH_HEIGHT;
+        if x >= area.right() || y >= area.bottom() {
+            continue;
+        }
+        let width = (MONTH_WIDTH - 1).min(area.right() - x);
+        let height = (MONTH_HEIGHT - 1).min(area.bottom() - y);
+        let mut title_style = app.theme.title;
+        if heat_map && (year, month) == (selected_date.year(), selected_date.month()) {
+            title_style = title_style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        }
+        let title =
+            Paragraph::new(format!("{} {year}", app.locale.month_name(month))).style(title_style);
+        f.render_widget(title, Rect::new(x, y, width, 1));
+        if height > 1 {
+            let (calendar, _) = build_calendar_table(
+                year,
+                month,
+                &events,
+                selected_date,
+                heat_map,
+                &app.theme,
+                &app.locale,
+            );
+            let table_area = Rect::new(x, y + 1, width, height - 1);
+            f.render_widget(calendar, table_area);
+            add_day_cells(app, table_area, year, month);
+        }
+    }
+}
+
+/// Makes the days of a month table drawn into `area` clickable. The table
+/// has a header row and a week number column, with columns 3 wide and 1
+/// apart.
+fn add_day_cells(app: &App, area: Rect, year: i32, month: u32) {
+    let mut hit_map = app.hit_map.borrow_mut();
+    let mut date = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    let first_weekday = app.locale.weekday_column(date.weekday()) as u16;
+    while date.month() == month {
+        let index = first_weekday + date.day0() as u16;
+        let cell = Rect::new(area.x + 4 * (index % 7 + 1), area.y + 1 + index / 7, 3, 1);
+        if cell.right() <= area.right() && cell.bottom() <= area.bottom() {
+            hit_map.add(cell, HitTarget::Date(date));
+        }
+        date = date.succ_opt().unwrap();
+    }
+}
+
+/// Returns the events of the months, with recurring instances.
+fn events_of_months(app: &App, months: &[(i32, u32)]) -> Vec<CalendarEvent> {
+    let (Some(&(first_year, first_month)), Some(&(last_year, last_month))) =
+        (months.first(), months.last())
+    else {
+        return Vec::new();
+    };
+    let (next_year, next_month) = month_after(last_year, last_month, 1);
+    let start = NaiveDate::from_ymd_opt(first_year, first_month, 1).unwrap();
+    let end = NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .unwrap()
+        .pred_opt()
+        .unwrap();
+    app.get_all_events_for_range(start, end)
+}
+
+/// Draws as many months as fit, starting with the view start month. Twelve
+/// months are shaded by event count.
+fn render_month_view(f: &mut Frame, app: &mut App, calendar_area: Rect) {
+    let (months, columns) = month_layout(calendar_area.width, calendar_area.height);
+    app.month_columns = columns;
+    if app.visible_months != months {
+        app.visible_months = months;
+        app.adjust_view_boundaries();
+    }
+    let months: Vec<_> = (0..months)
+        .map(|offset| month_after(app.view_start_year, app.view_start_month, offset))
+        .collect();
+    let heat_map = months.len() == 12;
+    render_month_grid(
+        f,
+        app,
+        calendar_area,
+        &months,
+        columns as usize,
+        0,
+        heat_map,
+    );
+}
+
+/// Draws the twelve months of the selected year shaded by event count,
+/// scrolled to the selected month if they don't all fit.
+fn render_year_view(f: &mut Frame, app: &mut App, area: Rect) {
+    let columns_fit = ((area.width + 1) / MONTH_WIDTH).max(1);
+    let rows_fit = ((area.height + 1) / MONTH_HEIGHT).max(1) as usize;
+    let columns = columns_fit.min(4) as usize;
+    app.month_columns = columns as u32;
+    let months: Vec<_> = (0..12)
+        .map(|offset| (app.date.year(), offset + 1))
+        .collect();
+    let selected_row = (app.date.month0() as usize) / columns;
+    let skip_rows = (selected_row + 1).saturating_sub(rows_fit);
+    render_month_grid(f, app, area, &months, columns, skip_rows, true);
+}
+
 pub fn ui(f: &mut Frame, app: &mut App) {
+    app.hit_map.borrow_mut().clear();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
@@ -209,99 +378,58 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     let calendar_chunk = chunks[0];
     let hints_chunk = chunks[1];
 
+    let title = match &app.event_filter {
+        Some(filter) => format!("RCal [filter: {}]", filter.label),
+        None => "RCal".to_string(),
+    };
     let calendar_block = Block::default()
-        .title("RCal")
+        .title(title)
         .borders(Borders::ALL)
-        .style(Style::default().fg(Color::White));
+        .style(app.theme.text);
     let calendar_area = calendar_block.inner(calendar_chunk);
     f.render_widget(calendar_block, calendar_chunk);
 
-    // Calculate visible date range
-    let mut overall_start = NaiveDate::MAX;
-    let mut overall_end = NaiveDate::MIN;
-    for i in 0..3 {
-        let month_offset = i as u32;
-        let (year, month) = if app.view_start_month + month_offset > 12 {
-            (
-                app.view_start_year + 1,
-                app.view_start_month + month_offset - 12,
-            )
-        } else {
-            (app.view_start_year, app.view_start_month + month_offset)
-        };
-        let start_of_month = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-        let end_of_month = NaiveDate::from_ymd_opt(year, month + 1, 1)
-            .unwrap_or(NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap())
-            .pred_opt()
-            .unwrap(); // last day of month
-        if start_of_month < overall_start {
-            overall_start = start_of_month;
-        }
-        if end_of_month > overall_end {
-            overall_end = end_of_month;
-        }
+    match app.view_mode {
+        ViewMode::Month => render_month_view(f, app, calendar_area),
+        ViewMode::Year => render_year_view(f, app, calendar_area),
+        ViewMode::Week => render_week_view(f, app, calendar_area),
+        ViewMode::Day => render_day_view(f, app, calendar_area),
     }
-    let all_events = app.get_all_events_for_range(overall_start, overall_end);
-
-    let mut calendars = vec![];
-    let mut constraints = vec![];
 
-    for i in 0..3 {
-        let month_offset = i as u32;
-        let (year, month) = if app.view_start_month + month_offset > 12 {
-            (
-                app.view_start_year + 1,
-                app.view_start_month + month_offset - 12,
-            )
-        } else {
-            (app.view_start_year, app.view_start_month + month_offset)
-        };
-        let month_name = match month {
-            1 => "January",
-            2 => "February",
-            3 => "March",
-            4 => "April",
-            5 => "May",
-            6 => "June",
-            7 => "July",
-            8 => "August",
-            9 => "September",
-            10 => "October",
-            11 => "November",
-            12 => "December",
-            _ => "",
-        };
-        constraints.push(Constraint::Length(1)); // title
-        let (calendar, height) = build_calendar_table(year, month, &all_events, app.date);
-        constraints.push(Constraint::Length(height as u16)); // table
-        if i < 2 {
-            constraints.push(Constraint::Length(1)); // spacing
+    // Render main hints
+    let hints = main_hints(app);
+    let mut hint_spans = if app.input_mode == InputMode::CommandLine {
+        // The command line replaces the hints, followed by completions
+        f.set_cursor_position((
+            hints_chunk.x + 1 + app.command_input.chars().count() as u16,
+            hints_chunk.y,
+        ));
+        vec![
+            Span::styled(format!(":{}", app.command_input), app.theme.text),
+            Span::styled(format!("  {}", app.status_message), app.theme.hint),
+        ]
+    } else if app.status_message.is_empty() {
+        if app.input_mode == InputMode::Normal {
+            app.hit_map.borrow_mut().add_hints(hints_chunk, &hints);
         }
-        calendars.push((month_name, year, calendar));
-    }
-
-    let month_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(calendar_area);
-
-    for (i, (month_name, year, calendar)) in calendars.into_iter().enumerate() {
-        let title =
-            Paragraph::new(format!("{month_name} {year}")).style(Style::default().fg(Color::Cyan));
-        f.render_widget(title, month_chunks[i * 3]);
-        f.render_widget(calendar, month_chunks[i * 3 + 1]);
+        vec![Span::styled(hints, app.theme.hint)]
+    } else {
+        vec![Span::styled(app.status_message.as_str(), app.theme.title)]
+    };
+    if app.pending_sync_operations > 0 {
+        hint_spans.push(Span::styled(
+            format!("  [{} pending sync]", app.pending_sync_operations),
+            app.theme.label,
+        ));
     }
-
-    // Render main hints
-    let main_hints = Paragraph::new("q: quit, a: add, o: view, s: sync, h/j/k/l: navigate")
-        .style(Style::default().fg(Color::Gray));
+    let main_hints = Paragraph::new(Line::from(hint_spans));
     f.render_widget(main_hints, hints_chunk);
 
     if app.show_view_events_popup {
         let popup_block = Block::default()
-            .title(format!("Events on {}", app.date.format("%Y-%m-%d")))
+            .title(format!("Events on {}", app.locale.format_date(app.date)))
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::LightCyan));
+            .style(app.theme.popup);
 
         let popup_events: Vec<ListItem> = app
             .events_to_display_in_popup
@@ -317,7 +445,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 let time_str = if event.is_all_day {
                     "All day".to_string()
                 } else {
-                    event.start_time.format("%H:%M").to_string()
+                    app.locale.format_time(event.start_time)
                 };
                 let content = if event.description.is_empty() {
                     format!("{} - {}{}", time_str, event.title, recurring_indicator)
@@ -329,8 +457,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 };
 
                 if index == app.selected_event_index {
-                    ListItem::new(content)
-                        .style(Style::default().fg(Color::Black).bg(Color::LightBlue))
+                    ListItem::new(content).style(app.theme.selected)
                 } else {
                     ListItem::new(content)
                 }
@@ -339,7 +466,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
 
         let popup_list = List::new(popup_events)
             .block(Block::default().borders(Borders::NONE))
-            .highlight_style(Style::default().fg(Color::Black).bg(Color::LightBlue));
+            .highlight_style(app.theme.selected);
 
         let area = Layout::default()
             .direction(Direction::Vertical)
@@ -369,10 +496,23 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         f.render_widget(Clear, area);
         f.render_widget(popup_block, area);
         f.render_widget(popup_list, chunks[0]);
+        let mut hit_map = app.hit_map.borrow_mut();
+        hit_map.add(area, HitTarget::Popup);
+        for index in 0..app
+            .events_to_display_in_popup
+            .len()
+            .min(chunks[0].height as usize)
+        {
+            let row = Rect::new(chunks[0].x, chunks[0].y + index as u16, chunks[0].width, 1);
+            hit_map.add(row, HitTarget::PopupEvent(index));
+        }
 
         // Render hints
-        let hints = Paragraph::new("j/k: navigate, e: edit, a: add, d: delete, Esc: close")
-            .style(Style::default().fg(Color::Gray));
+        let hints_text = app.keymap.hints(KeyMode::Popup, POPUP_HINTS);
+        if app.input_mode == InputMode::ViewEventsPopup {
+            hit_map.add_hints(chunks[1], &hints_text);
+        }
+        let hints = Paragraph::new(hints_text).style(app.theme.hint);
         f.render_widget(hints, chunks[1]);
     }
 
@@ -384,12 +524,12 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 let confirm_block = Block::default()
                     .title("Confirm Delete")
                     .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::LightRed));
+                    .style(app.theme.warning);
 
                 let time_str = if event.is_all_day {
                     "All day".to_string()
                 } else {
-                    event.start_time.format("%H:%M").to_string()
+                    app.locale.format_time(event.start_time)
                 };
                 let confirmation_text = format!(
                     "Delete event:\n\n  {}\n  {}\n\nPress 'y' to confirm, 'n' to cancel",
@@ -412,8 +552,24 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 f.render_widget(Clear, area);
                 f.render_widget(confirm_block, area);
 
+                // The last line holds the confirm and cancel buttons
+                let button_line = "Press 'y' to confirm, 'n' to cancel";
+                let line_x =
+                    inner_area.x + inner_area.width.saturating_sub(button_line.len() as u16) / 2;
+                let line_y = inner_area.y + 5;
+                let mut hit_map = app.hit_map.borrow_mut();
+                hit_map.add(area, HitTarget::Popup);
+                hit_map.add(
+                    Rect::new(line_x, line_y, 20, 1),
+                    HitTarget::Key(KeyCode::Char('y')),
+                );
+                hit_map.add(
+                    Rect::new(line_x + 22, line_y, 13, 1),
+                    HitTarget::Key(KeyCode::Char('n')),
+                );
+
                 let text = ratatui::widgets::Paragraph::new(confirmation_text)
-                    .style(Style::default().fg(Color::White))
+                    .style(app.theme.text)
                     .alignment(ratatui::layout::Alignment::Center);
                 f.render_widget(text, inner_area);
             }
@@ -424,18 +580,18 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         let title = if app.is_editing {
             format!(
                 "Edit Event for {}",
-                app.current_date_for_new_event.format("%Y-%m-%d")
+                app.locale.format_date(app.current_date_for_new_event)
             )
         } else {
             format!(
                 "Add Event for {}",
-                app.current_date_for_new_event.format("%Y-%m-%d")
+                app.locale.format_date(app.current_date_for_new_event)
             )
         };
         let popup_block = Block::default()
             .title(title)
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::LightCyan));
+            .style(app.theme.popup);
 
         let area = {
             let size = f.area();
@@ -452,6 +608,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         let inner_area = popup_block.inner(area);
         f.render_widget(Clear, area);
         f.render_widget(popup_block, area);
+        app.hit_map.borrow_mut().add(area, HitTarget::Popup);
 
         let (input_area, error_area, hints_area) = if app.error_message.is_empty() {
             let chunks = Layout::default()
@@ -491,32 +648,32 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             .split(input_area);
 
         let title_style = if app.selected_input_field == PopupInputField::Title {
-            Style::default().fg(Color::Black).bg(Color::LightBlue)
+            app.theme.selected
         } else {
             Style::default()
         };
         let time_style = if app.selected_input_field == PopupInputField::Time {
-            Style::default().fg(Color::Black).bg(Color::LightBlue)
+            app.theme.selected
         } else {
             Style::default()
         };
         let end_date_style = if app.selected_input_field == PopupInputField::EndDate {
-            Style::default().fg(Color::Black).bg(Color::LightBlue)
+            app.theme.selected
         } else {
             Style::default()
         };
         let end_time_style = if app.selected_input_field == PopupInputField::EndTime {
-            Style::default().fg(Color::Black).bg(Color::LightBlue)
+            app.theme.selected
         } else {
             Style::default()
         };
         let recurrence_style = if app.selected_input_field == PopupInputField::Recurrence {
-            Style::default().fg(Color::Black).bg(Color::LightBlue)
+            app.theme.selected
         } else {
             Style::default()
         };
         let description_style = if app.selected_input_field == PopupInputField::Description {
-            Style::default().fg(Color::Black).bg(Color::LightBlue)
+            app.theme.selected
         } else {
             Style::default()
         };
@@ -535,7 +692,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             Block::default()
                 .borders(Borders::ALL)
                 .title("Time")
-                .border_style(Style::default().fg(Color::Red))
+                .border_style(app.theme.error)
         } else {
             Block::default().borders(Borders::ALL).title("Time")
         };
@@ -553,7 +710,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             Block::default()
                 .borders(Borders::ALL)
                 .title("End Date")
-                .border_style(Style::default().fg(Color::Red))
+                .border_style(app.theme.error)
         } else {
             Block::default().borders(Borders::ALL).title("End Date")
         };
@@ -576,7 +733,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             Block::default()
                 .borders(Borders::ALL)
                 .title("End Time")
-                .border_style(Style::default().fg(Color::Red))
+                .border_style(app.theme.error)
         } else {
             Block::default().borders(Borders::ALL).title("End Time")
         };
@@ -642,18 +799,34 @@ pub fn ui(f: &mut Frame, app: &mut App) {
 
         // Render error message if present
         if let Some(error_area) = error_area {
-            let error =
-                Paragraph::new(app.error_message.as_str()).style(Style::default().fg(Color::Red));
+            let error = Paragraph::new(app.error_message.as_str()).style(app.theme.error);
             f.render_widget(error, error_area);
         }
 
         // Render hints
         let hints_text = if app.input_mode == InputMode::SelectingRecurrence {
-            "j/k: navigate, Enter: select, Esc: cancel"
+            "j/k: navigate, Enter: select, Esc: cancel".to_string()
+        } else if !app.free_slots.is_empty() {
+            let next_slot = format!(
+                "next free slot ({}/{})",
+                app.free_slot_index + 1,
+                app.free_slots.len()
+            );
+            app.keymap.hints(
+                KeyMode::Form,
+                &[
+                    (&[Action::ProposeFreeSlot], &next_slot),
+                    (&[Action::Submit], "save"),
+                    (&[Action::Cancel], "cancel"),
+                ],
+            )
         } else {
-            "Tab/Shift+Tab: switch field, Enter: save, Esc: cancel"
+            app.keymap.hints(KeyMode::Form, FORM_HINTS)
         };
-        let hints = Paragraph::new(hints_text).style(Style::default().fg(Color::Gray));
+        if app.input_mode == InputMode::EditingEventPopup {
+            app.hit_map.borrow_mut().add_hints(hints_area, &hints_text);
+        }
+        let hints = Paragraph::new(hints_text).style(app.theme.hint);
         f.render_widget(hints, hints_area);
 
         // Render suggestions overlay if active
@@ -664,7 +837,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         let popup_block = Block::default()
             .title("Select Recurrence")
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::LightCyan));
+            .style(app.theme.popup);
 
         let area = {
             let size = f.area();
@@ -687,7 +860,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             .enumerate()
             .map(|(i, &opt)| {
                 if i == app.selected_recurrence_index {
-                    ListItem::new(opt).style(Style::default().fg(Color::Black).bg(Color::LightBlue))
+                    ListItem::new(opt).style(app.theme.selected)
                 } else {
                     ListItem::new(opt)
                 }
@@ -702,7 +875,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         let popup_block = Block::default()
             .title("Sync")
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::LightGreen));
+            .style(app.theme.popup);
 
         let area = {
             let size = f.area();
@@ -733,10 +906,19 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             )
             .split(inner_area);
 
-        let instructions = List::new(vec![
-            ListItem::new("f: Pull from remote"),
-            ListItem::new("p: Push to remote"),
-        ]);
+        let mut instructions = vec!["f: Pull from remote", "p: Push to remote"];
+        if app.sync_status == Some(SyncStatus::Conflicts) {
+            instructions.push("c: Resolve conflicts");
+        }
+        let mut hit_map = app.hit_map.borrow_mut();
+        hit_map.add(area, HitTarget::Popup);
+        for (row, instruction) in instructions.iter().enumerate() {
+            if row < chunks[0].height as usize {
+                let line = Rect::new(chunks[0].x, chunks[0].y + row as u16, chunks[0].width, 1);
+                hit_map.add_hints(line, instruction);
+            }
+        }
+        let instructions = List::new(instructions);
         f.render_widget(instructions, chunks[0]);
 
         if !app.sync_message.is_empty() {
@@ -752,7 +934,644 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             Some(SyncStatus::Error(_)) => "Status: Error",
             None => "Status: Unknown",
         };
+        let status_text = if app.pending_sync_operations > 0 {
+            format!(
+                "{status_text} ({} pending, p to retry)",
+                app.pending_sync_operations
+            )
+        } else {
+            status_text.to_string()
+        };
         let status = List::new(vec![ListItem::new(status_text)]);
         f.render_widget(status, chunks[2]);
     }
+    if app.input_mode == InputMode::ConflictResolver {
+        render_conflict_resolver(f, app);
+    }
+    if app.input_mode == InputMode::EventHistory {
+        render_event_history(f, app);
+    }
+    if app.input_mode == InputMode::Search {
+        render_search(f, app);
+    }
+    if app.input_mode == InputMode::TargetDate {
+        render_target_date_prompt(f, app);
+    }
+    if app.input_mode == InputMode::GoTo {
+        render_goto_prompt(f, app);
+    }
+    if app.input_mode == InputMode::Help {
+        render_help(f, app);
+    }
+}
+
+/// The hints of the view popup, as actions with their labels.
+const FORM_HINTS: &[(&[Action], &str)] = &[
+    (&[Action::NextField], "next field"),
+    (&[Action::ProposeFreeSlot], "free slot"),
+    (&[Action::Submit], "save"),
+    (&[Action::Cancel], "cancel"),
+];
+
+const POPUP_HINTS: &[(&[Action], &str)] = &[
+    (&[Action::SelectPrevious, Action::SelectNext], "navigate"),
+    (&[Action::EditEvent], "edit"),
+    (&[Action::AddEvent], "add"),
+    (&[Action::DeleteEvent], "delete"),
+    (
+        &[Action::Copy, Action::Cut, Action::Paste],
+        "copy/cut/paste",
+    ),
+    (&[Action::MoveEvent], "move"),
+    (&[Action::DuplicateEvent], "duplicate"),
+    (&[Action::ShiftBack, Action::ShiftForward], "shift"),
+    (&[Action::Undo], "undo"),
+    (&[Action::EventHistory], "history"),
+    (&[Action::Help], "help"),
+    (&[Action::Close], "close"),
+];
+
+/// Builds the hints line below the calendar from the active key bindings.
+fn main_hints(app: &App) -> String {
+    let undo: (&[Action], &str) = (&[Action::Undo, Action::Redo], "undo/redo");
+    let help: (&[Action], &str) = (&[Action::Help], "help");
+    let navigate: (&[Action], &str) = (
+        &[
+            Action::NavigateLeft,
+            Action::NavigateDown,
+            Action::NavigateUp,
+            Action::NavigateRight,
+        ],
+        "navigate",
+    );
+    let items: Vec<(&[Action], &str)> = match app.view_mode {
+        ViewMode::Month | ViewMode::Week => vec![
+            (&[Action::Quit], "quit"),
+            (&[Action::AddEvent], "add"),
+            (&[Action::OpenDay], "day"),
+            (&[Action::OpenEvents], "view"),
+            (&[Action::Search], "search"),
+            (&[Action::Sync], "sync"),
+            undo,
+            (
+                &[Action::ToggleWeekView],
+                if app.view_mode == ViewMode::Week {
+                    "month"
+                } else {
+                    "week"
+                },
+            ),
+            (&[Action::ToggleYearView], "year"),
+            navigate,
+            (&[Action::GoTo], "go to"),
+            (&[Action::CommandLine], "command"),
+            help,
+        ],
+        ViewMode::Year => vec![
+            (&[Action::Quit], "quit"),
+            (&[Action::OpenDay], "month"),
+            (&[Action::Back, Action::ToggleYearView], "back"),
+            (&[Action::NavigateLeft, Action::NavigateRight], "month"),
+            (&[Action::NavigateDown, Action::NavigateUp], "row of months"),
+            (&[Action::PageUp, Action::PageDown], "year"),
+            (&[Action::GoTo], "go to"),
+            help,
+        ],
+        ViewMode::Day => vec![
+            (&[Action::Quit], "quit"),
+            (&[Action::AddEvent], "add"),
+            (&[Action::OpenDay, Action::OpenEvents], "open"),
+            (&[Action::Back], "back"),
+            undo,
+            (&[Action::ToggleWeekView], "week view"),
+            (&[Action::NavigateLeft, Action::NavigateRight], "day"),
+            (&[Action::NavigateDown, Action::NavigateUp], "select"),
+            (&[Action::GoTo], "go to"),
+            help,
+        ],
+    };
+    app.keymap.hints(KeyMode::Normal, &items)
+}
+
+/// Renders the help screen: the active key bindings of each mode.
+fn render_help(f: &mut Frame, app: &App) {
+    let popup_block = Block::default()
+        .title("Key bindings (j/k: scroll, Esc: close)")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+    let area = {
+        let size = f.area();
+        let popup_width = 76.min(size.width.saturating_sub(2));
+        let popup_height = size.height.saturating_sub(4);
+        Rect::new(
+            (size.width - popup_width) / 2,
+            (size.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        )
+    };
+    let inner_area = popup_block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let mut lines = Vec::new();
+    for mode in KeyMode::ALL {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            mode.title(),
+            app.theme.label.add_modifier(Modifier::BOLD),
+        )));
+        for (action, keys) in app.keymap.bindings(mode) {
+            let keys = if keys.is_empty() {
+                "(unbound)".to_string()
+            } else {
+                keys.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{keys:<14}"), app.theme.text),
+                Span::styled(action.description(), app.theme.hint),
+            ]));
+        }
+    }
+    f.render_widget(
+        Paragraph::new(lines).scroll((app.help_scroll, 0)),
+        inner_area,
+    );
+    app.hit_map.borrow_mut().add(area, HitTarget::Popup);
+}
+
+const GOTO_FORMATS: &str = "Date, w42, month name, +1m or next week";
+
+const GOTO_HINTS: &[(&[Action], &str)] =
+    &[(&[Action::Submit], "go"), (&[Action::Cancel], "cancel")];
+
+const TARGET_DATE_FORMATS: &str = "Date, +N/-N days or +Nw weeks";
+
+const TARGET_DATE_HINTS: &[(&[Action], &str)] =
+    &[(&[Action::Submit], "apply"), (&[Action::Cancel], "cancel")];
+
+/// Builds the hints line of a prompt: the accepted formats, then the keys.
+fn prompt_hints(app: &App, formats: &str, items: &[(&[Action], &str)]) -> String {
+    let keys = app.keymap.hints(KeyMode::Prompt, items);
+    if keys.is_empty() {
+        formats.to_string()
+    } else {
+        format!("{formats}, {keys}")
+    }
+}
+
+/// Renders the prompt for the date to move or duplicate the selected event to.
+fn render_target_date_prompt(f: &mut Frame, app: &App) {
+    let action = app.date_action.map_or("Move", |action| action.label());
+    let title = app
+        .events_to_display_in_popup
+        .get(app.selected_event_index)
+        .map_or("", |event| event.title.as_str());
+    let popup_block = Block::default()
+        .title(format!("{action} '{title}'"))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    let area = {
+        let size = f.area();
+        let popup_width = 50.min(size.width.saturating_sub(2));
+        let popup_height = 5.min(size.height.saturating_sub(2));
+        Rect::new(
+            (size.width - popup_width) / 2,
+            (size.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        )
+    };
+    let inner_area = popup_block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("To: ", app.theme.label),
+            Span::styled(app.target_date_input.as_str(), app.theme.text),
+        ])),
+        chunks[0],
+    );
+    f.set_cursor_position((
+        chunks[0].x + 4 + app.target_date_input.chars().count() as u16,
+        chunks[0].y,
+    ));
+    f.render_widget(
+        Paragraph::new(app.status_message.as_str()).style(app.theme.error),
+        chunks[1],
+    );
+    let hints = prompt_hints(app, TARGET_DATE_FORMATS, TARGET_DATE_HINTS);
+    let mut hit_map = app.hit_map.borrow_mut();
+    hit_map.add(area, HitTarget::Popup);
+    hit_map.add_hints(chunks[2], &hints);
+    f.render_widget(Paragraph::new(hints).style(app.theme.hint), chunks[2]);
+}
+
+/// Draws the go-to prompt, with the error of the last attempt.
+fn render_goto_prompt(f: &mut Frame, app: &App) {
+    let popup_block = Block::default()
+        .title("Go to")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    let area = {
+        let size = f.area();
+        let popup_width = 66.min(size.width.saturating_sub(2));
+        let popup_height = 5.min(size.height.saturating_sub(2));
+        Rect::new(
+            (size.width - popup_width) / 2,
+            (size.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        )
+    };
+    let inner_area = popup_block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("To: ", app.theme.label),
+            Span::styled(app.goto_input.as_str(), app.theme.text),
+        ])),
+        chunks[0],
+    );
+    f.set_cursor_position((
+        chunks[0].x + 4 + app.goto_input.chars().count() as u16,
+        chunks[0].y,
+    ));
+    f.render_widget(
+        Paragraph::new(app.status_message.as_str()).style(app.theme.error),
+        chunks[1],
+    );
+    let hints = prompt_hints(app, GOTO_FORMATS, GOTO_HINTS);
+    let mut hit_map = app.hit_map.borrow_mut();
+    hit_map.add(area, HitTarget::Popup);
+    hit_map.add_hints(chunks[2], &hints);
+    f.render_widget(Paragraph::new(hints).style(app.theme.hint), chunks[2]);
+}
+
+const SEARCH_HINTS: &[(&[Action], &str)] = &[
+    (&[Action::SelectPrevious, Action::SelectNext], "navigate"),
+    (&[Action::Submit], "go to date"),
+    (&[Action::ToggleRegex], "text/regex"),
+    (&[Action::Cancel], "close"),
+];
+
+/// Renders the search popup: the query and the matching events.
+fn render_search(f: &mut Frame, app: &App) {
+    let mode = if app.search_regex { "regex" } else { "text" };
+    let popup_block = Block::default()
+        .title(format!("Search ({mode})"))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    let area = {
+        let size = f.area();
+        let popup_width = 80.min(size.width.saturating_sub(2));
+        let popup_height = 20.min(size.height.saturating_sub(2));
+        Rect::new(
+            (size.width - popup_width) / 2,
+            (size.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        )
+    };
+    let inner_area = popup_block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(inner_area);
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("/", app.theme.label),
+            Span::styled(app.search_query.as_str(), app.theme.text),
+        ])),
+        chunks[0],
+    );
+    f.set_cursor_position((
+        chunks[0].x + 1 + app.search_query.chars().count() as u16,
+        chunks[0].y,
+    ));
+
+    // Keep the selected result in view
+    let visible = chunks[1].height as usize;
+    let skip = (app.selected_search_index + 1).saturating_sub(visible);
+    let results: Vec<ListItem> = app
+        .search_results
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(i, event)| {
+            let time = if event.is_all_day {
+                "all day".to_string()
+            } else {
+                app.locale.format_time(event.start_time)
+            };
+            let text = format!(
+                "{}  {:<7}  {}",
+                app.locale.format_date(event.start_date),
+                time,
+                event.title
+            );
+            if i == app.selected_search_index {
+                ListItem::new(text).style(app.theme.selected)
+            } else {
+                ListItem::new(text).style(app.theme.text)
+            }
+        })
+        .collect();
+    f.render_widget(List::new(results), chunks[1]);
+
+    f.render_widget(
+        Paragraph::new(app.search_message.as_str()).style(app.theme.error),
+        chunks[2],
+    );
+    let hints = app.keymap.hints(KeyMode::Search, SEARCH_HINTS);
+    f.render_widget(
+        Paragraph::new(hints.as_str()).style(app.theme.hint),
+        chunks[3],
+    );
+    let mut hit_map = app.hit_map.borrow_mut();
+    hit_map.add(area, HitTarget::Popup);
+    for index in skip..app.search_results.len().min(skip + visible) {
+        let row = Rect::new(
+            chunks[1].x,
+            chunks[1].y + (index - skip) as u16,
+            chunks[1].width,
+            1,
+        );
+        hit_map.add(row, HitTarget::SearchResult(index));
+    }
+    hit_map.add_hints(chunks[3], &hints);
+}
+
+const HISTORY_HINTS: &str = "j/k: navigate, r/Enter: restore this version, Esc: back";
+
+/// Renders the history popup: the committed versions of an event, newest
+/// first, and the fields changed by the selected one.
+fn render_event_history(f: &mut Frame, app: &App) {
+    let title = if app.history_file_name.is_empty() {
+        "History".to_string()
+    } else {
+        format!("History: {}", app.history_file_name)
+    };
+    let popup_block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    let area = {
+        let size = f.area();
+        let popup_width = 90.min(size.width.saturating_sub(2));
+        let popup_height = 20.min(size.height.saturating_sub(2));
+        Rect::new(
+            (size.width - popup_width) / 2,
+            (size.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        )
+    };
+    let inner_area = popup_block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(3),
+                Constraint::Length(7),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(inner_area);
+
+    let revisions: Vec<ListItem> = app
+        .event_history
+        .iter()
+        .enumerate()
+        .map(|(i, revision)| {
+            let mut text = format!(
+                "{}  {} {}  {}  {}",
+                revision.short_id(),
+                app.locale.format_date(revision.date.date_naive()),
+                app.locale.format_time(revision.date.time()),
+                revision.author,
+                revision.summary
+            );
+            if revision.is_deletion() {
+                text.push_str("  (deleted)");
+            }
+            if i == app.selected_revision_index {
+                ListItem::new(text).style(app.theme.selected)
+            } else {
+                ListItem::new(text).style(app.theme.text)
+            }
+        })
+        .collect();
+    f.render_widget(List::new(revisions), chunks[0]);
+
+    let changes: Vec<Line> = app
+        .selected_revision()
+        .map(|revision| {
+            revision
+                .changes
+                .iter()
+                .map(|change| {
+                    Line::from(vec![
+                        Span::styled(format!("{:<12}", change.field.label()), app.theme.label),
+                        Span::styled(
+                            change.old.clone().unwrap_or_else(|| "(none)".to_string()),
+                            app.theme.error,
+                        ),
+                        Span::raw(" -> "),
+                        Span::styled(
+                            change
+                                .new
+                                .clone()
+                                .unwrap_or_else(|| "(deleted)".to_string()),
+                            app.theme.added,
+                        ),
+                    ])
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    f.render_widget(
+        Paragraph::new(changes).block(Block::default().borders(Borders::TOP).title("Changes")),
+        chunks[1],
+    );
+
+    f.render_widget(
+        Paragraph::new(app.history_message.as_str()).style(app.theme.error),
+        chunks[2],
+    );
+    f.render_widget(
+        Paragraph::new(HISTORY_HINTS).style(app.theme.hint),
+        chunks[3],
+    );
+    let mut hit_map = app.hit_map.borrow_mut();
+    hit_map.add(area, HitTarget::Popup);
+    hit_map.add_hints(chunks[3], HISTORY_HINTS);
+}
+
+const CONFLICT_RESOLVER_HINTS: [&str; 2] = [
+    "j/k: field, h/l: local/remote, L/R: all local/remote, b: keep both, e: edit",
+    "Enter: accept, Tab: next conflict, Esc: cancel",
+];
+
+/// Renders the conflict resolver: both versions of the current conflict side
+/// by side, with the side chosen for each field highlighted.
+fn render_conflict_resolver(f: &mut Frame, app: &App) {
+    let Some(conflict) = app.current_conflict() else {
+        return;
+    };
+    let choices = app.conflict_choices[app.selected_conflict_index];
+    let resolved = app.conflict_resolutions.iter().flatten().count();
+
+    let popup_block = Block::default()
+        .title(format!(
+            "Resolve Conflicts ({}/{}, {resolved} resolved)",
+            app.selected_conflict_index + 1,
+            app.sync_conflicts.len()
+        ))
+        .borders(Borders::ALL)
+        .style(app.theme.warning);
+
+    let area = {
+        let size = f.area();
+        let popup_width = 90.min(size.width.saturating_sub(2));
+        let popup_height = 14.min(size.height.saturating_sub(2));
+        Rect::new(
+            (size.width - popup_width) / 2,
+            (size.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        )
+    };
+    let inner_area = popup_block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+                Constraint::Length(2),
+            ]
+            .as_ref(),
+        )
+        .split(inner_area);
+
+    let header = Paragraph::new(format!("File: {}", conflict.file_name)).style(app.theme.text);
+    f.render_widget(header, chunks[0]);
+
+    let differing = conflict.differing_fields();
+    let side_cell = |event: Option<&CalendarEvent>, field: EventField, side: ConflictSide| {
+        let text = event.map_or("(deleted)".to_string(), |e| field.display(e));
+        let style = if choices.get(field) == side {
+            app.theme.added.add_modifier(Modifier::BOLD)
+        } else {
+            app.theme.hint
+        };
+        Cell::from(text).style(style)
+    };
+    let rows: Vec<Row> = EventField::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, &field)| {
+            let label_style = if differing.contains(&field) {
+                app.theme.label
+            } else {
+                app.theme.hint
+            };
+            let row = Row::new(vec![
+                Cell::from(field.label()).style(label_style),
+                side_cell(conflict.local.as_ref(), field, ConflictSide::Local),
+                side_cell(conflict.remote.as_ref(), field, ConflictSide::Remote),
+            ]);
+            if i == app.selected_conflict_field {
+                row.style(app.theme.highlight)
+            } else {
+                row
+            }
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ],
+    )
+    .header(
+        Row::new(vec!["", "Local", "Remote"]).style(Style::default().add_modifier(Modifier::BOLD)),
+    );
+    f.render_widget(table, chunks[1]);
+
+    let status = match &app.conflict_resolutions[app.selected_conflict_index] {
+        Some(resolution) if resolution.events.is_empty() => "Resolved: delete".to_string(),
+        Some(resolution) => format!("Resolved: {} event(s)", resolution.events.len()),
+        None => "Unresolved".to_string(),
+    };
+    f.render_widget(Paragraph::new(status).style(app.theme.text), chunks[2]);
+
+    let mut hit_map = app.hit_map.borrow_mut();
+    hit_map.add(area, HitTarget::Popup);
+    for (row, line) in CONFLICT_RESOLVER_HINTS.iter().enumerate() {
+        if row < chunks[3].height as usize {
+            let line_area = Rect::new(chunks[3].x, chunks[3].y + row as u16, chunks[3].width, 1);
+            hit_map.add_hints(line_area, line);
+        }
+    }
+    let hints = Paragraph::new(CONFLICT_RESOLVER_HINTS.join("\n")).style(app.theme.hint);
+    f.render_widget(hints, chunks[3]);
 }
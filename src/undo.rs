@@ -0,0 +1,163 @@
+//! Undo and redo of the changes made to events in the TUI.
+//!
+//! A change keeps full copies of the event before and after it rather than
+//! ids, which are regenerated whenever the events are reloaded, so the
+//! history stays usable across background reloads and syncs.
+
+use rcal_lib::CalendarEvent;
+
+/// Most changes kept for undo.
+const MAX_CHANGES: usize = 100;
+
+/// A change to one stored event: `before` is `None` for a created event and
+/// `after` is `None` for a deleted one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub before: Option<CalendarEvent>,
+    pub after: Option<CalendarEvent>,
+}
+
+impl Change {
+    pub fn created(event: CalendarEvent) -> Self {
+        Self {
+            before: None,
+            after: Some(event),
+        }
+    }
+
+    pub fn edited(before: CalendarEvent, after: CalendarEvent) -> Self {
+        Self {
+            before: Some(before),
+            after: Some(after),
+        }
+    }
+
+    pub fn deleted(event: CalendarEvent) -> Self {
+        Self {
+            before: Some(event),
+            after: None,
+        }
+    }
+
+    /// Returns the change that reverts this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+
+    /// Names the change for messages: create, delete, move (only the dates
+    /// or times changed) or edit.
+    pub fn label(&self) -> &'static str {
+        match (&self.before, &self.after) {
+            (None, _) => "create",
+            (_, None) => "delete",
+            (Some(before), Some(after))
+                if before.title == after.title
+                    && before.description == after.description
+                    && before.recurrence == after.recurrence
+                    && before.is_all_day == after.is_all_day =>
+            {
+                "move"
+            }
+            _ => "edit",
+        }
+    }
+
+    /// Returns the title of the event the change is about.
+    pub fn title(&self) -> &str {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .map_or("", |event| event.title.as_str())
+    }
+}
+
+/// Changes that can be undone and redone, most recent last. Recording a new
+/// change clears the redo side.
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+impl UndoHistory {
+    /// Records a change made by the user.
+    pub fn record(&mut self, change: Change) {
+        self.undo.push(change);
+        if self.undo.len() > MAX_CHANGES {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Returns the change the next undo reverts.
+    pub fn next_undo(&self) -> Option<&Change> {
+        self.undo.last()
+    }
+
+    /// Returns the change the next redo applies again.
+    pub fn next_redo(&self) -> Option<&Change> {
+        self.redo.last()
+    }
+
+    /// Moves the last change to the redo side once it has been reverted.
+    pub fn mark_undone(&mut self) {
+        if let Some(change) = self.undo.pop() {
+            self.redo.push(change);
+        }
+    }
+
+    /// Moves the last undone change back once it has been applied again.
+    pub fn mark_redone(&mut self) {
+        if let Some(change) = self.redo.pop() {
+            self.undo.push(change);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+
+    fn event(title: &str, day: u32) -> CalendarEvent {
+        CalendarEvent::new(
+            title.to_string(),
+            String::new(),
+            NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_undo_history() {
+        let mut history = UndoHistory::default();
+        history.record(Change::created(event("Dentist", 19)));
+        history.record(Change::edited(event("Dentist", 19), event("Dentist", 20)));
+        assert_eq!(history.next_undo().unwrap().label(), "move");
+
+        history.mark_undone();
+        assert_eq!(history.next_undo().unwrap().label(), "create");
+        assert_eq!(history.next_redo().unwrap().label(), "move");
+        history.mark_redone();
+        assert!(history.next_redo().is_none());
+
+        history.mark_undone();
+        history.record(Change::deleted(event("Dentist", 19)));
+        assert!(history.next_redo().is_none());
+        let change = history.next_undo().unwrap();
+        assert_eq!(change.label(), "delete");
+        assert_eq!(change.inverse().label(), "create");
+        assert_eq!(
+            Change::edited(event("Dentist", 19), event("Doctor", 19)).label(),
+            "edit"
+        );
+
+        for day in 1..=MAX_CHANGES as u32 + 5 {
+            history.record(Change::created(event("Standup", day % 28 + 1)));
+        }
+        assert_eq!(history.undo.len(), MAX_CHANGES);
+    }
+}
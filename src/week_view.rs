@@ -0,0 +1,417 @@
+//! Week view: seven day columns with an hourly time grid.
+//!
+//! `layout_week` places the events of a week without drawing anything, so
+//! the placement can be tested; `render_week_view` draws the layout into the
+//! calendar area. All-day and multi-day events go into a strip above the
+//! grid, timed events become blocks as tall as they last, and overlapping
+//! blocks share their day column side by side.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, Timelike};
+use ratatui::{layout::Rect, style::Modifier, Frame};
+
+use rcal_lib::CalendarEvent;
+
+use crate::app::App;
+use crate::mouse::HitTarget;
+use crate::ui::weekday_style;
+
+/// Width of the hour labels left of the grid.
+const GUTTER_WIDTH: u16 = 6;
+/// Most lanes of the all-day strip shown before it is cut off.
+const MAX_ALL_DAY_LANES: usize = 3;
+/// First hour shown when no event starts earlier.
+const DEFAULT_FIRST_HOUR: u32 = 8;
+
+/// An all-day or multi-day event in the strip above the grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllDayBar {
+    pub event: CalendarEvent,
+    /// Columns the bar spans, 0 being the first day of the week.
+    pub first_day: usize,
+    pub last_day: usize,
+    /// Row of the strip the bar is drawn in.
+    pub lane: usize,
+}
+
+/// A timed event in the grid of its day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimedBlock {
+    pub event: CalendarEvent,
+    /// Column of the day, 0 being the first day of the week.
+    pub day: usize,
+    /// Grid rows the block covers, `end_row` excluded; a row is
+    /// `slot_minutes` long and row 0 starts at midnight.
+    pub start_row: usize,
+    pub end_row: usize,
+    /// Sub-column of the block and the number of sub-columns its group of
+    /// overlapping events needs.
+    pub lane: usize,
+    pub lanes: usize,
+}
+
+/// The events of a week placed for drawing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WeekLayout {
+    pub all_day: Vec<AllDayBar>,
+    pub all_day_lanes: usize,
+    pub timed: Vec<TimedBlock>,
+}
+
+fn minute_of_day(time: chrono::NaiveTime) -> usize {
+    (time.hour() * 60 + time.minute()) as usize
+}
+
+/// Returns the first free lane for an item starting at `start`, given the
+/// end of the last item in each lane, and records `end` for it.
+fn take_lane(lane_ends: &mut Vec<usize>, start: usize, end: usize) -> usize {
+    match lane_ends.iter().position(|&lane_end| lane_end <= start) {
+        Some(lane) => {
+            lane_ends[lane] = end;
+            lane
+        }
+        None => {
+            lane_ends.push(end);
+            lane_ends.len() - 1
+        }
+    }
+}
+
+/// Places the events overlapping the week starting on `start` (a Monday).
+/// `events` may contain events outside the week, as returned by
+/// `App::get_all_events_for_range`; timed blocks are measured in rows of
+/// `slot_minutes`.
+pub fn layout_week(events: &[CalendarEvent], start: NaiveDate, slot_minutes: usize) -> WeekLayout {
+    let end = start + Duration::days(6);
+    let slot_minutes = slot_minutes.max(1);
+    let mut in_week: Vec<&CalendarEvent> = events
+        .iter()
+        .filter(|event| event.start_date <= end && event.effective_end_date() >= start)
+        .collect();
+    in_week.sort_by(|a, b| {
+        (a.start_date, a.start_time, &a.title).cmp(&(b.start_date, b.start_time, &b.title))
+    });
+
+    let mut layout = WeekLayout::default();
+    let mut strip_lanes = Vec::new();
+    let mut timed = Vec::new();
+    for event in in_week {
+        if event.is_all_day || event.is_multi_day() {
+            let first_day = (event.start_date.max(start) - start).num_days() as usize;
+            let last_day = (event.effective_end_date().min(end) - start).num_days() as usize;
+            let lane = take_lane(&mut strip_lanes, first_day, last_day + 1);
+            layout.all_day.push(AllDayBar {
+                event: event.clone(),
+                first_day,
+                last_day,
+                lane,
+            });
+        } else {
+            let day = (event.start_date - start).num_days() as usize;
+            let start_minute = minute_of_day(event.start_time);
+            let end_minute = event
+                .end_time
+                .map(minute_of_day)
+                .filter(|&end| end > start_minute)
+                .unwrap_or(start_minute);
+            let start_row = start_minute / slot_minutes;
+            let end_row = end_minute.div_ceil(slot_minutes).max(start_row + 1);
+            timed.push(TimedBlock {
+                event: event.clone(),
+                day,
+                start_row,
+                end_row,
+                lane: 0,
+                lanes: 1,
+            });
+        }
+    }
+    layout.all_day_lanes = strip_lanes.len();
+
+    // Blocks sharing rows, directly or through others, form a group whose
+    // members split the column between them
+    timed.sort_by_key(|block| (block.day, block.start_row, block.end_row));
+    let mut group_start = 0;
+    while group_start < timed.len() {
+        let mut lane_ends = Vec::new();
+        let mut group_end = group_start;
+        let mut rows_end = 0;
+        while group_end < timed.len()
+            && timed[group_end].day == timed[group_start].day
+            && (group_end == group_start || timed[group_end].start_row < rows_end)
+        {
+            let block = &mut timed[group_end];
+            block.lane = take_lane(&mut lane_ends, block.start_row, block.end_row);
+            rows_end = rows_end.max(block.end_row);
+            group_end += 1;
+        }
+        for block in &mut timed[group_start..group_end] {
+            block.lanes = lane_ends.len();
+        }
+        group_start = group_end;
+    }
+    layout.timed = timed;
+    layout
+}
+
+/// Returns the first grid row to show so that the earliest event of the
+/// week, or else 08:00, is at the top, without leaving rows empty after
+/// midnight.
+fn first_visible_row(layout: &WeekLayout, slot_minutes: usize, visible_rows: usize) -> usize {
+    let rows_per_day = 24 * 60 / slot_minutes;
+    let earliest = layout
+        .timed
+        .iter()
+        .map(|block| block.start_row)
+        .min()
+        .unwrap_or(usize::MAX);
+    let default = DEFAULT_FIRST_HOUR as usize * 60 / slot_minutes;
+    earliest
+        .min(default)
+        .min(rows_per_day.saturating_sub(visible_rows))
+}
+
+/// Draws the week of the selected date into `area`.
+pub fn render_week_view(f: &mut Frame, app: &App, area: Rect) {
+    if area.width <= GUTTER_WIDTH + 7 || area.height < 4 {
+        return;
+    }
+    let start = app.locale.week_start_of(app.date);
+    let end = start + Duration::days(6);
+    let today = Local::now().date_naive();
+    let events = app.get_all_events_for_range(start, end);
+
+    let column_width = (area.width - GUTTER_WIDTH) / 7;
+    let column_x = |day: usize| area.x + GUTTER_WIDTH + day as u16 * column_width;
+
+    // Title and day headers
+    let week = app.locale.week_number(start);
+    let locale = &app.locale;
+    let title = if start.year() == end.year() {
+        format!(
+            "Week {week}: {} - {}, {}",
+            locale.format_day_month(start),
+            locale.format_day_month(end),
+            end.year()
+        )
+    } else {
+        format!(
+            "Week {week}: {}, {} - {}, {}",
+            locale.format_day_month(start),
+            start.year(),
+            locale.format_day_month(end),
+            end.year()
+        )
+    };
+    let buffer = f.buffer_mut();
+    buffer.set_stringn(area.x, area.y, title, area.width as usize, app.theme.title);
+    let header_y = area.y + 1;
+    for (day, weekday) in locale.weekdays().into_iter().enumerate() {
+        let date = start + Duration::days(day as i64);
+        app.hit_map.borrow_mut().add(
+            Rect::new(
+                column_x(day),
+                header_y,
+                column_width,
+                area.bottom() - header_y,
+            ),
+            HitTarget::Date(date),
+        );
+        let mut style = weekday_style(&app.theme, weekday);
+        if date == today {
+            style = style.patch(app.theme.today);
+        }
+        if date == app.date {
+            style = style.patch(app.theme.selected);
+        }
+        buffer.set_stringn(
+            column_x(day),
+            header_y,
+            format!("{} {}", locale.short_weekday_name(weekday), date.day()),
+            column_width.saturating_sub(1) as usize,
+            style,
+        );
+    }
+
+    // The grid uses half-hour rows when the day from 08:00 to 20:00 fits
+    let strip_y = header_y + 1;
+    let probe = layout_week(&events, start, 60);
+    let strip_rows = probe.all_day_lanes.min(MAX_ALL_DAY_LANES) as u16;
+    let grid_y = strip_y + strip_rows;
+    let grid_rows = (area.y + area.height).saturating_sub(grid_y) as usize;
+    let slot_minutes = if grid_rows >= 24 { 30 } else { 60 };
+    let layout = if slot_minutes == 60 {
+        probe
+    } else {
+        layout_week(&events, start, slot_minutes)
+    };
+
+    // All-day strip
+    for bar in &layout.all_day {
+        if bar.lane >= MAX_ALL_DAY_LANES {
+            continue;
+        }
+        let x = column_x(bar.first_day);
+        let width = (bar.last_day - bar.first_day + 1) as u16 * column_width - 1;
+        let y = strip_y + bar.lane as u16;
+        let style = app.theme.all_day;
+        buffer.set_style(Rect::new(x, y, width, 1), style);
+        buffer.set_stringn(x, y, &bar.event.title, width as usize, style);
+    }
+    let hidden = layout
+        .all_day
+        .iter()
+        .filter(|bar| bar.lane >= MAX_ALL_DAY_LANES)
+        .count();
+    if hidden > 0 {
+        buffer.set_stringn(
+            area.x,
+            strip_y + strip_rows - 1,
+            format!("+{hidden}"),
+            GUTTER_WIDTH as usize - 1,
+            app.theme.all_day,
+        );
+    }
+
+    // Hour labels
+    let first_row = first_visible_row(&layout, slot_minutes, grid_rows);
+    let rows_per_day = 24 * 60 / slot_minutes;
+    let visible_rows = grid_rows.min(rows_per_day - first_row);
+    for offset in 0..visible_rows {
+        let minute = (first_row + offset) * slot_minutes;
+        if minute.is_multiple_of(60) {
+            buffer.set_string(
+                area.x,
+                grid_y + offset as u16,
+                locale.format_hour(minute as u32 / 60),
+                app.theme.hint,
+            );
+        }
+    }
+
+    // Event blocks, clipped to the visible rows
+    for block in &layout.timed {
+        let top = block.start_row.max(first_row);
+        let bottom = block.end_row.min(first_row + visible_rows);
+        if top >= bottom {
+            continue;
+        }
+        let lane_width = ((column_width - 1) / block.lanes as u16).max(1);
+        let x = column_x(block.day) + block.lane as u16 * lane_width;
+        let width = if block.lane + 1 == block.lanes {
+            column_x(block.day) + column_width - 1 - x
+        } else {
+            lane_width.saturating_sub(1).max(1)
+        };
+        let y = grid_y + (top - first_row) as u16;
+        let style = if block.event.start_date == app.date {
+            app.theme.selected
+        } else {
+            app.theme.event
+        };
+        buffer.set_style(Rect::new(x, y, width, (bottom - top) as u16), style);
+        // Narrow lanes show the title alone
+        let title = &block.event.title;
+        let label = format!("{} {title}", locale.format_time(block.event.start_time));
+        let label = if label.chars().count() <= width as usize {
+            label
+        } else {
+            title.clone()
+        };
+        buffer.set_stringn(
+            x,
+            y,
+            label,
+            width as usize,
+            style.add_modifier(Modifier::BOLD),
+        );
+        if bottom - top > 1 && !block.event.description.is_empty() {
+            buffer.set_stringn(x, y + 1, &block.event.description, width as usize, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::Locale;
+    use chrono::NaiveTime;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn timed(title: &str, day: u32, start: (u32, u32), end: Option<(u32, u32)>) -> CalendarEvent {
+        let time = |(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        let mut event =
+            CalendarEvent::new(title.to_string(), String::new(), date(day), time(start));
+        event.end_time = end.map(time);
+        event
+    }
+
+    #[test]
+    fn test_layout_week_places_events() {
+        let mut holiday = timed("Holiday", 16, (0, 0), None);
+        holiday.is_all_day = true;
+        holiday.end_date = Some(date(21));
+        let mut trip = timed("Trip", 21, (18, 0), Some((9, 0)));
+        trip.end_date = Some(date(22));
+        let events = vec![
+            holiday,
+            trip,
+            timed("Review", 19, (10, 0), Some((12, 0))),
+            timed("Standup", 19, (9, 0), Some((9, 15))),
+            timed("Call", 19, (11, 30), Some((13, 0))),
+            timed("Lunch", 19, (13, 0), None),
+            timed("Last week", 12, (10, 0), None),
+        ];
+        let layout = layout_week(&events, Locale::default().week_start_of(date(21)), 60);
+
+        let bars: Vec<_> = layout
+            .all_day
+            .iter()
+            .map(|bar| {
+                (
+                    bar.event.title.as_str(),
+                    bar.first_day,
+                    bar.last_day,
+                    bar.lane,
+                )
+            })
+            .collect();
+        assert_eq!(bars, vec![("Holiday", 0, 2, 0), ("Trip", 2, 3, 1)]);
+        assert_eq!(layout.all_day_lanes, 2);
+
+        let blocks: Vec<_> = layout
+            .timed
+            .iter()
+            .map(|b| {
+                (
+                    b.event.title.as_str(),
+                    b.start_row,
+                    b.end_row,
+                    b.lane,
+                    b.lanes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                ("Standup", 9, 10, 0, 1),
+                ("Review", 10, 12, 0, 2),
+                ("Call", 11, 13, 1, 2),
+                ("Lunch", 13, 14, 0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_visible_row() {
+        let events = vec![timed("Early", 19, (6, 30), Some((7, 0)))];
+        let layout = layout_week(&events, date(19), 30);
+        assert_eq!(first_visible_row(&layout, 30, 20), 13);
+        assert_eq!(first_visible_row(&WeekLayout::default(), 60, 10), 8);
+        // Late rows stay filled on short terminals
+        assert_eq!(first_visible_row(&WeekLayout::default(), 60, 20), 4);
+    }
+}
@@ -0,0 +1,622 @@
+//! Integration tests for the CalDAV sync provider against an in-process
+//! CalDAV server stand-in.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use chrono::{NaiveDate, NaiveTime};
+use rcal_lib::sync::{ConflictResolution, CALDAV_STATE_FILE};
+use rcal_lib::{
+    event_to_ical, parse_ical, CalDavSyncProvider, CalendarEvent, FileEventRepository,
+    SyncProvider, SyncStatus,
+};
+use tempfile::TempDir;
+
+const AUTH: &str = "Basic dXNlcjpzZWNyZXQ=";
+const COLLECTION: &str = "/dav/calendars/user/personal/";
+const TOKEN_PREFIX: &str = "http://rcal.test/sync/";
+
+#[derive(Default)]
+struct ServerState {
+    /// Resources of the calendar collection: name -> (etag, body).
+    resources: BTreeMap<String, (String, String)>,
+    /// Change log: (token, resource name).
+    log: Vec<(u64, String)>,
+    token: u64,
+    /// Tokens below this value are rejected as invalid.
+    min_token: u64,
+    etag_counter: u64,
+}
+
+impl ServerState {
+    fn record_change(&mut self, name: &str) {
+        self.token += 1;
+        self.log.push((self.token, name.to_string()));
+    }
+
+    fn next_etag(&mut self) -> String {
+        self.etag_counter += 1;
+        format!("\"etag-{}\"", self.etag_counter)
+    }
+}
+
+struct DavServer {
+    base_url: String,
+    state: Arc<Mutex<ServerState>>,
+}
+
+impl DavServer {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(ServerState::default()));
+        let server_state = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                handle_connection(stream, &server_state);
+            }
+        });
+        Self { base_url, state }
+    }
+
+    fn provider(&self) -> CalDavSyncProvider {
+        CalDavSyncProvider::new(format!("{}/dav/", self.base_url))
+            .with_credentials("user", "secret")
+    }
+
+    /// Creates or replaces a resource as another client would.
+    fn put_remote(&self, name: &str, body: &str) {
+        let mut state = self.state.lock().unwrap();
+        let etag = state.next_etag();
+        state
+            .resources
+            .insert(name.to_string(), (etag, body.to_string()));
+        state.record_change(name);
+    }
+
+    /// Deletes a resource as another client would.
+    fn delete_remote(&self, name: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.resources.remove(name);
+        state.record_change(name);
+    }
+
+    fn remote_events(&self) -> Vec<(String, CalendarEvent)> {
+        let state = self.state.lock().unwrap();
+        state
+            .resources
+            .iter()
+            .map(|(name, (_, body))| {
+                let parsed = parse_ical(body).unwrap().remove(0);
+                (name.clone(), parsed.event)
+            })
+            .collect()
+    }
+
+    /// Invalidates all previously issued sync tokens.
+    fn expire_tokens(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.min_token = state.token + 1;
+        state.log.clear();
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = BTreeMap::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    Some(Request {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+fn handle_connection(mut stream: TcpStream, state: &Mutex<ServerState>) {
+    let Some(request) = read_request(&mut stream) else {
+        return;
+    };
+    let (status, headers, body) =
+        if request.headers.get("authorization").map(String::as_str) != Some(AUTH) {
+            (401, Vec::new(), String::new())
+        } else {
+            respond(&request, &mut state.lock().unwrap())
+        };
+
+    let mut response = format!(
+        "HTTP/1.1 {status} Status\r\nConnection: close\r\nContent-Length: {}\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("\r\n");
+    response.push_str(&body);
+    let _ = stream.write_all(response.as_bytes());
+}
+
+fn multistatus(responses: &str, sync_token: Option<u64>) -> String {
+    let token = sync_token
+        .map(|t| format!("<d:sync-token>{TOKEN_PREFIX}{t}</d:sync-token>"))
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">{responses}{token}</d:multistatus>"#
+    )
+}
+
+fn prop_response(href: &str, props: &str) -> String {
+    format!(
+        "<d:response><d:href>{href}</d:href><d:propstat><d:prop>{props}</d:prop>\
+         <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"
+    )
+}
+
+fn collection_props(state: &ServerState) -> String {
+    format!(
+        "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>\
+         <d:displayname>Personal</d:displayname>\
+         <d:sync-token>{TOKEN_PREFIX}{}</d:sync-token><cs:getctag>ctag-{}</cs:getctag>",
+        state.token, state.token
+    )
+}
+
+fn respond(request: &Request, state: &mut ServerState) -> (u16, Vec<(String, String)>, String) {
+    let xml = vec![(
+        "Content-Type".to_string(),
+        "application/xml; charset=utf-8".to_string(),
+    )];
+    let depth = request.headers.get("depth").map(String::as_str);
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("PROPFIND", "/dav/") => {
+            let body = prop_response(
+                "/dav/",
+                "<d:resourcetype><d:collection/></d:resourcetype>\
+                 <d:current-user-principal><d:href>/dav/principals/user/</d:href></d:current-user-principal>",
+            );
+            (207, xml, multistatus(&body, None))
+        }
+        ("PROPFIND", "/dav/principals/user/") => {
+            let body = prop_response(
+                "/dav/principals/user/",
+                "<d:resourcetype><d:principal/></d:resourcetype>\
+                 <c:calendar-home-set><d:href>/dav/calendars/user/</d:href></c:calendar-home-set>",
+            );
+            (207, xml, multistatus(&body, None))
+        }
+        ("PROPFIND", "/dav/calendars/user/") => {
+            let mut body = prop_response(
+                "/dav/calendars/user/",
+                "<d:resourcetype><d:collection/></d:resourcetype>",
+            );
+            if depth == Some("1") {
+                body.push_str(&prop_response(
+                    "/dav/calendars/user/inbox/",
+                    "<d:resourcetype><d:collection/><c:schedule-inbox/></d:resourcetype>",
+                ));
+                body.push_str(&prop_response(COLLECTION, &collection_props(state)));
+            }
+            (207, xml, multistatus(&body, None))
+        }
+        ("PROPFIND", COLLECTION) => {
+            let mut body = prop_response(COLLECTION, &collection_props(state));
+            if depth == Some("1") {
+                for (name, (etag, _)) in &state.resources {
+                    body.push_str(&prop_response(
+                        &format!("{COLLECTION}{name}"),
+                        &format!(
+                            "<d:resourcetype/><d:getetag>{}</d:getetag>",
+                            etag.replace('"', "&quot;")
+                        ),
+                    ));
+                }
+            }
+            (207, xml, multistatus(&body, None))
+        }
+        ("REPORT", COLLECTION) => {
+            let token = request
+                .body
+                .split("<d:sync-token>")
+                .nth(1)
+                .and_then(|rest| rest.split("</d:sync-token>").next())
+                .and_then(|token| token.strip_prefix(TOKEN_PREFIX))
+                .and_then(|n| n.parse::<u64>().ok());
+            let Some(since) = token.filter(|t| *t >= state.min_token && *t <= state.token) else {
+                return (403, Vec::new(), String::new());
+            };
+
+            let mut changed: Vec<&String> = state
+                .log
+                .iter()
+                .filter(|(t, _)| *t > since)
+                .map(|(_, name)| name)
+                .collect();
+            changed.sort();
+            changed.dedup();
+
+            let mut body = String::new();
+            for name in changed {
+                let href = format!("{COLLECTION}{name}");
+                match state.resources.get(name) {
+                    Some((etag, _)) => body.push_str(&prop_response(
+                        &href,
+                        &format!("<d:getetag>{}</d:getetag>", etag.replace('"', "&quot;")),
+                    )),
+                    None => body.push_str(&format!(
+                        "<d:response><d:href>{href}</d:href>\
+                         <d:status>HTTP/1.1 404 Not Found</d:status></d:response>"
+                    )),
+                }
+            }
+            (207, xml, multistatus(&body, Some(state.token)))
+        }
+        (method, path) if path.starts_with(COLLECTION) => {
+            let name = path[COLLECTION.len()..].to_string();
+            let current_etag = state.resources.get(&name).map(|(etag, _)| etag.clone());
+            let if_match = request.headers.get("if-match");
+            if if_match.is_some() && if_match != current_etag.as_ref() {
+                return (412, Vec::new(), String::new());
+            }
+
+            match method {
+                "GET" => match state.resources.get(&name) {
+                    Some((etag, body)) => (
+                        200,
+                        vec![
+                            ("ETag".to_string(), etag.clone()),
+                            ("Content-Type".to_string(), "text/calendar".to_string()),
+                        ],
+                        body.clone(),
+                    ),
+                    None => (404, Vec::new(), String::new()),
+                },
+                "PUT" => {
+                    if request.headers.get("if-none-match").map(String::as_str) == Some("*")
+                        && current_etag.is_some()
+                    {
+                        return (412, Vec::new(), String::new());
+                    }
+                    let etag = state.next_etag();
+                    let status = if current_etag.is_some() { 204 } else { 201 };
+                    state
+                        .resources
+                        .insert(name.clone(), (etag.clone(), request.body.clone()));
+                    state.record_change(&name);
+                    (status, vec![("ETag".to_string(), etag)], String::new())
+                }
+                "DELETE" => {
+                    if state.resources.remove(&name).is_none() {
+                        return (404, Vec::new(), String::new());
+                    }
+                    state.record_change(&name);
+                    (204, Vec::new(), String::new())
+                }
+                _ => (405, Vec::new(), String::new()),
+            }
+        }
+        _ => (404, Vec::new(), String::new()),
+    }
+}
+
+fn test_event(title: &str, day: u32) -> CalendarEvent {
+    let mut event = CalendarEvent::new(
+        title.to_string(),
+        format!("{title} description"),
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+        NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+    );
+    event.end_time = Some(NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+    event
+}
+
+fn write_local(dir: &Path, file: &str, event: &CalendarEvent) {
+    fs::write(
+        dir.join(file),
+        FileEventRepository::event_to_markdown(event),
+    )
+    .unwrap();
+}
+
+fn read_local(dir: &Path, file: &str) -> CalendarEvent {
+    FileEventRepository::event_from_markdown(&fs::read_to_string(dir.join(file)).unwrap()).unwrap()
+}
+
+/// Starts a server and returns an initialized, fully synced calendar directory.
+fn setup() -> (DavServer, CalDavSyncProvider, TempDir) {
+    let server = DavServer::start();
+    let provider = server.provider();
+    let temp_dir = TempDir::new().unwrap();
+    provider.init(temp_dir.path()).unwrap();
+    provider.pull(temp_dir.path()).unwrap();
+    (server, provider, temp_dir)
+}
+
+#[test]
+fn test_caldav_init_discovers_calendar_collection() {
+    let server = DavServer::start();
+    let provider = server.provider();
+    let temp_dir = TempDir::new().unwrap();
+
+    assert_eq!(
+        provider.discover_collection().unwrap(),
+        format!("{}{COLLECTION}", server.base_url)
+    );
+    provider.init(temp_dir.path()).unwrap();
+    assert!(temp_dir.path().join(CALDAV_STATE_FILE).exists());
+
+    assert_eq!(
+        provider.pull(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+    assert_eq!(
+        provider.status(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+}
+
+#[test]
+fn test_caldav_init_rejects_bad_credentials() {
+    let server = DavServer::start();
+    let provider = CalDavSyncProvider::new(format!("{}/dav/", server.base_url))
+        .with_credentials("user", "wrong");
+    let temp_dir = TempDir::new().unwrap();
+
+    let err = provider.init(temp_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("401"));
+    assert!(!temp_dir.path().join(CALDAV_STATE_FILE).exists());
+}
+
+#[test]
+fn test_caldav_push_new_event() {
+    let (server, provider, temp_dir) = setup();
+    write_local(temp_dir.path(), "Dentist.md", &test_event("Dentist", 10));
+
+    assert_eq!(provider.status(temp_dir.path()).unwrap(), SyncStatus::Ahead);
+    assert_eq!(
+        provider.push(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+
+    let remote = server.remote_events();
+    assert_eq!(remote.len(), 1);
+    assert_eq!(remote[0].0, "Dentist.ics");
+    assert_eq!(remote[0].1.title, "Dentist");
+    assert_eq!(
+        remote[0].1.end_time,
+        Some(NaiveTime::from_hms_opt(10, 0, 0).unwrap())
+    );
+    assert_eq!(
+        provider.status(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+}
+
+#[test]
+fn test_caldav_pull_incremental_changes() {
+    let (server, provider, temp_dir) = setup();
+    server.put_remote("abc.ics", &event_to_ical(&test_event("Standup", 3), "abc"));
+
+    assert_eq!(
+        provider.status(temp_dir.path()).unwrap(),
+        SyncStatus::Behind
+    );
+    assert_eq!(
+        provider.pull(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+    assert_eq!(
+        read_local(temp_dir.path(), "Standup.md").start_date,
+        NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+    );
+
+    // Remote modification
+    let mut moved = test_event("Standup", 4);
+    moved.description = "Moved".to_string();
+    server.put_remote("abc.ics", &event_to_ical(&moved, "abc"));
+    provider.pull(temp_dir.path()).unwrap();
+    let local = read_local(temp_dir.path(), "Standup.md");
+    assert_eq!(local.start_date, moved.start_date);
+    assert_eq!(local.description, "Moved");
+
+    // Remote deletion
+    server.delete_remote("abc.ics");
+    assert_eq!(
+        provider.pull(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+    assert!(!temp_dir.path().join("Standup.md").exists());
+}
+
+#[test]
+fn test_caldav_push_local_changes_and_deletes() {
+    let (server, provider, temp_dir) = setup();
+    write_local(temp_dir.path(), "Review.md", &test_event("Review", 12));
+    provider.push(temp_dir.path()).unwrap();
+
+    let mut edited = test_event("Review", 13);
+    edited.description = "Edited locally".to_string();
+    write_local(temp_dir.path(), "Review.md", &edited);
+    provider.push(temp_dir.path()).unwrap();
+    let remote = server.remote_events();
+    assert_eq!(remote.len(), 1);
+    assert_eq!(remote[0].1.description, "Edited locally");
+
+    fs::remove_file(temp_dir.path().join("Review.md")).unwrap();
+    assert_eq!(
+        provider.push(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+    assert!(server.remote_events().is_empty());
+}
+
+#[test]
+fn test_caldav_conflict_detected_on_pull_and_resolved() {
+    let (server, provider, temp_dir) = setup();
+    write_local(temp_dir.path(), "Lunch.md", &test_event("Lunch", 20));
+    provider.push(temp_dir.path()).unwrap();
+
+    let mut local = test_event("Lunch", 20);
+    local.description = "Local edit".to_string();
+    write_local(temp_dir.path(), "Lunch.md", &local);
+    let mut remote = test_event("Lunch", 20);
+    remote.description = "Remote edit".to_string();
+    server.put_remote("Lunch.ics", &event_to_ical(&remote, "lunch"));
+
+    assert_eq!(
+        provider.pull(temp_dir.path()).unwrap(),
+        SyncStatus::Conflicts
+    );
+    assert_eq!(
+        read_local(temp_dir.path(), "Lunch.md").description,
+        "Local edit"
+    );
+    let conflicts = provider.conflicts(temp_dir.path()).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].file_name, "Lunch.md");
+    assert_eq!(
+        provider.status(temp_dir.path()).unwrap(),
+        SyncStatus::Conflicts
+    );
+
+    provider
+        .resolve_conflict(temp_dir.path(), "Lunch.md", ConflictResolution::KeepRemote)
+        .unwrap();
+    assert_eq!(
+        read_local(temp_dir.path(), "Lunch.md").description,
+        "Remote edit"
+    );
+    assert_eq!(
+        provider.status(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+}
+
+#[test]
+fn test_caldav_stale_push_records_conflict_and_keep_local() {
+    let (server, provider, temp_dir) = setup();
+    write_local(temp_dir.path(), "Gym.md", &test_event("Gym", 5));
+    provider.push(temp_dir.path()).unwrap();
+
+    // Remote change not pulled yet
+    let mut remote = test_event("Gym", 5);
+    remote.description = "Remote edit".to_string();
+    server.put_remote("Gym.ics", &event_to_ical(&remote, "gym"));
+    let mut local = test_event("Gym", 5);
+    local.description = "Local edit".to_string();
+    write_local(temp_dir.path(), "Gym.md", &local);
+
+    assert_eq!(
+        provider.push(temp_dir.path()).unwrap(),
+        SyncStatus::Conflicts
+    );
+    assert_eq!(server.remote_events()[0].1.description, "Remote edit");
+
+    provider
+        .resolve_conflict(temp_dir.path(), "Gym.md", ConflictResolution::KeepLocal)
+        .unwrap();
+    provider.push(temp_dir.path()).unwrap();
+    assert_eq!(server.remote_events()[0].1.description, "Local edit");
+}
+
+#[test]
+fn test_caldav_conflict_keep_both_versions() {
+    let (server, provider, temp_dir) = setup();
+    write_local(temp_dir.path(), "Lunch.md", &test_event("Lunch", 20));
+    provider.push(temp_dir.path()).unwrap();
+
+    let mut local = test_event("Lunch", 20);
+    local.description = "Local edit".to_string();
+    write_local(temp_dir.path(), "Lunch.md", &local);
+    let mut remote = test_event("Lunch", 20);
+    remote.description = "Remote edit".to_string();
+    server.put_remote("Lunch.ics", &event_to_ical(&remote, "lunch"));
+    provider.pull(temp_dir.path()).unwrap();
+
+    let conflicts = provider.sync_conflicts(temp_dir.path()).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(
+        conflicts[0].local.as_ref().unwrap().description,
+        "Local edit"
+    );
+    assert_eq!(
+        conflicts[0].remote.as_ref().unwrap().description,
+        "Remote edit"
+    );
+
+    let status = provider
+        .resolve_sync_conflicts(temp_dir.path(), &[conflicts[0].keep_both()])
+        .unwrap();
+    assert_ne!(status, SyncStatus::Conflicts);
+    assert_eq!(
+        read_local(temp_dir.path(), "Lunch_1.md").description,
+        "Remote edit"
+    );
+    let mut descriptions: Vec<String> = server
+        .remote_events()
+        .into_iter()
+        .map(|(_, event)| event.description)
+        .collect();
+    descriptions.sort();
+    assert_eq!(descriptions, vec!["Local edit", "Remote edit"]);
+}
+
+#[test]
+fn test_caldav_pull_falls_back_when_token_invalid() {
+    let (server, provider, temp_dir) = setup();
+    server.put_remote("one.ics", &event_to_ical(&test_event("One", 1), "one"));
+    server.put_remote("two.ics", &event_to_ical(&test_event("Two", 2), "two"));
+    provider.pull(temp_dir.path()).unwrap();
+
+    server.expire_tokens();
+    server.delete_remote("one.ics");
+    server.put_remote(
+        "three.ics",
+        &event_to_ical(&test_event("Three", 3), "three"),
+    );
+
+    assert_eq!(
+        provider.pull(temp_dir.path()).unwrap(),
+        SyncStatus::UpToDate
+    );
+    assert!(!temp_dir.path().join("One.md").exists());
+    assert!(temp_dir.path().join("Two.md").exists());
+    assert!(temp_dir.path().join("Three.md").exists());
+}
@@ -0,0 +1,262 @@
+//! Runs the same sync scenarios against the `git` CLI provider and the native
+//! libgit2 provider, using local bare repositories as remotes.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use chrono::{NaiveDate, NaiveTime};
+use rcal_lib::sync::{
+    restore_revision, ConflictChoices, ConflictSide, GitSyncProvider, SyncProvider,
+};
+use rcal_lib::{CalendarEvent, EventField, FileEventRepository, SyncStatus};
+use tempfile::TempDir;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Backend {
+    Cli,
+    #[cfg(feature = "native-git")]
+    Native,
+}
+
+impl Backend {
+    fn provider(self, url: &str) -> Box<dyn SyncProvider> {
+        match self {
+            Backend::Cli => Box::new(GitSyncProvider::new(url.to_string())),
+            #[cfg(feature = "native-git")]
+            Backend::Native => Box::new(rcal_lib::NativeGitSyncProvider::new(url.to_string())),
+        }
+    }
+
+    /// Creates an initialized client checkout of the remote.
+    fn client(self, url: &str) -> (TempDir, Box<dyn SyncProvider>) {
+        let temp_dir = TempDir::new().unwrap();
+        if self == Backend::Cli {
+            // The CLI provider relies on the user's git setup, which the
+            // sandbox doesn't have
+            git(temp_dir.path(), &["init", "-b", "main"]);
+            git(temp_dir.path(), &["config", "user.name", "rcal test"]);
+            git(
+                temp_dir.path(),
+                &["config", "user.email", "test@rcal.invalid"],
+            );
+        }
+        let provider = self.provider(url);
+        provider.init(temp_dir.path()).unwrap();
+        (temp_dir, provider)
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+}
+
+fn bare_remote() -> (TempDir, String) {
+    let temp_dir = TempDir::new().unwrap();
+    git(temp_dir.path(), &["init", "--bare", "-b", "main"]);
+    let url = format!("file://{}", temp_dir.path().display());
+    (temp_dir, url)
+}
+
+fn push_and_pull_between_clients(backend: Backend) {
+    let (_remote, url) = bare_remote();
+    let (dir1, provider1) = backend.client(&url);
+    fs::write(dir1.path().join("Meeting.md"), "# Event: Meeting\n").unwrap();
+    assert_eq!(provider1.push(dir1.path()).unwrap(), SyncStatus::UpToDate);
+
+    let (dir2, provider2) = backend.client(&url);
+    provider2.pull(dir2.path()).unwrap();
+    assert_eq!(
+        fs::read_to_string(dir2.path().join("Meeting.md")).unwrap(),
+        "# Event: Meeting\n"
+    );
+}
+
+fn diverged_clients_are_rebased(backend: Backend) {
+    let (_remote, url) = bare_remote();
+    let (dir1, provider1) = backend.client(&url);
+    fs::write(dir1.path().join("A.md"), "a").unwrap();
+    provider1.push(dir1.path()).unwrap();
+    let (dir2, provider2) = backend.client(&url);
+    provider2.pull(dir2.path()).unwrap();
+
+    fs::write(dir1.path().join("B.md"), "b").unwrap();
+    provider1.push(dir1.path()).unwrap();
+    // The second client commits but can't push until it has pulled
+    fs::write(dir2.path().join("C.md"), "c").unwrap();
+    assert!(provider2.push(dir2.path()).is_err());
+
+    provider2.pull(dir2.path()).unwrap();
+    assert_eq!(provider2.push(dir2.path()).unwrap(), SyncStatus::UpToDate);
+    provider1.pull(dir1.path()).unwrap();
+    for file in ["A.md", "B.md", "C.md"] {
+        assert!(dir1.path().join(file).exists());
+        assert!(dir2.path().join(file).exists());
+    }
+}
+
+fn conflicting_edits_fail_pull(backend: Backend) {
+    let (_remote, url) = bare_remote();
+    let (dir1, provider1) = backend.client(&url);
+    fs::write(dir1.path().join("A.md"), "base").unwrap();
+    provider1.push(dir1.path()).unwrap();
+    let (dir2, provider2) = backend.client(&url);
+    provider2.pull(dir2.path()).unwrap();
+
+    fs::write(dir1.path().join("A.md"), "one").unwrap();
+    provider1.push(dir1.path()).unwrap();
+    fs::write(dir2.path().join("A.md"), "two").unwrap();
+    assert!(provider2.push(dir2.path()).is_err());
+
+    let err = provider2.pull(dir2.path()).unwrap_err();
+    assert!(err.to_string().contains("onflict"), "{err}");
+}
+
+fn event_file(description: &str, hour: u32) -> String {
+    FileEventRepository::event_to_markdown(&CalendarEvent::new(
+        "Meeting".to_string(),
+        description.to_string(),
+        NaiveDate::from_ymd_opt(2025, 4, 2).unwrap(),
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+    ))
+}
+
+fn read_event(dir: &Path) -> CalendarEvent {
+    FileEventRepository::event_from_markdown(&fs::read_to_string(dir.join("Meeting.md")).unwrap())
+        .unwrap()
+}
+
+fn conflicts_are_resolved_per_field(backend: Backend) {
+    let (_remote, url) = bare_remote();
+    let (dir1, provider1) = backend.client(&url);
+    fs::write(dir1.path().join("Meeting.md"), event_file("base", 9)).unwrap();
+    provider1.push(dir1.path()).unwrap();
+    let (dir2, provider2) = backend.client(&url);
+    provider2.pull(dir2.path()).unwrap();
+
+    fs::write(dir1.path().join("Meeting.md"), event_file("Room 1", 9)).unwrap();
+    fs::write(dir1.path().join("Other.md"), "other").unwrap();
+    provider1.push(dir1.path()).unwrap();
+    fs::write(dir2.path().join("Meeting.md"), event_file("Room 2", 10)).unwrap();
+    assert!(provider2.push(dir2.path()).is_err());
+    assert!(provider2.pull(dir2.path()).is_err());
+
+    let conflicts = provider2.sync_conflicts(dir2.path()).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].file_name, "Meeting.md");
+    assert_eq!(conflicts[0].local.as_ref().unwrap().description, "Room 2");
+    assert_eq!(conflicts[0].remote.as_ref().unwrap().description, "Room 1");
+
+    // Keep the local time but take the remote description
+    let mut choices = ConflictChoices::default();
+    choices.set(EventField::Description, ConflictSide::Remote);
+    let resolution = conflicts[0].resolve(&choices);
+    let status = provider2
+        .resolve_sync_conflicts(dir2.path(), &[resolution])
+        .unwrap();
+    assert_ne!(status, SyncStatus::Conflicts);
+    assert!(provider2.sync_conflicts(dir2.path()).unwrap().is_empty());
+    assert!(dir2.path().join("Other.md").exists());
+
+    provider1.pull(dir1.path()).unwrap();
+    let merged = read_event(dir1.path());
+    assert_eq!(merged.description, "Room 1");
+    assert_eq!(
+        merged.start_time,
+        NaiveTime::from_hms_opt(10, 0, 0).unwrap()
+    );
+}
+
+fn history_restores_deleted_events(backend: Backend) {
+    let (_remote, url) = bare_remote();
+    let (dir, provider) = backend.client(&url);
+    assert!(provider
+        .event_history(dir.path(), "Meeting.md")
+        .unwrap()
+        .is_empty());
+    for (description, hour) in [("Room 1", 9), ("Room 2", 10)] {
+        fs::write(dir.path().join("Meeting.md"), event_file(description, hour)).unwrap();
+        provider.push(dir.path()).unwrap();
+    }
+    fs::remove_file(dir.path().join("Meeting.md")).unwrap();
+    provider.push(dir.path()).unwrap();
+
+    let history = provider.event_history(dir.path(), "Meeting.md").unwrap();
+    assert_eq!(history.len(), 3);
+    assert!(history[0].is_deletion());
+    assert_eq!(history[1].event.as_ref().unwrap().description, "Room 2");
+    let changed: Vec<EventField> = history[1].changes.iter().map(|c| c.field).collect();
+    assert_eq!(changed, vec![EventField::Times, EventField::Description]);
+    assert_eq!(history[2].changes.len(), EventField::ALL.len());
+    assert!(!history[2].author.is_empty());
+
+    let deleted = provider.deleted_events(dir.path()).unwrap();
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].commit, history[0].commit);
+    restore_revision(dir.path(), &deleted[0]).unwrap();
+    assert_eq!(read_event(dir.path()).description, "Room 2");
+    assert!(provider.deleted_events(dir.path()).unwrap().is_empty());
+
+    restore_revision(dir.path(), &history[2]).unwrap();
+    assert_eq!(read_event(dir.path()).description, "Room 1");
+}
+
+#[test]
+fn test_cli_push_and_pull_between_clients() {
+    push_and_pull_between_clients(Backend::Cli);
+}
+
+#[test]
+fn test_cli_diverged_clients_are_rebased() {
+    diverged_clients_are_rebased(Backend::Cli);
+}
+
+#[test]
+fn test_cli_conflicting_edits_fail_pull() {
+    conflicting_edits_fail_pull(Backend::Cli);
+}
+
+#[test]
+fn test_cli_conflicts_are_resolved_per_field() {
+    conflicts_are_resolved_per_field(Backend::Cli);
+}
+
+#[test]
+fn test_cli_history_restores_deleted_events() {
+    history_restores_deleted_events(Backend::Cli);
+}
+
+#[cfg(feature = "native-git")]
+#[test]
+fn test_native_push_and_pull_between_clients() {
+    push_and_pull_between_clients(Backend::Native);
+}
+
+#[cfg(feature = "native-git")]
+#[test]
+fn test_native_diverged_clients_are_rebased() {
+    diverged_clients_are_rebased(Backend::Native);
+}
+
+#[cfg(feature = "native-git")]
+#[test]
+fn test_native_conflicting_edits_fail_pull() {
+    conflicting_edits_fail_pull(Backend::Native);
+}
+
+#[cfg(feature = "native-git")]
+#[test]
+fn test_native_conflicts_are_resolved_per_field() {
+    conflicts_are_resolved_per_field(Backend::Native);
+}
+
+#[cfg(feature = "native-git")]
+#[test]
+fn test_native_history_restores_deleted_events() {
+    history_restores_deleted_events(Backend::Native);
+}
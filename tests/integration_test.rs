@@ -1,6 +1,7 @@
 use chrono::Utc;
 use chrono::{NaiveDate, NaiveTime};
-use crossterm::event::{Event, KeyCode, KeyEvent};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
 use rcal::app::{App, InputMode, PopupInputField};
 use rcal::event_handling::handle_event;
 use rcal_lib::{CalendarEvent, Recurrence};
@@ -1983,12 +1984,12 @@ fn test_yearly_recurring_event_creation_and_display() {
 }
 
 // Caching is now handled internally by EventService.
-/// Tests for cache behavior would need to be rewritten to test behavior rather than
-/// internal state. The functionality is still tested implicitly through other tests.
+// Tests for cache behavior would need to be rewritten to test behavior rather than
+// internal state. The functionality is still tested implicitly through other tests.
 
 // Caching is now handled internally by EventService.
-/// Tests for cache behavior would need to be rewritten to test behavior rather than
-/// internal state. The functionality is still tested implicitly through other tests.
+// Tests for cache behavior would need to be rewritten to test behavior rather than
+// internal state. The functionality is still tested implicitly through other tests.
 
 #[test]
 fn test_cache_invalidation_on_event_edit() {
@@ -2016,7 +2017,7 @@ fn test_cache_invalidation_on_event_edit() {
     // internal state.
 
     let events1 = app.get_all_events_for_range(start, end);
-    assert!(events1.len() > 0);
+    assert!(!events1.is_empty());
 
     app.invalidate_instance_cache(None);
 
@@ -2333,7 +2334,7 @@ fn test_feb29_event_cache_invalidation() {
     });
 
     let events1 = app.get_all_events_for_range(start, end);
-    assert!(events1.len() > 0);
+    assert!(!events1.is_empty());
 
     app.invalidate_instance_cache(None);
 
@@ -2524,3 +2525,1372 @@ fn test_sync_status_method() {
         _ => panic!("Expected Error status, got {:?}", status),
     }
 }
+
+#[test]
+fn test_failed_push_is_journaled_and_replayed() {
+    use rcal_lib::sync::{replay_journal, GitSyncProvider, SyncProvider, SYNC_JOURNAL_FILE};
+    use rcal_lib::{SyncJournal, SyncStatus};
+    use std::process::Command;
+
+    let remote_temp = TempDir::new().unwrap();
+    let remote_path = remote_temp.path().join("calendar.git");
+    let remote_url = format!("file://{}", remote_path.display());
+
+    let client_temp = TempDir::new().unwrap();
+    let client_path = client_temp.path();
+    Command::new("git")
+        .args(["init", "-b", "main"])
+        .current_dir(client_path)
+        .output()
+        .unwrap();
+    for args in [
+        vec!["config", "user.name", "rcal test"],
+        vec!["config", "user.email", "test@rcal.invalid"],
+        vec!["remote", "add", "origin", &remote_url],
+    ] {
+        Command::new("git")
+            .args(args)
+            .current_dir(client_path)
+            .output()
+            .unwrap();
+    }
+    let provider = GitSyncProvider::new(remote_url);
+
+    // The remote does not exist yet, so the push fails and is queued
+    let mut event = CalendarEvent {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: "Offline Event".to_string(),
+        description: String::new(),
+        recurrence: Recurrence::None,
+        is_recurring_instance: false,
+        base_date: None,
+        start_date: NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+        end_date: None,
+        start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        end_time: None,
+        is_all_day: false,
+    };
+    rcal::persistence::save_event_to_path(&mut event, client_path, Some(&provider)).unwrap();
+    let journal = SyncJournal::load(client_path).unwrap();
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal.operations[0].title, "Offline Event");
+    assert!(journal.operations[0].last_error.is_some());
+
+    // The TUI shows the queue length
+    let mut app = App::new_with_calendar_dir(client_path.to_path_buf());
+    app.sync_provider = Some(Box::new(GitSyncProvider::new(provider.remote_url.clone())));
+    app.refresh_pending_sync_operations();
+    assert_eq!(app.pending_sync_operations, 1);
+
+    // Once the remote is reachable, replaying flushes the queue
+    Command::new("git")
+        .args(["init", "--bare", remote_path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(replay_journal(&provider, client_path).unwrap(), 1);
+    assert_eq!(SyncJournal::pending_count(client_path), 0);
+    app.refresh_pending_sync_operations();
+    assert_eq!(app.pending_sync_operations, 0);
+
+    let files = Command::new("git")
+        .args(["ls-tree", "--name-only", "main"])
+        .current_dir(&remote_path)
+        .output()
+        .unwrap();
+    let files = String::from_utf8_lossy(&files.stdout);
+    assert!(files.contains("Offline_Event.md"));
+    assert!(!files.contains(SYNC_JOURNAL_FILE));
+    assert_eq!(provider.status(client_path).unwrap(), SyncStatus::UpToDate);
+}
+
+/// Sets up a calendar directory synced with a second directory in which
+/// "Standup" was edited on both sides, and returns an app in the sync popup.
+fn setup_conflicting_directory_sync() -> (App, TempDir, TempDir) {
+    use rcal_lib::sync::{DirectorySyncProvider, SyncProvider};
+    use rcal_lib::FileEventRepository;
+
+    let (mut app, local) = setup_app();
+    let remote = TempDir::new().unwrap();
+    let provider = DirectorySyncProvider::new(remote.path().to_path_buf());
+    provider.init(local.path()).unwrap();
+
+    let event = |description: &str, hour: u32| {
+        let mut event = CalendarEvent::new(
+            "Standup".to_string(),
+            description.to_string(),
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+            NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+        );
+        event.end_time = Some(NaiveTime::from_hms_opt(hour, 30, 0).unwrap());
+        FileEventRepository::event_to_markdown(&event)
+    };
+    std::fs::write(local.path().join("Standup.md"), event("base", 9)).unwrap();
+    provider.push(local.path()).unwrap();
+    std::fs::write(local.path().join("Standup.md"), event("Room 1", 9)).unwrap();
+    std::fs::write(remote.path().join("Standup.md"), event("Room 2", 10)).unwrap();
+    provider.pull(local.path()).unwrap();
+
+    app.sync_provider = Some(Box::new(provider));
+    app.input_mode = InputMode::Sync;
+    (app, local, remote)
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    handle_event(app, Event::Key(KeyEvent::from(code))).unwrap();
+}
+
+#[test]
+fn test_conflict_resolver_picks_sides_per_field() {
+    let (mut app, local, remote) = setup_conflicting_directory_sync();
+
+    press(&mut app, KeyCode::Char('c'));
+    assert_eq!(app.input_mode, InputMode::ConflictResolver);
+    assert_eq!(app.sync_conflicts.len(), 1);
+    assert_eq!(app.current_conflict().unwrap().file_name, "Standup.md");
+
+    // Take the remote description, keep the local time
+    while app.current_conflict_field() != rcal_lib::EventField::Description {
+        press(&mut app, KeyCode::Char('j'));
+    }
+    press(&mut app, KeyCode::Char('l'));
+    press(&mut app, KeyCode::Enter);
+
+    assert_eq!(app.input_mode, InputMode::Sync);
+    assert_eq!(app.sync_message, "Resolved 1 conflict(s)");
+    assert!(app.sync_conflicts.is_empty());
+    let standup = app
+        .events()
+        .iter()
+        .find(|e| e.title == "Standup")
+        .cloned()
+        .unwrap();
+    assert_eq!(standup.description, "Room 2");
+    assert_eq!(
+        standup.start_time,
+        NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+    );
+
+    let files: Vec<String> = std::fs::read_dir(local.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".md"))
+        .collect();
+    assert_eq!(files, vec!["Standup.md".to_string()]);
+    let remote_content = std::fs::read_to_string(remote.path().join("Standup.md")).unwrap();
+    assert!(remote_content.contains("Room 2"));
+}
+
+#[test]
+fn test_conflict_resolver_cancel_edit_then_keep_both() {
+    let (mut app, local, _remote) = setup_conflicting_directory_sync();
+    press(&mut app, KeyCode::Char('c'));
+
+    // Editing opens the event popup with the merged result
+    press(&mut app, KeyCode::Char('e'));
+    assert_eq!(app.input_mode, InputMode::EditingEventPopup);
+    assert!(app.is_editing_conflict);
+    assert_eq!(app.popup_event_description, "Room 1");
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::ConflictResolver);
+    assert!(!app.is_editing_conflict);
+
+    press(&mut app, KeyCode::Char('b'));
+    assert_eq!(app.input_mode, InputMode::Sync);
+    assert!(local.path().join("Standup.md").exists());
+    assert!(local.path().join("Standup_1.md").exists());
+    assert_eq!(
+        app.events().iter().filter(|e| e.title == "Standup").count(),
+        2
+    );
+}
+
+#[test]
+fn test_conflict_resolver_edit_merged_result() {
+    let (mut app, local, _remote) = setup_conflicting_directory_sync();
+    press(&mut app, KeyCode::Char('c'));
+    press(&mut app, KeyCode::Char('e'));
+
+    app.popup_event_description = "Room 3".to_string();
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::Sync);
+    let content = std::fs::read_to_string(local.path().join("Standup.md")).unwrap();
+    assert!(content.contains("Room 3"));
+}
+
+#[test]
+fn test_conflict_resolver_cancel() {
+    let (mut app, _local, _remote) = setup_conflicting_directory_sync();
+    press(&mut app, KeyCode::Char('c'));
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::Sync);
+    assert_eq!(app.sync_message, "Conflict resolution cancelled");
+    assert!(app.sync_conflicts.is_empty());
+}
+
+/// Creates an app whose calendar directory is a git repository holding two
+/// committed versions of an event.
+fn setup_event_history() -> (App, TempDir) {
+    use rcal_lib::FileEventRepository;
+    use std::process::Command;
+
+    let (mut app, local) = setup_app();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(local.path())
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+    git(&["init", "-b", "main"]);
+    git(&["config", "user.name", "rcal test"]);
+    git(&["config", "user.email", "test@rcal.invalid"]);
+    for description in ["Room 1", "Room 2"] {
+        let event = CalendarEvent::new(
+            "Standup".to_string(),
+            description.to_string(),
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        );
+        std::fs::write(
+            local.path().join("Standup.md"),
+            FileEventRepository::event_to_markdown(&event),
+        )
+        .unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-m", description]);
+    }
+
+    app.set_events(rcal::persistence::load_events_from_path(local.path()).unwrap());
+    app.sync_provider = Some(Box::new(rcal_lib::GitSyncProvider::new(String::new())));
+    app.date = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+    (app, local)
+}
+
+#[test]
+fn test_event_history_restores_earlier_version() {
+    let (mut app, local) = setup_event_history();
+
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('H'));
+    assert_eq!(app.input_mode, InputMode::EventHistory);
+    assert_eq!(app.history_file_name, "Standup.md");
+    assert_eq!(app.event_history.len(), 2);
+    assert_eq!(app.event_history[0].summary, "Room 2");
+    assert_eq!(app.event_history[0].changes.len(), 1);
+
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.selected_revision().unwrap().summary, "Room 1");
+    press(&mut app, KeyCode::Char('r'));
+
+    assert_eq!(app.input_mode, InputMode::ViewEventsPopup);
+    assert_eq!(app.events_to_display_in_popup[0].description, "Room 1");
+    let content = std::fs::read_to_string(local.path().join("Standup.md")).unwrap();
+    assert!(content.contains("Room 1"));
+}
+
+#[test]
+fn test_event_history_without_sync() {
+    let (mut app, _local) = setup_event_history();
+    app.sync_provider = None;
+
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('H'));
+    assert_eq!(app.input_mode, InputMode::EventHistory);
+    assert!(app.event_history.is_empty());
+    assert_eq!(app.history_message, "Event history requires git sync");
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::ViewEventsPopup);
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+fn setup_search() -> (App, TempDir) {
+    let (app, temp_dir) = setup_app();
+    for (title, description, date) in [
+        (
+            "Dentist",
+            "Dr. Molar",
+            NaiveDate::from_ymd_opt(2025, 3, 2).unwrap(),
+        ),
+        (
+            "Lunch",
+            "Near the dentist",
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+        ),
+        ("Gym", "", NaiveDate::from_ymd_opt(2025, 7, 9).unwrap()),
+    ] {
+        app.add_event(CalendarEvent::new(
+            title.to_string(),
+            description.to_string(),
+            date,
+            NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+        ));
+    }
+    (app, temp_dir)
+}
+
+#[test]
+fn test_search_jumps_to_selected_result() {
+    let (mut app, _temp_dir) = setup_search();
+
+    press(&mut app, KeyCode::Char('/'));
+    assert_eq!(app.input_mode, InputMode::Search);
+    type_text(&mut app, "DENT");
+    let titles: Vec<_> = app
+        .search_results
+        .iter()
+        .map(|e| e.title.as_str())
+        .collect();
+    assert_eq!(titles, vec!["Dentist", "Lunch"]);
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::Normal);
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2025, 6, 1).unwrap());
+    assert_eq!(app.view_start_month, 6);
+    assert_eq!(app.view_start_year, 2025);
+
+    // The query is kept for the next search
+    press(&mut app, KeyCode::Char('/'));
+    assert_eq!(app.search_query, "DENT");
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::Normal);
+}
+
+#[test]
+fn test_search_regex_mode() {
+    let (mut app, _temp_dir) = setup_search();
+
+    press(&mut app, KeyCode::Char('/'));
+    press(&mut app, KeyCode::Tab);
+    assert!(app.search_regex);
+    type_text(&mut app, "^(gym|lunch");
+    assert!(app.search_results.is_empty());
+    assert!(app.search_message.contains("invalid regular expression"));
+
+    type_text(&mut app, ")$");
+    assert_eq!(app.search_results.len(), 2);
+    assert!(app.search_message.is_empty());
+
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Tab);
+    assert!(!app.search_regex);
+    assert!(app.search_results.is_empty());
+    assert_eq!(app.search_message, "No matching events");
+    // Enter without results stays in the popup
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::Search);
+}
+
+fn meeting(title: &str, date: NaiveDate, start: u32, end: u32) -> CalendarEvent {
+    let mut event = CalendarEvent::new(
+        title.to_string(),
+        String::new(),
+        date,
+        NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+    );
+    event.end_time = Some(NaiveTime::from_hms_opt(end, 0, 0).unwrap());
+    event
+}
+
+#[test]
+fn test_propose_free_slots_in_event_popup() {
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    app.add_event(meeting("Review", monday, 9, 11));
+    app.add_event(meeting("Lunch", monday, 12, 13));
+
+    app.current_date_for_new_event = monday;
+    app.popup_event_time = "10:00".to_string();
+    app.popup_event_end_time = "11:30".to_string();
+    let now = monday.and_hms_opt(8, 50, 0).unwrap();
+
+    // 90 minutes don't fit between the review and lunch
+    assert!(app.propose_free_slot(now));
+    assert_eq!(app.current_date_for_new_event, monday);
+    assert_eq!(app.popup_event_time, "13:00");
+    assert_eq!(app.popup_event_end_time, "14:30");
+
+    assert!(app.propose_free_slot(now));
+    assert_eq!(app.current_date_for_new_event, monday.succ_opt().unwrap());
+    assert_eq!(app.popup_event_time, "09:00");
+    assert_eq!(app.free_slot_index, 1);
+    assert_eq!(app.free_slots.len(), 14);
+}
+
+#[test]
+fn test_ctrl_f_fills_event_popup_with_free_slot() {
+    let (mut app, _temp_dir) = setup_app();
+    let date = NaiveDate::from_ymd_opt(2099, 1, 5).unwrap();
+    app.add_event(meeting("Standup", date, 9, 10));
+    app.date = date;
+
+    press(&mut app, KeyCode::Char('a'));
+    type_text(&mut app, "Planning");
+    let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+    handle_event(&mut app, Event::Key(ctrl_f)).unwrap();
+    assert_eq!(app.popup_event_title, "Planning");
+    assert_eq!(app.popup_event_time, "10:00");
+    assert_eq!(app.popup_event_end_time, "11:00");
+
+    press(&mut app, KeyCode::Enter);
+    let saved = app
+        .events()
+        .iter()
+        .find(|event| event.title == "Planning")
+        .cloned()
+        .unwrap();
+    assert_eq!(saved.start_date, date);
+    assert_eq!(saved.start_time, NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+    assert_eq!(saved.end_time, NaiveTime::from_hms_opt(11, 0, 0));
+}
+
+#[test]
+fn test_week_view_toggle_and_render() {
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    app.add_event(meeting("Review", monday, 9, 11));
+    app.date = monday;
+
+    press(&mut app, KeyCode::Char('w'));
+    assert_eq!(app.view_mode, rcal::app::ViewMode::Week);
+    // h/j/k/l keep moving the selected date
+    press(&mut app, KeyCode::Char('l'));
+    assert_eq!(app.date, monday.succ_opt().unwrap());
+    press(&mut app, KeyCode::Char('h'));
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| rcal::ui::ui(f, &mut app)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Week 43: Oct 19 - Oct 25, 2026"));
+    assert!(screen.contains("Mo 19"));
+    assert!(screen.contains("09:00 Review"));
+    assert!(screen.contains("w: month, y: year"));
+
+    press(&mut app, KeyCode::Char('w'));
+    assert_eq!(app.view_mode, rcal::app::ViewMode::Month);
+}
+
+#[test]
+fn test_day_view_navigation() {
+    use rcal::app::ViewMode;
+
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    let mut review = meeting("Review", monday, 9, 11);
+    review.description = "Quarterly numbers\nBring laptop".to_string();
+    app.add_event(review);
+    app.add_event(meeting("Standup", monday, 8, 9));
+    app.add_event(meeting("Retro", monday.succ_opt().unwrap(), 14, 15));
+    app.date = monday;
+    app.view_mode = ViewMode::Week;
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.view_mode, ViewMode::Day);
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.day_view_index, 1);
+    assert_eq!(app.selected_day_event().unwrap().title, "Review");
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| rcal::ui::ui(f, &mut app)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Monday, October 19, 2026"));
+    assert!(screen.contains("08:00-09:00 │ Standup"));
+    assert!(screen.contains("Bring laptop"));
+    assert!(screen.contains("11:00-18:00 ┆ free 7h"));
+
+    // Enter opens the view popup on the selected event
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::ViewEventsPopup);
+    assert_eq!(
+        app.events_to_display_in_popup[app.selected_event_index].title,
+        "Review"
+    );
+    press(&mut app, KeyCode::Esc);
+
+    press(&mut app, KeyCode::Char('l'));
+    assert_eq!(app.date, monday.succ_opt().unwrap());
+    assert_eq!(app.day_view_index, 0);
+    assert_eq!(app.selected_day_event().unwrap().title, "Retro");
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.view_mode, ViewMode::Week);
+}
+
+fn render_screen(app: &mut App, width: u16, height: u16) -> Terminal<TestBackend> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| rcal::ui::ui(f, app)).unwrap();
+    terminal
+}
+
+#[test]
+fn test_month_count_follows_terminal_size() {
+    use rcal::ui::month_layout;
+
+    assert_eq!(month_layout(30, 20), (1, 1));
+    assert_eq!(month_layout(78, 21), (3, 2));
+    assert_eq!(month_layout(40, 30), (3, 1));
+    assert_eq!(month_layout(100, 20), (6, 3));
+    assert_eq!(month_layout(130, 30), (12, 4));
+
+    let (mut app, _temp_dir) = setup_app();
+    app.date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    app.view_start_month = 10;
+    app.view_start_year = 2026;
+    render_screen(&mut app, 132, 32);
+    assert_eq!(app.visible_months, 12);
+    assert_eq!(app.month_columns, 4);
+
+    // Shrinking to one month keeps the selected date in view
+    render_screen(&mut app, 34, 20);
+    assert_eq!(app.visible_months, 1);
+    assert_eq!((app.view_start_year, app.view_start_month), (2026, 10));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.view_start_month, 11);
+}
+
+#[test]
+fn test_year_view_jumps_months_and_shades_days() {
+    use rcal::app::ViewMode;
+
+    let (mut app, _temp_dir) = setup_app();
+    let date = NaiveDate::from_ymd_opt(2026, 1, 31).unwrap();
+    app.date = date;
+    for (title, start) in [("Standup", 9), ("Review", 11), ("Lunch", 12)] {
+        app.add_event(meeting(title, date, start, start + 1));
+    }
+
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(app.view_mode, ViewMode::Year);
+    let terminal = render_screen(&mut app, 132, 40);
+    assert_eq!(app.month_columns, 4);
+    let buffer = terminal.backend().buffer();
+    let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("January 2026"));
+    assert!(screen.contains("December 2026"));
+    // The selected day is highlighted; shade another day with events
+    press(&mut app, KeyCode::Char('l'));
+    let terminal = render_screen(&mut app, 132, 40);
+    let buffer = terminal.backend().buffer();
+    let shaded = buffer
+        .content()
+        .iter()
+        .filter(|cell| cell.bg == ratatui::style::Color::Indexed(34))
+        .count();
+    assert!(shaded > 0);
+
+    // Month steps keep the day within the month
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 6, 28).unwrap());
+    press(&mut app, KeyCode::PageUp);
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2025, 6, 28).unwrap());
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.view_mode, ViewMode::Month);
+    assert_eq!(app.view_start_year, 2025);
+    press(&mut app, KeyCode::Char('y'));
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.view_mode, ViewMode::Month);
+}
+
+fn reload(app: &App) {
+    let events = rcal::persistence::load_events_from_path(&app.calendar_dir).unwrap();
+    app.set_events(events);
+    app.invalidate_instance_cache(None);
+}
+
+fn stored_titles(app: &App) -> Vec<String> {
+    let mut titles: Vec<String> = rcal::persistence::load_events_from_path(&app.calendar_dir)
+        .unwrap()
+        .into_iter()
+        .map(|event| event.title)
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn test_undo_redo_delete_survives_reload() {
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    let mut standup = meeting("Standup", monday, 9, 10);
+    rcal::persistence::save_event_to_path(&mut standup, &app.calendar_dir, None).unwrap();
+    reload(&app);
+    app.date = monday;
+
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(app.status_message, "Nothing to undo");
+
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('d'));
+    press(&mut app, KeyCode::Char('y'));
+    assert!(app.events_to_display_in_popup.is_empty());
+    assert!(stored_titles(&app).is_empty());
+
+    // A background reload regenerates ids; the history still applies
+    reload(&app);
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(app.status_message, "Undid delete of 'Standup'");
+    assert_eq!(app.events_to_display_in_popup.len(), 1);
+    assert_eq!(stored_titles(&app), vec!["Standup"]);
+
+    reload(&app);
+    press(&mut app, KeyCode::Esc);
+    handle_event(
+        &mut app,
+        Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+    )
+    .unwrap();
+    assert_eq!(app.status_message, "Redid delete of 'Standup'");
+    assert!(app.events_on(monday).is_empty());
+    assert!(stored_titles(&app).is_empty());
+
+    let mut terminal = render_screen(&mut app, 80, 24);
+    let screen: String = terminal
+        .backend_mut()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Redid delete of 'Standup'"));
+
+    // The message lasts until the next key press
+    press(&mut app, KeyCode::Char('l'));
+    assert!(app.status_message.is_empty());
+}
+
+#[test]
+fn test_undo_edit_restores_previous_event() {
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    let mut review = meeting("Review", monday, 9, 10);
+    rcal::persistence::save_event_to_path(&mut review, &app.calendar_dir, None).unwrap();
+    reload(&app);
+    app.date = monday;
+
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('e'));
+    type_text(&mut app, " 2");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(stored_titles(&app), vec!["Review 2"]);
+
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(app.status_message, "Undid edit of 'Review 2'");
+    assert_eq!(stored_titles(&app), vec!["Review"]);
+    assert_eq!(app.events_to_display_in_popup[0].title, "Review");
+
+    // A new change clears what could be redone
+    press(&mut app, KeyCode::Char('e'));
+    type_text(&mut app, " 3");
+    press(&mut app, KeyCode::Enter);
+    handle_event(
+        &mut app,
+        Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+    )
+    .unwrap();
+    assert_eq!(app.status_message, "Nothing to redo");
+    assert_eq!(stored_titles(&app), vec!["Review 3"]);
+
+    // Undo refuses to touch an event changed elsewhere
+    for entry in std::fs::read_dir(&app.calendar_dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|extension| extension == "md") {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+    reload(&app);
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(
+        app.status_message,
+        "Cannot undo: 'Review 3' was changed elsewhere"
+    );
+    assert!(stored_titles(&app).is_empty());
+}
+
+#[test]
+fn test_yank_cut_and_paste_events_between_days() {
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    let mut trip = meeting("Trip", monday, 9, 17);
+    trip.end_date = Some(monday + chrono::Duration::days(2));
+    rcal::persistence::save_event_to_path(&mut trip, &app.calendar_dir, None).unwrap();
+    reload(&app);
+    app.date = monday;
+
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(
+        app.status_message,
+        "Copied 'Trip', p pastes it on the selected day"
+    );
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char('p'));
+    assert_eq!(app.status_message, "Pasted 'Trip' to 2026-10-26");
+
+    let stored = rcal::persistence::load_events_from_path(&app.calendar_dir).unwrap();
+    let copy = stored
+        .iter()
+        .find(|event| event.start_date == app.date)
+        .unwrap();
+    assert_eq!(copy.end_date, Some(app.date + chrono::Duration::days(2)));
+    assert_eq!(copy.start_time, trip.start_time);
+    assert_eq!(copy.end_time, trip.end_time);
+    assert_eq!(stored.len(), 2);
+
+    // Pasting onto a day that already has the event is refused
+    press(&mut app, KeyCode::Char('p'));
+    assert_eq!(
+        app.status_message,
+        "Cannot paste: 'Trip' is already on 2026-10-26"
+    );
+
+    // Cutting moves the event on paste
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('x'));
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Char('p'));
+    assert_eq!(app.status_message, "Moved 'Trip' to 2026-10-27");
+    assert!(app.clipboard.is_none());
+    let mut dates: Vec<_> = rcal::persistence::load_events_from_path(&app.calendar_dir)
+        .unwrap()
+        .into_iter()
+        .map(|event| event.start_date)
+        .collect();
+    dates.sort();
+    assert_eq!(dates, vec![monday, app.date]);
+}
+
+#[test]
+fn test_move_duplicate_and_shift_recurring_series() {
+    let (mut app, _temp_dir) = setup_app();
+    let first = NaiveDate::from_ymd_opt(2026, 10, 5).unwrap();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    let mut standup = meeting("Standup", first, 9, 10);
+    standup.recurrence = Recurrence::Weekly;
+    rcal::persistence::save_event_to_path(&mut standup, &app.calendar_dir, None).unwrap();
+    reload(&app);
+    app.date = monday;
+
+    // Shifting an occurrence shifts the whole series and follows it
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('>'));
+    assert_eq!(app.status_message, "Moved 'Standup' to 2026-10-20");
+    assert_eq!(app.date, monday.succ_opt().unwrap());
+    assert_eq!(app.events_to_display_in_popup[0].title, "Standup");
+    let stored = rcal::persistence::load_events_from_path(&app.calendar_dir).unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].start_date, first.succ_opt().unwrap());
+    assert_eq!(stored[0].recurrence, Recurrence::Weekly);
+
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(app.status_message, "Undid move of 'Standup'");
+    let stored = rcal::persistence::load_events_from_path(&app.calendar_dir).unwrap();
+    assert_eq!(stored[0].start_date, first);
+
+    // The prompt takes offsets from the selected occurrence
+    app.date = monday;
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('m'));
+    assert_eq!(app.input_mode, InputMode::TargetDate);
+    type_text(&mut app, "+2x");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::TargetDate);
+    assert!(app.status_message.starts_with("Invalid offset"));
+    press(&mut app, KeyCode::Backspace);
+    type_text(&mut app, "d");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::ViewEventsPopup);
+    assert_eq!(app.status_message, "Moved 'Standup' to 2026-10-21");
+    let stored = rcal::persistence::load_events_from_path(&app.calendar_dir).unwrap();
+    assert_eq!(
+        stored[0].start_date,
+        NaiveDate::from_ymd_opt(2026, 10, 7).unwrap()
+    );
+
+    // Duplicating copies the series, keeping the original
+    press(&mut app, KeyCode::Char('D'));
+    type_text(&mut app, "2026-10-23");
+    let mut terminal = render_screen(&mut app, 80, 24);
+    let screen: String = terminal
+        .backend_mut()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Duplicate 'Standup'"));
+    assert!(screen.contains("To: 2026-10-23"));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.status_message, "Duplicated 'Standup' to 2026-10-23");
+    let mut dates: Vec<_> = rcal::persistence::load_events_from_path(&app.calendar_dir)
+        .unwrap()
+        .into_iter()
+        .map(|event| (event.start_date, event.recurrence))
+        .collect();
+    dates.sort_by_key(|(date, _)| *date);
+    assert_eq!(
+        dates,
+        vec![
+            (
+                NaiveDate::from_ymd_opt(2026, 10, 7).unwrap(),
+                Recurrence::Weekly
+            ),
+            (
+                NaiveDate::from_ymd_opt(2026, 10, 9).unwrap(),
+                Recurrence::Weekly
+            ),
+        ]
+    );
+}
+
+fn find_target(app: &App, target: &rcal::mouse::HitTarget, width: u16, height: u16) -> (u16, u16) {
+    let hit_map = app.hit_map.borrow();
+    (0..height)
+        .flat_map(|row| (0..width).map(move |column| (column, row)))
+        .find(|&(column, row)| hit_map.target_at(column, row) == Some(target))
+        .unwrap_or_else(|| panic!("{target:?} not drawn"))
+}
+
+fn mouse(app: &mut App, kind: crossterm::event::MouseEventKind, (column, row): (u16, u16)) {
+    let event = crossterm::event::MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    };
+    handle_event(app, Event::Mouse(event)).unwrap();
+}
+
+fn click(app: &mut App, position: (u16, u16)) {
+    use crossterm::event::{MouseButton, MouseEventKind};
+    mouse(app, MouseEventKind::Down(MouseButton::Left), position);
+}
+
+/// Draws the app and clicks on where `target` was drawn.
+fn click_on(app: &mut App, target: &rcal::mouse::HitTarget) {
+    render_screen(app, 80, 24);
+    let position = find_target(app, target, 80, 24);
+    click(app, position);
+}
+
+#[test]
+fn test_mouse_selects_dates_and_scrolls_months() {
+    use crossterm::event::MouseEventKind;
+    use rcal::app::ViewMode;
+    use rcal::mouse::HitTarget;
+
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    let friday = NaiveDate::from_ymd_opt(2026, 10, 23).unwrap();
+    app.date = monday;
+    app.adjust_view_boundaries();
+
+    render_screen(&mut app, 80, 24);
+    let cell = find_target(&app, &HitTarget::Date(friday), 80, 24);
+    let screen = render_screen(&mut app, 80, 24);
+    assert_eq!(screen.backend().buffer()[cell].symbol(), "2");
+
+    click(&mut app, cell);
+    assert_eq!(app.date, friday);
+    assert_eq!(app.view_mode, ViewMode::Month);
+    render_screen(&mut app, 80, 24);
+    click(&mut app, cell);
+    assert_eq!(app.view_mode, ViewMode::Day);
+    press(&mut app, KeyCode::Esc);
+
+    // Clicking outside anything resets the double click
+    render_screen(&mut app, 80, 24);
+    click(&mut app, cell);
+    click(&mut app, (0, 0));
+    click(&mut app, cell);
+    assert_eq!(app.view_mode, ViewMode::Month);
+
+    mouse(&mut app, MouseEventKind::ScrollDown, (0, 0));
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 11, 23).unwrap());
+    mouse(&mut app, MouseEventKind::ScrollUp, (0, 0));
+    mouse(&mut app, MouseEventKind::ScrollUp, (0, 0));
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 9, 23).unwrap());
+
+    // Week view columns select their day
+    app.date = monday;
+    app.view_mode = ViewMode::Week;
+    click_on(&mut app, &HitTarget::Date(friday));
+    assert_eq!(app.date, friday);
+}
+
+#[test]
+fn test_mouse_clicks_popup_events_and_buttons() {
+    use rcal::mouse::HitTarget;
+
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    for (title, start) in [("Standup", 9), ("Review", 14)] {
+        let mut event = meeting(title, monday, start, start + 1);
+        rcal::persistence::save_event_to_path(&mut event, &app.calendar_dir, None).unwrap();
+    }
+    reload(&app);
+    app.date = monday;
+
+    // The main hints are buttons
+    click_on(&mut app, &HitTarget::Key(KeyCode::Char('a')));
+    assert_eq!(app.input_mode, InputMode::EditingEventPopup);
+    click_on(&mut app, &HitTarget::Key(KeyCode::Esc));
+    assert_eq!(app.input_mode, InputMode::Normal);
+
+    press(&mut app, KeyCode::Char('o'));
+    render_screen(&mut app, 80, 24);
+    let review = find_target(&app, &HitTarget::PopupEvent(1), 80, 24);
+    click(&mut app, review);
+    assert_eq!(app.selected_event_index, 1);
+
+    // The popup covers the dates below it
+    let hit_map = app.hit_map.borrow();
+    assert_eq!(
+        hit_map.target_at(review.0, review.1 + 3),
+        Some(&HitTarget::Popup)
+    );
+    drop(hit_map);
+
+    click_on(&mut app, &HitTarget::Key(KeyCode::Char('d')));
+    assert_eq!(app.input_mode, InputMode::DeleteConfirmation);
+    click_on(&mut app, &HitTarget::Key(KeyCode::Char('y')));
+    assert_eq!(app.input_mode, InputMode::ViewEventsPopup);
+    assert_eq!(stored_titles(&app), vec!["Standup"]);
+
+    // Double clicking an event edits it
+    render_screen(&mut app, 80, 24);
+    let standup = find_target(&app, &HitTarget::PopupEvent(0), 80, 24);
+    click(&mut app, standup);
+    click(&mut app, standup);
+    assert_eq!(app.input_mode, InputMode::EditingEventPopup);
+    assert_eq!(app.popup_event_title, "Standup");
+}
+
+#[test]
+fn test_mouse_ignores_rebound_keys() {
+    use crossterm::event::MouseEventKind;
+    use rcal::keybindings::Keymap;
+    use rcal::mouse::HitTarget;
+
+    let (mut app, _temp_dir) = setup_app();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    for (title, start) in [("Standup", 9), ("Review", 14)] {
+        let mut event = meeting(title, monday, start, start + 1);
+        rcal::persistence::save_event_to_path(&mut event, &app.calendar_dir, None).unwrap();
+    }
+    reload(&app);
+    app.date = monday;
+    let config: toml::Value = toml::from_str(
+        "[keys.popup]\ndelete_event = \"e\"\nedit_event = \"E\"\nselect_next = \"n\"\nselect_previous = \"N\"\n",
+    )
+    .unwrap();
+    app.keymap = Keymap::from_config(config.get("keys")).unwrap();
+
+    // Scrolling still moves the selection
+    press(&mut app, KeyCode::Char('o'));
+    mouse(&mut app, MouseEventKind::ScrollDown, (0, 0));
+    assert_eq!(app.selected_event_index, 1);
+    mouse(&mut app, MouseEventKind::ScrollUp, (0, 0));
+    assert_eq!(app.selected_event_index, 0);
+
+    // Double clicking edits the event instead of deleting it
+    render_screen(&mut app, 80, 24);
+    let review = find_target(&app, &HitTarget::PopupEvent(1), 80, 24);
+    click(&mut app, review);
+    click(&mut app, review);
+    assert_eq!(app.input_mode, InputMode::EditingEventPopup);
+    assert_eq!(app.popup_event_title, "Review");
+    assert_eq!(stored_titles(&app), vec!["Review", "Standup"]);
+}
+
+#[test]
+fn test_custom_key_bindings() {
+    use rcal::keybindings::Keymap;
+
+    let (mut app, _temp_dir) = setup_app();
+    let config: toml::Value =
+        toml::from_str("[keys.normal]\nadd_event = \"n\"\nquit = \"ctrl+q\"\n").unwrap();
+    app.keymap = Keymap::from_config(config.get("keys")).unwrap();
+
+    // The default keys of rebound actions do nothing
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.input_mode, InputMode::Normal);
+    assert!(handle_event(&mut app, Event::Key(KeyEvent::from(KeyCode::Char('q')))).unwrap());
+
+    let terminal = render_screen(&mut app, 160, 30);
+    let buffer = terminal.backend().buffer();
+    let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Ctrl+q: quit, n: add"));
+
+    press(&mut app, KeyCode::Char('n'));
+    assert_eq!(app.input_mode, InputMode::EditingEventPopup);
+    press(&mut app, KeyCode::Esc);
+    let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+    assert!(!handle_event(&mut app, Event::Key(ctrl_q)).unwrap());
+}
+
+#[test]
+fn test_custom_text_mode_key_bindings() {
+    use rcal::keybindings::Keymap;
+
+    let (mut app, _temp_dir) = setup_app();
+    let config: toml::Value = toml::from_str(
+        "[keys.prompt]\ncancel = \"ctrl+c\"\n[keys.command_line]\nhistory_previous = \"ctrl+p\"\n[keys.form]\nnext_field = [\"tab\", \"ctrl+j\"]\n",
+    )
+    .unwrap();
+    app.keymap = Keymap::from_config(config.get("keys")).unwrap();
+
+    // Esc is no longer bound in the prompt, so it does not close it
+    press(&mut app, KeyCode::Char('g'));
+    assert_eq!(app.input_mode, InputMode::GoTo);
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::GoTo);
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    handle_event(&mut app, Event::Key(ctrl_c)).unwrap();
+    assert_eq!(app.input_mode, InputMode::Normal);
+
+    press(&mut app, KeyCode::Char(':'));
+    for c in "goto +1d".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Char(':'));
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.command_input, "");
+    let ctrl_p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+    handle_event(&mut app, Event::Key(ctrl_p)).unwrap();
+    assert_eq!(app.command_input, "goto +1d");
+    press(&mut app, KeyCode::Esc);
+
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.selected_input_field, PopupInputField::Title);
+    let ctrl_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+    handle_event(&mut app, Event::Key(ctrl_j)).unwrap();
+    assert_eq!(app.selected_input_field, PopupInputField::Time);
+    assert_eq!(app.popup_event_title, "");
+    press(&mut app, KeyCode::Esc);
+
+    press(&mut app, KeyCode::Char('?'));
+    let terminal = render_screen(&mut app, 100, 120);
+    let buffer = terminal.backend().buffer();
+    let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Event form"));
+    assert!(screen.contains("Go-to and target date prompts"));
+    assert!(screen.contains("Command line"));
+    assert!(screen.contains("Ctrl+c"));
+    assert!(screen.contains("Ctrl+p"));
+}
+
+#[test]
+fn test_help_screen_lists_active_bindings() {
+    use rcal::keybindings::Keymap;
+
+    let (mut app, _temp_dir) = setup_app();
+    let config: toml::Value = toml::from_str("[keys.popup]\ncopy = \"c\"\n").unwrap();
+    app.keymap = Keymap::from_config(config.get("keys")).unwrap();
+
+    press(&mut app, KeyCode::Char('?'));
+    assert_eq!(app.input_mode, InputMode::Help);
+    let terminal = render_screen(&mut app, 100, 60);
+    let buffer = terminal.backend().buffer();
+    let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Key bindings"));
+    assert!(screen.contains("Calendar"));
+    assert!(screen.contains("Ctrl+r"));
+    assert!(screen.contains("Event popup"));
+    assert!(screen.contains("c             Copy the selected event"));
+
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.help_scroll, 1);
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::Normal);
+
+    // Opened from the view popup, the help screen returns to it
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('?'));
+    assert_eq!(app.input_mode, InputMode::Help);
+    press(&mut app, KeyCode::Char('?'));
+    assert_eq!(app.input_mode, InputMode::ViewEventsPopup);
+}
+
+#[test]
+fn test_monochrome_theme_draws_without_colours() {
+    use ratatui::style::{Color, Modifier};
+    use rcal::app::ViewMode;
+    use rcal::theme::Theme;
+
+    let (mut app, _temp_dir) = setup_app();
+    let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    app.date = date;
+    app.add_event(meeting("Standup", date, 9, 10));
+    app.theme = Theme::monochrome();
+
+    for view in [
+        ViewMode::Month,
+        ViewMode::Year,
+        ViewMode::Week,
+        ViewMode::Day,
+    ] {
+        app.view_mode = view;
+        let terminal = render_screen(&mut app, 132, 40);
+        let buffer = terminal.backend().buffer();
+        assert!(buffer
+            .content()
+            .iter()
+            .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+        // The selected day or event stands out without colours
+        assert!(buffer
+            .content()
+            .iter()
+            .any(|cell| cell.modifier.contains(Modifier::REVERSED)));
+    }
+
+    press(&mut app, KeyCode::Char('o'));
+    let terminal = render_screen(&mut app, 132, 40);
+    assert!(terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+}
+
+#[test]
+fn test_locale_settings() {
+    use rcal::app::ViewMode;
+    use rcal::locale::Locale;
+
+    let (mut app, _temp_dir) = setup_app();
+    let config: toml::Value = toml::from_str(
+        r#"
+        language = "de"
+        week_start = "sunday"
+        date_input = "mm/dd"
+        date_display = "dd/mm"
+        time_format = "12h"
+        "#,
+    )
+    .unwrap();
+    app.locale = Locale::from_config(Some(&config)).unwrap();
+    let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    app.date = date;
+
+    // The month grid starts on Sunday with German names
+    let terminal = render_screen(&mut app, 132, 40);
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Oktober"));
+    let sunday = screen.find("So").unwrap();
+    assert!(sunday < screen.find("Mo").unwrap());
+
+    // 12-hour times and month-first end dates are accepted
+    app.show_add_event_popup = true;
+    app.input_mode = InputMode::EditingEventPopup;
+    app.current_date_for_new_event = date;
+    app.popup_event_title = "Party".to_string();
+    app.popup_event_time = "2pm".to_string();
+    app.popup_event_end_date = "12/24".to_string();
+    app.popup_event_end_time = "5:30 pm".to_string();
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::Normal);
+    let event = app.events()[0].clone();
+    assert_eq!(event.start_time, NaiveTime::from_hms_opt(14, 0, 0).unwrap());
+    assert_eq!(event.end_date, NaiveDate::from_ymd_opt(2026, 12, 24));
+    assert_eq!(event.end_time, NaiveTime::from_hms_opt(17, 30, 0));
+
+    app.view_mode = ViewMode::Day;
+    let terminal = render_screen(&mut app, 132, 40);
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Montag, 19 Oktober 2026"));
+    assert!(screen.contains("2:00 PM"));
+}
+
+#[test]
+fn test_goto_prompt() {
+    let (mut app, _temp_dir) = setup_app();
+    app.date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+
+    // Week numbers are shown next to each row of the month grid
+    let terminal = render_screen(&mut app, 132, 40);
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Wk"));
+    assert!(screen.contains("43 "));
+
+    press(&mut app, KeyCode::Char('g'));
+    assert_eq!(app.input_mode, InputMode::GoTo);
+    type_text(&mut app, "w50");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::Normal);
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 12, 7).unwrap());
+    assert_eq!((app.view_start_month, app.view_start_year), (12, 2026));
+
+    press(&mut app, KeyCode::Char('g'));
+    type_text(&mut app, "march 2027");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2027, 3, 1).unwrap());
+    assert_eq!(app.view_start_month, 3);
+
+    press(&mut app, KeyCode::Char('g'));
+    type_text(&mut app, "+1m");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2027, 4, 1).unwrap());
+
+    // Invalid input keeps the prompt open with the error
+    press(&mut app, KeyCode::Char('g'));
+    type_text(&mut app, "w60");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::GoTo);
+    assert_eq!(app.status_message, "Week 60 does not exist in 2027");
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::Normal);
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2027, 4, 1).unwrap());
+
+    // An offset beyond the supported dates is an error, not a crash
+    press(&mut app, KeyCode::Char('g'));
+    type_text(&mut app, "+999999999");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.input_mode, InputMode::GoTo);
+    assert_eq!(app.status_message, "offset out of range");
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2027, 4, 1).unwrap());
+}
+
+#[test]
+fn test_command_line() {
+    let (mut app, _temp_dir) = setup_app();
+    let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    app.date = date;
+    let run = |app: &mut App, command: &str| {
+        press(app, KeyCode::Char(':'));
+        assert_eq!(app.input_mode, InputMode::CommandLine);
+        type_text(app, command);
+        handle_event(app, Event::Key(KeyEvent::from(KeyCode::Enter))).unwrap()
+    };
+
+    // :add takes the arguments of `rcal add`, on the selected date
+    assert!(run(&mut app, "add Standup #work 09:00 for 15m"));
+    assert!(run(&mut app, "add Gym --time 18:00"));
+    assert_eq!(stored_titles(&app), vec!["Gym", "Standup #work"]);
+    assert_eq!(app.status_message, "Added 'Gym' on 2026-10-19");
+    assert_eq!(app.events_on(date).len(), 2);
+
+    // An event with the same title and date is only replaced with --force
+    run(&mut app, "add Gym");
+    assert_eq!(
+        app.status_message,
+        "Cannot add: 'Gym' is already on 2026-10-19"
+    );
+    run(&mut app, "add Gym --force");
+    assert_eq!(app.events_on(date).len(), 2);
+    let gym = app
+        .events_on(date)
+        .into_iter()
+        .find(|e| e.title == "Gym")
+        .unwrap();
+    assert!(gym.is_all_day);
+    press(&mut app, KeyCode::Char('u'));
+    let gym = app
+        .events_on(date)
+        .into_iter()
+        .find(|e| e.title == "Gym")
+        .unwrap();
+    assert!(!gym.is_all_day);
+
+    // :filter hides the events without the tag from the views
+    run(&mut app, "filter tag:work");
+    let titles: Vec<String> = app.events_on(date).into_iter().map(|e| e.title).collect();
+    assert_eq!(titles, vec!["Standup #work"]);
+    let terminal = render_screen(&mut app, 132, 40);
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("RCal [filter: tag:work]"));
+    run(&mut app, "filter");
+    assert_eq!(app.events_on(date).len(), 2);
+
+    // Tab completes commands and event titles, Up recalls earlier commands
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "del");
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_input, "delete ");
+    type_text(&mut app, "gy");
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_input, "delete Gym ");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(stored_titles(&app), vec!["Standup #work"]);
+    assert_eq!(app.status_message, "Deleted 'Gym' on 2026-10-19");
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(stored_titles(&app), vec!["Gym", "Standup #work"]);
+
+    press(&mut app, KeyCode::Char(':'));
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.command_input, "delete Gym");
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.command_input, "filter");
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.input_mode, InputMode::Normal);
+
+    run(&mut app, "goto w50");
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 12, 7).unwrap());
+    run(&mut app, "goto -1w");
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 11, 30).unwrap());
+    run(&mut app, "goto +999999999");
+    assert_eq!(app.status_message, "offset out of range");
+    assert_eq!(app.date, NaiveDate::from_ymd_opt(2026, 11, 30).unwrap());
+    run(&mut app, "frobnicate");
+    assert_eq!(app.status_message, "unrecognized subcommand 'frobnicate'");
+    run(&mut app, "delete");
+    assert!(app
+        .status_message
+        .starts_with("Cannot delete: select an event"));
+
+    assert!(!run(&mut app, "q"));
+}